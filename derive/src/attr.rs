@@ -0,0 +1,175 @@
+use crate::case::RenameRule;
+use syn::meta::ParseNestedMeta;
+use syn::{Attribute, Data, DeriveInput, Field, Fields, LitStr, Variant};
+
+/// Calls `f` for every item inside the `#[json(...)]` attributes.
+fn parse_json_attrs(
+    attrs: &[Attribute],
+    mut f: impl FnMut(&ParseNestedMeta) -> syn::Result<bool>,
+) -> syn::Result<()> {
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("json")) {
+        attr.parse_nested_meta(|meta| {
+            if f(&meta)? {
+                Ok(())
+            } else {
+                Err(meta.error("unsupported json attribute"))
+            }
+        })?;
+    }
+    Ok(())
+}
+
+fn parse_rename_all(meta: &ParseNestedMeta) -> syn::Result<RenameRule> {
+    RenameRule::from_lit(&meta.value()?.parse()?)
+}
+
+/// How enum variants are told apart in JSON.
+#[derive(Default)]
+pub enum Repr {
+    /// `{"Variant": content}`, or `"Variant"` for unit variants.
+    #[default]
+    External,
+    /// The variant name is stored in the given member, next to the fields.
+    Internal(String),
+    /// Only the content, each variant is tried in turn when reading.
+    Untagged,
+}
+
+/// Attributes on the struct or enum itself.
+#[derive(Default)]
+pub struct Container {
+    pub rename_all: Option<RenameRule>,
+    pub repr: Repr,
+}
+
+impl Container {
+    pub fn from_input(input: &DeriveInput) -> syn::Result<Self> {
+        let mut container = Container::default();
+        parse_json_attrs(&input.attrs, |meta| {
+            if meta.path.is_ident("rename_all") {
+                container.rename_all = Some(parse_rename_all(meta)?);
+                return Ok(true);
+            }
+            let repr = if meta.path.is_ident("tag") {
+                Repr::Internal(meta.value()?.parse::<LitStr>()?.value())
+            } else if meta.path.is_ident("untagged") {
+                Repr::Untagged
+            } else {
+                return Ok(false);
+            };
+            if !matches!(container.repr, Repr::External) {
+                return Err(meta.error("only one of tag and untagged can be given"));
+            }
+            container.repr = repr;
+            Ok(true)
+        })?;
+        match &input.data {
+            Data::Enum(data) => {
+                if let Repr::Internal(_) = container.repr {
+                    let tuple = data.variants.iter().find(|variant| {
+                        matches!(&variant.fields, Fields::Unnamed(fields) if fields.unnamed.len() > 1)
+                    });
+                    if let Some(variant) = tuple {
+                        return Err(syn::Error::new_spanned(
+                            variant,
+                            "internally tagged enums cannot have tuple variants",
+                        ));
+                    }
+                }
+            }
+            _ if !matches!(container.repr, Repr::External) => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "tag and untagged only apply to enums",
+                ))
+            }
+            _ => {}
+        }
+        Ok(container)
+    }
+}
+
+/// An enum variant with its attributes applied.
+pub struct VariantInfo<'a> {
+    /// Name of the variant in JSON.
+    pub key: String,
+    pub fields: Vec<FieldInfo<'a>>,
+}
+
+impl<'a> VariantInfo<'a> {
+    pub fn new(variant: &'a Variant, container: &Container) -> syn::Result<Self> {
+        let mut rename = None;
+        let mut rename_all = None;
+        parse_json_attrs(&variant.attrs, |meta| {
+            if meta.path.is_ident("rename") {
+                rename = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("rename_all") {
+                rename_all = Some(parse_rename_all(meta)?);
+            } else {
+                return Ok(false);
+            }
+            Ok(true)
+        })?;
+        let name = variant.ident.to_string();
+        Ok(VariantInfo {
+            key: rename.unwrap_or_else(|| match container.rename_all {
+                Some(rule) => rule.apply(&name),
+                None => name,
+            }),
+            fields: FieldInfo::all(&variant.fields, rename_all)?,
+        })
+    }
+}
+
+/// A field with its attributes applied.
+pub struct FieldInfo<'a> {
+    pub field: &'a Field,
+    /// Name of the member in JSON, for named fields.
+    pub key: String,
+    /// Left out of the JSON and filled with `Default::default()`.
+    pub skip: bool,
+    /// Filled with `Default::default()` when missing from the JSON.
+    pub default: bool,
+}
+
+impl<'a> FieldInfo<'a> {
+    pub fn all(fields: &'a Fields, rename_all: Option<RenameRule>) -> syn::Result<Vec<Self>> {
+        fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let mut info = FieldInfo {
+                    field,
+                    key: match &field.ident {
+                        Some(ident) => {
+                            let name = ident.to_string();
+                            let name = name.strip_prefix("r#").unwrap_or(&name);
+                            rename_all.map_or_else(|| name.to_string(), |rule| rule.apply(name))
+                        }
+                        None => i.to_string(),
+                    },
+                    skip: false,
+                    default: false,
+                };
+                parse_json_attrs(&field.attrs, |meta| {
+                    if meta.path.is_ident("rename") {
+                        info.key = meta.value()?.parse::<LitStr>()?.value();
+                    } else if meta.path.is_ident("skip") {
+                        info.skip = true;
+                    } else if meta.path.is_ident("default") {
+                        info.default = true;
+                    } else {
+                        return Ok(false);
+                    }
+                    Ok(true)
+                })?;
+                Ok(info)
+            })
+            .collect()
+    }
+}
+
+/// Whether the fields are written as the single inner value rather than an array.
+pub fn is_newtype(fields: &Fields, infos: &[FieldInfo]) -> bool {
+    matches!(fields, Fields::Unnamed(_)) && infos.len() == 1 && !infos[0].skip
+}
@@ -0,0 +1,11 @@
+use proc_macro2::TokenStream;
+use syn::{parse_quote, Generics};
+
+/// Adds `bound` to every type parameter.
+pub fn add_bounds(generics: &Generics, bound: TokenStream) -> Generics {
+    let mut generics = generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(#bound));
+    }
+    generics
+}
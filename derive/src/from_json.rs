@@ -0,0 +1,157 @@
+use crate::attr::{is_newtype, Container, FieldInfo, Repr, VariantInfo};
+use crate::bounds::add_bounds;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields};
+
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let generics = add_bounds(&input.generics, quote!(::lite_json::FromJson));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let container = Container::from_input(input)?;
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let infos = FieldInfo::all(&data.fields, container.rename_all)?;
+            let value = fields_from_json(quote!(Self), &data.fields, &infos, quote!(value));
+            quote!(::core::result::Result::Ok(#value))
+        }
+        Data::Enum(data) => {
+            let mut arms = Vec::new();
+            for variant in &data.variants {
+                let info = VariantInfo::new(variant, &container)?;
+                let ident = &variant.ident;
+                let key = &info.key;
+                let content = match container.repr {
+                    Repr::Untagged => quote!(::core::clone::Clone::clone(&value)),
+                    _ => quote!(__content),
+                };
+                let value =
+                    fields_from_json(quote!(Self::#ident), &variant.fields, &info.fields, content);
+                arms.push(match container.repr {
+                    Repr::External => quote! {
+                        #key => ::lite_json::__private::in_variant(#key, move || {
+                            ::core::result::Result::Ok(#value)
+                        }),
+                    },
+                    // The remaining members of a unit variant are ignored.
+                    Repr::Internal(_) if variant.fields.is_empty() => quote! {
+                        #key => ::core::result::Result::Ok(Self::#ident),
+                    },
+                    Repr::Internal(_) => quote! {
+                        #key => ::core::result::Result::Ok(#value),
+                    },
+                    Repr::Untagged => quote! {
+                        let __attempt = ::lite_json::__private::attempt(|| {
+                            ::core::result::Result::Ok(#value)
+                        });
+                        if __attempt.is_ok() {
+                            return __attempt;
+                        }
+                    },
+                });
+            }
+            match &container.repr {
+                Repr::External => quote! {
+                    let (__tag, __content) = ::lite_json::__private::tagged(value)?;
+                    match __tag.as_str() {
+                        #(#arms)*
+                        _ => ::lite_json::__private::unknown_variant(),
+                    }
+                },
+                Repr::Internal(tag) => quote! {
+                    #[allow(unused_variables)]
+                    let (__tag, __content) = ::lite_json::__private::internally_tagged(value, #tag)?;
+                    match __tag.as_str() {
+                        #(#arms)*
+                        _ => ::lite_json::__private::unknown_variant()
+                            .map_err(|e: ::lite_json::FromJsonError| e.at_key(#tag)),
+                    }
+                },
+                Repr::Untagged => quote! {
+                    #(#arms)*
+                    ::lite_json::__private::unknown_variant()
+                },
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "FromJson cannot be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::lite_json::FromJson for #name #ty_generics #where_clause {
+            fn from_json(
+                value: ::lite_json::JsonValue,
+            ) -> ::core::result::Result<Self, ::lite_json::FromJsonError> {
+                #body
+            }
+        }
+
+        impl #impl_generics ::core::convert::TryFrom<::lite_json::JsonValue> for #name #ty_generics #where_clause {
+            type Error = ::lite_json::FromJsonError;
+
+            fn try_from(
+                value: ::lite_json::JsonValue,
+            ) -> ::core::result::Result<Self, ::lite_json::FromJsonError> {
+                ::lite_json::FromJson::from_json(value)
+            }
+        }
+    })
+}
+
+/// Builds `path` from the JSON in `value`, returning early from the enclosing function on error.
+fn fields_from_json(
+    path: TokenStream,
+    fields: &Fields,
+    infos: &[FieldInfo],
+    value: TokenStream,
+) -> TokenStream {
+    let default = quote!(::core::default::Default::default());
+    match fields {
+        Fields::Named(_) => {
+            let members = infos.iter().map(|info| {
+                let ident = &info.field.ident;
+                let key = &info.key;
+                if info.skip {
+                    quote!(#ident: #default)
+                } else if info.default {
+                    quote!(#ident: ::lite_json::__private::field_or_default(&mut __object, #key)?)
+                } else {
+                    quote!(#ident: ::lite_json::__private::field(&mut __object, #key)?)
+                }
+            });
+            quote! {{
+                #[allow(unused_mut, unused_variables)]
+                let mut __object = ::lite_json::__private::object(#value)?;
+                #path { #(#members),* }
+            }}
+        }
+        Fields::Unnamed(_) if is_newtype(fields, infos) => {
+            quote!(#path(::lite_json::FromJson::from_json(#value)?))
+        }
+        Fields::Unnamed(_) => {
+            let len = infos.iter().filter(|info| !info.skip).count();
+            let items = infos.iter().scan(0usize, |index, info| {
+                if info.skip {
+                    return Some(default.clone());
+                }
+                let item = quote!(::lite_json::__private::element(&mut __items, #index)?);
+                *index += 1;
+                Some(item)
+            });
+            quote! {{
+                #[allow(unused_mut, unused_variables)]
+                let mut __items = ::lite_json::__private::array(#value, #len)?;
+                #path(#(#items),*)
+            }}
+        }
+        Fields::Unit => quote! {{
+            ::lite_json::__private::null(#value)?;
+            #path
+        }},
+    }
+}
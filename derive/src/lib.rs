@@ -0,0 +1,46 @@
+extern crate proc_macro;
+
+mod attr;
+mod bounds;
+mod case;
+mod from_json;
+mod to_json;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+/// Derives `lite_json::ToJson` and `lite_json::Serialize`.
+///
+/// Structs with named fields become objects, tuple structs become arrays, newtype structs
+/// become their inner value and unit structs become `null`. Enum variants are externally
+/// tagged: unit variants become their name as a string and all others an object with the
+/// variant name as its only key.
+///
+/// Fields accept `#[json(rename = "...")]`, `#[json(skip)]` and `#[json(default)]`, variants
+/// accept `rename` and `rename_all`, and the container accepts `rename_all` with one of
+/// `lowercase`, `UPPERCASE`, `PascalCase`, `camelCase`, `snake_case`,
+/// `SCREAMING_SNAKE_CASE`, `kebab-case` or `SCREAMING-KEBAB-CASE`.
+///
+/// Enums accept `#[json(tag = "type")]` to store the variant name in the given member next
+/// to the variant's fields, which requires every variant to be a unit, a struct or a newtype
+/// around a type that becomes an object. `#[json(untagged)]` writes only the variant
+/// content, and reading picks the first variant that accepts the value.
+#[proc_macro_derive(ToJson, attributes(json))]
+pub fn derive_to_json(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    to_json::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `lite_json::FromJson` and `TryFrom<lite_json::JsonValue>`.
+///
+/// Accepts the JSON produced by `#[derive(ToJson)]`. Errors carry a JSON Pointer to the
+/// offending field. Takes the same `#[json(...)]` attributes as `ToJson`.
+#[proc_macro_derive(FromJson, attributes(json))]
+pub fn derive_from_json(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    from_json::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,141 @@
+use crate::attr::{is_newtype, Container, FieldInfo, Repr, VariantInfo};
+use crate::bounds::add_bounds;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, Index};
+
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let generics = add_bounds(&input.generics, quote!(::lite_json::ToJson));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let container = Container::from_input(input)?;
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let infos = FieldInfo::all(&data.fields, container.rename_all)?;
+            let access = data.fields.iter().enumerate().map(|(i, field)| {
+                let member = match &field.ident {
+                    Some(ident) => quote!(#ident),
+                    None => {
+                        let index = Index::from(i);
+                        quote!(#index)
+                    }
+                };
+                quote!(&self.#member)
+            });
+            fields_to_json(&data.fields, &infos, access.collect())
+        }
+        Data::Enum(data) => {
+            let mut arms = Vec::new();
+            for variant in &data.variants {
+                let info = VariantInfo::new(variant, &container)?;
+                let ident = &variant.ident;
+                let key = &info.key;
+                let bindings: Vec<_> = info
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, field)| {
+                        if field.skip {
+                            quote!(_)
+                        } else {
+                            let binding = format_ident!("__field{}", i);
+                            quote!(#binding)
+                        }
+                    })
+                    .collect();
+                let pattern = match &variant.fields {
+                    Fields::Unit => quote!(Self::#ident),
+                    Fields::Unnamed(_) => quote!(Self::#ident(#(#bindings),*)),
+                    Fields::Named(fields) => {
+                        let names = fields.named.iter().map(|f| &f.ident);
+                        quote!(Self::#ident { #(#names: #bindings),* })
+                    }
+                };
+                let value = fields_to_json(&variant.fields, &info.fields, bindings);
+                let value = match (&container.repr, &variant.fields) {
+                    (Repr::External, Fields::Unit) => quote! {
+                        ::lite_json::JsonValue::String(::lite_json::__private::key(#key))
+                    },
+                    (Repr::External, _) => quote!(::lite_json::__private::variant(#key, #value)),
+                    (Repr::Internal(tag), _) => {
+                        quote!(::lite_json::__private::with_tag(#tag, #key, #value))
+                    }
+                    (Repr::Untagged, _) => value,
+                };
+                arms.push(quote!(#pattern => #value,));
+            }
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "ToJson cannot be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::lite_json::ToJson for #name #ty_generics #where_clause {
+            fn to_json(&self) -> ::lite_json::JsonValue {
+                #body
+            }
+        }
+
+        impl #impl_generics ::lite_json::Serialize for #name #ty_generics #where_clause {
+            fn serialize_to(
+                &self,
+                buffer: &mut ::lite_json::__private::Vec<u8>,
+                indent: u32,
+                level: u32,
+            ) {
+                ::lite_json::Serialize::serialize_to(
+                    &::lite_json::ToJson::to_json(self),
+                    buffer,
+                    indent,
+                    level,
+                )
+            }
+        }
+    })
+}
+
+/// Builds the JSON for a set of fields, each read through the matching `access` expression.
+/// Skipped fields are left out.
+fn fields_to_json(fields: &Fields, infos: &[FieldInfo], access: Vec<TokenStream>) -> TokenStream {
+    let included = infos
+        .iter()
+        .zip(access)
+        .filter(|(info, _)| !info.skip)
+        .collect::<Vec<_>>();
+    match fields {
+        Fields::Named(_) => {
+            let members = included.iter().map(|(info, access)| {
+                let key = &info.key;
+                quote! {
+                    (::lite_json::__private::key(#key), ::lite_json::ToJson::to_json(#access))
+                }
+            });
+            quote! {
+                ::lite_json::JsonValue::Object(::lite_json::__private::vec![#(#members),*])
+            }
+        }
+        Fields::Unnamed(_) if is_newtype(fields, infos) => {
+            let access = &included[0].1;
+            quote!(::lite_json::ToJson::to_json(#access))
+        }
+        Fields::Unnamed(_) => {
+            let access = included.iter().map(|(_, access)| access);
+            quote! {
+                ::lite_json::JsonValue::Array(::lite_json::__private::vec![
+                    #(::lite_json::ToJson::to_json(#access)),*
+                ])
+            }
+        }
+        Fields::Unit => quote!(::lite_json::JsonValue::Null),
+    }
+}
@@ -1,16 +1,15 @@
 use crate::traits::{Error, Position};
+use core::fmt;
 
 #[cfg(not(feature = "std"))]
 extern crate alloc;
 
-#[cfg(not(feature = "std"))]
-use alloc::fmt::Formatter;
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 
-#[cfg_attr(feature = "std", derive(Debug))]
-#[derive(Copy, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
 pub struct SimplePosition {
+    /// The byte offset into the input.
     pub index: u32,
     pub line: u32,
     pub column: u32,
@@ -20,7 +19,7 @@ impl SimplePosition {
     pub fn next(&self, c: char) -> Self {
         let new_line = c == '\n';
         Self {
-            index: self.index + 1,
+            index: self.index + c.len_utf8() as u32,
             line: if new_line { self.line + 1 } else { self.line },
             column: if new_line { 0 } else { self.column + 1 },
         }
@@ -53,18 +52,78 @@ impl core::ops::Sub<Self> for SimplePosition {
     }
 }
 
-#[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
-pub struct SimpleError {
-    pub reasons: Vec<(SimplePosition, &'static str)>,
+/// The category of a parse failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParseErrorKind {
+    /// The input ended in the middle of a value.
+    UnexpectedEof,
+    /// A character that cannot appear at this point, such as a missing comma or colon.
+    UnexpectedToken,
+    /// A backslash escape in a string is not valid.
+    InvalidEscape,
+    /// A number is malformed, such as `01`, `1.` or `-`.
+    InvalidNumber,
+    /// A number does not fit `NumberValue` and the `NumberOverflow` policy rejects it.
+    NumberOutOfRange,
+    /// Arrays and objects are nested deeper than `ParserOptions::max_nest_level`.
+    DepthLimitExceeded,
+    /// A complete value is followed by more input.
+    TrailingCharacters,
+    /// The input is longer than `ParserOptions::max_input_length`, or 4 GiB or more.
+    InputTooLong,
+    /// A string or key is longer than `ParserOptions::max_string_length`.
+    StringTooLong,
+    /// An array has more items than `ParserOptions::max_array_length`.
+    TooManyArrayItems,
+    /// An object has more members than `ParserOptions::max_object_members`.
+    TooManyObjectMembers,
+    /// The parsed values would take more memory than `ParserOptions::memory_budget`.
+    BudgetExceeded,
+    /// Parsing would take more steps than `ParserOptions::step_budget`.
+    StepBudgetExceeded,
+    /// The input is UTF-16, as shown by its byte order mark. Only UTF-8 is supported.
+    UnsupportedEncoding,
+    /// The input is not valid UTF-8.
+    InvalidUtf8,
 }
 
-#[cfg(not(feature = "std"))]
-impl core::fmt::Debug for SimpleError {
-    fn fmt(&self, _f: &mut Formatter<'_>) -> core::fmt::Result {
-        Ok(())
+impl ParseErrorKind {
+    /// A short description of the failure, as also recorded as its reason.
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            ParseErrorKind::UnexpectedEof => "unexpected end of input",
+            ParseErrorKind::UnexpectedToken => "unexpected character",
+            ParseErrorKind::InvalidEscape => "invalid escape",
+            ParseErrorKind::InvalidNumber => "invalid number",
+            ParseErrorKind::NumberOutOfRange => "number out of range",
+            ParseErrorKind::DepthLimitExceeded => "nesting too deep",
+            ParseErrorKind::TrailingCharacters => "trailing characters",
+            ParseErrorKind::InputTooLong => "input too long",
+            ParseErrorKind::StringTooLong => "string too long",
+            ParseErrorKind::TooManyArrayItems => "too many array items",
+            ParseErrorKind::TooManyObjectMembers => "too many object members",
+            ParseErrorKind::BudgetExceeded => "memory budget exceeded",
+            ParseErrorKind::StepBudgetExceeded => "step budget exceeded",
+            ParseErrorKind::UnsupportedEncoding => "UTF-16 input is not supported",
+            ParseErrorKind::InvalidUtf8 => "invalid UTF-8",
+        }
+    }
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
+#[cfg_attr(feature = "std", derive(PartialEq, Eq))]
+#[derive(Debug)]
+pub struct SimpleError {
+    pub reasons: Vec<(SimplePosition, &'static str)>,
+    /// The category of the innermost failure.
+    pub kind: ParseErrorKind,
+}
+
 impl Error for SimpleError {
     type Position = SimplePosition;
 
@@ -75,6 +134,9 @@ impl Error for SimpleError {
     fn add_reason(self, position: Self::Position, reason: &'static str) -> Self {
         let mut reasons = self.reasons;
         reasons.push((position, reason));
-        Self { reasons }
+        Self {
+            reasons,
+            kind: self.kind,
+        }
     }
 }
@@ -1,52 +1,305 @@
 #[cfg(not(feature = "std"))]
 pub extern crate alloc;
 
-use crate::impls::{SimpleError, SimplePosition};
+use crate::impls::{ParseErrorKind, SimpleError, SimplePosition};
 use crate::traits::{Error, Input, Position, ResultOf};
 #[cfg(not(feature = "std"))]
-use alloc::vec::Vec;
+use alloc::{vec, vec::Vec};
+use core::cell::Cell;
 use core::marker::PhantomData;
 
-#[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
-#[derive(Clone)]
+/// What to do when a number has more digits than its integer or fraction part can hold, or
+/// an exponent beyond the range of `i32`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberOverflow {
+    /// Fail to parse.
+    Error,
+    /// Clamp an oversized integer part to its maximum value and drop the fraction, and
+    /// truncate an oversized fraction part. Exponents are clamped to the range of `i32`.
+    Saturate,
+    /// Keep the most significant digits that fit, moving the dropped integer digits into the
+    /// exponent and truncating the fraction. Exponents are clamped to the range of `i32`.
+    Lossy,
+    /// Keep the number's text as is, for parsers that have a representation for it, and
+    /// fail otherwise.
+    Raw,
+}
+
+/// What to do with a `\u` escape of a surrogate that is not part of a pair, which does not
+/// stand for a character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoneSurrogates {
+    /// Fail to parse.
+    Error,
+    /// Read it as the replacement character U+FFFD.
+    Replace,
+    /// Keep it in a string value, which is then read as UTF-16 code units and written back
+    /// with the surrogate escaped. An object key, which cannot hold it, reads it as U+FFFD.
+    Preserve,
+}
+
+/// How to parse, starting from `ParserOptions::default()`, `ParserOptions::strict()` or
+/// `ParserOptions::relaxed()` and adjusted with the setter of each option, such as
+/// `ParserOptions::default().max_nest_level(Some(10)).allow_comments(true)`. `strict` is set
+/// with its preset. New options may be added, so the struct cannot be built with a literal
+/// outside this crate.
+#[cfg_attr(feature = "std", derive(PartialEq, Eq))]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct ParserOptions {
+    /// How deeply arrays and objects may be nested, defaults to 100. Deeper input is rejected
+    /// before it is descended into. Parsing does not recurse, but dropping, comparing or
+    /// serializing a value does, so `None` removes the limit and should only be used for
+    /// trusted input.
     pub max_nest_level: Option<u32>,
+    /// Policy for numbers that overflow their parts, defaults to `NumberOverflow::Error`.
+    pub number_overflow: NumberOverflow,
+    /// Policy for fractions with more digits than their part can hold, defaults to
+    /// `number_overflow`. `Saturate` and `Lossy` both keep the leading digits that fit.
+    pub fraction_overflow: Option<NumberOverflow>,
+    /// Policy for unpaired surrogate escapes such as `"\uD800"`, defaults to
+    /// `LoneSurrogates::Error`.
+    pub lone_surrogates: LoneSurrogates,
+    /// The longest input accepted, in bytes. Unlimited by default.
+    pub max_input_length: Option<usize>,
+    /// The most characters a string or object key may have. Unlimited by default.
+    pub max_string_length: Option<usize>,
+    /// The most items an array may have. Unlimited by default.
+    pub max_array_length: Option<usize>,
+    /// The most members an object may have. Unlimited by default.
+    pub max_object_members: Option<usize>,
+    /// Roughly how many bytes the parsed values may occupy, counting each value, string
+    /// character and key. Unlimited by default.
+    pub memory_budget: Option<usize>,
+    /// The most steps parsing may take, counting one per byte of input and one per value, so
+    /// metered environments can bound the cost deterministically. Unlimited by default.
+    pub step_budget: Option<u64>,
+    /// Whether `//` line comments and `/* */` block comments are allowed wherever whitespace
+    /// is, as in JSONC. Off by default.
+    pub allow_comments: bool,
+    /// Whether strings and keys may be in single quotes, as in JavaScript object literals.
+    /// Inside them `"` needs no escape and `\'` is an escape. Off by default.
+    pub allow_single_quotes: bool,
+    /// Whether object keys may be bare identifiers, as in `{foo: 1}`. An identifier starts with
+    /// `$`, `_` or a letter, and continues with those, digits, U+200C or U+200D, where letters
+    /// and digits are alphabetic and numeric Unicode characters. Off by default.
+    pub allow_unquoted_keys: bool,
+    /// Whether the numbers `NaN`, `Infinity` and `-Infinity` are accepted, as written by
+    /// JavaScript and Python. Off by default.
+    pub allow_non_finite: bool,
+    /// Whether hexadecimal integers such as `0x1A2B` or `-0xff` are accepted. They are stored as
+    /// their value, so they serialize as decimal. Off by default.
+    pub allow_hex_numbers: bool,
+    /// Whether numbers may start with `+` or a decimal point, or end with a decimal point, as
+    /// in `+1.5`, `.5` and `5.`. Off by default.
+    pub allow_loose_numbers: bool,
+    /// Whether strings may span lines, either with a backslash before a line break, which is
+    /// dropped, or with raw line breaks, which are kept. Off by default.
+    pub allow_multiline_strings: bool,
+    /// Whether strings may contain two-digit hexadecimal escapes such as `\x41`, which stand for
+    /// the code points U+0000 to U+00FF. Off by default.
+    pub allow_hex_escapes: bool,
+    /// Whether strings may contain unescaped control characters, U+0000 to U+001F, including
+    /// raw tabs and line breaks. Off by default, so they are rejected as RFC 8259 requires.
+    pub allow_control_characters: bool,
+    /// Whether the whole JSON5 grammar is accepted: comments, trailing commas, unquoted keys,
+    /// single-quoted and multi-line strings, extra escapes and hexadecimal numbers. Off by
+    /// default.
+    #[cfg(feature = "json5")]
+    pub json5: bool,
+    /// Whether only RFC 8259 JSON is accepted. The `allow_*` options and `json5` are ignored,
+    /// lone surrogates are rejected and a leading byte order mark is rejected. Off by default.
+    pub strict: bool,
 }
 
 impl Default for ParserOptions {
     fn default() -> Self {
         ParserOptions {
             max_nest_level: Some(100),
+            number_overflow: NumberOverflow::Error,
+            fraction_overflow: None,
+            lone_surrogates: LoneSurrogates::Error,
+            max_input_length: None,
+            max_string_length: None,
+            max_array_length: None,
+            max_object_members: None,
+            memory_budget: None,
+            step_budget: None,
+            allow_comments: false,
+            allow_single_quotes: false,
+            allow_unquoted_keys: false,
+            allow_non_finite: false,
+            allow_hex_numbers: false,
+            allow_loose_numbers: false,
+            allow_multiline_strings: false,
+            allow_hex_escapes: false,
+            allow_control_characters: false,
+            #[cfg(feature = "json5")]
+            json5: false,
+            strict: false,
+        }
+    }
+}
+
+/// Defines a setter for each option, which returns the options for chaining.
+macro_rules! setters {
+    ($( $( #[ $attr:meta ] )* $name:ident: $type:ty; )*) => {
+        $(
+            $( #[ $attr ] )*
+            #[doc = concat!("Sets `", stringify!($name), "`.")]
+            pub fn $name(mut self, $name: $type) -> Self {
+                self.$name = $name;
+                self
+            }
+        )*
+    };
+}
+
+impl ParserOptions {
+    setters! {
+        max_nest_level: Option<u32>;
+        number_overflow: NumberOverflow;
+        fraction_overflow: Option<NumberOverflow>;
+        lone_surrogates: LoneSurrogates;
+        max_input_length: Option<usize>;
+        max_string_length: Option<usize>;
+        max_array_length: Option<usize>;
+        max_object_members: Option<usize>;
+        memory_budget: Option<usize>;
+        step_budget: Option<u64>;
+        allow_comments: bool;
+        allow_single_quotes: bool;
+        allow_unquoted_keys: bool;
+        allow_non_finite: bool;
+        allow_hex_numbers: bool;
+        allow_loose_numbers: bool;
+        allow_multiline_strings: bool;
+        allow_hex_escapes: bool;
+        allow_control_characters: bool;
+        #[cfg(feature = "json5")]
+        json5: bool;
+    }
+
+    /// Options that accept only RFC 8259 JSON, with the default limits.
+    pub fn strict() -> Self {
+        ParserOptions {
+            strict: true,
+            ..Default::default()
+        }
+    }
+
+    /// Options that accept every extension with an `allow_*` option, as found in hand-written
+    /// configuration files, with the default limits. Lone surrogates are replaced.
+    pub fn relaxed() -> Self {
+        ParserOptions {
+            lone_surrogates: LoneSurrogates::Replace,
+            allow_comments: true,
+            allow_single_quotes: true,
+            allow_unquoted_keys: true,
+            allow_non_finite: true,
+            allow_hex_numbers: true,
+            allow_loose_numbers: true,
+            allow_multiline_strings: true,
+            allow_hex_escapes: true,
+            allow_control_characters: true,
+            ..Default::default()
         }
     }
 }
 
-#[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq))]
-#[derive(Clone)]
-pub struct ParserContext {
+/// What parsing has counted so far, shared by a context and every context nested in it. The
+/// caller owns it and can read it once parsing is done.
+#[cfg_attr(feature = "std", derive(PartialEq, Eq))]
+#[derive(Debug, Default)]
+pub struct ParserState {
+    lost_precision: Cell<bool>,
+    allocated: Cell<usize>,
+    steps: Cell<u64>,
+}
+
+impl ParserState {
+    /// Returns whether a number was truncated or clamped to fit.
+    pub fn lost_precision(&self) -> bool {
+        self.lost_precision.get()
+    }
+
+    /// Returns roughly how many bytes the parsed values occupy.
+    pub fn allocated(&self) -> usize {
+        self.allocated.get()
+    }
+
+    /// Returns how many steps parsing has taken.
+    pub fn steps(&self) -> u64 {
+        self.steps.get()
+    }
+}
+
+#[cfg_attr(feature = "std", derive(PartialEq, Eq))]
+#[derive(Debug, Clone, Copy)]
+pub struct ParserContext<'a> {
     nest_level: u32,
-    options: ParserOptions,
+    options: &'a ParserOptions,
+    state: &'a ParserState,
 }
 
-impl ParserContext {
-    pub fn new(options: ParserOptions) -> Self {
+impl<'a> ParserContext<'a> {
+    pub fn new(options: &'a ParserOptions, state: &'a ParserState) -> Self {
         Self {
             nest_level: 0,
             options,
+            state,
         }
     }
 
-    pub fn options(&self) -> &ParserOptions {
-        &self.options
+    pub fn options(&self) -> &'a ParserOptions {
+        self.options
+    }
+
+    /// Records that a number was truncated or clamped to fit.
+    pub fn mark_lost_precision(&self) {
+        self.state.lost_precision.set(true);
     }
 
+    /// Counts `bytes` of parsed output against `ParserOptions::memory_budget`, failing at `pos`
+    /// once the budget is exceeded.
+    pub fn allocate<I: Input>(
+        &self,
+        input: &I,
+        pos: I::Position,
+        bytes: usize,
+    ) -> Result<(), I::Error> {
+        let allocated = self.state.allocated.get().saturating_add(bytes);
+        self.state.allocated.set(allocated);
+        match self.options.memory_budget {
+            Some(budget) if allocated > budget => {
+                Err(input.error_with_kind(pos, ParseErrorKind::BudgetExceeded))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Counts `steps` against `ParserOptions::step_budget`, failing at `pos` once the budget is
+    /// exhausted.
+    pub fn step<I: Input>(&self, input: &I, pos: I::Position, steps: u64) -> Result<(), I::Error> {
+        let taken = self.state.steps.get().saturating_add(steps);
+        self.state.steps.set(taken);
+        match self.options.step_budget {
+            Some(budget) if taken > budget => {
+                Err(input.error_with_kind(pos, ParseErrorKind::StepBudgetExceeded))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the context for the contents of an array or object, which shares this one's
+    /// options and state.
     pub fn nest<I: Input>(&self, input: &I, pos: I::Position) -> Result<Self, I::Error> {
         if Some(self.nest_level) == self.options.max_nest_level {
-            Err(input.error_at(pos, "Exceeded nest level"))
+            Err(input.error_with_kind(pos, ParseErrorKind::DepthLimitExceeded))
         } else {
             Ok(Self {
                 nest_level: self.nest_level + 1,
-                options: self.options.clone(),
+                ..*self
             })
         }
     }
@@ -116,7 +369,7 @@ pub type Concat3<P, P2, P3> = Concat<P, Concat<P2, P3>>;
 pub type Concat4<P, P2, P3, P4> = Concat<P, Concat<P2, Concat<P3, P4>>>;
 pub type Concat5<P, P2, P3, P4, P5> = Concat<P, Concat<P2, Concat<P3, Concat<P4, P5>>>>;
 
-#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Debug)]
 pub enum Either<A, B> {
     A(A),
     B(B),
@@ -183,9 +436,9 @@ impl Input for &str {
     type Error = SimpleError;
 
     fn next(&self, pos: Self::Position) -> Result<(char, Self::Position), Self::Error> {
-        self.chars()
-            .nth(pos.index() as usize)
-            .ok_or_else(|| self.error_at(pos, "Out of bounds"))
+        self.get(pos.index() as usize..)
+            .and_then(|s| s.chars().next())
+            .ok_or_else(|| self.error_with_kind(pos, ParseErrorKind::UnexpectedEof))
             .map(|c| (c, pos.next(c)))
     }
 
@@ -204,13 +457,21 @@ impl Input for &str {
                 }
                 (s, pos)
             })
-            .ok_or_else(|| self.error_at(start, "Out of bounds"))
+            .ok_or_else(|| self.error_with_kind(start, ParseErrorKind::UnexpectedEof))
     }
 
     fn error_at(&self, pos: Self::Position, reason: &'static str) -> Self::Error {
-        let mut reasons = Vec::new();
-        reasons.push((pos, reason));
-        SimpleError { reasons }
+        SimpleError {
+            reasons: vec![(pos, reason)],
+            kind: ParseErrorKind::UnexpectedToken,
+        }
+    }
+
+    fn error_with_kind(&self, pos: Self::Position, kind: ParseErrorKind) -> Self::Error {
+        SimpleError {
+            reasons: vec![(pos, kind.as_str())],
+            kind,
+        }
     }
 
     fn is_end(&self, pos: Self::Position) -> bool {
@@ -278,3 +539,141 @@ macro_rules! parsers {
         )*
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impls::SimplePosition;
+
+    struct DigitPredicate;
+
+    impl Predicate<char> for DigitPredicate {
+        fn eval(c: &char) -> bool {
+            c.is_ascii_digit()
+        }
+    }
+
+    type Digit = ExpectChar<DigitPredicate>;
+
+    fn at(index: u32, line: u32, column: u32) -> SimplePosition {
+        SimplePosition {
+            index,
+            line,
+            column,
+        }
+    }
+
+    #[test]
+    fn positions_count_bytes_of_multi_byte_input() {
+        let input = "aé😀\nb";
+        let mut pos = SimplePosition::default();
+        let mut seen = Vec::new();
+        while let Ok((c, next)) = input.next(pos) {
+            seen.push((c, next));
+            pos = next;
+        }
+        assert_eq!(
+            seen,
+            [
+                ('a', at(1, 0, 1)),
+                ('é', at(3, 0, 2)),
+                ('😀', at(7, 0, 3)),
+                ('\n', at(8, 1, 0)),
+                ('b', at(9, 1, 1)),
+            ]
+        );
+        assert!(input.is_end(pos));
+        assert_eq!(
+            input.next(pos).unwrap_err().kind,
+            ParseErrorKind::UnexpectedEof
+        );
+
+        // Ranges are counted in bytes and may not end inside a character.
+        let (text, next) = input.next_range(at(1, 0, 1), 6).unwrap();
+        assert_eq!((text, next), ("é😀", at(7, 0, 3)));
+        let error = input.next_range(at(1, 0, 1), 1).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::UnexpectedEof);
+        assert_eq!(error.reasons, [(at(1, 0, 1), "unexpected end of input")]);
+
+        // Errors point at the byte offset of the character that failed.
+        let options = ParserOptions::default();
+        let state = ParserState::default();
+        let context = ParserContext::new(&options, &state);
+        let (digits, next) =
+            <OneOrMore<Digit> as Parser<&str>>::parse(&"12é", at(0, 0, 0), &context).unwrap();
+        assert_eq!((digits, next), (vec!['1', '2'], at(2, 0, 2)));
+        let error = <Digit as Parser<&str>>::parse(&"12é", next, &context).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+        assert_eq!(error.reasons.last().unwrap().0, at(2, 0, 2));
+        // A position inside a character has nothing to read.
+        let error = <Digit as Parser<&str>>::parse(&"é", at(1, 0, 1), &context).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let state = ParserState::default();
+        let options = ParserOptions::default().max_nest_level(Some(2));
+        let context = ParserContext::new(&options, &state);
+        let input = "[[[";
+        let inner = context
+            .nest(&input, at(0, 0, 0))
+            .and_then(|c| c.nest(&input, at(1, 0, 1)))
+            .unwrap();
+        let error = inner.nest(&input, at(2, 0, 2)).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::DepthLimitExceeded);
+        assert_eq!(error.reasons, [(at(2, 0, 2), "nesting too deep")]);
+        // Nesting does not change the context it starts from.
+        assert!(context.nest(&input, at(0, 0, 0)).is_ok());
+
+        let options = ParserOptions::default().max_nest_level(Some(0));
+        let context = ParserContext::new(&options, &state);
+        assert!(context.nest(&input, at(0, 0, 0)).is_err());
+
+        let options = ParserOptions::default().max_nest_level(None);
+        let mut context = ParserContext::new(&options, &state);
+        for _ in 0..1000 {
+            context = context.nest(&input, at(0, 0, 0)).unwrap();
+        }
+        assert_eq!(ParserOptions::default().max_nest_level, Some(100));
+    }
+
+    #[test]
+    fn presets_set_their_options() {
+        let default = ParserOptions::default();
+        assert!(!default.strict);
+        assert_eq!(default.lone_surrogates, LoneSurrogates::Error);
+
+        assert_eq!(
+            ParserOptions::strict(),
+            ParserOptions {
+                strict: true,
+                ..default.clone()
+            }
+        );
+
+        let relaxed = default
+            .clone()
+            .lone_surrogates(LoneSurrogates::Replace)
+            .allow_comments(true)
+            .allow_single_quotes(true)
+            .allow_unquoted_keys(true)
+            .allow_non_finite(true)
+            .allow_hex_numbers(true)
+            .allow_loose_numbers(true)
+            .allow_multiline_strings(true)
+            .allow_hex_escapes(true)
+            .allow_control_characters(true);
+        assert_eq!(ParserOptions::relaxed(), relaxed);
+        assert!(!relaxed.strict);
+
+        // Both keep the default limits.
+        for preset in [ParserOptions::strict(), ParserOptions::relaxed()] {
+            assert_eq!(preset.max_nest_level, default.max_nest_level);
+            assert_eq!(preset.number_overflow, NumberOverflow::Error);
+            assert_eq!(preset.max_input_length, None);
+            assert_eq!(preset.memory_budget, None);
+            assert_eq!(preset.step_budget, None);
+        }
+    }
+}
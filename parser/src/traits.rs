@@ -1,3 +1,5 @@
+use crate::impls::ParseErrorKind;
+
 pub trait Position: core::ops::Sub<Self, Output = i32> + Copy {
     fn index(&self) -> u32;
     fn line(&self) -> u32;
@@ -20,6 +22,11 @@ pub trait Input: Default {
         counts: u32,
     ) -> Result<(&str, Self::Position), Self::Error>;
     fn error_at(&self, pos: Self::Position, reason: &'static str) -> Self::Error;
+    /// Returns an error of a known kind. By default it is an `error_at` with the kind's
+    /// description as the reason, for inputs whose errors do not record kinds.
+    fn error_with_kind(&self, pos: Self::Position, kind: ParseErrorKind) -> Self::Error {
+        self.error_at(pos, kind.as_str())
+    }
     fn is_end(&self, pos: Self::Position) -> bool;
 }
 
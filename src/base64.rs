@@ -0,0 +1,155 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+use crate::json::JsonValue;
+
+/// The 64 characters used to encode binary data as base64 (RFC 4648).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Base64Alphabet {
+    /// The standard alphabet, ending in `+` and `/`.
+    Standard,
+    /// The URL and filename safe alphabet, ending in `-` and `_`, as used by JWTs.
+    UrlSafe,
+}
+
+impl Base64Alphabet {
+    fn chars(self) -> &'static [u8; 64] {
+        match self {
+            Base64Alphabet::Standard => {
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/"
+            }
+            Base64Alphabet::UrlSafe => {
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_"
+            }
+        }
+    }
+}
+
+/// Encodes bytes as padded base64.
+#[cfg_attr(not(feature = "bson"), allow(dead_code))]
+pub(crate) fn encode(bytes: &[u8], alphabet: Base64Alphabet) -> String {
+    let chars = alphabet.chars();
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(chars[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Decodes base64 with or without padding, or returns None if it is malformed.
+pub(crate) fn decode(text: &[char], alphabet: Base64Alphabet) -> Option<Vec<u8>> {
+    let chars = alphabet.chars();
+    let unpadded = match text {
+        [rest @ .., '=', '='] | [rest @ .., '='] if text.len().is_multiple_of(4) => rest,
+        _ => text,
+    };
+    let mut out = Vec::with_capacity(unpadded.len() * 3 / 4);
+    for chunk in unpadded.chunks(4) {
+        let mut n = 0u32;
+        for (i, c) in chunk.iter().enumerate() {
+            let digit = chars.iter().position(|b| *b as char == *c)? as u32;
+            n |= digit << (18 - 6 * i);
+        }
+        match chunk.len() {
+            1 => return None,
+            len => out.extend_from_slice(&n.to_be_bytes()[1..len]),
+        }
+    }
+    Some(out)
+}
+
+impl JsonValue {
+    /// Decodes a base64 string into bytes, with or without padding. Returns None for other
+    /// values or malformed base64.
+    pub fn as_base64_bytes(&self, alphabet: Base64Alphabet) -> Option<Vec<u8>> {
+        decode(self.as_string()?, alphabet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_round_trips() {
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+        for (bytes, text) in [
+            (&b""[..], ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"\xfb\xff", "+/8="),
+        ] {
+            assert_eq!(encode(bytes, Base64Alphabet::Standard), text);
+            assert_eq!(
+                decode(&chars(text), Base64Alphabet::Standard).as_deref(),
+                Some(bytes)
+            );
+        }
+        assert_eq!(encode(b"\xfb\xff", Base64Alphabet::UrlSafe), "-_8=");
+        assert_eq!(
+            decode(&chars("-_8"), Base64Alphabet::UrlSafe).as_deref(),
+            Some(&b"\xfb\xff"[..])
+        );
+        assert_eq!(decode(&chars("-_8"), Base64Alphabet::Standard), None);
+        assert_eq!(decode(&chars("Zm9vY"), Base64Alphabet::Standard), None);
+        assert_eq!(decode(&chars("Zg="), Base64Alphabet::Standard), None);
+
+        let value = JsonValue::String(chars("aGk/Pz8="));
+        assert_eq!(
+            value.as_base64_bytes(Base64Alphabet::Standard).as_deref(),
+            Some(&b"hi???"[..])
+        );
+        assert_eq!(value.as_base64_bytes(Base64Alphabet::UrlSafe), None);
+        assert_eq!(
+            JsonValue::Null.as_base64_bytes(Base64Alphabet::Standard),
+            None
+        );
+    }
+
+    #[test]
+    fn malformed_base64_is_rejected() {
+        let decode = |s: &str, alphabet| decode(&s.chars().collect::<Vec<_>>(), alphabet);
+        for bad in [
+            "Z", "Zm9vY", "Zg=", "Z===", "====", "Zg==Zg==", "Zm=v", "=Zm9", "Zm9v=", "Zm9v\n",
+            "Zm 9v", "Zm9é",
+        ] {
+            assert_eq!(decode(bad, Base64Alphabet::Standard), None, "{:?}", bad);
+            assert_eq!(decode(bad, Base64Alphabet::UrlSafe), None, "{:?}", bad);
+        }
+
+        // Each alphabet rejects the other's last two characters, alone or mixed.
+        for mixed in ["+_8=", "-/8=", "+/8-"] {
+            assert_eq!(decode(mixed, Base64Alphabet::Standard), None, "{:?}", mixed);
+            assert_eq!(decode(mixed, Base64Alphabet::UrlSafe), None, "{:?}", mixed);
+        }
+        assert_eq!(decode("+/8=", Base64Alphabet::UrlSafe), None);
+        assert_eq!(decode("-_8=", Base64Alphabet::Standard), None);
+
+        assert_eq!(
+            decode("", Base64Alphabet::Standard).as_deref(),
+            Some(&b""[..])
+        );
+        assert_eq!(
+            decode("Zg", Base64Alphabet::Standard).as_deref(),
+            Some(&b"f"[..])
+        );
+        assert_eq!(
+            JsonValue::Array(vec![]).as_base64_bytes(Base64Alphabet::Standard),
+            None
+        );
+    }
+}
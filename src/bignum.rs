@@ -0,0 +1,158 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::json::{Decimal, JsonValue, NumberValue};
+use core::convert::TryInto;
+use num_bigint::{BigInt, BigUint, Sign};
+
+/// The most digits a converted big integer may have, so a short exponent such as `1e999999999`
+/// cannot make a conversion allocate without bound.
+pub const MAX_BIG_INTEGER_DIGITS: usize = 10_000;
+
+/// Returns the magnitude of an integral decimal, or None if it has a fraction or more than
+/// `MAX_BIG_INTEGER_DIGITS` digits.
+fn magnitude(decimal: &Decimal) -> Option<BigUint> {
+    if decimal.digits.is_empty() {
+        return Some(BigUint::default());
+    }
+    if decimal.point > MAX_BIG_INTEGER_DIGITS as i64 {
+        return None;
+    }
+    let zeros: u32 = decimal
+        .point
+        .checked_sub(decimal.digits.len() as i64)?
+        .try_into()
+        .ok()?;
+    let digits = BigUint::parse_bytes(&decimal.digits, 10)?;
+    Some(digits * BigUint::from(10u32).pow(zeros))
+}
+
+fn to_bigint(decimal: &Decimal) -> Option<BigInt> {
+    let sign = if decimal.negative {
+        Sign::Minus
+    } else {
+        Sign::Plus
+    };
+    Some(BigInt::from_biguint(sign, magnitude(decimal)?))
+}
+
+fn to_biguint(decimal: &Decimal) -> Option<BigUint> {
+    if decimal.negative {
+        None
+    } else {
+        magnitude(decimal)
+    }
+}
+
+impl NumberValue {
+    /// Returns the number as a big integer, or None if it is not an integer or has more than
+    /// `MAX_BIG_INTEGER_DIGITS` digits.
+    pub fn to_bigint(self) -> Option<BigInt> {
+        to_bigint(&Decimal::from_number(&self))
+    }
+
+    /// Returns the number as an unsigned big integer, or None if it is negative, not an
+    /// integer or has more than `MAX_BIG_INTEGER_DIGITS` digits.
+    pub fn to_biguint(self) -> Option<BigUint> {
+        to_biguint(&Decimal::from_number(&self))
+    }
+}
+
+impl JsonValue {
+    /// Returns a number or big number as a big integer, or None if it is not an integer or has
+    /// more than `MAX_BIG_INTEGER_DIGITS` digits.
+    ///
+    /// Integers too long for `NumberValue` are kept when parsing with
+    /// `NumberOverflow::Raw`.
+    pub fn to_bigint(&self) -> Option<BigInt> {
+        to_bigint(&self.decimal()?)
+    }
+
+    /// Returns a number or big number as an unsigned big integer, or None if it is negative,
+    /// not an integer or has more than `MAX_BIG_INTEGER_DIGITS` digits.
+    pub fn to_biguint(&self) -> Option<BigUint> {
+        to_biguint(&self.decimal()?)
+    }
+}
+
+impl From<BigUint> for JsonValue {
+    /// Builds a `Number` if the integer fits, and a `BigNumber` otherwise.
+    fn from(n: BigUint) -> Self {
+        BigInt::from(n).into()
+    }
+}
+
+impl From<BigInt> for JsonValue {
+    /// Builds a `Number` if the integer fits, and a `BigNumber` otherwise.
+    fn from(n: BigInt) -> Self {
+        match n.magnitude().try_into() {
+            Ok(integer) => JsonValue::Number(NumberValue {
+                integer,
+                fraction: 0,
+                fraction_length: 0,
+                exponent: 0,
+                negative: n.sign() == Sign::Minus,
+            }),
+            Err(_) => JsonValue::BigNumber(Vec::from(n.to_str_radix(10))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_parser::{parse_json_with_options, NumberOverflow, ParserOptions};
+
+    #[test]
+    fn numbers_convert_to_big_integers() {
+        let num: NumberValue = "12.5e3".parse().unwrap();
+        assert_eq!(num.to_bigint(), Some(BigInt::from(12500)));
+        assert_eq!(num.to_biguint(), Some(BigUint::from(12500u32)));
+
+        let num: NumberValue = "-1200e-2".parse().unwrap();
+        assert_eq!(num.to_bigint(), Some(BigInt::from(-12)));
+        assert_eq!(num.to_biguint(), None);
+
+        let num: NumberValue = "1.5".parse().unwrap();
+        assert_eq!(num.to_bigint(), None);
+        assert_eq!(
+            NumberValue::default().to_biguint(),
+            Some(BigUint::default())
+        );
+    }
+
+    #[test]
+    fn big_numbers_round_trip() {
+        let wei = "1000000000000000000000000000000000000000000";
+        let options = ParserOptions::default().number_overflow(NumberOverflow::Raw);
+        let value = parse_json_with_options(wei, options).unwrap();
+        let expected = BigUint::parse_bytes(wei.as_bytes(), 10).unwrap();
+        assert_eq!(value.to_biguint(), Some(expected.clone()));
+        assert_eq!(JsonValue::from(expected), value);
+
+        assert_eq!(
+            JsonValue::from(BigInt::from(-7)),
+            JsonValue::Number("-7".parse().unwrap())
+        );
+        assert_eq!(JsonValue::String(Vec::new()).to_bigint(), None);
+    }
+
+    #[test]
+    fn huge_exponents_are_refused() {
+        use crate::json_parser::parse_json;
+
+        assert_eq!(parse_json("1e999999999").unwrap().to_biguint(), None);
+        assert_eq!(parse_json("-1e999999999").unwrap().to_bigint(), None);
+        assert_eq!(
+            parse_json("0e999999999").unwrap().to_biguint(),
+            Some(BigUint::default())
+        );
+
+        let limit = parse_json("1e9999").unwrap().to_biguint().unwrap();
+        assert_eq!(limit.to_str_radix(10).len(), MAX_BIG_INTEGER_DIGITS);
+        assert_eq!(parse_json("1e10000").unwrap().to_biguint(), None);
+    }
+}
@@ -0,0 +1,588 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use crate::base64::{self, Base64Alphabet};
+use crate::hex;
+use crate::json::{utf16_lossy, Decimal, JsonObject, JsonValue, NumberValue};
+use crate::time::{parse_rfc3339, MS_PER_DAY};
+use core::convert::{TryFrom, TryInto};
+
+/// Nesting depth at which `from_bson` gives up, matching the parser's default limit.
+const MAX_DEPTH: usize = 100;
+
+/// The error returned by `to_bson` and `from_bson`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BsonError {
+    /// Only objects can be encoded as a BSON document.
+    NotAnObject,
+    /// A key contains a NUL character, which BSON cannot represent.
+    InvalidKey,
+    /// A document is larger than BSON's 2 GiB limit.
+    TooLarge,
+    /// The input ends in the middle of a document or a length is inconsistent.
+    UnexpectedEnd,
+    /// The input continues after the document.
+    TrailingBytes,
+    /// A string is not valid UTF-8.
+    InvalidUtf8,
+    /// The element type has no mapping, such as a DBPointer. The type byte is included.
+    Unsupported(u8),
+    /// Documents are nested too deeply.
+    TooDeep,
+}
+
+/// Converts days since 1970-01-01 to a (year, month, day) date.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (yoe + era * 400 + (month <= 2) as i64, month, day)
+}
+
+/// Formats milliseconds since the epoch as `YYYY-MM-DDTHH:MM:SS.sssZ`, for years 1970 to 9999.
+fn format_date(ms: i64) -> Option<String> {
+    let (year, month, day) = civil_from_days(ms.div_euclid(MS_PER_DAY));
+    if !(1970..=9999).contains(&year) {
+        return None;
+    }
+    let time = ms.rem_euclid(MS_PER_DAY);
+    Some(format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        time / 3_600_000,
+        time / 60_000 % 60,
+        time / 1000 % 60,
+        time % 1000
+    ))
+}
+
+fn key(name: &str) -> Vec<char> {
+    name.chars().collect()
+}
+
+fn string(text: &str) -> JsonValue {
+    JsonValue::String(text.chars().collect())
+}
+
+fn number(val: i64) -> JsonValue {
+    JsonValue::Number(NumberValue {
+        integer: val.unsigned_abs() as u128,
+        negative: val < 0,
+        ..Default::default()
+    })
+}
+
+/// Wraps `value` in a single-member object, the shape Extended JSON uses for BSON types.
+fn wrap(name: &str, value: JsonValue) -> JsonValue {
+    JsonValue::Object(vec![(key(name), value)])
+}
+
+/// Decodes a decimal128 into `{"$numberDecimal": "..."}`.
+fn decimal128(bytes: [u8; 16]) -> JsonValue {
+    let low = u64::from_le_bytes(bytes[..8].try_into().unwrap());
+    let high = u64::from_le_bytes(bytes[8..].try_into().unwrap());
+    let sign = if high >> 63 == 1 { "-" } else { "" };
+    let text = match (high >> 58) & 0x1f {
+        0x1f => String::from("NaN"),
+        0x1e => format!("{}Infinity", sign),
+        _ => {
+            let (exponent, coefficient) = if (high >> 61) & 3 == 3 {
+                // Coefficients in this form exceed the maximum and are treated as zero.
+                ((high >> 47) & 0x3fff, 0)
+            } else {
+                (
+                    (high >> 49) & 0x3fff,
+                    ((high & 0x1_ffff_ffff_ffff) as u128) << 64 | low as u128,
+                )
+            };
+            format!("{}{}E{}", sign, coefficient, exponent as i64 - 6176)
+        }
+    };
+    wrap("$numberDecimal", JsonValue::String(text.chars().collect()))
+}
+
+/// Encodes a finite decimal number as decimal128, or returns None if it needs rounding.
+fn to_decimal128(text: &[char]) -> Option<[u8; 16]> {
+    let text: String = text.iter().collect();
+    let number = crate::json_parser::parse_json(&text.replace('E', "e")).ok()?;
+    let decimal = number.decimal()?;
+    let Decimal {
+        negative,
+        digits,
+        point,
+    } = decimal;
+    if digits.len() > 34 {
+        return None;
+    }
+    let coefficient = digits
+        .iter()
+        .fold(0u128, |acc, d| acc * 10 + (d - b'0') as u128);
+    let exponent: u64 = (point - digits.len() as i64 + 6176).try_into().ok()?;
+    if exponent > 0x2fff {
+        return None;
+    }
+    let high = (negative as u64) << 63 | exponent << 49 | (coefficient >> 64) as u64;
+    let mut bytes = [0; 16];
+    bytes[..8].copy_from_slice(&(coefficient as u64).to_le_bytes());
+    bytes[8..].copy_from_slice(&high.to_le_bytes());
+    Some(bytes)
+}
+
+struct Writer {
+    buffer: Vec<u8>,
+}
+
+impl Writer {
+    fn cstring(&mut self, text: &[char]) -> Result<(), BsonError> {
+        if text.contains(&'\0') {
+            return Err(BsonError::InvalidKey);
+        }
+        self.buffer.extend(text.iter().collect::<String>().bytes());
+        self.buffer.push(0);
+        Ok(())
+    }
+
+    fn string(&mut self, text: &str) -> Result<(), BsonError> {
+        let len: i32 = (text.len() + 1)
+            .try_into()
+            .map_err(|_| BsonError::TooLarge)?;
+        self.buffer.extend_from_slice(&len.to_le_bytes());
+        self.buffer.extend_from_slice(text.as_bytes());
+        self.buffer.push(0);
+        Ok(())
+    }
+
+    fn document<'a>(
+        &mut self,
+        members: impl Iterator<Item = (Vec<char>, &'a JsonValue)>,
+    ) -> Result<(), BsonError> {
+        let start = self.buffer.len();
+        self.buffer.extend_from_slice(&[0; 4]);
+        for (name, value) in members {
+            let type_index = self.buffer.len();
+            self.buffer.push(0);
+            self.cstring(&name)?;
+            self.buffer[type_index] = self.element(value)?;
+        }
+        self.buffer.push(0);
+        let len: i32 = (self.buffer.len() - start)
+            .try_into()
+            .map_err(|_| BsonError::TooLarge)?;
+        self.buffer[start..start + 4].copy_from_slice(&len.to_le_bytes());
+        Ok(())
+    }
+
+    /// Recognizes the Extended JSON forms produced by `from_bson`.
+    fn extended(&mut self, obj: &JsonObject) -> Result<Option<u8>, BsonError> {
+        let (name, value) = match obj.as_slice() {
+            [(name, value)] => (name.iter().collect::<String>(), value),
+            _ => return Ok(None),
+        };
+        let member = |key: &str| {
+            value
+                .as_object()?
+                .iter()
+                .find(|(k, _)| k.iter().copied().eq(key.chars()))
+                .map(|(_, v)| v)
+        };
+        let text = value.as_string();
+        Ok(Some(match name.as_str() {
+            "$oid" => match text.and_then(hex::decode).filter(|id| id.len() == 12) {
+                Some(id) => {
+                    self.buffer.extend_from_slice(&id);
+                    0x07
+                }
+                None => return Ok(None),
+            },
+            "$date" => {
+                let ms = match text {
+                    Some(text) => parse_rfc3339(text),
+                    None => member("$numberLong")
+                        .and_then(JsonValue::as_string)
+                        .and_then(|t| t.iter().collect::<String>().parse().ok()),
+                };
+                match ms {
+                    Some(ms) => {
+                        self.buffer.extend_from_slice(&ms.to_le_bytes());
+                        0x09
+                    }
+                    None => return Ok(None),
+                }
+            }
+            "$binary" => {
+                let data = member("base64")
+                    .and_then(JsonValue::as_string)
+                    .and_then(|data| base64::decode(data, Base64Alphabet::Standard));
+                let subtype = member("subType")
+                    .and_then(JsonValue::as_string)
+                    .and_then(hex::decode);
+                match (data, subtype.as_deref()) {
+                    (Some(data), Some([subtype])) => {
+                        let len: i32 = data.len().try_into().map_err(|_| BsonError::TooLarge)?;
+                        self.buffer.extend_from_slice(&len.to_le_bytes());
+                        self.buffer.push(*subtype);
+                        self.buffer.extend_from_slice(&data);
+                        0x05
+                    }
+                    _ => return Ok(None),
+                }
+            }
+            "$timestamp" => {
+                let part = |key| member(key)?.as_number()?.to_u64()?.try_into().ok();
+                match (part("t"), part("i")) {
+                    (Some(t), Some(i)) => {
+                        let t: u32 = t;
+                        let i: u32 = i;
+                        self.buffer.extend_from_slice(&i.to_le_bytes());
+                        self.buffer.extend_from_slice(&t.to_le_bytes());
+                        0x11
+                    }
+                    _ => return Ok(None),
+                }
+            }
+            "$regularExpression" => {
+                let part = |key| member(key).and_then(JsonValue::as_string);
+                match (part("pattern"), part("options")) {
+                    (Some(pattern), Some(options)) => {
+                        self.cstring(pattern)?;
+                        self.cstring(options)?;
+                        0x0b
+                    }
+                    _ => return Ok(None),
+                }
+            }
+            "$numberDecimal" => match text.and_then(to_decimal128) {
+                Some(bytes) => {
+                    self.buffer.extend_from_slice(&bytes);
+                    0x13
+                }
+                None => return Ok(None),
+            },
+            "$numberDouble" => {
+                let val = match text.map(|t| t.iter().collect::<String>()).as_deref() {
+                    Some("NaN") => f64::NAN,
+                    Some("Infinity") => f64::INFINITY,
+                    Some("-Infinity") => f64::NEG_INFINITY,
+                    _ => return Ok(None),
+                };
+                self.buffer.extend_from_slice(&val.to_le_bytes());
+                0x01
+            }
+            "$minKey" => 0xff,
+            "$maxKey" => 0x7f,
+            _ => return Ok(None),
+        }))
+    }
+
+    /// Writes the value of an element and returns its type byte.
+    fn element(&mut self, value: &JsonValue) -> Result<u8, BsonError> {
+        Ok(match value {
+            JsonValue::Null => 0x0a,
+            JsonValue::Boolean(b) => {
+                self.buffer.push(*b as u8);
+                0x08
+            }
+            JsonValue::Number(n) => {
+                // -0 is kept as a double so its sign survives.
+                let integer = n.to_i64().filter(|val| !(n.negative && *val == 0));
+                match integer {
+                    Some(val) => match i32::try_from(val) {
+                        Ok(val) => {
+                            self.buffer.extend_from_slice(&val.to_le_bytes());
+                            0x10
+                        }
+                        Err(_) => {
+                            self.buffer.extend_from_slice(&val.to_le_bytes());
+                            0x12
+                        }
+                    },
+                    None => {
+                        self.buffer.extend_from_slice(&n.to_f64().to_le_bytes());
+                        0x01
+                    }
+                }
+            }
+            JsonValue::BigNumber(text) => {
+                let val: f64 = core::str::from_utf8(text)
+                    .ok()
+                    .and_then(|t| t.parse().ok())
+                    .unwrap_or(f64::NAN);
+                self.buffer.extend_from_slice(&val.to_le_bytes());
+                0x01
+            }
+            JsonValue::NonFinite(n) => {
+                self.buffer.extend_from_slice(&n.to_f64().to_le_bytes());
+                0x01
+            }
+            JsonValue::String(s) => {
+                self.string(&s.iter().collect::<String>())?;
+                0x02
+            }
+            JsonValue::Utf16String(s) => {
+                self.string(&utf16_lossy(s).into_iter().collect::<String>())?;
+                0x02
+            }
+            JsonValue::Array(arr) => {
+                let names = (0..arr.len()).map(|i| i.to_string().chars().collect());
+                self.document(names.zip(arr.iter()))?;
+                0x04
+            }
+            JsonValue::Object(obj) => match self.extended(obj)? {
+                Some(ty) => ty,
+                None => {
+                    self.document(obj.iter().map(|(k, v)| (k.clone(), v)))?;
+                    0x03
+                }
+            },
+        })
+    }
+}
+
+/// Encodes an object as a BSON document.
+///
+/// Integers that fit `i32` or `i64` become BSON integers and other numbers become doubles.
+/// Single-member objects in the Extended JSON forms produced by `from_bson`, such as
+/// `{"$oid": "..."}` or `{"$date": "..."}`, become the matching BSON types.
+pub fn to_bson(value: &JsonValue) -> Result<Vec<u8>, BsonError> {
+    let obj = value.as_object().ok_or(BsonError::NotAnObject)?;
+    let mut writer = Writer { buffer: Vec::new() };
+    writer.document(obj.iter().map(|(k, v)| (k.clone(), v)))?;
+    Ok(writer.buffer)
+}
+
+struct Reader<'a> {
+    input: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], BsonError> {
+        if self.input.len() < len {
+            return Err(BsonError::UnexpectedEnd);
+        }
+        let (head, rest) = self.input.split_at(len);
+        self.input = rest;
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], BsonError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn len(&mut self) -> Result<usize, BsonError> {
+        i32::from_le_bytes(self.take_array()?)
+            .try_into()
+            .map_err(|_| BsonError::UnexpectedEnd)
+    }
+
+    fn cstring(&mut self) -> Result<String, BsonError> {
+        let len = self
+            .input
+            .iter()
+            .position(|b| *b == 0)
+            .ok_or(BsonError::UnexpectedEnd)?;
+        let bytes = self.take(len + 1)?;
+        String::from_utf8(bytes[..len].to_vec()).map_err(|_| BsonError::InvalidUtf8)
+    }
+
+    fn string(&mut self) -> Result<String, BsonError> {
+        let len = self.len()?;
+        let bytes = self.take(len)?;
+        match bytes.split_last() {
+            Some((0, text)) => String::from_utf8(text.to_vec()).map_err(|_| BsonError::InvalidUtf8),
+            _ => Err(BsonError::UnexpectedEnd),
+        }
+    }
+
+    fn document(&mut self, depth: usize) -> Result<JsonObject, BsonError> {
+        if depth > MAX_DEPTH {
+            return Err(BsonError::TooDeep);
+        }
+        let len = self.len()?;
+        let body = len.checked_sub(5).ok_or(BsonError::UnexpectedEnd)?;
+        let mut reader = Reader {
+            input: self.take(body)?,
+        };
+        if self.take_array::<1>()? != [0] {
+            return Err(BsonError::UnexpectedEnd);
+        }
+        let mut obj = Vec::new();
+        while !reader.input.is_empty() {
+            let ty = reader.take_array::<1>()?[0];
+            let name = reader.cstring()?;
+            obj.push((name.chars().collect(), reader.element(ty, depth)?));
+        }
+        Ok(obj)
+    }
+
+    fn element(&mut self, ty: u8, depth: usize) -> Result<JsonValue, BsonError> {
+        Ok(match ty {
+            0x01 => {
+                let val = f64::from_le_bytes(self.take_array()?);
+                match NumberValue::from_f64(val) {
+                    Some(n) => JsonValue::Number(n),
+                    None if val.is_nan() => wrap("$numberDouble", string("NaN")),
+                    None if val > 0.0 => wrap("$numberDouble", string("Infinity")),
+                    None => wrap("$numberDouble", string("-Infinity")),
+                }
+            }
+            0x02 => JsonValue::String(self.string()?.chars().collect()),
+            0x03 => JsonValue::Object(self.document(depth + 1)?),
+            0x04 => JsonValue::Array(
+                self.document(depth + 1)?
+                    .into_iter()
+                    .map(|(_, v)| v)
+                    .collect(),
+            ),
+            0x05 => {
+                let len = self.len()?;
+                let subtype = self.take_array::<1>()?[0];
+                let data = self.take(len)?;
+                wrap(
+                    "$binary",
+                    JsonValue::Object(vec![
+                        (
+                            key("base64"),
+                            string(&base64::encode(data, Base64Alphabet::Standard)),
+                        ),
+                        (key("subType"), string(&hex::encode(&[subtype]))),
+                    ]),
+                )
+            }
+            0x06 | 0x0a => JsonValue::Null,
+            0x07 => wrap("$oid", string(&hex::encode(self.take(12)?))),
+            0x08 => JsonValue::Boolean(self.take_array::<1>()? != [0]),
+            0x09 => {
+                let ms = i64::from_le_bytes(self.take_array()?);
+                match format_date(ms) {
+                    Some(date) => wrap("$date", string(&date)),
+                    None => wrap("$date", wrap("$numberLong", string(&ms.to_string()))),
+                }
+            }
+            0x0b => {
+                let pattern = self.cstring()?;
+                let options = self.cstring()?;
+                wrap(
+                    "$regularExpression",
+                    JsonValue::Object(vec![
+                        (key("pattern"), string(&pattern)),
+                        (key("options"), string(&options)),
+                    ]),
+                )
+            }
+            0x0d => wrap("$code", string(&self.string()?)),
+            0x0e => wrap("$symbol", string(&self.string()?)),
+            0x10 => number(i32::from_le_bytes(self.take_array()?) as i64),
+            0x11 => {
+                let i = u32::from_le_bytes(self.take_array()?);
+                let t = u32::from_le_bytes(self.take_array()?);
+                wrap(
+                    "$timestamp",
+                    JsonValue::Object(vec![
+                        (key("t"), number(t as i64)),
+                        (key("i"), number(i as i64)),
+                    ]),
+                )
+            }
+            0x12 => number(i64::from_le_bytes(self.take_array()?)),
+            0x13 => decimal128(self.take_array()?),
+            0x7f => wrap("$maxKey", number(1)),
+            0xff => wrap("$minKey", number(1)),
+            _ => return Err(BsonError::Unsupported(ty)),
+        })
+    }
+}
+
+/// Decodes a BSON document into an object.
+///
+/// Types without a JSON equivalent use MongoDB's relaxed Extended JSON, for example
+/// `{"$oid": "..."}` for object ids, `{"$date": "2020-01-01T00:00:00.000Z"}` for dates,
+/// `{"$binary": {"base64": "...", "subType": "00"}}` for binary data and
+/// `{"$numberDecimal": "..."}` for decimal128. Undefined becomes `null`.
+pub fn from_bson(input: &[u8]) -> Result<JsonValue, BsonError> {
+    let mut reader = Reader { input };
+    let obj = reader.document(0)?;
+    if !reader.input.is_empty() {
+        return Err(BsonError::TrailingBytes);
+    }
+    Ok(JsonValue::Object(obj))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_parser::parse_json;
+
+    #[test]
+    fn bson_round_trips() {
+        let value = parse_json(
+            r#"{"a":1,"b":-5000000000,"c":1.5,"d":"x","e":[true,null,{"f":[]}],"g":-0}"#,
+        )
+        .unwrap();
+        let bytes = to_bson(&value).unwrap();
+        assert_eq!(&bytes[4..11], &[0x10, b'a', 0, 1, 0, 0, 0]);
+        assert_eq!(from_bson(&bytes), Ok(value));
+        assert_eq!(to_bson(&JsonValue::Null), Err(BsonError::NotAnObject));
+    }
+
+    #[test]
+    fn bson_types_map_to_extended_json() {
+        let value = parse_json(
+            r#"{"id":{"$oid":"5f0c8a7e1c9d440000a1b2c3"},"at":{"$date":"2020-02-29T12:34:56.789Z"},"old":{"$date":{"$numberLong":"-1"}},"bin":{"$binary":{"base64":"AQID/w==","subType":"80"}},"ts":{"$timestamp":{"t":7,"i":2}},"re":{"$regularExpression":{"pattern":"^a","options":"i"}},"dec":{"$numberDecimal":"-12345E-2"},"nan":{"$numberDouble":"NaN"},"min":{"$minKey":1}}"#,
+        )
+        .unwrap();
+        let bytes = to_bson(&value).unwrap();
+        let types: Vec<u8> = {
+            let mut reader = Reader { input: &bytes[4..] };
+            let mut types = Vec::new();
+            while reader.input.len() > 1 {
+                let ty = reader.take_array::<1>().unwrap()[0];
+                reader.cstring().unwrap();
+                reader.element(ty, 0).unwrap();
+                types.push(ty);
+            }
+            types
+        };
+        assert_eq!(
+            types,
+            [0x07, 0x09, 0x09, 0x05, 0x11, 0x0b, 0x13, 0x01, 0xff]
+        );
+        assert_eq!(from_bson(&bytes), Ok(value));
+        assert_eq!(format_date(0).as_deref(), Some("1970-01-01T00:00:00.000Z"));
+        assert_eq!(
+            parse_rfc3339(&key("2000-03-01T00:00:00Z")),
+            Some(951_868_800_000)
+        );
+    }
+
+    #[test]
+    fn bson_rejects_bad_input() {
+        assert_eq!(from_bson(&[5, 0, 0]), Err(BsonError::UnexpectedEnd));
+        assert_eq!(
+            from_bson(&[5, 0, 0, 0, 0, 1]),
+            Err(BsonError::TrailingBytes)
+        );
+        assert_eq!(
+            from_bson(&[8, 0, 0, 0, 0x0c, b'a', 0, 0]),
+            Err(BsonError::Unsupported(0x0c))
+        );
+        assert_eq!(
+            from_bson(&[12, 0, 0, 0, 0x02, b'a', 0, 0xff, 0xff, 0xff, 0xff, 0]),
+            Err(BsonError::UnexpectedEnd)
+        );
+    }
+}
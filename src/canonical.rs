@@ -0,0 +1,199 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec::Vec};
+
+use crate::json::JsonValue;
+use core::fmt::Write as _;
+
+/// Writes a number the way ECMAScript's `Number.prototype.toString` does, or returns None if
+/// it is not finite.
+fn write_number(buffer: &mut Vec<u8>, val: f64) -> Option<()> {
+    if !val.is_finite() {
+        return None;
+    }
+    if val == 0.0 {
+        buffer.push(b'0');
+        return Some(());
+    }
+    if val < 0.0 {
+        buffer.push(b'-');
+    }
+    // `{:e}` gives the shortest digits that round-trip, which is what ECMAScript uses too.
+    let text = format!("{:e}", val.abs());
+    let (mantissa, exponent) = text.split_once('e')?;
+    let digits: Vec<u8> = mantissa.bytes().filter(|b| *b != b'.').collect();
+    let k = digits.len() as i32;
+    let n = exponent.parse::<i32>().ok()? + 1;
+    if k <= n && n <= 21 {
+        buffer.extend_from_slice(&digits);
+        buffer.resize(buffer.len() + (n - k) as usize, b'0');
+    } else if 0 < n && n <= 21 {
+        buffer.extend_from_slice(&digits[..n as usize]);
+        buffer.push(b'.');
+        buffer.extend_from_slice(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        buffer.extend_from_slice(b"0.");
+        buffer.resize(buffer.len() + (-n) as usize, b'0');
+        buffer.extend_from_slice(&digits);
+    } else {
+        buffer.push(digits[0]);
+        if k > 1 {
+            buffer.push(b'.');
+            buffer.extend_from_slice(&digits[1..]);
+        }
+        buffer.extend_from_slice(
+            format!("e{}{}", if n > 0 { "+" } else { "-" }, (n - 1).abs()).as_bytes(),
+        );
+    }
+    Some(())
+}
+
+/// Writes a string with only the escapes JCS allows.
+fn write_string(buffer: &mut Vec<u8>, chars: &[char]) {
+    let mut text = String::with_capacity(chars.len() + 2);
+    text.push('"');
+    for ch in chars {
+        match ch {
+            '\x08' => text.push_str(r#"\b"#),
+            '\x0c' => text.push_str(r#"\f"#),
+            '\n' => text.push_str(r#"\n"#),
+            '\r' => text.push_str(r#"\r"#),
+            '\t' => text.push_str(r#"\t"#),
+            '"' => text.push_str(r#"\""#),
+            '\\' => text.push_str(r#"\\"#),
+            '\0'..='\x1f' => {
+                let _ = write!(text, "\\u{:04x}", *ch as u32);
+            }
+            _ => text.push(*ch),
+        }
+    }
+    text.push('"');
+    buffer.extend_from_slice(text.as_bytes());
+}
+
+fn write_value(buffer: &mut Vec<u8>, value: &JsonValue) -> Option<()> {
+    match value {
+        JsonValue::Null => buffer.extend_from_slice(b"null"),
+        JsonValue::Boolean(true) => buffer.extend_from_slice(b"true"),
+        JsonValue::Boolean(false) => buffer.extend_from_slice(b"false"),
+        JsonValue::Number(num) => write_number(buffer, num.to_f64())?,
+        JsonValue::BigNumber(num) => {
+            let val = core::str::from_utf8(num).ok()?.parse().ok()?;
+            write_number(buffer, val)?
+        }
+        JsonValue::NonFinite(_) | JsonValue::Utf16String(_) => return None,
+        JsonValue::String(s) => write_string(buffer, s),
+        JsonValue::Array(arr) => {
+            buffer.push(b'[');
+            for (i, val) in arr.iter().enumerate() {
+                if i > 0 {
+                    buffer.push(b',');
+                }
+                write_value(buffer, val)?;
+            }
+            buffer.push(b']');
+        }
+        JsonValue::Object(obj) => {
+            // The last member wins for repeated keys, as in `stable_hash`: sorting later members
+            // first within a key lets `dedup_by` keep them.
+            let mut members = Vec::with_capacity(obj.len());
+            for (i, (key, val)) in obj.iter().enumerate() {
+                let mut utf16 = Vec::with_capacity(key.len());
+                let mut units = [0; 2];
+                for ch in key {
+                    utf16.extend_from_slice(ch.encode_utf16(&mut units));
+                }
+                members.push((utf16, i, key, val));
+            }
+            members.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+            members.dedup_by(|a, b| a.0 == b.0);
+
+            buffer.push(b'{');
+            for (i, (_, _, key, val)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    buffer.push(b',');
+                }
+                write_string(buffer, key);
+                buffer.push(b':');
+                write_value(buffer, val)?;
+            }
+            buffer.push(b'}');
+        }
+    }
+    Some(())
+}
+
+impl JsonValue {
+    /// Serializes the document as canonical JSON following RFC 8785 (JCS), so equal documents
+    /// produce identical bytes that can be hashed or signed.
+    ///
+    /// Object members are sorted by the UTF-16 code units of their keys, keeping the last
+    /// member for repeated keys. Numbers are written as the nearest `f64` in ECMAScript
+    /// format, and strings escape only what JSON requires. Returns None if a number is too
+    /// large to be represented as a finite `f64`.
+    pub fn serialize_canonical(&self) -> Option<Vec<u8>> {
+        let mut buffer = Vec::new();
+        write_value(&mut buffer, self)?;
+        Some(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json::JsonValue;
+    use crate::json_parser::parse_json;
+
+    fn canonical(value: &JsonValue) -> String {
+        String::from_utf8(value.serialize_canonical().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn canonical_sorts_and_escapes() {
+        let value =
+            parse_json(r#"{"b": [true, null, "x"], "a": {"d": 1, "c": 2}, "b": 3}"#).unwrap();
+        assert_eq!(canonical(&value), r#"{"a":{"c":2,"d":1},"b":3}"#);
+
+        // U+1F600 sorts before U+FB33 in UTF-16 even though it is the larger code point.
+        let value = JsonValue::Object(vec![
+            (vec!['\u{fb33}'], JsonValue::Null),
+            (vec!['\u{1f600}'], JsonValue::Null),
+            (
+                vec!['\r'],
+                JsonValue::String(vec!['\u{1}', '\u{7f}', '"', 'é']),
+            ),
+        ]);
+        assert_eq!(
+            canonical(&value),
+            "{\"\\r\":\"\\u0001\u{7f}\\\"é\",\"\u{1f600}\":null,\"\u{fb33}\":null}"
+        );
+    }
+
+    #[test]
+    fn canonical_handles_wide_objects() {
+        // Every key appears twice, and the second member must win.
+        let keys = 30_000;
+        let member = |i: usize, val| (format!("k{}", i % keys).chars().collect(), val);
+        let obj: Vec<_> = (0..2 * keys)
+            .map(|i| member(i, JsonValue::Boolean(i >= keys)))
+            .collect();
+        let text = canonical(&JsonValue::Object(obj));
+        assert_eq!(text.matches("true").count(), keys);
+        assert!(!text.contains("false"));
+        assert!(text.starts_with(r#"{"k0":true,"k1":true,"k10":true"#));
+    }
+
+    #[test]
+    fn canonical_formats_numbers() {
+        let value = parse_json(
+            "[0, -0, 1, -1.5, 1e2, 1e21, 1e20, 0.000001, 1e-7, 123.456e-10, 4.50, 9007199254740993, 333333333.33333329, 1E30, 295147905179352830000]",
+        )
+        .unwrap();
+        assert_eq!(
+            canonical(&value),
+            "[0,0,1,-1.5,100,1e+21,100000000000000000000,0.000001,1e-7,1.23456e-8,4.5,9007199254740992,333333333.3333333,1e+30,295147905179352830000]"
+        );
+        assert_eq!(parse_json("1e400").unwrap().serialize_canonical(), None);
+    }
+}
@@ -0,0 +1,143 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::json::JsonValue;
+
+/// Naming convention for object keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    /// `camelCase`
+    Camel,
+    /// `snake_case`
+    Snake,
+    /// `kebab-case`
+    Kebab,
+}
+
+/// Splits a key into words at `_`, `-` and case boundaries, so that `userID`, `user_id`
+/// and `user-id` all produce `user` and `ID`/`id`.
+fn split_words(key: &[char]) -> Vec<Vec<char>> {
+    let mut words: Vec<Vec<char>> = Vec::new();
+    let mut word = Vec::new();
+    for (i, ch) in key.iter().enumerate() {
+        if *ch == '_' || *ch == '-' {
+            if !word.is_empty() {
+                words.push(core::mem::take(&mut word));
+            }
+            continue;
+        }
+        if ch.is_uppercase() {
+            if let Some(prev) = word.last() {
+                let next_is_lower = key.get(i + 1).is_some_and(|c| c.is_lowercase());
+                if !prev.is_uppercase() || next_is_lower {
+                    words.push(core::mem::take(&mut word));
+                }
+            }
+        }
+        word.push(*ch);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+impl Case {
+    /// Converts a single key to this naming convention.
+    pub fn convert(self, key: &[char]) -> Vec<char> {
+        let mut res = Vec::with_capacity(key.len());
+        for (i, word) in split_words(key).iter().enumerate() {
+            match self {
+                Case::Camel => {
+                    for (j, ch) in word.iter().enumerate() {
+                        if i > 0 && j == 0 {
+                            res.extend(ch.to_uppercase());
+                        } else {
+                            res.extend(ch.to_lowercase());
+                        }
+                    }
+                }
+                Case::Snake | Case::Kebab => {
+                    if i > 0 {
+                        res.push(if self == Case::Snake { '_' } else { '-' });
+                    }
+                    res.extend(word.iter().flat_map(|ch| ch.to_lowercase()));
+                }
+            }
+        }
+        res
+    }
+}
+
+impl JsonValue {
+    /// Renames object keys at every level of the document to the given naming convention.
+    pub fn rename_keys(&mut self, case: Case) {
+        match self {
+            JsonValue::Object(obj) => {
+                for (key, val) in obj.iter_mut() {
+                    *key = case.convert(key);
+                    val.rename_keys(case);
+                }
+            }
+            JsonValue::Array(arr) => {
+                for val in arr.iter_mut() {
+                    val.rename_keys(case);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_parser::parse_json;
+
+    fn convert(case: Case, key: &str) -> std::string::String {
+        case.convert(&key.chars().collect::<Vec<_>>())
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn case_convert_works() {
+        assert_eq!(convert(Case::Snake, "userId"), "user_id");
+        assert_eq!(convert(Case::Snake, "HTTPServerError"), "http_server_error");
+        assert_eq!(convert(Case::Snake, "kebab-case-key"), "kebab_case_key");
+        assert_eq!(convert(Case::Snake, "address2Line"), "address2_line");
+        assert_eq!(convert(Case::Kebab, "userId"), "user-id");
+        assert_eq!(convert(Case::Kebab, "snake__case_"), "snake-case");
+        assert_eq!(convert(Case::Camel, "user_id"), "userId");
+        assert_eq!(convert(Case::Camel, "UserID"), "userId");
+        assert_eq!(
+            convert(Case::Camel, "already-camelCase"),
+            "alreadyCamelCase"
+        );
+        assert_eq!(convert(Case::Camel, ""), "");
+    }
+
+    #[test]
+    fn rename_keys_works() {
+        let mut value =
+            parse_json(r#"{"firstName":"a","itemList":[{"itemId":1}],"meta":{"createdAt":null}}"#)
+                .unwrap();
+        value.rename_keys(Case::Snake);
+        assert_eq!(
+            value,
+            parse_json(
+                r#"{"first_name":"a","item_list":[{"item_id":1}],"meta":{"created_at":null}}"#
+            )
+            .unwrap()
+        );
+        value.rename_keys(Case::Camel);
+        assert_eq!(
+            value,
+            parse_json(r#"{"firstName":"a","itemList":[{"itemId":1}],"meta":{"createdAt":null}}"#)
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,327 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec::Vec};
+
+use crate::json::JsonValue;
+use crate::schema::infer_schema;
+use crate::traits::JsonObjectExt;
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where",
+    "while", "abstract", "become", "box", "do", "final", "gen", "macro", "override", "priv", "try",
+    "typeof", "unsized", "virtual", "yield",
+];
+
+/// Splits a JSON name into lowercase words at non-alphanumeric characters and case
+/// boundaries.
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut prev_lower = false;
+    for ch in name.chars() {
+        let boundary = !ch.is_alphanumeric() || (ch.is_uppercase() && prev_lower);
+        if boundary && !word.is_empty() {
+            words.push(core::mem::take(&mut word));
+        }
+        if ch.is_alphanumeric() {
+            word.extend(ch.to_lowercase());
+        }
+        prev_lower = ch.is_lowercase() || ch.is_numeric();
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn snake_case(name: &str) -> String {
+    let ident = words(name).join("_");
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_numeric()) {
+        format!("field_{}", ident)
+    } else if KEYWORDS.contains(&ident.as_str()) {
+        format!("r#{}", ident)
+    } else {
+        ident
+    }
+}
+
+fn pascal_case(name: &str) -> String {
+    let ident: String = words(name)
+        .iter()
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|c| c.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+        })
+        .collect();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_numeric()) {
+        format!("Type{}", ident)
+    } else {
+        ident
+    }
+}
+
+fn derive(extra: &str) -> String {
+    format!(
+        "#[derive(Debug, Clone, PartialEq, {}::lite_json::ToJson, ::lite_json::FromJson)]",
+        extra
+    )
+}
+
+fn string_of(value: &JsonValue) -> Option<String> {
+    value.as_string().map(|s| s.iter().collect())
+}
+
+struct Generator<'a> {
+    root: &'a JsonValue,
+    names: Vec<String>,
+    output: String,
+}
+
+impl Generator<'_> {
+    /// Reserves a type name based on `hint`, adding a number if it is taken.
+    fn claim(&mut self, hint: &str) -> String {
+        let base = pascal_case(hint);
+        let mut name = base.clone();
+        let mut n = 2;
+        while self.names.contains(&name) {
+            name = format!("{}{}", base, n);
+            n += 1;
+        }
+        self.names.push(name.clone());
+        name
+    }
+
+    /// Returns the Rust type for `schema`, generating any structs and enums it needs.
+    fn type_of(&mut self, schema: &JsonValue, hint: &str) -> String {
+        if let Ok(reference) = schema.get_str("$ref") {
+            let reference: String = reference.iter().collect();
+            let target = [("#/definitions/", "definitions"), ("#/$defs/", "$defs")]
+                .iter()
+                .find_map(|(prefix, section)| {
+                    let name = reference.strip_prefix(prefix)?;
+                    Some((name, self.root.member(section).ok()?.member(name).ok()?))
+                });
+            return match target {
+                Some((name, target)) => {
+                    let ty = pascal_case(name);
+                    if !self.names.contains(&ty) {
+                        self.define(target, name);
+                    }
+                    ty
+                }
+                None => "::lite_json::JsonValue".into(),
+            };
+        }
+
+        let types: Vec<String> = match schema.member("type") {
+            Ok(JsonValue::Array(arr)) => arr.iter().filter_map(string_of).collect(),
+            Ok(ty) => string_of(ty).into_iter().collect(),
+            Err(_) => Vec::new(),
+        };
+        let nullable = types.iter().any(|t| t == "null");
+        let others: Vec<&String> = types.iter().filter(|t| *t != "null").collect();
+        let ty = match others.as_slice() {
+            [_] if schema.get_array("enum").is_ok() => self.define(schema, hint),
+            [ty] => match ty.as_str() {
+                "object" => self.define(schema, hint),
+                "array" => match schema.member("items") {
+                    Ok(items) => format!("Vec<{}>", self.type_of(items, &format!("{}Item", hint))),
+                    Err(_) => "Vec<::lite_json::JsonValue>".into(),
+                },
+                "string" => "String".into(),
+                "integer" => "i64".into(),
+                "number" => "f64".into(),
+                "boolean" => "bool".into(),
+                _ => "::lite_json::JsonValue".into(),
+            },
+            _ => "::lite_json::JsonValue".into(),
+        };
+        if nullable {
+            format!("Option<{}>", ty)
+        } else {
+            ty
+        }
+    }
+
+    /// Generates a struct, map or enum for an object or enum schema and returns its type.
+    fn define(&mut self, schema: &JsonValue, hint: &str) -> String {
+        let hint = schema
+            .get_str("title")
+            .map(|t| t.iter().collect())
+            .unwrap_or_else(|_| String::from(hint));
+
+        if let Ok(values) = schema.get_array("enum") {
+            let values: Option<Vec<String>> = values.iter().map(string_of).collect();
+            return match values {
+                Some(values) => {
+                    let name = self.claim(&hint);
+                    let mut code = format!("{}\npub enum {} {{\n", derive("Eq, "), name);
+                    for value in &values {
+                        code += &format!(
+                            "    #[json(rename = {:?})]\n    {},\n",
+                            value,
+                            pascal_case(value)
+                        );
+                    }
+                    self.output += &code;
+                    self.output += "}\n\n";
+                    name
+                }
+                None => "::lite_json::JsonValue".into(),
+            };
+        }
+
+        let properties = match schema.get_object("properties") {
+            Ok(properties) => properties,
+            Err(_) => {
+                return match schema.member("additionalProperties") {
+                    Ok(JsonValue::Boolean(_)) | Err(_) => {
+                        "::std::collections::BTreeMap<String, ::lite_json::JsonValue>".into()
+                    }
+                    Ok(values) => format!(
+                        "::std::collections::BTreeMap<String, {}>",
+                        self.type_of(values, &format!("{}Value", hint))
+                    ),
+                };
+            }
+        };
+        let required: Vec<String> = schema
+            .get_array("required")
+            .map(|r| r.iter().filter_map(string_of).collect())
+            .unwrap_or_default();
+
+        let name = self.claim(&hint);
+        let mut fields = String::new();
+        for (key, value) in properties {
+            let key: String = key.iter().collect();
+            let mut ty = self.type_of(value, &key);
+            if !required.contains(&key) && !ty.starts_with("Option<") {
+                ty = format!("Option<{}>", ty);
+            }
+            let field = snake_case(&key);
+            if field.trim_start_matches("r#") != key {
+                fields += &format!("    #[json(rename = {:?})]\n", key);
+            }
+            fields += &format!("    pub {}: {},\n", field, ty);
+        }
+        self.output += &format!("{}\npub struct {} {{\n{}}}\n\n", derive(""), name, fields);
+        name
+    }
+}
+
+/// Generates Rust source for the types described by a JSON Schema, meant to be called from a
+/// build script and pulled in with `include!`.
+///
+/// Objects with `properties` become structs deriving `ToJson` and `FromJson`, with members
+/// outside `required` wrapped in `Option`. Other objects become maps, string `enum`s become
+/// enums and `$ref`s into `definitions` or `$defs` become named types. Anything else, such as
+/// a value allowing several types, becomes `JsonValue`. A root that is not an object schema
+/// gets a type alias named `root`.
+pub fn generate_types(schema: &JsonValue, root: &str) -> String {
+    let mut generator = Generator {
+        root: schema,
+        names: Vec::new(),
+        output: String::new(),
+    };
+    let ty = generator.type_of(schema, root);
+    if ty != pascal_case(root) {
+        let name = generator.claim(root);
+        generator.output += &format!("pub type {} = {};\n\n", name, ty);
+    }
+    generator.output.truncate(generator.output.trim_end().len());
+    generator.output.push('\n');
+    generator.output
+}
+
+/// Like `generate_types`, but derives the types from an example document with
+/// `infer_schema`.
+pub fn generate_types_from_example(example: &JsonValue, root: &str) -> String {
+    generate_types(&infer_schema(example), root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_parser::parse_json;
+
+    #[test]
+    fn generates_from_schema() {
+        let schema = parse_json(
+            r##"{
+                "type": "object",
+                "required": ["id", "type", "items"],
+                "properties": {
+                    "id": {"type": "integer"},
+                    "type": {"type": "string", "enum": ["a-b", "c"]},
+                    "displayName": {"type": ["string", "null"]},
+                    "items": {"type": "array", "items": {"$ref": "#/definitions/item"}},
+                    "extra": {"type": "object", "additionalProperties": {"type": "number"}}
+                },
+                "definitions": {
+                    "item": {"type": "object", "properties": {"ok": {"type": "boolean"}}}
+                }
+            }"##,
+        )
+        .unwrap();
+        assert_eq!(
+            generate_types(&schema, "order"),
+            r#"#[derive(Debug, Clone, PartialEq, Eq, ::lite_json::ToJson, ::lite_json::FromJson)]
+pub enum Type {
+    #[json(rename = "a-b")]
+    AB,
+    #[json(rename = "c")]
+    C,
+}
+
+#[derive(Debug, Clone, PartialEq, ::lite_json::ToJson, ::lite_json::FromJson)]
+pub struct Item {
+    pub ok: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, ::lite_json::ToJson, ::lite_json::FromJson)]
+pub struct Order {
+    pub id: i64,
+    pub r#type: Type,
+    #[json(rename = "displayName")]
+    pub display_name: Option<String>,
+    pub items: Vec<Item>,
+    pub extra: Option<::std::collections::BTreeMap<String, f64>>,
+}
+"#
+        );
+    }
+
+    #[test]
+    fn generates_from_example() {
+        let example = parse_json(
+            r#"[{"id":1,"tags":["x"],"geo":{"lat":1.5},"note":null},{"id":2,"tags":[],"note":"n"}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            generate_types_from_example(&example, "feed"),
+            r#"#[derive(Debug, Clone, PartialEq, ::lite_json::ToJson, ::lite_json::FromJson)]
+pub struct Geo {
+    pub lat: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, ::lite_json::ToJson, ::lite_json::FromJson)]
+pub struct FeedItem {
+    pub id: i64,
+    pub tags: Vec<String>,
+    pub geo: Option<Geo>,
+    pub note: Option<String>,
+}
+
+pub type Feed = Vec<FeedItem>;
+"#
+        );
+    }
+}
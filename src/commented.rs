@@ -0,0 +1,401 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+use crate::json::{push_new_line_indent, push_string, JsonValue};
+use crate::json_parser::{skip_bom, Comment, Value, WhitespaceChar};
+use crate::parse_error::{ParseError, ParseErrorKind};
+use crate::pointer::{push_index, push_key};
+use crate::traits::Serialize;
+use lite_parser::{
+    impls::{SimpleError, SimplePosition},
+    parser::{Parser, ParserContext, ParserOptions, ParserState},
+    traits::Input,
+};
+
+/// A comment kept by `parse_json_with_comments`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonComment {
+    /// The text between the `//` and the end of the line, or between `/*` and `*/`.
+    pub text: Vec<char>,
+    /// Whether this is a `/* */` block comment rather than a `//` line comment.
+    pub block: bool,
+}
+
+/// A document parsed together with its comments, so that they survive programmatic edits.
+///
+/// Comments are attached to the value that follows them and keyed by its JSON Pointer, so
+/// they stay with a member when other members are added, removed or changed. Serializing
+/// writes each comment on its own line before its member.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommentedJson {
+    pub value: JsonValue,
+    /// Comments before a value, keyed by its pointer. Comments before the root are keyed by `""`.
+    pub comments: BTreeMap<String, Vec<JsonComment>>,
+    /// Comments after the last item of an array or object, keyed by the container's pointer.
+    pub trailing: BTreeMap<String, Vec<JsonComment>>,
+    /// Comments after the root value.
+    pub footer: Vec<JsonComment>,
+}
+
+type Parsed<T> = Result<(T, SimplePosition), SimpleError>;
+
+struct Collector<'a> {
+    input: &'a str,
+    doc: CommentedJson,
+}
+
+impl<'a> Collector<'a> {
+    /// Skips whitespace, adding the comments in it to `comments`.
+    fn skip(
+        &self,
+        mut pos: SimplePosition,
+        comments: &mut Vec<JsonComment>,
+        context: &ParserContext,
+    ) -> Parsed<()> {
+        loop {
+            if let Ok((_, next)) =
+                <WhitespaceChar as Parser<&str>>::parse(&self.input, pos, context)
+            {
+                pos = next;
+            } else if let Ok(('/', after)) = self.input.next(pos) {
+                let block = matches!(self.input.next(after), Ok(('*', _)));
+                let (text, next) = <Comment as Parser<&str>>::parse(&self.input, pos, context)?;
+                comments.push(JsonComment { text, block });
+                pos = next;
+            } else {
+                return Ok(((), pos));
+            }
+        }
+    }
+
+    /// Parses the value at `pos`, preceded by `comments`, recording them under `path`.
+    fn value(
+        &mut self,
+        pos: SimplePosition,
+        path: &mut String,
+        comments: Vec<JsonComment>,
+        context: &ParserContext,
+    ) -> Parsed<JsonValue> {
+        if !comments.is_empty() {
+            self.doc.comments.insert(path.clone(), comments);
+        }
+        match self.input.next(pos) {
+            Ok(('{', next)) => {
+                let context = &context.nest(&self.input, pos)?;
+                let (obj, next) = self.object(next, path, context)?;
+                Ok((JsonValue::Object(obj), next))
+            }
+            Ok(('[', next)) => {
+                let context = &context.nest(&self.input, pos)?;
+                let (arr, next) = self.array(next, path, context)?;
+                Ok((JsonValue::Array(arr), next))
+            }
+            _ => <Value as Parser<&str>>::parse(&self.input, pos, context),
+        }
+    }
+
+    fn object(
+        &mut self,
+        mut pos: SimplePosition,
+        path: &mut String,
+        context: &ParserContext,
+    ) -> Parsed<Vec<(Vec<char>, JsonValue)>> {
+        let mut output = Vec::new();
+        let mut comments = Vec::new();
+        loop {
+            pos = self.skip(pos, &mut comments, context)?.1;
+            if output.is_empty() {
+                if let Ok(('}', end)) = self.input.next(pos) {
+                    return Ok((output, self.finish(end, path, comments)));
+                }
+            }
+            let (key, next) =
+                <crate::json_parser::String as Parser<&str>>::parse(&self.input, pos, context)?;
+            pos = self.skip(next, &mut comments, context)?.1;
+            pos = match self.input.next(pos) {
+                Ok((':', next)) => next,
+                _ => return Err(self.input.error_at(pos, "Character")),
+            };
+            pos = self.skip(pos, &mut comments, context)?.1;
+
+            let len = path.len();
+            push_key(path, &key);
+            let (value, next) = self.value(pos, path, core::mem::take(&mut comments), context)?;
+            path.truncate(len);
+            output.push((key, value));
+
+            pos = self.skip(next, &mut comments, context)?.1;
+            match self.input.next(pos) {
+                Ok((',', next)) => pos = next,
+                Ok(('}', end)) => return Ok((output, self.finish(end, path, comments))),
+                _ => return Err(self.input.error_at(pos, "Object")),
+            }
+        }
+    }
+
+    fn array(
+        &mut self,
+        mut pos: SimplePosition,
+        path: &mut String,
+        context: &ParserContext,
+    ) -> Parsed<Vec<JsonValue>> {
+        let mut output = Vec::new();
+        let mut comments = Vec::new();
+        loop {
+            pos = self.skip(pos, &mut comments, context)?.1;
+            if output.is_empty() {
+                if let Ok((']', end)) = self.input.next(pos) {
+                    return Ok((output, self.finish(end, path, comments)));
+                }
+            }
+
+            let len = path.len();
+            push_index(path, output.len());
+            let (value, next) = self.value(pos, path, core::mem::take(&mut comments), context)?;
+            path.truncate(len);
+            output.push(value);
+
+            pos = self.skip(next, &mut comments, context)?.1;
+            match self.input.next(pos) {
+                Ok((',', next)) => pos = next,
+                Ok((']', end)) => return Ok((output, self.finish(end, path, comments))),
+                _ => return Err(self.input.error_at(pos, "Array")),
+            }
+        }
+    }
+
+    /// Records the comments before the closing bracket of the container at `path`.
+    fn finish(
+        &mut self,
+        end: SimplePosition,
+        path: &str,
+        comments: Vec<JsonComment>,
+    ) -> SimplePosition {
+        if !comments.is_empty() {
+            self.doc.trailing.insert(path.into(), comments);
+        }
+        end
+    }
+}
+
+/// Parses JSON that may contain `//` and `/* */` comments, keeping the comments so they can be
+/// written back by `CommentedJson::serialize`.
+pub fn parse_json_with_comments(input: &str) -> Result<CommentedJson, ParseError> {
+    let options = ParserOptions::default();
+    let state = ParserState::default();
+    let context = ParserContext::new(&options, &state);
+    let start = skip_bom(input);
+    let mut collector = Collector {
+        input,
+        doc: CommentedJson {
+            value: JsonValue::Null,
+            comments: BTreeMap::new(),
+            trailing: BTreeMap::new(),
+            footer: Vec::new(),
+        },
+    };
+    let result = (|| {
+        let mut comments = Vec::new();
+        let (_, pos) = collector.skip(start, &mut comments, &context)?;
+        let (value, pos) = collector.value(pos, &mut String::new(), comments, &context)?;
+        let mut footer = Vec::new();
+        let (_, pos) = collector.skip(pos, &mut footer, &context)?;
+        Ok((value, footer, pos))
+    })();
+    let (value, footer, pos) = result.map_err(|e| ParseError::from_simple(input, e))?;
+    if !input.is_end(pos) {
+        return Err(ParseError::new(
+            input,
+            pos,
+            ParseErrorKind::TrailingCharacters,
+        ));
+    }
+    collector.doc.value = value;
+    collector.doc.footer = footer;
+    Ok(collector.doc)
+}
+
+fn push_comment(buffer: &mut Vec<u8>, comment: &JsonComment, indent: u32) {
+    let text: String = comment.text.iter().collect();
+    if comment.block {
+        buffer.extend_from_slice(b"/*");
+        buffer.extend_from_slice(text.as_bytes());
+        buffer.extend_from_slice(b"*/");
+    } else {
+        buffer.extend_from_slice(b"//");
+        buffer.extend_from_slice(text.as_bytes());
+        // Compact output has no line breaks of its own to end the comment.
+        if indent == 0 {
+            buffer.push(b'\n');
+        }
+    }
+}
+
+impl CommentedJson {
+    /// Writes the comments before the value at `path`, each followed by a line break.
+    fn write_comments(&self, path: &str, buffer: &mut Vec<u8>, indent: u32, level: u32) {
+        for comment in self.comments.get(path).into_iter().flatten() {
+            push_comment(buffer, comment, indent);
+            push_new_line_indent(buffer, indent, level);
+        }
+    }
+
+    /// Writes the comments before the closing bracket of the container at `path`.
+    fn write_trailing(&self, path: &str, buffer: &mut Vec<u8>, indent: u32, level: u32) {
+        for comment in self.trailing.get(path).into_iter().flatten() {
+            push_new_line_indent(buffer, indent, level + 1);
+            push_comment(buffer, comment, indent);
+        }
+    }
+
+    fn write_value(
+        &self,
+        value: &JsonValue,
+        path: &mut String,
+        buffer: &mut Vec<u8>,
+        indent: u32,
+        level: u32,
+    ) {
+        match value {
+            JsonValue::Object(obj) if !obj.is_empty() || self.trailing.contains_key(&*path) => {
+                buffer.push(b'{');
+                for (i, (key, val)) in obj.iter().enumerate() {
+                    if i > 0 {
+                        buffer.push(b',');
+                    }
+                    push_new_line_indent(buffer, indent, level + 1);
+                    let len = path.len();
+                    push_key(path, key);
+                    self.write_comments(path, buffer, indent, level + 1);
+                    push_string(buffer, key);
+                    buffer.push(b':');
+                    if indent > 0 {
+                        buffer.push(b' ');
+                    }
+                    self.write_value(val, path, buffer, indent, level + 1);
+                    path.truncate(len);
+                }
+                self.write_trailing(path, buffer, indent, level);
+                push_new_line_indent(buffer, indent, level);
+                buffer.push(b'}');
+            }
+            JsonValue::Array(arr) if !arr.is_empty() || self.trailing.contains_key(&*path) => {
+                buffer.push(b'[');
+                for (i, val) in arr.iter().enumerate() {
+                    if i > 0 {
+                        buffer.push(b',');
+                    }
+                    push_new_line_indent(buffer, indent, level + 1);
+                    let len = path.len();
+                    push_index(path, i);
+                    self.write_comments(path, buffer, indent, level + 1);
+                    self.write_value(val, path, buffer, indent, level + 1);
+                    path.truncate(len);
+                }
+                self.write_trailing(path, buffer, indent, level);
+                push_new_line_indent(buffer, indent, level);
+                buffer.push(b']');
+            }
+            _ => value.serialize_to(buffer, indent, level),
+        }
+    }
+}
+
+impl Serialize for CommentedJson {
+    fn serialize_to(&self, buffer: &mut Vec<u8>, indent: u32, level: u32) {
+        let mut path = String::new();
+        self.write_comments(&path, buffer, indent, level);
+        self.write_value(&self.value, &mut path, buffer, indent, level);
+        for comment in &self.footer {
+            push_new_line_indent(buffer, indent, level);
+            push_comment(buffer, comment, indent);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_parser::parse_json;
+    use crate::traits::JsonObjectExt;
+
+    const CONFIG: &str = r#"// Service settings
+{
+  // Port to listen on
+  "port": 80,
+  /* Enables TLS */
+  "tls": false,
+  "hosts": [
+    // primary
+    "a.example"
+    // more go here
+  ]
+}
+// end"#;
+
+    #[test]
+    fn comments_survive_edits() {
+        let mut doc = parse_json_with_comments(CONFIG).unwrap();
+        assert_eq!(
+            doc.value,
+            parse_json(r#"{"port": 80, "tls": false, "hosts": ["a.example"]}"#).unwrap()
+        );
+        assert_eq!(doc.comments.len(), 4);
+        assert!(doc.comments["/tls"][0].block);
+        assert_eq!(doc.trailing["/hosts"].len(), 1);
+        assert_eq!(doc.footer.len(), 1);
+
+        assert_eq!(String::from_utf8(doc.format(2)).unwrap(), CONFIG);
+
+        if let JsonValue::Object(obj) = &mut doc.value {
+            obj.remove(0);
+            obj.push((vec!['p', 'o', 'r', 't'], JsonValue::Boolean(true)));
+        }
+        let edited = String::from_utf8(doc.serialize()).unwrap();
+        assert_eq!(
+            edited,
+            "// Service settings\n{/* Enables TLS */\"tls\":false,\"hosts\":[// primary\n\
+             \"a.example\"// more go here\n],// Port to listen on\n\"port\":true}// end\n"
+        );
+        let reparsed = parse_json_with_comments(&edited).unwrap();
+        assert_eq!(reparsed, doc);
+        assert_eq!(reparsed.value.get_bool("port"), Ok(true));
+
+        assert_eq!(
+            parse_json_with_comments("[1] /* open").unwrap_err().kind,
+            ParseErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn malformed_input_is_rejected() {
+        use ParseErrorKind::*;
+        let error = |input: &str| {
+            let e = parse_json_with_comments(input).unwrap_err();
+            (e.kind, e.offset, e.line, e.column)
+        };
+        assert_eq!(error(""), (UnexpectedEof, 0, 1, 1));
+        assert_eq!(error("/* only */"), (UnexpectedEof, 10, 1, 11));
+        assert_eq!(error("/ 1"), (UnexpectedToken, 0, 1, 1));
+        assert_eq!(error("[1,]"), (UnexpectedToken, 3, 1, 4));
+        assert_eq!(error(r#"{"a": 1,}"#), (UnexpectedToken, 8, 1, 9));
+        // A comment does not stand in for a comma or colon.
+        assert_eq!(error("[1 /* c */ 2]"), (UnexpectedToken, 11, 1, 12));
+        assert_eq!(error(r#"{"a" /* c */ 1}"#), (UnexpectedToken, 13, 1, 14));
+        assert_eq!(error("[1] /* a */ /"), (UnexpectedToken, 12, 1, 13));
+        assert_eq!(error("[1] // c\n x"), (TrailingCharacters, 10, 2, 2));
+        // Offsets are in bytes and columns in characters.
+        assert_eq!(error("[1] /* é */ *"), (TrailingCharacters, 13, 1, 13));
+        assert_eq!(error("// é\n{\"a\": tru}"), (UnexpectedToken, 12, 2, 7));
+        assert_eq!(error(&"[".repeat(101)), (DepthLimitExceeded, 100, 1, 101));
+
+        let doc = parse_json_with_comments("{/* c */}").unwrap();
+        assert_eq!(doc.value, JsonValue::Object(vec![]));
+        assert!(doc.trailing[""][0].block);
+    }
+}
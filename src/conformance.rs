@@ -0,0 +1,389 @@
+//! Runs the parser against test_parsing cases from JSONTestSuite
+//! (https://github.com/nst/JSONTestSuite, MIT licensed). Files starting with `y_` must be
+//! accepted, `n_` must be rejected and `i_` may go either way but must not panic.
+
+use crate::json_parser::{parse_json_with_options, parse_slice, ParserOptions};
+
+const CASES: &[(&str, &[u8])] = &[
+    ("y_array_arraysWithSpaces.json", b"[[]   ]"),
+    ("y_array_empty-string.json", br#"[""]"#),
+    ("y_array_empty.json", b"[]"),
+    ("y_array_ending_with_newline.json", br#"["a"]"#),
+    ("y_array_false.json", b"[false]"),
+    ("y_array_heterogeneous.json", br#"[null, 1, "1", {}]"#),
+    ("y_array_null.json", b"[null]"),
+    ("y_array_with_1_and_newline.json", b"[1\n]"),
+    ("y_array_with_leading_space.json", b" [1]"),
+    ("y_array_with_several_null.json", b"[1,null,null,null,2]"),
+    ("y_array_with_trailing_space.json", b"[2] "),
+    ("y_number.json", b"[123e65]"),
+    ("y_number_0e+1.json", b"[0e+1]"),
+    ("y_number_0e1.json", b"[0e1]"),
+    ("y_number_after_space.json", b"[ 4]"),
+    (
+        "y_number_double_close_to_zero.json",
+        b"[-0.000000000000000000000000000000000000000000000000000000000000000000000000000001]",
+    ),
+    ("y_number_int_with_exp.json", b"[20e1]"),
+    ("y_number_minus_zero.json", b"[-0]"),
+    ("y_number_negative_int.json", b"[-123]"),
+    ("y_number_negative_one.json", b"[-1]"),
+    ("y_number_negative_zero.json", b"[-0]"),
+    ("y_number_real_capital_e.json", b"[1E22]"),
+    ("y_number_real_capital_e_neg_exp.json", b"[1E-2]"),
+    ("y_number_real_capital_e_pos_exp.json", b"[1E+2]"),
+    ("y_number_real_exponent.json", b"[123e45]"),
+    ("y_number_real_fraction_exponent.json", b"[123.456e78]"),
+    ("y_number_real_neg_exp.json", b"[1e-2]"),
+    ("y_number_real_pos_exponent.json", b"[1e+2]"),
+    ("y_number_simple_int.json", b"[123]"),
+    ("y_number_simple_real.json", b"[123.456789]"),
+    ("y_object.json", br#"{"asd":"sdf", "dfg":"fgh"}"#),
+    ("y_object_basic.json", br#"{"asd":"sdf"}"#),
+    ("y_object_duplicated_key.json", br#"{"a":"b","a":"c"}"#),
+    ("y_object_duplicated_key_and_value.json", br#"{"a":"b","a":"b"}"#),
+    ("y_object_empty.json", b"{}"),
+    ("y_object_empty_key.json", br#"{"":0}"#),
+    ("y_object_escaped_null_in_key.json", br#"{"foo\u0000bar": 42}"#),
+    ("y_object_extreme_numbers.json", br#"{ "min": -1.0e+28, "max": 1.0e+28 }"#),
+    (
+        "y_object_long_strings.json",
+        br#"{"x":[{"id": "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx"}], "id": "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx"}"#,
+    ),
+    ("y_object_simple.json", br#"{"a":[]}"#),
+    (
+        "y_object_string_unicode.json",
+        br#"{"title":"\u041f\u043e\u043b\u0442\u043e\u0440\u0430 \u0417\u0435\u043c\u043b\u0435\u043a\u043e\u043f\u0430" }"#,
+    ),
+    ("y_object_with_newlines.json", b"{\n\"a\": \"b\"\n}"),
+    ("y_string_1_2_3_bytes_UTF-8_sequences.json", br#"["\u0060\u012a\u12AB"]"#),
+    ("y_string_accepted_surrogate_pair.json", br#"["\uD801\udc37"]"#),
+    ("y_string_accepted_surrogate_pairs.json", br#"["\ud83d\ude39\ud83d\udc8d"]"#),
+    ("y_string_allowed_escapes.json", br#"["\"\\\/\b\f\n\r\t"]"#),
+    ("y_string_backslash_and_u_escaped_zero.json", br#"["\\u0000"]"#),
+    ("y_string_backslash_doublequotes.json", br#"["\""]"#),
+    ("y_string_comments.json", br#"["a/*b*/c/*d//e"]"#),
+    ("y_string_double_escape_a.json", br#"["\\a"]"#),
+    ("y_string_double_escape_n.json", br#"["\\n"]"#),
+    ("y_string_escaped_control_character.json", br#"["\u0012"]"#),
+    ("y_string_escaped_noncharacter.json", br#"["\uFFFF"]"#),
+    ("y_string_in_array.json", br#"["asd"]"#),
+    ("y_string_in_array_with_leading_space.json", br#"[ "asd"]"#),
+    ("y_string_last_surrogates_1_and_2.json", br#"["\uDBFF\uDFFF"]"#),
+    ("y_string_nbsp_uescaped.json", br#"["new\u00A0line"]"#),
+    ("y_string_nonCharacterInUTF-8_U+10FFFF.json", b"[\"\xf4\x8f\xbf\xbf\"]"),
+    ("y_string_nonCharacterInUTF-8_U+FFFF.json", b"[\"\xef\xbf\xbf\"]"),
+    ("y_string_null_escape.json", br#"["\u0000"]"#),
+    ("y_string_one-byte-utf-8.json", br#"["\u002c"]"#),
+    ("y_string_pi.json", b"[\"\xcf\x80\"]"),
+    ("y_string_reservedCharacterInUTF-8_U+1BFFF.json", b"[\"\xf0\x9b\xbf\xbf\"]"),
+    ("y_string_simple_ascii.json", br#"["asd "]"#),
+    ("y_string_space.json", br#"" ""#),
+    ("y_string_surrogates_U+1D11E_MUSICAL_SYMBOL_G_CLEF.json", br#"["\uD834\uDd1e"]"#),
+    ("y_string_three-byte-utf-8.json", br#"["\u0821"]"#),
+    ("y_string_two-byte-utf-8.json", br#"["\u0123"]"#),
+    ("y_string_u+2028_line_sep.json", b"[\"\xe2\x80\xa8\"]"),
+    ("y_string_u+2029_par_sep.json", b"[\"\xe2\x80\xa9\"]"),
+    ("y_string_uEscape.json", br#"["\u0061\u30af\u30EA\u30b9"]"#),
+    ("y_string_uescaped_newline.json", br#"["new\u000Aline"]"#),
+    ("y_string_unescaped_char_delete.json", b"[\"\x7f\"]"),
+    ("y_string_unicode.json", br#"["\uA66D"]"#),
+    ("y_string_unicodeEscapedBackslash.json", br#"["\u005C"]"#),
+    ("y_string_unicode_2.json", b"[\"\xe2\x8d\x82\xe3\x88\xb4\xe2\x8d\x82\"]"),
+    ("y_string_unicode_U+10FFFE_nonchar.json", br#"["\uDBFF\uDFFE"]"#),
+    ("y_string_unicode_U+1FFFE_nonchar.json", br#"["\uD83F\uDFFE"]"#),
+    ("y_string_unicode_U+200B_ZERO_WIDTH_SPACE.json", br#"["\u200B"]"#),
+    ("y_string_unicode_U+2064_invisible_plus.json", br#"["\u2064"]"#),
+    ("y_string_unicode_U+FDD0_nonchar.json", br#"["\uFDD0"]"#),
+    ("y_string_unicode_U+FFFE_nonchar.json", br#"["\uFFFE"]"#),
+    ("y_string_unicode_escaped_double_quote.json", br#"["\u0022"]"#),
+    ("y_string_utf8.json", b"[\"\xe2\x82\xac\xf0\x9d\x84\x9e\"]"),
+    ("y_string_with_del_character.json", b"[\"a\x7fa\"]"),
+    ("y_structure_lonely_false.json", b"false"),
+    ("y_structure_lonely_int.json", b"42"),
+    ("y_structure_lonely_negative_real.json", b"-0.1"),
+    ("y_structure_lonely_null.json", b"null"),
+    ("y_structure_lonely_string.json", br#""asd""#),
+    ("y_structure_lonely_true.json", b"true"),
+    ("y_structure_string_empty.json", br#""""#),
+    ("y_structure_trailing_newline.json", b"[\"a\"]\n"),
+    ("y_structure_true_in_array.json", b"[true]"),
+    ("y_structure_whitespace_array.json", b" [] "),
+    ("n_array_1_true_without_comma.json", b"[1 true]"),
+    ("n_array_a_invalid_utf8.json", b"[a\xe5]"),
+    ("n_array_colon_instead_of_comma.json", br#"["": 1]"#),
+    ("n_array_comma_after_close.json", br#"[""],"#),
+    ("n_array_comma_and_number.json", b"[,1]"),
+    ("n_array_double_comma.json", b"[1,,2]"),
+    ("n_array_double_extra_comma.json", br#"["x",,]"#),
+    ("n_array_extra_close.json", br#"["x"]]"#),
+    ("n_array_extra_comma.json", br#"["",]"#),
+    ("n_array_incomplete.json", br#"["x""#),
+    ("n_array_incomplete_invalid_value.json", b"[x"),
+    ("n_array_inner_array_no_comma.json", b"[3[4]]"),
+    ("n_array_invalid_utf8.json", b"[\xff]"),
+    ("n_array_items_separated_by_semicolon.json", b"[1:2]"),
+    ("n_array_just_comma.json", b"[,]"),
+    ("n_array_just_minus.json", b"[-]"),
+    ("n_array_missing_value.json", br#"[   , ""]"#),
+    ("n_array_newlines_unclosed.json", b"[\"a\",\n4\n,1,"),
+    ("n_array_number_and_comma.json", b"[1,]"),
+    ("n_array_number_and_several_commas.json", b"[1,,]"),
+    ("n_array_spaces_vertical_tab_formfeed.json", b"[\"\x0ba\"\\f]"),
+    ("n_array_star_inside.json", b"[*]"),
+    ("n_array_unclosed.json", br#"["""#),
+    ("n_array_unclosed_trailing_comma.json", b"[1,"),
+    ("n_array_unclosed_with_new_lines.json", b"[1,\n1\n,1"),
+    ("n_array_unclosed_with_object_inside.json", b"[{}"),
+    ("n_incomplete_false.json", b"[fals]"),
+    ("n_incomplete_null.json", b"[nul]"),
+    ("n_incomplete_true.json", b"[tru]"),
+    ("n_multidigit_number_then_00.json", b"123\x00"),
+    ("n_number_++.json", b"[++1234]"),
+    ("n_number_+1.json", b"[+1]"),
+    ("n_number_+Inf.json", b"[+Inf]"),
+    ("n_number_-01.json", b"[-01]"),
+    ("n_number_-1.0..json", b"[-1.0.]"),
+    ("n_number_-2..json", b"[-2.]"),
+    ("n_number_-NaN.json", b"[-NaN]"),
+    ("n_number_.-1.json", b"[.-1]"),
+    ("n_number_.2e-3.json", b"[.2e-3]"),
+    ("n_number_0.1.2.json", b"[0.1.2]"),
+    ("n_number_0.3e+.json", b"[0.3e+]"),
+    ("n_number_0.3e.json", b"[0.3e]"),
+    ("n_number_0.e1.json", b"[0.e1]"),
+    ("n_number_0_capital_E+.json", b"[0E+]"),
+    ("n_number_0_capital_E.json", b"[0E]"),
+    ("n_number_0e+.json", b"[0e+]"),
+    ("n_number_0e.json", b"[0e]"),
+    ("n_number_1.0e+.json", b"[1.0e+]"),
+    ("n_number_1.0e-.json", b"[1.0e-]"),
+    ("n_number_1.0e.json", b"[1.0e]"),
+    ("n_number_1_000.json", b"[1 000.0]"),
+    ("n_number_1eE2.json", b"[1eE2]"),
+    ("n_number_2.e+3.json", b"[2.e+3]"),
+    ("n_number_2.e-3.json", b"[2.e-3]"),
+    ("n_number_2.e3.json", b"[2.e3]"),
+    ("n_number_9.e+.json", b"[9.e+]"),
+    ("n_number_Inf.json", b"[Inf]"),
+    ("n_number_NaN.json", b"[NaN]"),
+    ("n_number_U+FF11_fullwidth_digit_one.json", b"[\xef\xbc\x91]"),
+    ("n_number_expression.json", b"[1+2]"),
+    ("n_number_hex_1_digit.json", b"[0x1]"),
+    ("n_number_hex_2_digits.json", b"[0x42]"),
+    ("n_number_infinity.json", b"[Infinity]"),
+    ("n_number_invalid+-.json", b"[0e+-1]"),
+    ("n_number_invalid-negative-real.json", b"[-123.123foo]"),
+    ("n_number_minus_infinity.json", b"[-Infinity]"),
+    ("n_number_minus_sign_with_trailing_garbage.json", b"[-foo]"),
+    ("n_number_minus_space_1.json", b"[- 1]"),
+    ("n_number_neg_int_starting_with_zero.json", b"[-012]"),
+    ("n_number_neg_real_without_int_part.json", b"[-.123]"),
+    ("n_number_neg_with_garbage_at_end.json", b"[-1x]"),
+    ("n_number_real_garbage_after_e.json", b"[1ea]"),
+    ("n_number_real_without_fractional_part.json", b"[1.]"),
+    ("n_number_starting_with_dot.json", b"[.123]"),
+    ("n_number_with_alpha.json", b"[1.2a-3]"),
+    ("n_number_with_alpha_char.json", b"[1.8011670033376514H-308]"),
+    ("n_number_with_leading_zero.json", b"[012]"),
+    ("n_object_bad_value.json", br#"["x", truth]"#),
+    ("n_object_bracket_key.json", br#"{[: "x"}"#),
+    ("n_object_comma_instead_of_colon.json", br#"{"x", null}"#),
+    ("n_object_double_colon.json", br#"{"x"::"b"}"#),
+    ("n_object_emoji.json", b"{\xf0\x9f\x87\xa8\xf0\x9f\x87\xad}"),
+    ("n_object_garbage_at_end.json", br#"{"a":"a" 123}"#),
+    ("n_object_key_with_single_quotes.json", b"{key: 'value'}"),
+    ("n_object_missing_colon.json", br#"{"a" b}"#),
+    ("n_object_missing_key.json", br#"{:"b"}"#),
+    ("n_object_missing_semicolon.json", br#"{"a" "b"}"#),
+    ("n_object_missing_value.json", br#"{"a":"#),
+    ("n_object_no-colon.json", br#"{"a""#),
+    ("n_object_non_string_key.json", b"{1:1}"),
+    ("n_object_non_string_key_but_huge_number_instead.json", b"{9999E9999:1}"),
+    ("n_object_repeated_null_null.json", b"{null:null,null:null}"),
+    ("n_object_several_trailing_commas.json", br#"{"id":0,,,,,}"#),
+    ("n_object_single_quote.json", b"{'a':0}"),
+    ("n_object_trailing_comma.json", br#"{"id":0,}"#),
+    ("n_object_trailing_comment.json", br#"{"a":"b"}/**/"#),
+    ("n_object_trailing_comment_open.json", br#"{"a":"b"}/**//"#),
+    ("n_object_trailing_comment_slash_open.json", br#"{"a":"b"}//"#),
+    ("n_object_trailing_comment_slash_open_incomplete.json", br#"{"a":"b"}/"#),
+    ("n_object_two_commas_in_a_row.json", br#"{"a":"b",,"c":"d"}"#),
+    ("n_object_unquoted_key.json", br#"{a: "b"}"#),
+    ("n_object_unterminated-value.json", br#"{"a":"a"#),
+    ("n_object_with_single_string.json", br#"{ "foo" : "bar", "a" }"#),
+    ("n_object_with_trailing_garbage.json", br#"{"a":"b"}#"#),
+    ("n_single_space.json", b" "),
+    ("n_string_1_surrogate_then_escape.json", br#"["\uD800\"]"#),
+    ("n_string_1_surrogate_then_escape_u.json", br#"["\uD800\u"]"#),
+    ("n_string_1_surrogate_then_escape_u1.json", br#"["\uD800\u1"]"#),
+    ("n_string_1_surrogate_then_escape_u1x.json", br#"["\uD800\u1x"]"#),
+    ("n_string_accentuated_char_no_quotes.json", b"[\xc3\xa9]"),
+    ("n_string_backslash_00.json", b"[\"\\\x00\"]"),
+    ("n_string_escape_x.json", br#"["\x00"]"#),
+    ("n_string_escaped_backslash_bad.json", br#"["\\\"]"#),
+    ("n_string_escaped_ctrl_char_tab.json", b"[\"\\\t\"]"),
+    ("n_string_escaped_emoji.json", b"[\"\\\xf0\x9f\x8c\x80\"]"),
+    ("n_string_incomplete_escape.json", br#"["\"]"#),
+    ("n_string_incomplete_escaped_character.json", br#"["\u00A"]"#),
+    ("n_string_incomplete_surrogate.json", br#"["\uD834\uDd"]"#),
+    ("n_string_incomplete_surrogate_escape_invalid.json", br#"["\uD800\uD800\x"]"#),
+    ("n_string_invalid-utf-8-in-escape.json", b"[\"\\u\xe5\"]"),
+    ("n_string_invalid_backslash_esc.json", br#"["\a"]"#),
+    ("n_string_invalid_unicode_escape.json", br#"["\uqqqq"]"#),
+    ("n_string_invalid_utf8_after_escape.json", b"[\"\\\xe5\"]"),
+    ("n_string_leading_uescaped_thinspace.json", br#"[\u0020"asd"]"#),
+    ("n_string_no_quotes_with_bad_escape.json", br#"[\n]"#),
+    ("n_string_single_doublequote.json", br#"""#),
+    ("n_string_single_quote.json", b"['single quote']"),
+    ("n_string_single_string_no_double_quotes.json", b"abc"),
+    ("n_string_start_escape_unclosed.json", br#"["\"#),
+    ("n_string_unescaped_ctrl_char.json", b"[\"a\x00a\"]"),
+    ("n_string_unescaped_newline.json", b"[\"new\nline\"]"),
+    ("n_string_unescaped_tab.json", b"[\"\t\"]"),
+    ("n_string_unicode_CapitalU.json", br#""\UA66D""#),
+    ("n_string_with_trailing_garbage.json", br#"""x"#),
+    ("n_structure_U+2060_word_joined.json", b"[\xe2\x81\xa0]"),
+    ("n_structure_UTF8_BOM_no_data.json", b"\xef\xbb\xbf"),
+    ("n_structure_angle_bracket_..json", b"<.>"),
+    ("n_structure_angle_bracket_null.json", b"[<null>]"),
+    ("n_structure_array_trailing_garbage.json", b"[1]x"),
+    ("n_structure_array_with_extra_array_close.json", b"[1]]"),
+    ("n_structure_array_with_unclosed_string.json", br#"["asd]"#),
+    ("n_structure_ascii-unicode-identifier.json", b"a\xc3\xa5"),
+    ("n_structure_capitalized_True.json", b"[True]"),
+    ("n_structure_close_unopened_array.json", b"1]"),
+    ("n_structure_comma_instead_of_closing_brace.json", br#"{"x": true,"#),
+    ("n_structure_double_array.json", b"[][]"),
+    ("n_structure_end_array.json", b"]"),
+    ("n_structure_lone-open-bracket.json", b"["),
+    ("n_structure_no_data.json", b""),
+    ("n_structure_null-byte-outside-string.json", b"[\x00]"),
+    ("n_structure_number_with_trailing_garbage.json", b"2@"),
+    ("n_structure_object_followed_by_closing_object.json", b"{}}"),
+    ("n_structure_object_unclosed_no_value.json", br#"{"":"#),
+    ("n_structure_object_with_comment.json", br#"{"a":/*comment*/"b"}"#),
+    ("n_structure_object_with_trailing_garbage.json", br#"{"a": true} "x""#),
+    ("n_structure_open_array_apostrophe.json", b"['"),
+    ("n_structure_open_array_comma.json", b"[,"),
+    ("n_structure_open_array_open_object.json", b"[{"),
+    ("n_structure_open_array_open_string.json", br#"["a"#),
+    ("n_structure_open_array_string.json", br#"["a""#),
+    ("n_structure_open_object.json", b"{"),
+    ("n_structure_open_object_close_array.json", b"{]"),
+    ("n_structure_open_object_comma.json", b"{,"),
+    ("n_structure_open_object_open_array.json", b"{["),
+    ("n_structure_open_object_open_string.json", br#"{"a"#),
+    ("n_structure_open_object_string_with_apostrophes.json", b"{'a'"),
+    ("n_structure_open_open.json", br#"["\{["\{["\{["\{"#),
+    ("n_structure_single_star.json", b"*"),
+    ("n_structure_trailing_#.json", br#"{"a":"b"}#{}"#),
+    ("n_structure_uescaped_LF_before_string.json", br#"[\u000A""]"#),
+    ("n_structure_unclosed_array.json", b"[1"),
+    ("n_structure_unclosed_array_partial_null.json", b"[ false, nul"),
+    ("n_structure_unclosed_array_unfinished_false.json", b"[ true, fals"),
+    ("n_structure_unclosed_array_unfinished_true.json", b"[ false, tru"),
+    ("n_structure_unclosed_object.json", br#"{"asd":"asd""#),
+    ("n_structure_unicode-identifier.json", b"\xc3\xa5"),
+    ("n_structure_whitespace_U+2060_word_joiner.json", b"[\xe2\x81\xa0]"),
+    ("n_structure_whitespace_formfeed.json", b"[\x0c]"),
+    ("i_number_double_huge_neg_exp.json", b"[123.456e-789]"),
+    ("i_number_huge_exp.json", b"[0.4e00669999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999969999999006]"),
+    ("i_number_neg_int_huge_exp.json", b"[-1e+9999]"),
+    ("i_number_pos_double_huge_exp.json", b"[1.5e+9999]"),
+    ("i_number_real_neg_overflow.json", b"[-123123e100000]"),
+    ("i_number_real_pos_overflow.json", b"[123123e100000]"),
+    ("i_number_real_underflow.json", b"[123e-10000000]"),
+    ("i_number_too_big_neg_int.json", b"[-123123123123123123123123123123]"),
+    ("i_number_too_big_pos_int.json", b"[100000000000000000000]"),
+    (
+        "i_number_very_big_negative_int.json",
+        b"[-237462374673276894279832749832423479823246327846]",
+    ),
+    ("i_object_key_lone_2nd_surrogate.json", br#"{"\uDFAA":0}"#),
+    ("i_string_1st_surrogate_but_2nd_missing.json", br#"["\uDADA"]"#),
+    ("i_string_1st_valid_surrogate_2nd_invalid.json", br#"["\uD888\u1234"]"#),
+    (
+        "i_string_UTF-16LE_with_BOM.json",
+        b"\xff\xfe[\x00\"\x00\xe9\x00\"\x00]\x00",
+    ),
+    ("i_string_UTF-8_invalid_sequence.json", b"[\"\xe6\x97\xa5\xd1\x88\xfa\"]"),
+    ("i_string_incomplete_surrogate_and_escape_valid.json", br#"["\uD800\n"]"#),
+    ("i_string_incomplete_surrogate_pair.json", br#"["\uDd1ea"]"#),
+    ("i_string_incomplete_surrogates_escape_valid.json", br#"["\uD800\uD800\n"]"#),
+    ("i_string_invalid_lonely_surrogate.json", br#"["\ud800"]"#),
+    ("i_string_invalid_surrogate.json", br#"["\ud800abc"]"#),
+    ("i_string_inverted_surrogates_U+1D11E.json", br#"["\uDd1e\uD834"]"#),
+    ("i_string_lone_second_surrogate.json", br#"["\uDFAA"]"#),
+    ("i_string_not_in_unicode_range.json", b"[\"\xf4\xbf\xbf\xbf\"]"),
+    ("i_string_overlong_sequence_2_bytes.json", b"[\"\xc0\xaf\"]"),
+    ("i_string_truncated-utf-8.json", b"[\"\xe0\xff\"]"),
+    ("i_structure_UTF-8_BOM_empty_object.json", b"\xef\xbb\xbf{}"),
+];
+
+/// Parses `bytes`, treating input that is not UTF-8 as rejected.
+fn accepts(bytes: &[u8], options: ParserOptions) -> bool {
+    match core::str::from_utf8(bytes) {
+        Ok(text) => parse_json_with_options(text, options).is_ok(),
+        Err(_) => false,
+    }
+}
+
+#[test]
+fn json_test_suite() {
+    let strict = ParserOptions::strict();
+    for (name, bytes) in CASES {
+        for options in [ParserOptions::default(), strict.clone()] {
+            let accepted = accepts(bytes, options);
+            if name.starts_with("y_") {
+                assert!(accepted, "{} was rejected", name);
+            } else if name.starts_with("n_") {
+                assert!(!accepted, "{} was accepted", name);
+            }
+        }
+        assert_eq!(
+            parse_slice(bytes).is_ok(),
+            accepts(bytes, ParserOptions::default()),
+            "{}",
+            name
+        );
+    }
+
+    // i_structure_500_nested_arrays.json, which the default depth limit rejects.
+    let nested = format!("{}{}", "[".repeat(500), "]".repeat(500));
+    assert!(!accepts(nested.as_bytes(), ParserOptions::default()));
+    let unlimited = ParserOptions::default().max_nest_level(None);
+    assert!(accepts(nested.as_bytes(), unlimited));
+}
+
+#[test]
+fn strict_rejects_extensions() {
+    let strict = ParserOptions::strict()
+        .allow_comments(true)
+        .allow_single_quotes(true)
+        .allow_unquoted_keys(true)
+        .allow_non_finite(true)
+        .allow_hex_numbers(true)
+        .allow_loose_numbers(true)
+        .allow_multiline_strings(true)
+        .allow_hex_escapes(true)
+        .allow_control_characters(true);
+    for name in [
+        "n_object_trailing_comment.json",
+        "n_object_single_quote.json",
+        "n_object_unquoted_key.json",
+        "n_number_NaN.json",
+        "n_number_hex_2_digits.json",
+        "n_number_+1.json",
+        "n_string_unescaped_newline.json",
+        "n_string_escape_x.json",
+        "n_string_unescaped_tab.json",
+        "i_string_invalid_lonely_surrogate.json",
+        "i_string_inverted_surrogates_U+1D11E.json",
+        "i_structure_UTF-8_BOM_empty_object.json",
+    ] {
+        let (_, bytes) = CASES.iter().find(|(n, _)| *n == name).unwrap();
+        assert!(!accepts(bytes, strict.clone()), "{} was accepted", name);
+    }
+    assert!(accepts(b"\xef\xbb\xbf{}", ParserOptions::default()));
+}
@@ -0,0 +1,1162 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, collections::BTreeMap, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+use crate::json::{utf16_lossy, JsonValue, NumberValue};
+use crate::json_parser::{parse_json, parse_json_with_options, NumberOverflow, ParserOptions};
+use crate::parse_error::ParseError;
+use crate::pointer::{push_index, push_key};
+use core::convert::TryInto;
+use core::fmt;
+
+#[cfg(feature = "derive")]
+pub use lite_json_derive::{FromJson, ToJson};
+
+/// A type that can be converted to a `JsonValue`.
+///
+/// With the `derive` feature, `#[derive(ToJson)]` implements it for structs and enums.
+pub trait ToJson {
+    fn to_json(&self) -> JsonValue;
+
+    /// Converts an owned value, which can avoid cloning.
+    fn into_json(self) -> JsonValue
+    where
+        Self: Sized,
+    {
+        self.to_json()
+    }
+}
+
+impl ToJson for JsonValue {
+    fn to_json(&self) -> JsonValue {
+        self.clone()
+    }
+
+    fn into_json(self) -> JsonValue {
+        self
+    }
+}
+
+impl ToJson for NumberValue {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Number(*self)
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Boolean(*self)
+    }
+}
+
+impl ToJson for () {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Null
+    }
+}
+
+macro_rules! impl_to_json_for_unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl ToJson for $ty {
+                fn to_json(&self) -> JsonValue {
+                    JsonValue::Number(NumberValue {
+                        integer: *self as u128,
+                        ..Default::default()
+                    })
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_to_json_for_signed {
+    ($($ty:ty),*) => {
+        $(
+            impl ToJson for $ty {
+                fn to_json(&self) -> JsonValue {
+                    JsonValue::Number(NumberValue {
+                        integer: (*self as i128).unsigned_abs(),
+                        negative: *self < 0,
+                        ..Default::default()
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_to_json_for_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_to_json_for_signed!(i8, i16, i32, i64, i128, isize);
+
+/// NaN and infinities become `null`.
+#[cfg(any(feature = "std", feature = "float"))]
+impl ToJson for f32 {
+    fn to_json(&self) -> JsonValue {
+        NumberValue::from_f32(*self).map_or(JsonValue::Null, JsonValue::Number)
+    }
+}
+
+/// NaN and infinities become `null`.
+#[cfg(any(feature = "std", feature = "float"))]
+impl ToJson for f64 {
+    fn to_json(&self) -> JsonValue {
+        NumberValue::from_f64(*self).map_or(JsonValue::Null, JsonValue::Number)
+    }
+}
+
+impl ToJson for char {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(__private::vec![*self])
+    }
+}
+
+impl ToJson for str {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::String(self.chars().collect())
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> JsonValue {
+        self.as_str().to_json()
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> JsonValue {
+        self.as_slice().to_json()
+    }
+
+    fn into_json(self) -> JsonValue {
+        JsonValue::Array(self.into_iter().map(ToJson::into_json).collect())
+    }
+}
+
+impl<T: ToJson, const N: usize> ToJson for [T; N] {
+    fn to_json(&self) -> JsonValue {
+        self.as_slice().to_json()
+    }
+}
+
+/// `None` becomes `null`.
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> JsonValue {
+        self.as_ref().map_or(JsonValue::Null, ToJson::to_json)
+    }
+
+    fn into_json(self) -> JsonValue {
+        self.map_or(JsonValue::Null, ToJson::into_json)
+    }
+}
+
+impl<T: ToJson> ToJson for BTreeMap<String, T> {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::Object(
+            self.iter()
+                .map(|(k, v)| (k.chars().collect(), v.to_json()))
+                .collect(),
+        )
+    }
+
+    fn into_json(self) -> JsonValue {
+        JsonValue::Object(
+            self.into_iter()
+                .map(|(k, v)| (k.chars().collect(), v.into_json()))
+                .collect(),
+        )
+    }
+}
+
+macro_rules! impl_json_for_tuple {
+    ($len:expr => $($name:ident $index:tt),+) => {
+        impl<$($name: ToJson),+> ToJson for ($($name,)+) {
+            fn to_json(&self) -> JsonValue {
+                JsonValue::Array(__private::vec![$(self.$index.to_json()),+])
+            }
+        }
+
+        impl<$($name: FromJson),+> FromJson for ($($name,)+) {
+            fn from_json(value: JsonValue) -> Result<Self, FromJsonError> {
+                let mut items = __private::array(value, $len)?;
+                Ok(($(__private::element::<$name>(&mut items, $index)?,)+))
+            }
+        }
+    };
+}
+
+impl_json_for_tuple!(1 => A 0);
+impl_json_for_tuple!(2 => A 0, B 1);
+impl_json_for_tuple!(3 => A 0, B 1, C 2);
+impl_json_for_tuple!(4 => A 0, B 1, C 2, D 3);
+impl_json_for_tuple!(5 => A 0, B 1, C 2, D 3, E 4);
+impl_json_for_tuple!(6 => A 0, B 1, C 2, D 3, E 4, F 5);
+impl_json_for_tuple!(7 => A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_json_for_tuple!(8 => A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    fn to_json(&self) -> JsonValue {
+        (**self).to_json()
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for Box<T> {
+    fn to_json(&self) -> JsonValue {
+        (**self).to_json()
+    }
+}
+
+/// Why a `JsonValue` could not be converted to a Rust type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FromJsonErrorKind {
+    /// The input is not valid JSON.
+    Parse(Box<ParseError>),
+    /// The input bytes are not valid UTF-8.
+    InvalidUtf8,
+    /// The value has a different type, the expected type is included.
+    WrongType(&'static str),
+    /// An array has a different number of items, the expected number is included.
+    InvalidLength(usize),
+    /// A required object member is missing.
+    MissingField,
+    /// A number does not fit the target type.
+    OutOfRange,
+    /// An enum tag does not name a known variant, or a value matches no variant of an
+    /// untagged enum.
+    UnknownVariant,
+}
+
+/// The error returned by `FromJson`, with the location of the offending value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FromJsonError {
+    /// JSON Pointer to the value that failed to convert, empty for the root.
+    pub path: String,
+    pub kind: FromJsonErrorKind,
+}
+
+impl FromJsonError {
+    pub fn new(kind: FromJsonErrorKind) -> Self {
+        FromJsonError {
+            path: String::new(),
+            kind,
+        }
+    }
+
+    /// Prefixes the path with an object key, for errors raised inside a member.
+    pub fn at_key(self, key: &str) -> Self {
+        let mut path = String::new();
+        push_key(&mut path, &key.chars().collect::<Vec<_>>());
+        path.push_str(&self.path);
+        FromJsonError { path, ..self }
+    }
+
+    /// Prefixes the path with an array index, for errors raised inside an item.
+    pub fn at_index(self, index: usize) -> Self {
+        let mut path = String::new();
+        push_index(&mut path, index);
+        path.push_str(&self.path);
+        FromJsonError { path, ..self }
+    }
+}
+
+impl fmt::Display for FromJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            FromJsonErrorKind::Parse(_) => f.write_str("invalid JSON")?,
+            FromJsonErrorKind::InvalidUtf8 => f.write_str("invalid UTF-8")?,
+            FromJsonErrorKind::WrongType(expected) => write!(f, "expected {}", expected)?,
+            FromJsonErrorKind::InvalidLength(len) => write!(f, "expected {} items", len)?,
+            FromJsonErrorKind::MissingField => f.write_str("missing field")?,
+            FromJsonErrorKind::OutOfRange => f.write_str("number out of range")?,
+            FromJsonErrorKind::UnknownVariant => f.write_str("unknown variant")?,
+        }
+        if !self.path.is_empty() {
+            write!(f, " at {}", self.path)?;
+        }
+        Ok(())
+    }
+}
+
+fn wrong_type<T>(expected: &'static str) -> Result<T, FromJsonError> {
+    Err(FromJsonError::new(FromJsonErrorKind::WrongType(expected)))
+}
+
+/// A type that can be built from a `JsonValue`.
+///
+/// With the `derive` feature, `#[derive(FromJson)]` implements it, together with
+/// `TryFrom<JsonValue>`, for structs and enums.
+pub trait FromJson: Sized {
+    fn from_json(value: JsonValue) -> Result<Self, FromJsonError>;
+
+    /// Builds the value for an absent object member, which is an error unless the type has
+    /// a natural empty value.
+    fn from_missing() -> Result<Self, FromJsonError> {
+        Err(FromJsonError::new(FromJsonErrorKind::MissingField))
+    }
+
+    /// Parses UTF-8 JSON text and converts it.
+    fn from_slice(bytes: &[u8]) -> Result<Self, FromJsonError> {
+        let text = core::str::from_utf8(bytes)
+            .map_err(|_| FromJsonError::new(FromJsonErrorKind::InvalidUtf8))?;
+        let value = parse_json(text)
+            .map_err(|e| FromJsonError::new(FromJsonErrorKind::Parse(Box::new(e))))?;
+        Self::from_json(value)
+    }
+}
+
+impl FromJson for JsonValue {
+    fn from_json(value: JsonValue) -> Result<Self, FromJsonError> {
+        Ok(value)
+    }
+}
+
+impl FromJson for NumberValue {
+    fn from_json(value: JsonValue) -> Result<Self, FromJsonError> {
+        match value {
+            JsonValue::Number(n) => Ok(n),
+            JsonValue::BigNumber(_) => Err(FromJsonError::new(FromJsonErrorKind::OutOfRange)),
+            _ => wrong_type("number"),
+        }
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(value: JsonValue) -> Result<Self, FromJsonError> {
+        match value {
+            JsonValue::Boolean(b) => Ok(b),
+            _ => wrong_type("boolean"),
+        }
+    }
+}
+
+impl FromJson for () {
+    fn from_json(value: JsonValue) -> Result<Self, FromJsonError> {
+        match value {
+            JsonValue::Null => Ok(()),
+            _ => wrong_type("null"),
+        }
+    }
+}
+
+/// Returns the sign and magnitude of an integer value.
+fn integer(value: JsonValue) -> Result<(bool, u128), FromJsonError> {
+    let out_of_range = || FromJsonError::new(FromJsonErrorKind::OutOfRange);
+    match value {
+        JsonValue::Number(n) => n.to_integer().ok_or_else(out_of_range),
+        JsonValue::BigNumber(_) => Err(out_of_range()),
+        _ => wrong_type("integer"),
+    }
+}
+
+macro_rules! impl_from_json_for_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl FromJson for $ty {
+                fn from_json(value: JsonValue) -> Result<Self, FromJsonError> {
+                    let (negative, magnitude) = integer(value)?;
+                    let val = if negative {
+                        magnitude.try_into().ok().and_then(|m: i128| m.checked_neg()?.try_into().ok())
+                    } else {
+                        magnitude.try_into().ok()
+                    };
+                    val.ok_or_else(|| FromJsonError::new(FromJsonErrorKind::OutOfRange))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_json_for_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, isize);
+
+impl FromJson for i128 {
+    fn from_json(value: JsonValue) -> Result<Self, FromJsonError> {
+        let (negative, magnitude) = integer(value)?;
+        let val = if negative {
+            0i128.checked_sub_unsigned(magnitude)
+        } else {
+            magnitude.try_into().ok()
+        };
+        val.ok_or_else(|| FromJsonError::new(FromJsonErrorKind::OutOfRange))
+    }
+}
+
+#[cfg(any(feature = "std", feature = "float"))]
+impl FromJson for f64 {
+    fn from_json(value: JsonValue) -> Result<Self, FromJsonError> {
+        NumberValue::from_json(value).map(NumberValue::to_f64)
+    }
+}
+
+#[cfg(any(feature = "std", feature = "float"))]
+impl FromJson for f32 {
+    fn from_json(value: JsonValue) -> Result<Self, FromJsonError> {
+        NumberValue::from_json(value).map(NumberValue::to_f32)
+    }
+}
+
+impl FromJson for String {
+    fn from_json(value: JsonValue) -> Result<Self, FromJsonError> {
+        match value {
+            JsonValue::String(s) => Ok(s.into_iter().collect()),
+            JsonValue::Utf16String(s) => Ok(utf16_lossy(&s).into_iter().collect()),
+            _ => wrong_type("string"),
+        }
+    }
+}
+
+impl FromJson for char {
+    fn from_json(value: JsonValue) -> Result<Self, FromJsonError> {
+        match value {
+            JsonValue::String(s) if s.len() == 1 => Ok(s[0]),
+            _ => wrong_type("single character string"),
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: JsonValue) -> Result<Self, FromJsonError> {
+        match value {
+            JsonValue::Array(arr) => arr
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| T::from_json(item).map_err(|e| e.at_index(i)))
+                .collect(),
+            _ => wrong_type("array"),
+        }
+    }
+}
+
+impl<T: FromJson, const N: usize> FromJson for [T; N] {
+    fn from_json(value: JsonValue) -> Result<Self, FromJsonError> {
+        let mut items = __private::array(value, N)?;
+        let items = (0..N)
+            .map(|i| __private::element(&mut items, i))
+            .collect::<Result<Vec<T>, _>>()?;
+        Ok(items
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("length checked by array")))
+    }
+}
+
+/// `null` and missing members become `None`.
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(value: JsonValue) -> Result<Self, FromJsonError> {
+        match value {
+            JsonValue::Null => Ok(None),
+            value => T::from_json(value).map(Some),
+        }
+    }
+
+    fn from_missing() -> Result<Self, FromJsonError> {
+        Ok(None)
+    }
+}
+
+/// Duplicate keys keep their last value.
+impl<T: FromJson> FromJson for BTreeMap<String, T> {
+    fn from_json(value: JsonValue) -> Result<Self, FromJsonError> {
+        __private::object(value)?
+            .into_iter()
+            .map(|(k, v)| {
+                let key: String = k.into_iter().collect();
+                match T::from_json(v) {
+                    Ok(v) => Ok((key, v)),
+                    Err(e) => Err(e.at_key(&key)),
+                }
+            })
+            .collect()
+    }
+}
+
+impl<T: FromJson> FromJson for Box<T> {
+    fn from_json(value: JsonValue) -> Result<Self, FromJsonError> {
+        T::from_json(value).map(Box::new)
+    }
+}
+
+/// Why `JsonValue::get_as` failed.
+#[cfg_attr(feature = "std", derive(PartialEq, Eq))]
+#[derive(Debug)]
+pub enum ExtractErrorKind {
+    /// The path is not a valid JSON Pointer.
+    InvalidPath,
+    /// Nothing is at the path.
+    NotFound,
+    /// The value, or a value nested in it, could not be converted.
+    Mismatch {
+        /// The expected JSON type, or the name of the requested Rust type.
+        expected: &'static str,
+        /// The JSON type of the offending value.
+        actual: &'static str,
+        reason: FromJsonErrorKind,
+    },
+}
+
+/// The error returned by `JsonValue::get_as`.
+#[cfg_attr(feature = "std", derive(PartialEq, Eq))]
+#[derive(Debug)]
+pub struct ExtractError {
+    /// JSON Pointer to the offending value, which may be nested below the requested path.
+    pub path: String,
+    pub kind: ExtractErrorKind,
+}
+
+impl fmt::Display for ExtractError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ExtractErrorKind::InvalidPath => write!(f, "invalid path {:?}", self.path),
+            ExtractErrorKind::NotFound => write!(f, "no value at {:?}", self.path),
+            ExtractErrorKind::Mismatch {
+                expected, actual, ..
+            } => write!(
+                f,
+                "expected {} but found {} at {:?}",
+                expected, actual, self.path
+            ),
+        }
+    }
+}
+
+/// Returns the name of the JSON type of `value`.
+fn json_type(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Object(_) => "object",
+        JsonValue::Array(_) => "array",
+        JsonValue::String(_) | JsonValue::Utf16String(_) => "string",
+        JsonValue::Number(_) | JsonValue::BigNumber(_) | JsonValue::NonFinite(_) => "number",
+        JsonValue::Boolean(_) => "boolean",
+        JsonValue::Null => "null",
+    }
+}
+
+impl JsonValue {
+    /// Converts the value at the JSON Pointer `path` to `T`.
+    ///
+    /// ```
+    /// # use lite_json::parse_json;
+    /// let value = parse_json(r#"{"items":[{"id":7}]}"#).unwrap();
+    /// assert_eq!(value.get_as::<u32>("/items/0/id").unwrap(), 7);
+    /// ```
+    pub fn get_as<T: FromJson>(&self, path: &str) -> Result<T, ExtractError> {
+        let error = |kind| ExtractError {
+            path: path.into(),
+            kind,
+        };
+        if crate::pointer::tokens(path.chars()).is_none() {
+            return Err(error(ExtractErrorKind::InvalidPath));
+        }
+        let value = self
+            .pointer(path)
+            .ok_or_else(|| error(ExtractErrorKind::NotFound))?;
+        T::from_json(value.clone()).map_err(|e| {
+            let actual = value.pointer(&e.path).map_or("nothing", json_type);
+            let expected = match e.kind {
+                FromJsonErrorKind::WrongType(expected) => expected,
+                _ => core::any::type_name::<T>(),
+            };
+            ExtractError {
+                path: String::from(path) + &e.path,
+                kind: ExtractErrorKind::Mismatch {
+                    expected,
+                    actual,
+                    reason: e.kind,
+                },
+            }
+        })
+    }
+}
+
+/// Resolves the JSON Pointer `path` and converts the number there to a fixed-point integer
+/// with `decimals` decimal places, truncating further digits.
+///
+/// Numbers given as strings, as many price APIs do, are accepted too. Negative values and
+/// values too large for `u128` are reported as out of range.
+///
+/// ```
+/// # use lite_json::{extract_fixed, parse_json};
+/// let value = parse_json(r#"{"data":{"price":"2543.21"}}"#).unwrap();
+/// assert_eq!(extract_fixed(&value, "/data/price", 4).unwrap(), 25_432_100);
+/// ```
+pub fn extract_fixed(json: &JsonValue, path: &str, decimals: u32) -> Result<u128, ExtractError> {
+    let error = |kind| ExtractError {
+        path: path.into(),
+        kind,
+    };
+    if crate::pointer::tokens(path.chars()).is_none() {
+        return Err(error(ExtractErrorKind::InvalidPath));
+    }
+    let value = json
+        .pointer(path)
+        .ok_or_else(|| error(ExtractErrorKind::NotFound))?;
+    let mismatch = |expected, reason| {
+        error(ExtractErrorKind::Mismatch {
+            expected,
+            actual: json_type(value),
+            reason,
+        })
+    };
+    let decimal = match value {
+        JsonValue::String(text) => {
+            let text: String = text.iter().collect();
+            let options = ParserOptions::default().number_overflow(NumberOverflow::Raw);
+            match parse_json_with_options(text.trim(), options) {
+                Ok(number @ JsonValue::Number(_)) | Ok(number @ JsonValue::BigNumber(_)) => {
+                    number.decimal()
+                }
+                _ => None,
+            }
+        }
+        value => value.decimal(),
+    }
+    .ok_or_else(|| {
+        let expected = "number or numeric string";
+        mismatch(expected, FromJsonErrorKind::WrongType(expected))
+    })?;
+    decimal
+        .to_fixed_point(decimals)
+        .ok_or_else(|| mismatch("u128", FromJsonErrorKind::OutOfRange))
+}
+
+/// Helpers for the code generated by the derive macros.
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(not(feature = "std"))]
+    pub use super::alloc::{vec, vec::Vec};
+    #[cfg(feature = "std")]
+    pub use std::{vec, vec::Vec};
+
+    #[cfg(not(feature = "std"))]
+    use super::alloc::string::String;
+    use super::{wrong_type, FromJson, FromJsonError, FromJsonErrorKind};
+    use crate::json::{JsonObject, JsonValue};
+
+    pub fn key(name: &str) -> Vec<char> {
+        name.chars().collect()
+    }
+
+    pub fn variant(name: &str, value: JsonValue) -> JsonValue {
+        JsonValue::Object(vec![(key(name), value)])
+    }
+
+    pub fn object(value: JsonValue) -> Result<JsonObject, FromJsonError> {
+        match value {
+            JsonValue::Object(obj) => Ok(obj),
+            _ => wrong_type("object"),
+        }
+    }
+
+    /// Like `field`, but uses `Default::default()` when the member is missing.
+    pub fn field_or_default<T: FromJson + Default>(
+        object: &mut JsonObject,
+        name: &str,
+    ) -> Result<T, FromJsonError> {
+        match object
+            .iter()
+            .position(|(k, _)| k.iter().copied().eq(name.chars()))
+        {
+            Some(i) => T::from_json(object.swap_remove(i).1).map_err(|e| e.at_key(name)),
+            None => Ok(T::default()),
+        }
+    }
+
+    /// Takes the first member named `name` out of `object` and converts it.
+    pub fn field<T: FromJson>(object: &mut JsonObject, name: &str) -> Result<T, FromJsonError> {
+        match object
+            .iter()
+            .position(|(k, _)| k.iter().copied().eq(name.chars()))
+        {
+            Some(i) => T::from_json(object.swap_remove(i).1),
+            None => T::from_missing(),
+        }
+        .map_err(|e| e.at_key(name))
+    }
+
+    pub fn array(value: JsonValue, len: usize) -> Result<vec::IntoIter<JsonValue>, FromJsonError> {
+        match value {
+            JsonValue::Array(arr) if arr.len() == len => Ok(arr.into_iter()),
+            JsonValue::Array(_) => Err(FromJsonError::new(FromJsonErrorKind::InvalidLength(len))),
+            _ => wrong_type("array"),
+        }
+    }
+
+    /// Converts the next item of an array checked by `array`.
+    pub fn element<T: FromJson>(
+        items: &mut vec::IntoIter<JsonValue>,
+        index: usize,
+    ) -> Result<T, FromJsonError> {
+        T::from_json(items.next().unwrap_or(JsonValue::Null)).map_err(|e| e.at_index(index))
+    }
+
+    pub fn null(value: JsonValue) -> Result<(), FromJsonError> {
+        <()>::from_json(value)
+    }
+
+    /// Splits an externally tagged enum into its tag and content, which is null for a tag
+    /// given as a plain string.
+    pub fn tagged(value: JsonValue) -> Result<(String, JsonValue), FromJsonError> {
+        match value {
+            JsonValue::String(tag) => Ok((tag.into_iter().collect(), JsonValue::Null)),
+            JsonValue::Object(obj) if obj.len() == 1 => {
+                let (tag, content) = obj.into_iter().next().unwrap();
+                Ok((tag.into_iter().collect(), content))
+            }
+            _ => wrong_type("string or single-member object"),
+        }
+    }
+
+    /// Runs the conversion of a variant's content, adding the variant name to error paths.
+    pub fn in_variant<T>(
+        name: &str,
+        f: impl FnOnce() -> Result<T, FromJsonError>,
+    ) -> Result<T, FromJsonError> {
+        f().map_err(|e| e.at_key(name))
+    }
+
+    /// Adds the tag of an internally tagged enum to the content, which is expected to be
+    /// an object or null. Other values are returned unchanged.
+    pub fn with_tag(tag: &str, name: &str, value: JsonValue) -> JsonValue {
+        match value {
+            JsonValue::Object(mut obj) => {
+                obj.insert(0, (key(tag), JsonValue::String(key(name))));
+                JsonValue::Object(obj)
+            }
+            JsonValue::Null => JsonValue::Object(vec![(key(tag), JsonValue::String(key(name)))]),
+            value => value,
+        }
+    }
+
+    /// Takes the `tag` member out of an internally tagged enum, returning the variant name
+    /// and the remaining members.
+    pub fn internally_tagged(
+        value: JsonValue,
+        tag: &str,
+    ) -> Result<(String, JsonValue), FromJsonError> {
+        let mut obj = object(value)?;
+        let name: String = field(&mut obj, tag)?;
+        Ok((name, JsonValue::Object(obj)))
+    }
+
+    /// Runs a conversion that may fail, used to try the variants of an untagged enum.
+    pub fn attempt<T>(f: impl FnOnce() -> Result<T, FromJsonError>) -> Result<T, FromJsonError> {
+        f()
+    }
+
+    pub fn unknown_variant<T>() -> Result<T, FromJsonError> {
+        Err(FromJsonError::new(FromJsonErrorKind::UnknownVariant))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Serialize;
+
+    #[test]
+    fn primitives_convert() {
+        assert_eq!(format!("{}", i8::MIN.to_json()), "-128");
+        assert_eq!(format!("{}", i64::MIN.to_json()), "-9223372036854775808");
+        assert_eq!(format!("{}", u128::MAX.to_json()), u128::MAX.to_string());
+        assert_eq!(format!("{}", 1.5f32.to_json()), "1.5");
+        assert_eq!(f64::NAN.to_json(), JsonValue::Null);
+        assert_eq!(
+            vec!["a".to_string(), "b".to_string()]
+                .into_json()
+                .serialize(),
+            br#"["a","b"]"#.to_vec()
+        );
+        assert_eq!(format!("{}", [true][..].to_json()), "[true]");
+        assert_eq!(format!("{}", Box::new('x').to_json()), r#""x""#);
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derived_to_json_works() {
+        #[derive(ToJson)]
+        struct Unit;
+
+        #[derive(ToJson)]
+        struct Meters(u32);
+
+        #[derive(ToJson)]
+        struct Pair<T>(T, T);
+
+        #[derive(ToJson)]
+        enum Shape {
+            Point,
+            Circle(Meters),
+            Line(i8, i8),
+            Rect { w: u8, h: u8 },
+        }
+
+        #[derive(ToJson)]
+        struct Doc<'a> {
+            name: &'a str,
+            unit: Unit,
+            pair: Pair<bool>,
+            shapes: Vec<Shape>,
+        }
+
+        let doc = Doc {
+            name: "d",
+            unit: Unit,
+            pair: Pair(true, false),
+            shapes: vec![
+                Shape::Point,
+                Shape::Circle(Meters(2)),
+                Shape::Line(-1, 1),
+                Shape::Rect { w: 1, h: 2 },
+            ],
+        };
+        assert_eq!(
+            format!("{}", doc.to_json()),
+            r#"{"name":"d","unit":null,"pair":[true,false],"shapes":["Point",{"Circle":2},{"Line":[-1,1]},{"Rect":{"w":1,"h":2}}]}"#
+        );
+        assert_eq!(doc.serialize(), doc.to_json().serialize());
+    }
+
+    #[test]
+    fn primitives_from_json() {
+        assert_eq!(u8::from_slice(b"255"), Ok(255));
+        assert_eq!(
+            i128::from_slice(b"-170141183460469231731687303715884105728"),
+            Ok(i128::MIN)
+        );
+        assert_eq!(
+            i8::from_slice(b"-129").unwrap_err().kind,
+            FromJsonErrorKind::OutOfRange
+        );
+        assert_eq!(
+            u32::from_slice(b"1.5").unwrap_err().kind,
+            FromJsonErrorKind::OutOfRange
+        );
+        assert_eq!(u32::from_slice(b"1e2"), Ok(100));
+        assert_eq!(f64::from_slice(b"0.1"), Ok(0.1));
+        assert_eq!(
+            Vec::<String>::from_slice(br#"["a","b"]"#),
+            Ok(vec!["a".to_string(), "b".to_string()])
+        );
+        let err = Vec::<bool>::from_slice(b"[true,1]").unwrap_err();
+        assert_eq!(err.path, "/1");
+        assert_eq!(err.to_string(), "expected boolean at /1");
+        assert_eq!(
+            bool::from_slice(b"\xff").unwrap_err().kind,
+            FromJsonErrorKind::InvalidUtf8
+        );
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derived_from_json_works() {
+        use core::convert::TryFrom;
+
+        #[derive(Debug, PartialEq, ToJson, FromJson)]
+        struct Meters(u32);
+
+        #[derive(Debug, PartialEq, ToJson, FromJson)]
+        enum Shape {
+            Point,
+            Circle(Meters),
+            Line(i8, i8),
+            Rect { w: u8, h: u8 },
+        }
+
+        #[derive(Debug, PartialEq, ToJson, FromJson)]
+        struct Layer {
+            name: String,
+            shapes: Vec<Shape>,
+        }
+
+        #[derive(Debug, PartialEq, ToJson, FromJson)]
+        struct Doc {
+            layers: Vec<Layer>,
+            scale: Meters,
+        }
+
+        let doc = Doc {
+            layers: vec![Layer {
+                name: "l".into(),
+                shapes: vec![
+                    Shape::Point,
+                    Shape::Circle(Meters(2)),
+                    Shape::Line(-1, 1),
+                    Shape::Rect { w: 1, h: 2 },
+                ],
+            }],
+            scale: Meters(3),
+        };
+        assert_eq!(Doc::try_from(doc.to_json()), Ok(doc));
+
+        let err = Doc::from_slice(
+            br#"{"scale":1,"layers":[{"name":"l","shapes":[{"Rect":{"w":1,"h":-2}}]}]}"#,
+        )
+        .unwrap_err();
+        assert_eq!(err.path, "/layers/0/shapes/0/Rect/h");
+        assert_eq!(err.kind, FromJsonErrorKind::OutOfRange);
+
+        let err = Doc::from_slice(br#"{"layers":[{"shapes":[]}],"scale":1}"#).unwrap_err();
+        assert_eq!(err.to_string(), "missing field at /layers/0/name");
+
+        let err = Shape::from_slice(br#"{"Line":[1]}"#).unwrap_err();
+        assert_eq!(err.path, "/Line");
+        assert_eq!(err.kind, FromJsonErrorKind::InvalidLength(2));
+        assert_eq!(
+            Shape::from_slice(br#""Square""#).unwrap_err().kind,
+            FromJsonErrorKind::UnknownVariant
+        );
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derive_attributes_work() {
+        #[derive(Debug, PartialEq, ToJson, FromJson)]
+        #[json(rename_all = "camelCase")]
+        struct User {
+            user_id: u32,
+            #[json(rename = "display")]
+            display_name: String,
+            #[json(skip)]
+            cache: Vec<u8>,
+            #[json(default)]
+            is_admin: bool,
+        }
+
+        #[derive(Debug, PartialEq, ToJson, FromJson)]
+        #[json(rename_all = "SCREAMING_SNAKE_CASE")]
+        enum Event {
+            SignedIn,
+            #[json(rename = "out")]
+            SignedOut(u32),
+            #[json(rename_all = "kebab-case")]
+            Renamed {
+                old_name: String,
+                #[json(skip)]
+                seen: bool,
+            },
+            Tagged(u8, #[json(skip)] u8, u8),
+        }
+
+        let user = User {
+            user_id: 1,
+            display_name: "a".into(),
+            cache: vec![1],
+            is_admin: false,
+        };
+        assert_eq!(
+            format!("{}", user.to_json()),
+            r#"{"userId":1,"display":"a","isAdmin":false}"#
+        );
+        assert_eq!(
+            User::from_slice(br#"{"userId":1,"display":"a"}"#),
+            Ok(User {
+                cache: vec![],
+                ..user
+            })
+        );
+
+        let events = vec![
+            Event::SignedIn,
+            Event::SignedOut(2),
+            Event::Renamed {
+                old_name: "b".into(),
+                seen: false,
+            },
+            Event::Tagged(1, 0, 3),
+        ];
+        let json = events.to_json();
+        assert_eq!(
+            format!("{}", json),
+            r#"["SIGNED_IN",{"out":2},{"RENAMED":{"old-name":"b"}},{"TAGGED":[1,3]}]"#
+        );
+        assert_eq!(Vec::<Event>::from_json(json), Ok(events));
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn enum_representations_work() {
+        #[derive(Debug, PartialEq, ToJson, FromJson)]
+        struct Circle {
+            r: u8,
+        }
+
+        #[derive(Debug, PartialEq, ToJson, FromJson)]
+        #[json(tag = "type", rename_all = "lowercase")]
+        enum Internal {
+            Empty,
+            Circle(Circle),
+            Rect { w: u8, h: u8 },
+        }
+
+        #[derive(Debug, PartialEq, ToJson, FromJson)]
+        #[json(untagged)]
+        enum Untagged {
+            Nothing,
+            Number(u8),
+            Pair(u8, u8),
+            Named { name: String },
+            Text(String),
+        }
+
+        let internal = vec![
+            Internal::Empty,
+            Internal::Circle(Circle { r: 1 }),
+            Internal::Rect { w: 2, h: 3 },
+        ];
+        let json = internal.to_json();
+        assert_eq!(
+            format!("{}", json),
+            r#"[{"type":"empty"},{"type":"circle","r":1},{"type":"rect","w":2,"h":3}]"#
+        );
+        assert_eq!(Vec::<Internal>::from_json(json), Ok(internal));
+        assert_eq!(
+            Internal::from_slice(br#"{"w":2,"h":3,"type":"rect"}"#),
+            Ok(Internal::Rect { w: 2, h: 3 })
+        );
+        let err = Internal::from_slice(br#"{"type":"square"}"#).unwrap_err();
+        assert_eq!(err.path, "/type");
+        assert_eq!(err.kind, FromJsonErrorKind::UnknownVariant);
+        let err = Internal::from_slice(br#"{"type":"rect","w":2}"#).unwrap_err();
+        assert_eq!(err.to_string(), "missing field at /h");
+
+        let untagged = vec![
+            Untagged::Nothing,
+            Untagged::Number(1),
+            Untagged::Pair(1, 2),
+            Untagged::Named { name: "n".into() },
+            Untagged::Text("t".into()),
+        ];
+        let json = untagged.to_json();
+        assert_eq!(format!("{}", json), r#"[null,1,[1,2],{"name":"n"},"t"]"#);
+        assert_eq!(Vec::<Untagged>::from_json(json), Ok(untagged));
+        assert_eq!(
+            Untagged::from_slice(b"true").unwrap_err().kind,
+            FromJsonErrorKind::UnknownVariant
+        );
+    }
+
+    #[test]
+    fn containers_convert() {
+        let mut map = BTreeMap::new();
+        map.insert("b".to_string(), vec![Some(1u8), None]);
+        map.insert("a".to_string(), vec![]);
+        let json = map.to_json();
+        assert_eq!(format!("{}", json), r#"{"a":[],"b":[1,null]}"#);
+        assert_eq!(BTreeMap::from_json(json), Ok(map));
+
+        let err = BTreeMap::<String, Option<u8>>::from_slice(br#"{"a/b":"x"}"#).unwrap_err();
+        assert_eq!(err.path, "/a~1b");
+
+        let tuple = (1u8, "a".to_string(), [true, false], ());
+        let json = tuple.to_json();
+        assert_eq!(format!("{}", json), r#"[1,"a",[true,false],null]"#);
+        assert_eq!(FromJson::from_json(json), Ok(tuple));
+        assert_eq!(
+            <[u8; 3]>::from_slice(b"[1,2]").unwrap_err().kind,
+            FromJsonErrorKind::InvalidLength(3)
+        );
+        assert_eq!(<(u8, i8)>::from_slice(b"[1,-1]"), Ok((1, -1)));
+        assert_eq!(Option::<u8>::from_missing(), Ok(None));
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derived_optional_fields_work() {
+        #[derive(Debug, PartialEq, ToJson, FromJson)]
+        struct Page {
+            next: Option<String>,
+            tags: BTreeMap<String, [u8; 2]>,
+        }
+
+        assert_eq!(
+            Page::from_slice(br#"{"tags":{"x":[1,2]}}"#),
+            Ok(Page {
+                next: None,
+                tags: vec![("x".to_string(), [1, 2])].into_iter().collect(),
+            })
+        );
+        assert_eq!(
+            Page::from_slice(br#"{"tags":{"x":[1]}}"#).unwrap_err().path,
+            "/tags/x"
+        );
+    }
+
+    #[test]
+    fn get_as_works() {
+        let value = parse_json(r#"{"user":{"name":"a","tags":["x",1]},"n":-1}"#).unwrap();
+        assert_eq!(value.get_as::<String>("/user/name"), Ok("a".to_string()));
+        assert_eq!(value.get_as::<Option<i8>>("/n"), Ok(Some(-1)));
+
+        let err = value.get_as::<Vec<String>>("/user/tags").unwrap_err();
+        assert_eq!(err.path, "/user/tags/1");
+        assert_eq!(
+            err.kind,
+            ExtractErrorKind::Mismatch {
+                expected: "string",
+                actual: "number",
+                reason: FromJsonErrorKind::WrongType("string"),
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            r#"expected string but found number at "/user/tags/1""#
+        );
+
+        let err = value.get_as::<u8>("/n").unwrap_err();
+        assert_eq!(
+            err.kind,
+            ExtractErrorKind::Mismatch {
+                expected: "u8",
+                actual: "number",
+                reason: FromJsonErrorKind::OutOfRange,
+            }
+        );
+        assert_eq!(
+            value.get_as::<u8>("/missing").unwrap_err().kind,
+            ExtractErrorKind::NotFound
+        );
+        assert_eq!(
+            value.get_as::<u8>("n").unwrap_err().kind,
+            ExtractErrorKind::InvalidPath
+        );
+    }
+    #[test]
+    fn extract_fixed_works() {
+        let value = parse_json(
+            r#"{"a":123.456,"b":" 0.5 ","c":"1e-2","d":-1,"e":"abc","f":1e40,"g":"999999999999999999999999999999999999999999"}"#,
+        )
+        .unwrap();
+        assert_eq!(extract_fixed(&value, "/a", 6), Ok(123_456_000));
+        assert_eq!(extract_fixed(&value, "/a", 1), Ok(1234));
+        assert_eq!(extract_fixed(&value, "/b", 2), Ok(50));
+        assert_eq!(extract_fixed(&value, "/c", 3), Ok(10));
+        let kind = |path| extract_fixed(&value, path, 2).unwrap_err().kind;
+        assert!(matches!(
+            kind("/d"),
+            ExtractErrorKind::Mismatch {
+                reason: FromJsonErrorKind::OutOfRange,
+                ..
+            }
+        ));
+        assert_eq!(
+            kind("/e"),
+            ExtractErrorKind::Mismatch {
+                expected: "number or numeric string",
+                actual: "string",
+                reason: FromJsonErrorKind::WrongType("number or numeric string"),
+            }
+        );
+        assert!(matches!(kind("/f"), ExtractErrorKind::Mismatch { .. }));
+        assert!(matches!(kind("/g"), ExtractErrorKind::Mismatch { .. }));
+        assert_eq!(kind("/x"), ExtractErrorKind::NotFound);
+        assert_eq!(kind("x"), ExtractErrorKind::InvalidPath);
+    }
+}
@@ -0,0 +1,834 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec::{self, Vec},
+};
+#[cfg(feature = "std")]
+use std::vec;
+
+use crate::json::{utf16_lossy, JsonValue, NumberValue};
+use crate::parse_error::ParseError;
+use crate::pull::{with_snippet, JsonEvent, PullParser};
+use core::fmt;
+use serde::de::value::StringDeserializer;
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    Unexpected, VariantAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+
+/// An error produced while converting between JSON and serde data types.
+#[derive(Debug)]
+pub enum Error {
+    /// The input is not valid JSON.
+    Parse(ParseError),
+    /// The data does not match the expected type.
+    Message(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(e) => e.fmt(f),
+            Error::Message(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl de::StdError for Error {}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::Parse(e)
+    }
+}
+
+/// Parses JSON text into any `T: Deserialize`, straight from the text with a `Deserializer`,
+/// so no `JsonValue` is built.
+pub fn from_str<T: DeserializeOwned>(s: &str) -> Result<T, Error> {
+    let mut deserializer = Deserializer::new(s.bytes());
+    T::deserialize(&mut deserializer)
+        .and_then(|value| deserializer.end().map(|_| value))
+        .map_err(|e| match e {
+            Error::Parse(e) => Error::Parse(with_snippet(s, e)),
+            e => e,
+        })
+}
+
+/// Converts a parsed `JsonValue` into any `T: Deserialize`.
+pub fn from_value<T: DeserializeOwned>(value: JsonValue) -> Result<T, Error> {
+    T::deserialize(value)
+}
+
+fn visit_number<'de, V: Visitor<'de>>(num: NumberValue, visitor: V) -> Result<V::Value, Error> {
+    if let Some(n) = num.to_u64() {
+        visitor.visit_u64(n)
+    } else if let Some(n) = num.to_i64() {
+        visitor.visit_i64(n)
+    } else {
+        visitor.visit_f64(num.to_f64())
+    }
+}
+
+/// Visits an integer that fits `i128` as one, and any other number like `deserialize_any`.
+fn visit_i128<'de, V: Visitor<'de>>(num: NumberValue, visitor: V) -> Result<V::Value, Error> {
+    match num.to_integer() {
+        Some((negative, val)) if val <= i128::MAX as u128 => {
+            let val = val as i128;
+            visitor.visit_i128(if negative { -val } else { val })
+        }
+        Some((true, val)) if val == i128::MIN.unsigned_abs() => visitor.visit_i128(i128::MIN),
+        _ => visit_number(num, visitor),
+    }
+}
+
+/// Visits a non-negative integer as a `u128`, and any other number like `deserialize_any`.
+fn visit_u128<'de, V: Visitor<'de>>(num: NumberValue, visitor: V) -> Result<V::Value, Error> {
+    match num.to_integer() {
+        Some((false, val)) => visitor.visit_u128(val),
+        _ => visit_number(num, visitor),
+    }
+}
+
+fn visit_big_number<'de, V: Visitor<'de>>(text: &[u8], visitor: V) -> Result<V::Value, Error> {
+    let val = core::str::from_utf8(text)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| {
+            <Error as de::Error>::invalid_value(Unexpected::Other("number"), &visitor)
+        })?;
+    visitor.visit_f64(val)
+}
+
+impl<'de> de::Deserializer<'de> for JsonValue {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            JsonValue::Object(obj) => visitor.visit_map(MapDeserializer {
+                iter: obj.into_iter(),
+                value: None,
+            }),
+            JsonValue::Array(arr) => visitor.visit_seq(SeqDeserializer {
+                iter: arr.into_iter(),
+            }),
+            JsonValue::String(s) => visitor.visit_string(s.into_iter().collect()),
+            JsonValue::Utf16String(s) => {
+                visitor.visit_string(utf16_lossy(&s).into_iter().collect())
+            }
+            JsonValue::Number(n) => visit_number(n, visitor),
+            JsonValue::BigNumber(n) => visit_big_number(&n, visitor),
+            JsonValue::NonFinite(n) => visitor.visit_f64(n.to_f64()),
+            JsonValue::Boolean(b) => visitor.visit_bool(b),
+            JsonValue::Null => visitor.visit_unit(),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            JsonValue::Null => visitor.visit_none(),
+            value => visitor.visit_some(value),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Accepts a string for unit variants and a single-member object for all others.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self {
+            JsonValue::String(s) => visitor.visit_enum(EnumDeserializer {
+                variant: s.into_iter().collect(),
+                value: None,
+            }),
+            JsonValue::Object(obj) if obj.len() == 1 => {
+                let (variant, value) = obj.into_iter().next().unwrap();
+                visitor.visit_enum(EnumDeserializer {
+                    variant: variant.into_iter().collect(),
+                    value: Some(value),
+                })
+            }
+            _ => Err(de::Error::invalid_type(
+                Unexpected::Other("non-enum value"),
+                &"string or single-member object",
+            )),
+        }
+    }
+
+    fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            JsonValue::Number(num) => visit_i128(num, visitor),
+            value => value.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            JsonValue::Number(num) => visit_u128(num, visitor),
+            value => value.deserialize_any(visitor),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for JsonValue {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+fn string_deserializer(s: String) -> StringDeserializer<Error> {
+    s.into_deserializer()
+}
+
+struct SeqDeserializer {
+    iter: vec::IntoIter<JsonValue>,
+}
+
+impl<'de> SeqAccess<'de> for SeqDeserializer {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        self.iter.next().map(|v| seed.deserialize(v)).transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct MapDeserializer {
+    iter: vec::IntoIter<(Vec<char>, JsonValue)>,
+    value: Option<JsonValue>,
+}
+
+impl<'de> MapAccess<'de> for MapDeserializer {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(KeyDeserializer {
+                    key: key.into_iter().collect(),
+                })
+                .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(value),
+            None => Err(de::Error::custom("value is missing")),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+/// Deserializes an object key, parsing it for integer and boolean keys.
+struct KeyDeserializer {
+    key: String,
+}
+
+macro_rules! deserialize_parsed_key {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                match self.key.parse() {
+                    Ok(val) => visitor.$visit(val),
+                    Err(_) => Err(de::Error::invalid_value(Unexpected::Str(&self.key), &visitor)),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(self.key)
+    }
+
+    deserialize_parsed_key! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        string_deserializer(self.key).deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_deserialize_any! {
+        f32 f64 char str string bytes byte_buf unit unit_struct seq tuple tuple_struct map
+        struct identifier ignored_any
+    }
+}
+
+struct EnumDeserializer {
+    variant: String,
+    value: Option<JsonValue>,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer {
+    type Error = Error;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, VariantDeserializer), Error> {
+        let variant = seed.deserialize(string_deserializer(self.variant))?;
+        Ok((variant, VariantDeserializer { value: self.value }))
+    }
+}
+
+struct VariantDeserializer {
+    value: Option<JsonValue>,
+}
+
+impl<'de> VariantAccess<'de> for VariantDeserializer {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        match self.value {
+            None | Some(JsonValue::Null) => Ok(()),
+            Some(_) => Err(de::Error::invalid_type(
+                Unexpected::Other("variant content"),
+                &"unit variant",
+            )),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        match self.value {
+            Some(value) => seed.deserialize(value),
+            None => Err(de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"newtype variant",
+            )),
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Some(JsonValue::Array(arr)) => visitor.visit_seq(SeqDeserializer {
+                iter: arr.into_iter(),
+            }),
+            _ => Err(de::Error::invalid_type(
+                Unexpected::Other("non-array variant"),
+                &"tuple variant",
+            )),
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.value {
+            Some(JsonValue::Object(obj)) => visitor.visit_map(MapDeserializer {
+                iter: obj.into_iter(),
+                value: None,
+            }),
+            _ => Err(de::Error::invalid_type(
+                Unexpected::Other("non-object variant"),
+                &"struct variant",
+            )),
+        }
+    }
+}
+
+/// Deserializes straight from JSON text, reading it as `JsonEvent`s with a `PullParser`, so no
+/// `JsonValue` is built for the document. Input is accepted as by `parse_json`, and ignored
+/// values are still checked.
+pub struct Deserializer<It> {
+    parser: PullParser<It>,
+    peeked: Option<JsonEvent>,
+}
+
+impl<It: Iterator<Item = u8>> Deserializer<It> {
+    /// Reads UTF-8 bytes, such as `text.bytes()`.
+    pub fn new(bytes: impl IntoIterator<IntoIter = It>) -> Self {
+        Deserializer {
+            parser: PullParser::new(bytes),
+            peeked: None,
+        }
+    }
+
+    /// Checks that nothing but whitespace follows the value.
+    pub fn end(&mut self) -> Result<(), Error> {
+        match self.peeked.take() {
+            Some(_) => Err(de::Error::custom("trailing value")),
+            None => self
+                .parser
+                .next()
+                .transpose()
+                .map(|_| ())
+                .map_err(Error::Parse),
+        }
+    }
+
+    fn next_event(&mut self) -> Result<JsonEvent, Error> {
+        match self.peeked.take() {
+            Some(event) => Ok(event),
+            None => match self.parser.next() {
+                Some(event) => event.map_err(Error::Parse),
+                None => Err(de::Error::custom("unexpected end of input")),
+            },
+        }
+    }
+
+    fn peek_event(&mut self) -> Result<&JsonEvent, Error> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.next_event()?);
+        }
+        Ok(self.peeked.as_ref().expect("just peeked"))
+    }
+
+    /// Visits the value that starts with `event`.
+    fn visit_event<'de, V: Visitor<'de>>(
+        &mut self,
+        event: JsonEvent,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match event {
+            JsonEvent::StartObject => {
+                let mut entries = Entries {
+                    de: self,
+                    done: false,
+                };
+                let value = visitor.visit_map(&mut entries)?;
+                entries.finish(JsonEvent::EndObject).map(|_| value)
+            }
+            JsonEvent::StartArray => {
+                let mut entries = Entries {
+                    de: self,
+                    done: false,
+                };
+                let value = visitor.visit_seq(&mut entries)?;
+                entries.finish(JsonEvent::EndArray).map(|_| value)
+            }
+            JsonEvent::String(s) => visitor.visit_string(s.into_iter().collect()),
+            JsonEvent::Number(n) => visit_number(n, visitor),
+            JsonEvent::Boolean(b) => visitor.visit_bool(b),
+            JsonEvent::Null => visitor.visit_unit(),
+            JsonEvent::Key(_) | JsonEvent::EndObject | JsonEvent::EndArray => {
+                Err(de::Error::custom("expected a value"))
+            }
+        }
+    }
+}
+
+impl<'de, It: Iterator<Item = u8>> de::Deserializer<'de> for &mut Deserializer<It> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let event = self.next_event()?;
+        self.visit_event(event, visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if *self.peek_event()? == JsonEvent::Null {
+            self.peeked = None;
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Accepts a string for unit variants and a single-member object for all others.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let invalid = || {
+            de::Error::invalid_type(
+                Unexpected::Other("non-enum value"),
+                &"string or single-member object",
+            )
+        };
+        match self.next_event()? {
+            JsonEvent::String(s) => visitor.visit_enum(StreamEnum {
+                de: self,
+                variant: s.into_iter().collect(),
+                member: false,
+            }),
+            JsonEvent::StartObject => {
+                let variant = match self.next_event()? {
+                    JsonEvent::Key(key) => key.into_iter().collect(),
+                    _ => return Err(invalid()),
+                };
+                let value = visitor.visit_enum(StreamEnum {
+                    de: self,
+                    variant,
+                    member: true,
+                })?;
+                match self.next_event()? {
+                    JsonEvent::EndObject => Ok(value),
+                    _ => Err(invalid()),
+                }
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.next_event()? {
+            JsonEvent::Number(num) => visit_i128(num, visitor),
+            event => self.visit_event(event, visitor),
+        }
+    }
+
+    fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.next_event()? {
+            JsonEvent::Number(num) => visit_u128(num, visitor),
+            event => self.visit_event(event, visitor),
+        }
+    }
+
+    /// Reads past the value without visiting its contents, still checking them.
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let mut depth = 0usize;
+        loop {
+            match self.next_event()? {
+                JsonEvent::StartObject | JsonEvent::StartArray => depth += 1,
+                JsonEvent::EndObject | JsonEvent::EndArray => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                return visitor.visit_unit();
+            }
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier
+    }
+}
+
+/// The items of an array or the members of an object being read by a `Deserializer`.
+struct Entries<'a, It> {
+    de: &'a mut Deserializer<It>,
+    /// Whether the closing bracket has been read.
+    done: bool,
+}
+
+impl<It: Iterator<Item = u8>> Entries<'_, It> {
+    /// Reads the closing bracket `end` if the visitor stopped before it.
+    fn finish(&mut self, end: JsonEvent) -> Result<(), Error> {
+        if self.done || self.de.next_event()? == end {
+            Ok(())
+        } else {
+            Err(de::Error::custom("expected the end of the array or object"))
+        }
+    }
+}
+
+impl<'de, It: Iterator<Item = u8>> SeqAccess<'de> for Entries<'_, It> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        if self.done {
+            return Ok(None);
+        }
+        if *self.de.peek_event()? == JsonEvent::EndArray {
+            self.de.peeked = None;
+            self.done = true;
+            return Ok(None);
+        }
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+}
+
+impl<'de, It: Iterator<Item = u8>> MapAccess<'de> for Entries<'_, It> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        if self.done {
+            return Ok(None);
+        }
+        match self.de.next_event()? {
+            JsonEvent::Key(key) => seed
+                .deserialize(KeyDeserializer {
+                    key: key.into_iter().collect(),
+                })
+                .map(Some),
+            JsonEvent::EndObject => {
+                self.done = true;
+                Ok(None)
+            }
+            _ => Err(de::Error::custom("expected a key")),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        seed.deserialize(&mut *self.de)
+    }
+}
+
+/// The variant of an enum being read by a `Deserializer`, either a string or the key of a
+/// single-member object whose value is the content.
+struct StreamEnum<'a, It> {
+    de: &'a mut Deserializer<It>,
+    variant: String,
+    member: bool,
+}
+
+impl<'de, It: Iterator<Item = u8>> EnumAccess<'de> for StreamEnum<'_, It> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(mut self, seed: V) -> Result<(V::Value, Self), Error> {
+        let variant = core::mem::take(&mut self.variant);
+        let variant = seed.deserialize(string_deserializer(variant))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, It: Iterator<Item = u8>> VariantAccess<'de> for StreamEnum<'_, It> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        if !self.member || self.de.next_event()? == JsonEvent::Null {
+            Ok(())
+        } else {
+            Err(de::Error::invalid_type(
+                Unexpected::Other("variant content"),
+                &"unit variant",
+            ))
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        if self.member {
+            seed.deserialize(&mut *self.de)
+        } else {
+            Err(de::Error::invalid_type(
+                Unexpected::UnitVariant,
+                &"newtype variant",
+            ))
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        if self.member && *self.de.peek_event()? == JsonEvent::StartArray {
+            de::Deserializer::deserialize_any(&mut *self.de, visitor)
+        } else {
+            Err(de::Error::invalid_type(
+                Unexpected::Other("non-array variant"),
+                &"tuple variant",
+            ))
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        if self.member && *self.de.peek_event()? == JsonEvent::StartObject {
+            de::Deserializer::deserialize_any(&mut *self.de, visitor)
+        } else {
+            Err(de::Error::invalid_type(
+                Unexpected::Other("non-object variant"),
+                &"struct variant",
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Shape {
+        Point,
+        Circle(f64),
+        Line(i32, i32),
+        Rect { w: u8, h: u8 },
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Doc {
+        name: String,
+        id: u64,
+        delta: i8,
+        ratio: f32,
+        tags: Vec<String>,
+        parent: Option<Box<Doc>>,
+        shapes: Vec<Shape>,
+        extra: BTreeMap<String, bool>,
+    }
+
+    #[test]
+    fn it_deserializes_structs() {
+        let doc: Doc = from_str(
+            r#"{
+                "name": "root", "id": 18446744073709551615, "delta": -3, "ratio": 0.5,
+                "tags": ["a", "b"],
+                "parent": {"name": "p", "id": 1, "delta": 0, "ratio": 1e1, "tags": [],
+                           "parent": null, "shapes": [], "extra": {}},
+                "shapes": ["Point", {"Circle": 1.5}, {"Line": [1, -1]}, {"Rect": {"w": 2, "h": 3}}],
+                "extra": {"x": true}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(doc.id, u64::MAX);
+        assert_eq!(doc.delta, -3);
+        assert_eq!(doc.parent.as_ref().unwrap().ratio, 10.0);
+        assert_eq!(
+            doc.shapes,
+            vec![
+                Shape::Point,
+                Shape::Circle(1.5),
+                Shape::Line(1, -1),
+                Shape::Rect { w: 2, h: 3 }
+            ]
+        );
+        assert_eq!(doc.extra.get("x"), Some(&true));
+
+        let keys: BTreeMap<i8, ()> = from_str(r#"{"-1":null,"2":null}"#).unwrap();
+        assert_eq!(keys.keys().copied().collect::<Vec<_>>(), vec![-1, 2]);
+        assert!(from_str::<BTreeMap<u8, ()>>(r#"{"x":null}"#).is_err());
+    }
+
+    #[test]
+    fn it_reports_errors() {
+        assert!(matches!(from_str::<Vec<u8>>("[1"), Err(Error::Parse(_))));
+        assert_eq!(
+            from_str::<u8>("300").unwrap_err().to_string(),
+            "invalid value: integer `300`, expected u8"
+        );
+        assert_eq!(
+            from_str::<String>("1").unwrap_err().to_string(),
+            "invalid type: integer `1`, expected a string"
+        );
+        assert_eq!(from_value::<()>(JsonValue::Null).unwrap(), ());
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Price {
+        symbol: String,
+        price: u128,
+        history: Option<Vec<(i128, Shape)>>,
+    }
+
+    #[test]
+    fn it_deserializes_without_values() {
+        let input = r#" {"skip": {"a": [1, {"b": null}]}, "symbol": "DOT", "price": 340282366920938463463374607431768211455,
+            "history": [[-170141183460469231731687303715884105728, "Point"], [2, {"Rect": {"h": 1, "w": 2}}]], "more": []} "#;
+        let expected = Price {
+            symbol: "DOT".into(),
+            price: u128::MAX,
+            history: Some(vec![
+                (i128::MIN, Shape::Point),
+                (2, Shape::Rect { w: 2, h: 1 }),
+            ]),
+        };
+        assert_eq!(from_str::<Price>(input).unwrap(), expected);
+        let value = crate::json_parser::parse_json(input).unwrap();
+        assert_eq!(from_value::<Price>(value).unwrap(), expected);
+        let mut deserializer = Deserializer::new(input.bytes());
+        assert_eq!(Price::deserialize(&mut deserializer).unwrap(), expected);
+        deserializer.end().unwrap();
+
+        // Ignored members and trailing input are still checked.
+        let error = from_str::<Price>(r#"{"skip": [1,], "symbol": "a", "price": 1}"#);
+        match error {
+            Err(Error::Parse(e)) => assert_eq!(e.offset, 12),
+            other => panic!("{:?}", other),
+        }
+        assert!(matches!(from_str::<u8>("1 2"), Err(Error::Parse(_))));
+        assert!(from_str::<Shape>(r#"{"Point": null}"#).is_ok());
+        assert!(from_str::<Shape>(r#"{"Circle": 1, "Point": null}"#).is_err());
+        assert!(from_str::<Shape>(r#"{"Line": {"a": 1}}"#).is_err());
+        assert!(from_str::<(u8,)>("[1, 2]").is_err());
+    }
+}
@@ -0,0 +1,222 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+use crate::json::JsonValue;
+use crate::pointer::{push_index, push_key};
+
+/// A single difference between two documents, located by a JSON Pointer path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffEntry<'a> {
+    /// The path only exists in the new document.
+    Added { path: String, value: &'a JsonValue },
+    /// The path only exists in the old document.
+    Removed { path: String, value: &'a JsonValue },
+    /// The path exists in both documents with different values.
+    Changed {
+        path: String,
+        old: &'a JsonValue,
+        new: &'a JsonValue,
+    },
+}
+
+impl DiffEntry<'_> {
+    /// Returns the JSON Pointer path of the difference.
+    pub fn path(&self) -> &str {
+        match self {
+            DiffEntry::Added { path, .. } => path,
+            DiffEntry::Removed { path, .. } => path,
+            DiffEntry::Changed { path, .. } => path,
+        }
+    }
+}
+
+/// Lists the differences from `a` to `b`.
+///
+/// Objects are compared member by member regardless of order, arrays index by index and
+/// numbers by value, so the result is empty exactly when `a.eq_unordered(b)`. When a value
+/// changes type, a single `Changed` entry is reported for it instead of entries for its children.
+pub fn diff<'a>(a: &'a JsonValue, b: &'a JsonValue) -> Vec<DiffEntry<'a>> {
+    let mut entries = Vec::new();
+    diff_at(&mut String::new(), a, b, &mut entries);
+    entries
+}
+
+/// Maps each key of an object to the index of its first member and the value of its last, which
+/// is the one that counts for repeated keys.
+fn members(obj: &[(Vec<char>, JsonValue)]) -> BTreeMap<&[char], (usize, &JsonValue)> {
+    let mut members = BTreeMap::new();
+    for (i, (key, val)) in obj.iter().enumerate() {
+        members
+            .entry(&key[..])
+            .and_modify(|member: &mut (usize, &JsonValue)| member.1 = val)
+            .or_insert((i, val));
+    }
+    members
+}
+
+fn diff_at<'a>(
+    path: &mut String,
+    a: &'a JsonValue,
+    b: &'a JsonValue,
+    entries: &mut Vec<DiffEntry<'a>>,
+) {
+    let len = path.len();
+    match (a, b) {
+        (JsonValue::Object(a_obj), JsonValue::Object(b_obj)) => {
+            let a_members = members(a_obj);
+            let b_members = members(b_obj);
+            for (i, (key, _)) in a_obj.iter().enumerate() {
+                let old = match a_members.get(&key[..]) {
+                    Some(&(first, old)) if first == i => old,
+                    _ => continue,
+                };
+                push_key(path, key);
+                match b_members.get(&key[..]) {
+                    Some(&(_, new)) => diff_at(path, old, new, entries),
+                    None => entries.push(DiffEntry::Removed {
+                        path: path.clone(),
+                        value: old,
+                    }),
+                }
+                path.truncate(len);
+            }
+            for (i, (key, _)) in b_obj.iter().enumerate() {
+                let new = match b_members.get(&key[..]) {
+                    Some(&(first, new)) if first == i && !a_members.contains_key(&key[..]) => new,
+                    _ => continue,
+                };
+                push_key(path, key);
+                entries.push(DiffEntry::Added {
+                    path: path.clone(),
+                    value: new,
+                });
+                path.truncate(len);
+            }
+        }
+        (JsonValue::Array(a_arr), JsonValue::Array(b_arr)) => {
+            for (i, old) in a_arr.iter().enumerate() {
+                push_index(path, i);
+                match b_arr.get(i) {
+                    Some(new) => diff_at(path, old, new, entries),
+                    None => entries.push(DiffEntry::Removed {
+                        path: path.clone(),
+                        value: old,
+                    }),
+                }
+                path.truncate(len);
+            }
+            for (i, new) in b_arr.iter().enumerate().skip(a_arr.len()) {
+                push_index(path, i);
+                entries.push(DiffEntry::Added {
+                    path: path.clone(),
+                    value: new,
+                });
+                path.truncate(len);
+            }
+        }
+        _ => {
+            if !a.eq_unordered(b) {
+                entries.push(DiffEntry::Changed {
+                    path: path.clone(),
+                    old: a,
+                    new: b,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_parser::parse_json;
+
+    #[test]
+    fn diff_works() {
+        let a = parse_json(r#"{"a":1,"b":[1,2,3],"c":{"d":true},"e/f":null,"g":"x"}"#).unwrap();
+        let b =
+            parse_json(r#"{"g":"x","a":1e0,"b":[1,5],"c":{"d":true,"h":null},"e/f":[]}"#).unwrap();
+
+        let entries = diff(&a, &b);
+        let b_arr = b.as_object().unwrap()[2].1.as_array().unwrap();
+        let a_arr = a.as_object().unwrap()[1].1.as_array().unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                DiffEntry::Changed {
+                    path: "/b/1".into(),
+                    old: &a_arr[1],
+                    new: &b_arr[1],
+                },
+                DiffEntry::Removed {
+                    path: "/b/2".into(),
+                    value: &a_arr[2],
+                },
+                DiffEntry::Added {
+                    path: "/c/h".into(),
+                    value: &JsonValue::Null,
+                },
+                DiffEntry::Changed {
+                    path: "/e~1f".into(),
+                    old: &JsonValue::Null,
+                    new: &JsonValue::Array(vec![]),
+                },
+            ]
+        );
+        assert_eq!(entries[2].path(), "/c/h");
+
+        assert!(diff(&a, &a).is_empty());
+        assert_eq!(
+            diff(&JsonValue::Null, &b),
+            vec![DiffEntry::Changed {
+                path: "".into(),
+                old: &JsonValue::Null,
+                new: &b,
+            }]
+        );
+    }
+
+    #[test]
+    fn diff_uses_last_members_of_wide_objects() {
+        let a = parse_json(r#"{"a":1,"b":2,"a":3}"#).unwrap();
+        let b = parse_json(r#"{"c":4,"a":3,"c":5}"#).unwrap();
+        let (two, five) = (parse_json("2").unwrap(), parse_json("5").unwrap());
+        assert_eq!(
+            diff(&a, &b),
+            vec![
+                DiffEntry::Removed {
+                    path: "/b".into(),
+                    value: &two,
+                },
+                DiffEntry::Added {
+                    path: "/c".into(),
+                    value: &five,
+                },
+            ]
+        );
+
+        let keys = 30_000;
+        let wide = |offset: usize| {
+            JsonValue::Object(
+                (0..keys)
+                    .map(|i| (format!("k{}", i).chars().collect(), JsonValue::Null))
+                    .chain((0..keys).map(|i| {
+                        (
+                            format!("k{}", i + offset).chars().collect(),
+                            JsonValue::Null,
+                        )
+                    }))
+                    .collect(),
+            )
+        };
+        let (a, b) = (wide(0), wide(1));
+        let entries = diff(&a, &b);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path(), format!("/k{}", keys));
+    }
+}
@@ -0,0 +1,170 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::json::JsonValue;
+use crate::json_parser::parse_slice;
+use crate::parse_error::ParseError;
+
+/// The result of `FeedParser::feed`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeedStatus {
+    /// The value is not complete yet.
+    NeedMore,
+    Complete(JsonValue),
+}
+
+/// Parses values that arrive in chunks of any size, such as a body read off the network.
+///
+/// The chunks are buffered and scanned as they come in for the end of the value, which is then
+/// parsed once, so the total cost stays linear in the input. Bytes after a complete value are
+/// kept for the next one, which `feed(&[])` returns if it is already complete. Error positions
+/// are relative to the start of the value.
+#[derive(Debug, Clone, Default)]
+pub struct FeedParser {
+    buffer: Vec<u8>,
+    /// How much of `buffer` has been scanned.
+    scanned: usize,
+    depth: usize,
+    started: bool,
+    /// Whether the value is a number or literal, which only ends at a delimiter.
+    scalar: bool,
+    in_string: bool,
+    escaped: bool,
+}
+
+impl FeedParser {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds the next chunk and returns the value if it is now complete.
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<FeedStatus, ParseError> {
+        self.buffer.extend_from_slice(chunk);
+        match self.scan() {
+            Some(end) => self.take(end).map(FeedStatus::Complete),
+            None => Ok(FeedStatus::NeedMore),
+        }
+    }
+
+    /// Ends the input, returning the value that was still pending, if any. A top-level number
+    /// such as `12` is only known to be complete here.
+    pub fn finish(&mut self) -> Result<Option<JsonValue>, ParseError> {
+        if self.buffer.iter().all(u8::is_ascii_whitespace) {
+            *self = Self::new();
+            return Ok(None);
+        }
+        let end = self.buffer.len();
+        self.take(end).map(Some)
+    }
+
+    /// Finds where the value ends, scanning only the bytes added since the last call.
+    fn scan(&mut self) -> Option<usize> {
+        while self.scanned < self.buffer.len() {
+            let i = self.scanned;
+            let byte = self.buffer[i];
+            self.scanned += 1;
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if byte == b'\\' {
+                    self.escaped = true;
+                } else if byte == b'"' {
+                    self.in_string = false;
+                    if self.depth == 0 {
+                        return Some(i + 1);
+                    }
+                }
+                continue;
+            }
+            let delimiter = matches!(
+                byte,
+                b' ' | b'\t' | b'\n' | b'\r' | b',' | b':' | b'[' | b']' | b'{' | b'}' | b'"'
+            );
+            if self.scalar && delimiter {
+                return Some(i);
+            }
+            if !self.started {
+                // Leading whitespace and byte order marks.
+                if matches!(byte, b' ' | b'\t' | b'\n' | b'\r' | 0xEF | 0xBB | 0xBF) {
+                    continue;
+                }
+                self.started = true;
+                self.scalar = !delimiter;
+            }
+            match byte {
+                b'"' => self.in_string = true,
+                b'[' | b'{' => self.depth += 1,
+                // An unmatched bracket ends the value, so the error is reported.
+                b']' | b'}' if self.depth <= 1 => return Some(i + 1),
+                b']' | b'}' => self.depth -= 1,
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Parses the first `end` bytes, keeping the rest for the next value.
+    fn take(&mut self, end: usize) -> Result<JsonValue, ParseError> {
+        let rest = self.buffer.split_off(end);
+        let value = parse_slice(&self.buffer);
+        *self = Self::new();
+        self.buffer = rest;
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_parser::parse_json;
+    use crate::parse_error::ParseErrorKind;
+
+    #[test]
+    fn chunks_are_fed() {
+        let input =
+            "\u{FEFF} {\"name\": \"caf\u{e9} \\\"[x]\\\"\", \"items\": [1, {\"a\": []}, -2.5e3]} ";
+        let expected = parse_json(input).unwrap();
+        for size in 1..8 {
+            let mut parser = FeedParser::new();
+            let mut values = Vec::new();
+            for chunk in input.as_bytes().chunks(size) {
+                if let FeedStatus::Complete(value) = parser.feed(chunk).unwrap() {
+                    values.push(value);
+                }
+            }
+            assert_eq!(values, vec![expected.clone()], "{}", size);
+            assert_eq!(parser.finish(), Ok(None));
+        }
+
+        let mut parser = FeedParser::new();
+        assert_eq!(
+            parser.feed(b"[1, 2]\n\"a\"  12"),
+            Ok(FeedStatus::Complete(parse_json("[1, 2]").unwrap()))
+        );
+        assert_eq!(
+            parser.feed(b""),
+            Ok(FeedStatus::Complete(parse_json("\"a\"").unwrap()))
+        );
+        assert_eq!(parser.feed(b"3"), Ok(FeedStatus::NeedMore));
+        assert_eq!(parser.finish(), Ok(Some(parse_json("123").unwrap())));
+
+        let mut parser = FeedParser::new();
+        assert_eq!(parser.feed(b"{\"a\": tru"), Ok(FeedStatus::NeedMore));
+        assert_eq!(
+            parser.feed(b"}").unwrap_err().kind,
+            ParseErrorKind::UnexpectedToken
+        );
+        assert_eq!(
+            parser.feed(b"]").unwrap_err().kind,
+            ParseErrorKind::UnexpectedToken
+        );
+        assert_eq!(parser.feed(b"[1, 2"), Ok(FeedStatus::NeedMore));
+        assert_eq!(
+            parser.finish().unwrap_err().kind,
+            ParseErrorKind::UnexpectedEof
+        );
+    }
+}
@@ -0,0 +1,143 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::json::{Decimal, JsonValue};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64-bit FNV-1a, chosen because its output is fixed across platforms and releases.
+struct StableHasher(u64);
+
+impl StableHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u64(&mut self, val: u64) {
+        self.write(&val.to_le_bytes());
+    }
+
+    fn write_chars(&mut self, chars: &[char]) {
+        self.write_u64(chars.len() as u64);
+        for ch in chars {
+            self.write(&(*ch as u32).to_le_bytes());
+        }
+    }
+
+    fn write_decimal(&mut self, decimal: &Decimal) {
+        self.write(&[2, decimal.negative as u8]);
+        self.write_u64(decimal.point as u64);
+        self.write_u64(decimal.digits.len() as u64);
+        self.write(&decimal.digits);
+    }
+
+    fn write_value(&mut self, value: &JsonValue) {
+        match value {
+            JsonValue::Null => self.write(&[0]),
+            JsonValue::Boolean(b) => self.write(&[1, *b as u8]),
+            JsonValue::Number(num) => self.write_decimal(&Decimal::from_number(num)),
+            JsonValue::BigNumber(num) => self.write_decimal(&Decimal::from_text(num)),
+            JsonValue::NonFinite(n) => {
+                self.write(&[6]);
+                self.write(n.literal().as_bytes());
+            }
+            JsonValue::String(s) => {
+                self.write(&[3]);
+                self.write_chars(s);
+            }
+            JsonValue::Utf16String(s) => {
+                self.write(&[7]);
+                self.write_u64(s.len() as u64);
+                for unit in s {
+                    self.write(&unit.to_le_bytes());
+                }
+            }
+            JsonValue::Array(arr) => {
+                self.write(&[4]);
+                self.write_u64(arr.len() as u64);
+                for val in arr {
+                    self.write_value(val);
+                }
+            }
+            JsonValue::Object(obj) => {
+                // Hash as a map sorted by key. Later members sort first within a key, so
+                // `dedup_by` keeps the last member for repeated keys.
+                let mut members: Vec<(usize, &(Vec<char>, JsonValue))> =
+                    obj.iter().enumerate().collect();
+                members.sort_by(|a, b| (a.1).0.cmp(&(b.1).0).then(b.0.cmp(&a.0)));
+                members.dedup_by(|a, b| (a.1).0 == (b.1).0);
+
+                self.write(&[5]);
+                self.write_u64(members.len() as u64);
+                for (_, (key, val)) in members {
+                    self.write_chars(key);
+                    self.write_value(val);
+                }
+            }
+        }
+    }
+}
+
+impl JsonValue {
+    /// Computes a canonical 64-bit hash of the document.
+    ///
+    /// Object members are hashed in key order and numbers by value, so documents that are
+    /// `eq_unordered` hash the same. The result is stable across platforms and program runs,
+    /// which makes it suitable for cache keys and deduplication, but not for security purposes.
+    pub fn stable_hash(&self) -> u64 {
+        let mut hasher = StableHasher(FNV_OFFSET_BASIS);
+        hasher.write_value(self);
+        hasher.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json::JsonValue;
+    use crate::json_parser::parse_json;
+
+    #[test]
+    fn stable_hash_works() {
+        let hash = |s: &str| parse_json(s).unwrap().stable_hash();
+
+        assert_eq!(
+            hash(r#"{"a":1000,"b":[1,2],"c":{"x":null}}"#),
+            hash(r#"{ "c": {"x": null}, "b": [1.0, 2], "a": 1e3 }"#)
+        );
+        assert_eq!(hash(r#"{"a":1,"a":2}"#), hash(r#"{"a":2}"#));
+        assert_eq!(hash("-0"), hash("0"));
+        assert_ne!(hash("[1,2]"), hash("[2,1]"));
+        assert_ne!(hash(r#"{"a":1}"#), hash(r#"{"a":"1"}"#));
+        assert_ne!(hash(r#"["ab"]"#), hash(r#"["a","b"]"#));
+        assert_ne!(hash("null"), hash("false"));
+        assert_eq!(
+            JsonValue::BigNumber(b"12.50e1".to_vec()).stable_hash(),
+            hash("125")
+        );
+        assert_eq!(hash("null"), 0xaf63_bd4c_8601_b7df);
+    }
+
+    #[test]
+    fn stable_hash_handles_wide_objects() {
+        let keys = 30_000;
+        let member = |i: usize, val| (format!("k{}", i % keys).chars().collect(), val);
+        let repeated: Vec<_> = (0..2 * keys)
+            .map(|i| member(i, JsonValue::Boolean(i >= keys)))
+            .collect();
+        let unique: Vec<_> = (0..keys)
+            .rev()
+            .map(|i| member(i, JsonValue::Boolean(true)))
+            .collect();
+        assert_eq!(
+            JsonValue::Object(repeated).stable_hash(),
+            JsonValue::Object(unique).stable_hash()
+        );
+    }
+}
@@ -0,0 +1,82 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+use crate::json::JsonValue;
+use core::convert::TryInto;
+use core::fmt::Write as _;
+
+/// Encodes bytes as lowercase hex without a prefix.
+#[cfg_attr(not(feature = "bson"), allow(dead_code))]
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        let _ = write!(out, "{:02x}", b);
+    }
+    out
+}
+
+/// Decodes hex digits of either case without a prefix, or returns None if they are malformed.
+pub(crate) fn decode(text: &[char]) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    text.chunks(2)
+        .map(|pair| Some((pair[0].to_digit(16)? * 16 + pair[1].to_digit(16)?) as u8))
+        .collect()
+}
+
+impl JsonValue {
+    /// Decodes a hex string such as `"0xdeadbeef"` into bytes. The `0x` prefix is optional,
+    /// and None is returned for other values or an odd number of digits.
+    pub fn as_hex_bytes(&self) -> Option<Vec<u8>> {
+        let text = self.as_string()?;
+        match text {
+            ['0', 'x' | 'X', digits @ ..] => decode(digits),
+            _ => decode(text),
+        }
+    }
+
+    /// Like `as_hex_bytes`, but also returns None unless there are exactly `N` bytes, for
+    /// fixed-size values such as hashes and addresses.
+    pub fn as_hex_array<const N: usize>(&self) -> Option<[u8; N]> {
+        self.as_hex_bytes()?.try_into().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json_parser::parse_json;
+
+    #[test]
+    fn hex_bytes_decode() {
+        let hex = |s: &str| parse_json(s).unwrap().as_hex_bytes();
+        assert_eq!(hex(r#""0xdeadBEEF""#), Some(vec![0xde, 0xad, 0xbe, 0xef]));
+        assert_eq!(hex(r#""0X00ff""#), Some(vec![0, 0xff]));
+        assert_eq!(hex(r#""0102""#), Some(vec![1, 2]));
+        assert_eq!(hex(r#""0x""#), Some(vec![]));
+        assert_eq!(hex(r#""0x123""#), None);
+        assert_eq!(hex(r#""0xzz""#), None);
+        assert_eq!(hex("12"), None);
+
+        let hash = parse_json(r#""0x0102030405060708""#).unwrap();
+        assert_eq!(hash.as_hex_array(), Some([1, 2, 3, 4, 5, 6, 7, 8]));
+        assert_eq!(hash.as_hex_array::<4>(), None);
+    }
+
+    #[test]
+    fn invalid_hex_digits_are_rejected() {
+        let hex = |s: &str| parse_json(&format!("\"{}\"", s)).unwrap().as_hex_bytes();
+        for bad in [
+            "0xg0", "0x0g", "0x-1", "0x+1", " 0x12", "0x12 ", "0x 12", "0x0x12", "00x12", "x12",
+            "0xé1", "0x１２",
+        ] {
+            assert_eq!(hex(bad), None, "{:?}", bad);
+        }
+        assert_eq!(hex("0xAbCd"), Some(vec![0xab, 0xcd]));
+        assert_eq!(parse_json("[]").unwrap().as_hex_bytes(), None);
+        assert_eq!(parse_json(r#""0xzz""#).unwrap().as_hex_array::<1>(), None);
+    }
+}
@@ -5,12 +5,34 @@ extern crate alloc;
 use alloc::vec::Vec;
 
 #[cfg(not(feature = "std"))]
-use alloc::string::ToString;
+use alloc::string::{String, ToString};
 
-use crate::traits::Serialize;
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap;
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+use crate::traits::{JsonObjectExt, Serialize};
 
-#[cfg_attr(feature = "std", derive(Debug))]
-#[derive(Clone, PartialEq, Copy)]
+use core::cmp::Ordering;
+use core::convert::TryInto;
+use core::fmt::{self, Write as _};
+
+/// A JSON number in its decomposed decimal form.
+///
+/// Equality and hashing are structural: two numbers are equal, and hash the
+/// same, only when every field matches. Values that are mathematically equal
+/// but written differently (`1e3` and `1000`) are therefore distinct keys.
+///
+/// Numbers are ordered by their mathematical value. Numbers with the same value
+/// but a different representation are ordered by their fields, so the ordering
+/// stays consistent with `Eq`.
+///
+/// Negative zero is part of the representation: parsing `-0` sets `negative`, which
+/// serializes back as `-0` and converts to `-0.0` as a float. As a value it equals `0`, so
+/// `value_eq`, ordering by value, hashing and `normalize` treat both zeros alike.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy, Default)]
+#[cfg_attr(feature = "scale-info", derive(scale_info::TypeInfo))]
 pub struct NumberValue {
     pub integer: u128,
     pub fraction: u128,
@@ -20,35 +42,248 @@ pub struct NumberValue {
 }
 
 impl NumberValue {
-    /// Losslessly convert the inner value to `f64`.
+    /// Returns a boolean indicating whether both numbers have the same mathematical value.
+    ///
+    /// Unlike `==`, which compares the representation, this treats `1e3`, `1000` and
+    /// `1000.0` as equal, and `-0` as equal to `0`.
+    pub fn value_eq(&self, other: &NumberValue) -> bool {
+        Decimal::from_number(self) == Decimal::from_number(other)
+    }
+
+    /// Compares the mathematical values of both numbers, ignoring their representation.
+    pub fn value_cmp(&self, other: &NumberValue) -> Ordering {
+        Decimal::from_number(self).cmp(&Decimal::from_number(other))
+    }
+
+    /// Returns the canonical representation of the value.
+    ///
+    /// Trailing zeros are removed from the fraction, the exponent is folded into the integer and
+    /// fraction parts whenever they fit, and zero is never negative. Values that cannot be written
+    /// without an exponent keep all significant digits in `integer` with the smallest possible
+    /// exponent. Numbers with more significant digits than `integer` can hold are returned
+    /// unchanged; for all others, two numbers are `value_eq` exactly when their normalized forms
+    /// are `==`.
+    pub fn normalize(self) -> NumberValue {
+        Decimal::from_number(&self).to_number().unwrap_or(self)
+    }
+
+    /// Returns the value as `u64` if it is a non-negative integer that fits, otherwise returns None.
+    ///
+    /// Like `to_i64`, this never rounds.
+    pub fn to_u64(self) -> Option<u64> {
+        match self.to_integer()? {
+            (false, val) => val.try_into().ok(),
+            (true, _) => None,
+        }
+    }
+
+    /// Returns the value as `i64` if it is an integer that fits, otherwise returns None.
+    pub fn to_i64(self) -> Option<i64> {
+        let (negative, val) = self.to_integer()?;
+        let val: i128 = val.try_into().ok()?;
+        (if negative { -val } else { val }).try_into().ok()
+    }
+
+    /// Returns the sign and magnitude if the value has no fractional part and the
+    /// magnitude fits in `u128`.
+    pub(crate) fn to_integer(self) -> Option<(bool, u128)> {
+        let decimal = Decimal::from_number(&self);
+        if decimal.point < decimal.digits.len() as i64 {
+            return None;
+        }
+        let mut val = 0u128;
+        for i in 0..decimal.point as usize {
+            let digit = decimal.digits.get(i).map_or(0, |d| d - b'0');
+            val = val.checked_mul(10)?.checked_add(digit as u128)?;
+        }
+        Some((decimal.negative, val))
+    }
+
+    /// Convert the inner value to `f64`, rounding to the nearest representable value.
+    ///
+    /// Values too large for `f64` become infinity and values too small become zero, keeping
+    /// their sign.
     #[cfg(any(feature = "std", feature = "float"))]
     pub fn to_f64(self) -> f64 {
         self.into()
     }
+
+    /// Convert the inner value to `f32`, rounding to the nearest representable value.
+    #[cfg(any(feature = "std", feature = "float"))]
+    pub fn to_f32(self) -> f32 {
+        self.into()
+    }
+}
+
+/// The error returned when a number cannot be converted without rounding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrecisionLoss;
+
+#[cfg(any(feature = "std", feature = "float"))]
+impl From<NumberValue> for f64 {
+    /// Rounds to the nearest `f64`, giving infinity or zero when the exponent is out of range.
+    fn from(val: NumberValue) -> Self {
+        parse_float(&val).unwrap_or(f64::NAN)
+    }
+}
+
+#[cfg(any(feature = "std", feature = "float"))]
+impl From<NumberValue> for f32 {
+    /// Rounds to the nearest `f32`, giving infinity or zero when the exponent is out of range.
+    fn from(val: NumberValue) -> Self {
+        parse_float(&val).unwrap_or(f32::NAN)
+    }
 }
 
+/// Zeros after the point of a number with a non-zero integer part past which the fraction can
+/// only break a rounding tie, so it is shortened to a single `1` at that position.
+#[cfg(any(feature = "std", feature = "float"))]
+const MAX_FRACTION_PADDING: u32 = 64;
+
+/// The longest text `write_float_text` produces: a sign, the 39 digits of `u128::MAX` for the
+/// integer part, the fraction padded with up to `MAX_FRACTION_PADDING` zeros before its own 39
+/// digits, and `e` with an `i64` exponent of up to 20 characters.
+#[cfg(any(feature = "std", feature = "float"))]
+const FLOAT_TEXT_LEN: usize = 1 + 39 + (MAX_FRACTION_PADDING as usize + 39) + 1 + 20;
+
+/// A stack buffer large enough for any text `write_float_text` produces.
 #[cfg(any(feature = "std", feature = "float"))]
-impl Into<f64> for NumberValue {
-    fn into(self) -> f64 {
-        #[cfg(not(feature = "std"))]
-        use num_traits::float::FloatCore as _;
+struct FloatText {
+    bytes: [u8; FLOAT_TEXT_LEN],
+    len: usize,
+}
 
-        let sign = if self.negative { -1.0 } else { 1.0 };
-        (self.integer as f64 + self.fraction as f64 / 10f64.powi(self.fraction_length as i32))
-            * 10f64.powi(self.exponent)
-            * sign
+#[cfg(any(feature = "std", feature = "float"))]
+impl fmt::Write for FloatText {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        self.bytes
+            .get_mut(self.len..end)
+            .ok_or(fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+/// Converts through the number's digits and exponent, so the float parser does the rounding.
+#[cfg(any(feature = "std", feature = "float"))]
+fn parse_float<F: core::str::FromStr>(val: &NumberValue) -> Option<F> {
+    let mut text = FloatText {
+        bytes: [0; FLOAT_TEXT_LEN],
+        len: 0,
+    };
+    write_float_text(&mut text, val).ok()?;
+    core::str::from_utf8(&text.bytes[..text.len])
+        .ok()?
+        .parse()
+        .ok()
+}
+
+/// Writes `val` as `<digits>e<exponent>`, which takes at most `FLOAT_TEXT_LEN` bytes.
+#[cfg(any(feature = "std", feature = "float"))]
+fn write_float_text<W: fmt::Write>(w: &mut W, val: &NumberValue) -> fmt::Result {
+    if val.negative {
+        w.write_char('-')?;
+    }
+    let mut shift = 0;
+    if val.fraction == 0 {
+        write!(w, "{}", val.integer)?;
+    } else {
+        let mut digits = 0;
+        let mut rest = val.fraction;
+        while rest > 0 {
+            digits += 1;
+            rest /= 10;
+        }
+        let padding = val.fraction_length.saturating_sub(digits);
+        if val.integer == 0 {
+            write!(w, "{}", val.fraction)?;
+            shift = i64::from(padding) + i64::from(digits);
+        } else {
+            let (fraction, width) = if padding > MAX_FRACTION_PADDING {
+                (1, MAX_FRACTION_PADDING + 1)
+            } else {
+                (val.fraction, padding + digits)
+            };
+            write!(
+                w,
+                "{}{:0width$}",
+                val.integer,
+                fraction,
+                width = width as usize
+            )?;
+            shift = i64::from(width);
+        }
+    }
+    write!(w, "e{}", i64::from(val.exponent) - shift)
+}
+
+/// A number JSON cannot represent, as held by `JsonValue::NonFinite`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "scale-info", derive(scale_info::TypeInfo))]
+pub enum NonFinite {
+    NaN,
+    Infinity,
+    NegativeInfinity,
+}
+
+impl NonFinite {
+    /// Returns the JavaScript literal for the number: `NaN`, `Infinity` or `-Infinity`.
+    pub fn literal(self) -> &'static str {
+        match self {
+            NonFinite::NaN => "NaN",
+            NonFinite::Infinity => "Infinity",
+            NonFinite::NegativeInfinity => "-Infinity",
+        }
+    }
+
+    /// Orders the number among finite ones, which rank 1.
+    fn rank(self) -> u8 {
+        match self {
+            NonFinite::NegativeInfinity => 0,
+            NonFinite::Infinity => 2,
+            NonFinite::NaN => 3,
+        }
+    }
+
+    /// Converts the number to `f64`.
+    pub fn to_f64(self) -> f64 {
+        match self {
+            NonFinite::NaN => f64::NAN,
+            NonFinite::Infinity => f64::INFINITY,
+            NonFinite::NegativeInfinity => f64::NEG_INFINITY,
+        }
     }
 }
 
 pub type JsonObject = Vec<(Vec<char>, JsonValue)>;
 
-#[cfg_attr(feature = "std", derive(Debug))]
-#[derive(Clone, PartialEq)]
+/// A parsed JSON value.
+///
+/// `Eq` and `Hash` follow the same structural rules as `NumberValue`, and
+/// object members are compared and hashed in insertion order.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "scale-info", derive(scale_info::TypeInfo))]
 pub enum JsonValue {
     Object(JsonObject),
     Array(Vec<JsonValue>),
     String(Vec<char>),
+    /// A string holding an unpaired surrogate, as UTF-16 code units since `Vec<char>` cannot
+    /// hold one. It is written back with the surrogate as a `\uXXXX` escape, while formats and
+    /// types that only hold Unicode text read each unpaired surrogate as U+FFFD.
+    ///
+    /// Only produced when parsing with `LoneSurrogates::Preserve`.
+    Utf16String(Vec<u16>),
     Number(NumberValue),
+    /// A number too large or precise for `NumberValue`, kept as its exact JSON text.
+    ///
+    /// Only produced when parsing with `NumberOverflow::Raw`.
+    BigNumber(Vec<u8>),
+    /// `NaN`, `Infinity` or `-Infinity`, which are not valid JSON.
+    ///
+    /// Only produced when parsing with `ParserOptions::allow_non_finite`.
+    NonFinite(NonFinite),
     Boolean(bool),
     Null,
 }
@@ -56,10 +291,7 @@ pub enum JsonValue {
 impl JsonValue {
     /// Returns a boolean indicating whether this value is an object or not.
     pub fn is_object(&self) -> bool {
-        match self {
-            JsonValue::Object(_) => true,
-            _ => false,
-        }
+        matches!(self, JsonValue::Object(_))
     }
 
     /// Returns a reference to the key-value vec if this value is an object, otherwise returns None.
@@ -80,10 +312,7 @@ impl JsonValue {
 
     /// Returns a boolean indicating whether this value is an array or not.
     pub fn is_array(&self) -> bool {
-        match self {
-            JsonValue::Array(_) => true,
-            _ => false,
-        }
+        matches!(self, JsonValue::Array(_))
     }
 
     /// Returns a reference to the wrapped array if this value is an array, otherwise returns None.
@@ -104,10 +333,7 @@ impl JsonValue {
 
     /// Returns a boolean indicating whether this value is a string or not.
     pub fn is_string(&self) -> bool {
-        match self {
-            JsonValue::String(_) => true,
-            _ => false,
-        }
+        matches!(self, JsonValue::String(_))
     }
 
     /// Returns a char slice if this value is a string, otherwise returns None.
@@ -128,10 +354,7 @@ impl JsonValue {
 
     /// Returns a boolean indicating whether this value is a number or not.
     pub fn is_number(&self) -> bool {
-        match self {
-            JsonValue::Number(_) => true,
-            _ => false,
-        }
+        matches!(self, JsonValue::Number(_))
     }
 
     /// Returns a reference to wrapped `NumberValue` if this value is a number, otherwise returns None.
@@ -150,14 +373,45 @@ impl JsonValue {
         }
     }
 
-    /// Returns a boolean indicating whether this value is a boolean or not.
-    pub fn is_bool(&self) -> bool {
+    /// Returns the UTF-16 code units if this value is a string with an unpaired surrogate,
+    /// otherwise returns None.
+    pub fn as_utf16_string(&self) -> Option<&[u16]> {
+        match self {
+            JsonValue::Utf16String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the JSON text of the number if this value is a big number, otherwise returns None.
+    pub fn as_big_number(&self) -> Option<&[u8]> {
         match self {
-            JsonValue::Boolean(_) => true,
-            _ => false,
+            JsonValue::BigNumber(n) => Some(n),
+            _ => None,
         }
     }
 
+    /// Returns the wrapped number if this value is `NaN` or an infinity, otherwise returns None.
+    pub fn as_non_finite(&self) -> Option<NonFinite> {
+        match self {
+            JsonValue::NonFinite(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Returns the decimal value of a number or big number.
+    pub(crate) fn decimal(&self) -> Option<Decimal> {
+        match self {
+            JsonValue::Number(n) => Some(Decimal::from_number(n)),
+            JsonValue::BigNumber(n) => Some(Decimal::from_text(n)),
+            _ => None,
+        }
+    }
+
+    /// Returns a boolean indicating whether this value is a boolean or not.
+    pub fn is_bool(&self) -> bool {
+        matches!(self, JsonValue::Boolean(_))
+    }
+
     /// Returns a reference to the wrapped boolean if this value is a boolean, otherwise returns None.
     pub fn as_bool(&self) -> Option<&bool> {
         match self {
@@ -176,13 +430,378 @@ impl JsonValue {
 
     /// Returns a boolean indicating whether this value is null or not.
     pub fn is_null(&self) -> bool {
+        matches!(self, JsonValue::Null)
+    }
+
+    /// Compares two documents treating objects as unordered maps and numbers by value.
+    ///
+    /// Object members are matched by key regardless of their order, with the last member
+    /// winning when a key is repeated, and numbers such as `1e3` and `1000` are equal.
+    /// Array elements are still compared in order.
+    pub fn eq_unordered(&self, other: &JsonValue) -> bool {
+        match (self, other) {
+            (JsonValue::Object(a), JsonValue::Object(b)) => {
+                fn last<'a>(
+                    obj: &'a [(Vec<char>, JsonValue)],
+                    key: &[char],
+                ) -> Option<&'a JsonValue> {
+                    obj.iter()
+                        .rev()
+                        .find(|(k, _)| k[..] == *key)
+                        .map(|(_, v)| v)
+                }
+                a.iter().all(|(key, _)| match (last(a, key), last(b, key)) {
+                    (Some(x), Some(y)) => x.eq_unordered(y),
+                    _ => false,
+                }) && b.iter().all(|(key, _)| last(a, key).is_some())
+            }
+            (JsonValue::Array(a), JsonValue::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.eq_unordered(y))
+            }
+            (JsonValue::Number(a), JsonValue::Number(b)) => a.value_eq(b),
+            _ => match (self.decimal(), other.decimal()) {
+                (Some(a), Some(b)) => a == b,
+                _ => self == other,
+            },
+        }
+    }
+
+    /// Returns the member `key` of every object element if this value is an array.
+    ///
+    /// Elements that are not objects or lack the member are skipped.
+    pub fn pluck(&self, key: &str) -> Vec<&JsonValue> {
+        self.as_array()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|val| val.member(key).ok())
+            .collect()
+    }
+
+    /// Removes repeated elements if this value is an array, keeping the first occurrence.
+    ///
+    /// Elements are compared with `eq_unordered`, so objects with the same members in a
+    /// different order and numbers with the same value count as duplicates.
+    pub fn dedup_array(&mut self) {
+        if let JsonValue::Array(arr) = self {
+            let mut seen: BTreeMap<u64, Vec<JsonValue>> = BTreeMap::new();
+            arr.retain(|val| {
+                let bucket = seen.entry(val.stable_hash()).or_default();
+                if bucket.iter().any(|other| other.eq_unordered(val)) {
+                    false
+                } else {
+                    bucket.push(val.clone());
+                    true
+                }
+            });
+        }
+    }
+
+    /// Sorts object members by key at every level of the document.
+    ///
+    /// The sort is stable, so repeated keys keep their relative order.
+    pub fn sort_keys(&mut self) {
+        match self {
+            JsonValue::Object(obj) => {
+                obj.sort_by(|a, b| a.0.cmp(&b.0));
+                for (_, val) in obj.iter_mut() {
+                    val.sort_keys();
+                }
+            }
+            JsonValue::Array(arr) => {
+                for val in arr.iter_mut() {
+                    val.sort_keys();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Converts an object into a map sorted by key, otherwise returns None.
+    ///
+    /// When a key appears more than once, the last member wins.
+    pub fn into_map(self) -> Option<BTreeMap<String, JsonValue>> {
+        self.to_object().map(|obj| {
+            obj.into_iter()
+                .map(|(key, val)| (key.into_iter().collect(), val))
+                .collect()
+        })
+    }
+}
+
+/// Creates an object whose members are in key order.
+impl From<BTreeMap<String, JsonValue>> for JsonValue {
+    fn from(map: BTreeMap<String, JsonValue>) -> Self {
+        JsonValue::Object(
+            map.into_iter()
+                .map(|(key, val)| (key.chars().collect(), val))
+                .collect(),
+        )
+    }
+}
+
+/// Sign, significant digits and decimal point position of a number.
+///
+/// The value is `0.d1d2d3... * 10^point`. Leading and trailing zeros are
+/// stripped, so zero has no digits and equal values have equal decimals.
+#[derive(PartialEq, Eq)]
+pub(crate) struct Decimal {
+    pub(crate) negative: bool,
+    pub(crate) digits: Vec<u8>,
+    pub(crate) point: i64,
+}
+
+impl Decimal {
+    /// Builds a decimal from the digits `0.digits * 10^point`, stripping zeros.
+    fn new(negative: bool, mut digits: Vec<u8>, mut point: i64) -> Self {
+        let leading = digits.iter().take_while(|d| **d == b'0').count();
+        digits.drain(..leading);
+        point = point.saturating_sub(leading as i64);
+        while digits.last() == Some(&b'0') {
+            digits.pop();
+        }
+        if digits.is_empty() {
+            point = 0;
+        }
+
+        Decimal {
+            negative: negative && !digits.is_empty(),
+            digits,
+            point,
+        }
+    }
+
+    pub(crate) fn from_number(num: &NumberValue) -> Self {
+        let mut digits: Vec<u8> = num.integer.to_string().into_bytes();
+        let point = digits.len() as i64 + num.exponent as i64;
+
+        let fraction = num.fraction.to_string();
+        if num.fraction > 0 || num.fraction_length > 0 {
+            let padding = (num.fraction_length as usize).saturating_sub(fraction.len());
+            digits.resize(digits.len() + padding, b'0');
+            digits.extend_from_slice(fraction.as_bytes());
+        }
+
+        Decimal::new(num.negative, digits, point)
+    }
+
+    /// Converts to a fixed-point integer with `decimals` decimal places, truncating further
+    /// digits. Returns None for negative numbers or if the result does not fit in `u128`.
+    pub(crate) fn to_fixed_point(&self, decimals: u32) -> Option<u128> {
+        if self.negative {
+            return None;
+        }
+        let point = self.point.checked_add(decimals as i64)?.max(0);
+        let whole = point.min(self.digits.len() as i64);
+        let mut val = 0u128;
+        for digit in &self.digits[..whole as usize] {
+            val = val.checked_mul(10)?.checked_add((digit - b'0') as u128)?;
+        }
+        if val == 0 {
+            return Some(0);
+        }
+        // The digits past the end are zeros, so the rest is a single scaling.
+        let remaining = (point - whole).try_into().ok()?;
+        val.checked_mul(10u128.checked_pow(remaining)?)
+    }
+
+    /// Builds a decimal from JSON number text. Exponents beyond `i64` saturate.
+    pub(crate) fn from_text(text: &[u8]) -> Self {
+        let negative = text.first() == Some(&b'-');
+        let mut digits = Vec::with_capacity(text.len());
+        let mut point = None;
+        let mut exponent = 0i64;
+        let mut exponent_sign = 1i64;
+        let mut rest = text.iter();
+        while let Some(c) = rest.next() {
+            match c {
+                b'0'..=b'9' => digits.push(*c),
+                b'.' => point = Some(digits.len() as i64),
+                b'e' | b'E' => {
+                    for c in rest.by_ref() {
+                        match c {
+                            b'-' => exponent_sign = -1,
+                            b'0'..=b'9' => {
+                                exponent = exponent
+                                    .saturating_mul(10)
+                                    .saturating_add((c - b'0') as i64)
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        let point = point.unwrap_or(digits.len() as i64);
+        Decimal::new(
+            negative,
+            digits,
+            point.saturating_add(exponent * exponent_sign),
+        )
+    }
+
+    /// Builds the canonical `NumberValue` for this decimal, see `NumberValue::normalize`.
+    fn to_number(&self) -> Option<NumberValue> {
+        fn digits_to_u128(digits: &[u8]) -> Option<u128> {
+            digits.iter().try_fold(0u128, |acc, d| {
+                acc.checked_mul(10)?.checked_add((d - b'0') as u128)
+            })
+        }
+
+        let num = |integer, fraction, fraction_length, exponent| NumberValue {
+            integer,
+            fraction,
+            fraction_length,
+            exponent,
+            negative: self.negative,
+        };
+        let len = self.digits.len() as i64;
+        if len == 0 {
+            return Some(NumberValue::default());
+        }
+
+        let plain = if self.point >= len {
+            (self.point - len)
+                .try_into()
+                .ok()
+                .and_then(|zeros| 10u128.checked_pow(zeros))
+                .and_then(|scale| digits_to_u128(&self.digits)?.checked_mul(scale))
+                .map(|integer| num(integer, 0, 0, 0))
+        } else if self.point > 0 {
+            let (integer, fraction) = self.digits.split_at(self.point as usize);
+            digits_to_u128(integer)
+                .zip(digits_to_u128(fraction))
+                .map(|(integer, fraction)| num(integer, fraction, (len - self.point) as u32, 0))
+        } else {
+            digits_to_u128(&self.digits)
+                .and_then(|fraction| Some(num(0, fraction, (len - self.point).try_into().ok()?, 0)))
+        };
+        plain.or_else(|| {
+            let exponent = (self.point - len).try_into().ok()?;
+            Some(num(digits_to_u128(&self.digits)?, 0, 0, exponent))
+        })
+    }
+
+    fn cmp_magnitude(&self, other: &Self) -> Ordering {
+        match (self.digits.is_empty(), other.digits.is_empty()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (false, false) => self
+                .point
+                .cmp(&other.point)
+                .then_with(|| self.digits.cmp(&other.digits)),
+        }
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.cmp_magnitude(other),
+            (true, true) => other.cmp_magnitude(self),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for NumberValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value_cmp(other).then_with(|| {
+            (
+                self.negative,
+                self.integer,
+                self.fraction,
+                self.fraction_length,
+                self.exponent,
+            )
+                .cmp(&(
+                    other.negative,
+                    other.integer,
+                    other.fraction,
+                    other.fraction_length,
+                    other.exponent,
+                ))
+        })
+    }
+}
+
+impl PartialOrd for NumberValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl JsonValue {
+    fn type_rank(&self) -> u8 {
         match self {
-            JsonValue::Null => true,
-            _ => false,
+            JsonValue::Null => 0,
+            JsonValue::Boolean(_) => 1,
+            JsonValue::Number(_) | JsonValue::BigNumber(_) | JsonValue::NonFinite(_) => 2,
+            JsonValue::String(_) | JsonValue::Utf16String(_) => 3,
+            JsonValue::Array(_) => 4,
+            JsonValue::Object(_) => 5,
         }
     }
 }
 
+/// Values are ordered by type first: null < boolean < number < string < array < object.
+/// Values of the same type are then compared by content: numbers by value, strings by
+/// characters, arrays element by element and objects member by member in insertion order.
+/// A string with an unpaired surrogate is ordered among strings as if the surrogate were a
+/// character, after a `String` of the same characters.
+/// Big numbers are ordered by value among numbers, after a `Number` of the same value.
+/// `-Infinity` and `Infinity` are ordered before and after every other number, and `NaN` last.
+impl Ord for JsonValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (JsonValue::Object(a), JsonValue::Object(b)) => a.cmp(b),
+            (JsonValue::Array(a), JsonValue::Array(b)) => a.cmp(b),
+            (JsonValue::String(a), JsonValue::String(b)) => a.cmp(b),
+            (JsonValue::Utf16String(a), JsonValue::Utf16String(b)) => {
+                code_points(a).cmp(code_points(b))
+            }
+            (JsonValue::String(a), JsonValue::Utf16String(b)) => a
+                .iter()
+                .map(|c| *c as u32)
+                .cmp(code_points(b))
+                .then(Ordering::Less),
+            (JsonValue::Utf16String(a), JsonValue::String(b)) => code_points(a)
+                .cmp(b.iter().map(|c| *c as u32))
+                .then(Ordering::Greater),
+            (JsonValue::Number(a), JsonValue::Number(b)) => a.cmp(b),
+            (JsonValue::Boolean(a), JsonValue::Boolean(b)) => a.cmp(b),
+            (JsonValue::BigNumber(a), JsonValue::BigNumber(b)) => Decimal::from_text(a)
+                .cmp(&Decimal::from_text(b))
+                .then_with(|| a.cmp(b)),
+            (JsonValue::Number(a), JsonValue::BigNumber(b)) => Decimal::from_number(a)
+                .cmp(&Decimal::from_text(b))
+                .then(Ordering::Less),
+            (JsonValue::BigNumber(a), JsonValue::Number(b)) => Decimal::from_text(a)
+                .cmp(&Decimal::from_number(b))
+                .then(Ordering::Greater),
+            (JsonValue::NonFinite(a), b) if b.type_rank() == 2 => {
+                a.rank().cmp(&b.as_non_finite().map_or(1, NonFinite::rank))
+            }
+            (a, JsonValue::NonFinite(b)) if a.type_rank() == 2 => 1.cmp(&b.rank()),
+            _ => self.type_rank().cmp(&other.type_rank()),
+        }
+    }
+}
+
+impl PartialOrd for JsonValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Serialize for NumberValue {
     fn serialize_to(&self, buffer: &mut Vec<u8>, _indent: u32, _level: u32) {
         if self.negative {
@@ -205,14 +824,51 @@ impl Serialize for NumberValue {
             if self.exponent < 0 {
                 buffer.push(b'-');
             }
-            buffer.extend_from_slice(self.exponent.abs().to_string().as_bytes());
+            buffer.extend_from_slice(self.exponent.unsigned_abs().to_string().as_bytes());
         }
     }
 }
 
-fn push_string(buffer: &mut Vec<u8>, chars: &Vec<char>) {
-    buffer.push('"' as u8);
-    for ch in chars {
+/// Decodes UTF-16 code units into characters, with each unpaired surrogate as an error.
+pub(crate) fn decode_utf16(units: &[u16]) -> impl Iterator<Item = Result<char, u16>> + '_ {
+    char::decode_utf16(units.iter().copied()).map(|c| c.map_err(|e| e.unpaired_surrogate()))
+}
+
+/// Decodes UTF-16 code units into characters, with U+FFFD for each unpaired surrogate.
+pub(crate) fn utf16_lossy(units: &[u16]) -> Vec<char> {
+    decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+/// Decodes UTF-16 code units into code points, with each unpaired surrogate as itself.
+fn code_points(units: &[u16]) -> impl Iterator<Item = u32> + '_ {
+    decode_utf16(units).map(|c| c.map_or_else(u32::from, u32::from))
+}
+
+pub(crate) fn push_string(buffer: &mut Vec<u8>, chars: &[char]) {
+    push_units(buffer, chars.iter().map(|c| Ok(*c)));
+}
+
+/// Writes a string with unpaired surrogates, each as a `\uXXXX` escape.
+pub(crate) fn push_utf16_string(buffer: &mut Vec<u8>, units: &[u16]) {
+    push_units(buffer, decode_utf16(units));
+}
+
+fn push_units(buffer: &mut Vec<u8>, units: impl Iterator<Item = Result<char, u16>>) {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    buffer.push(b'"');
+    for unit in units {
+        let ch = match unit {
+            Ok(ch) => ch,
+            Err(surrogate) => {
+                buffer.extend_from_slice(br#"\u"#);
+                for shift in [12, 8, 4, 0] {
+                    buffer.push(HEX[(surrogate >> shift) as usize & 0xf]);
+                }
+                continue;
+            }
+        };
         match ch {
             '\x08' => buffer.extend_from_slice(br#"\b"#),
             '\x0c' => buffer.extend_from_slice(br#"\f"#),
@@ -221,6 +877,12 @@ fn push_string(buffer: &mut Vec<u8>, chars: &Vec<char>) {
             '\t' => buffer.extend_from_slice(br#"\t"#),
             '\"' => buffer.extend_from_slice(br#"\""#),
             '\\' => buffer.extend_from_slice(br#"\\"#),
+            '\0'..='\x1f' => {
+                let code = ch as usize;
+                buffer.extend_from_slice(br#"\u00"#);
+                buffer.push(HEX[code >> 4]);
+                buffer.push(HEX[code & 0xf]);
+            }
             _ => match ch.len_utf8() {
                 1 => {
                     let mut buff = [0u8; 1];
@@ -246,71 +908,226 @@ fn push_string(buffer: &mut Vec<u8>, chars: &Vec<char>) {
             },
         }
     }
-    buffer.push('"' as u8);
+    buffer.push(b'"');
 }
 
-fn push_new_line_indent(buffer: &mut Vec<u8>, indent: u32, level: u32) {
+pub(crate) fn push_new_line_indent(buffer: &mut Vec<u8>, indent: u32, level: u32) {
     if indent > 0 {
-        buffer.push('\n' as u8);
+        buffer.push(b'\n');
     }
     let count = (indent * level) as usize;
     buffer.reserve(count);
     for _ in 0..count {
-        buffer.push(' ' as u8);
+        buffer.push(b' ');
     }
 }
 
-impl Serialize for JsonValue {
-    fn serialize_to(&self, buffer: &mut Vec<u8>, indent: u32, level: u32) {
+/// How `JsonValue::NonFinite` numbers are serialized, since JSON has no syntax for them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonFinitePolicy {
+    /// Writes `NaN`, `Infinity` or `-Infinity`, as JavaScript and JSON5 read them. This is what
+    /// `Serialize` does.
+    Literal,
+    /// Writes `null`, as JavaScript's `JSON.stringify` does.
+    Null,
+    /// Fails with `NonFiniteError`.
+    Error,
+}
+
+/// The error returned when serializing `NaN` or an infinity under `NonFinitePolicy::Error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonFiniteError;
+
+impl fmt::Display for NonFiniteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("NaN and infinities cannot be serialized as JSON")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NonFiniteError {}
+
+impl JsonValue {
+    /// Serializes like `Serialize::format`, writing `NaN` and infinities as `policy` says. An
+    /// `indent` of 0 gives compact output.
+    pub fn format_with(
+        &self,
+        indent: u32,
+        policy: NonFinitePolicy,
+    ) -> Result<Vec<u8>, NonFiniteError> {
+        let mut res = Vec::new();
+        self.write_json(&mut res, indent, 0, policy)?;
+        Ok(res)
+    }
+
+    fn write_json(
+        &self,
+        buffer: &mut Vec<u8>,
+        indent: u32,
+        level: u32,
+        policy: NonFinitePolicy,
+    ) -> Result<(), NonFiniteError> {
         match self {
             JsonValue::Object(obj) => {
-                buffer.push('{' as u8);
-                if obj.len() > 0 {
+                buffer.push(b'{');
+                if !obj.is_empty() {
                     push_new_line_indent(buffer, indent, level + 1);
                     push_string(buffer, &obj[0].0);
-                    buffer.push(':' as u8);
+                    buffer.push(b':');
                     if indent > 0 {
-                        buffer.push(' ' as u8);
+                        buffer.push(b' ');
                     }
-                    obj[0].1.serialize_to(buffer, indent, level + 1);
+                    obj[0].1.write_json(buffer, indent, level + 1, policy)?;
                     for (key, val) in obj.iter().skip(1) {
-                        buffer.push(',' as u8);
+                        buffer.push(b',');
                         push_new_line_indent(buffer, indent, level + 1);
                         push_string(buffer, key);
-                        buffer.push(':' as u8);
+                        buffer.push(b':');
                         if indent > 0 {
-                            buffer.push(' ' as u8);
+                            buffer.push(b' ');
                         }
-                        val.serialize_to(buffer, indent, level + 1);
+                        val.write_json(buffer, indent, level + 1, policy)?;
                     }
                     push_new_line_indent(buffer, indent, level);
-                    buffer.push('}' as u8);
+                    buffer.push(b'}');
                 } else {
-                    buffer.push('}' as u8);
+                    buffer.push(b'}');
                 }
             }
             JsonValue::Array(arr) => {
-                buffer.push('[' as u8);
-                if arr.len() > 0 {
+                buffer.push(b'[');
+                if !arr.is_empty() {
                     push_new_line_indent(buffer, indent, level + 1);
-                    arr[0].serialize_to(buffer, indent, level + 1);
+                    arr[0].write_json(buffer, indent, level + 1, policy)?;
                     for val in arr.iter().skip(1) {
-                        buffer.push(',' as u8);
+                        buffer.push(b',');
                         push_new_line_indent(buffer, indent, level + 1);
-                        val.serialize_to(buffer, indent, level);
+                        val.write_json(buffer, indent, level, policy)?;
                     }
                     push_new_line_indent(buffer, indent, level);
-                    buffer.push(']' as u8);
+                    buffer.push(b']');
                 } else {
-                    buffer.push(']' as u8);
+                    buffer.push(b']');
                 }
             }
             JsonValue::String(str) => push_string(buffer, str),
+            JsonValue::Utf16String(str) => push_utf16_string(buffer, str),
             JsonValue::Number(num) => num.serialize_to(buffer, indent, level),
+            JsonValue::BigNumber(num) => buffer.extend_from_slice(num),
+            JsonValue::NonFinite(n) => match policy {
+                NonFinitePolicy::Literal => buffer.extend_from_slice(n.literal().as_bytes()),
+                NonFinitePolicy::Null => buffer.extend_from_slice(b"null"),
+                NonFinitePolicy::Error => return Err(NonFiniteError),
+            },
             JsonValue::Boolean(true) => buffer.extend_from_slice(b"true"),
             JsonValue::Boolean(false) => buffer.extend_from_slice(b"false"),
             JsonValue::Null => buffer.extend_from_slice(b"null"),
         }
+        Ok(())
+    }
+}
+
+impl Serialize for JsonValue {
+    fn serialize_to(&self, buffer: &mut Vec<u8>, indent: u32, level: u32) {
+        // Writing literals cannot fail.
+        let _ = self.write_json(buffer, indent, level, NonFinitePolicy::Literal);
+    }
+}
+
+/// Writes the number as JSON, identical to the output of `serialize`.
+impl fmt::Display for NumberValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negative {
+            f.write_char('-')?;
+        }
+        write!(f, "{}", self.integer)?;
+        if self.fraction > 0 {
+            write!(
+                f,
+                ".{:0width$}",
+                self.fraction,
+                width = self.fraction_length as usize
+            )?;
+        }
+        if self.exponent != 0 {
+            write!(f, "e{}", self.exponent)?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn write_string<W: fmt::Write>(w: &mut W, chars: &[char]) -> fmt::Result {
+    write_units(w, chars.iter().map(|c| Ok(*c)))
+}
+
+fn write_units<W: fmt::Write>(
+    w: &mut W,
+    units: impl Iterator<Item = Result<char, u16>>,
+) -> fmt::Result {
+    w.write_char('"')?;
+    for unit in units {
+        let ch = match unit {
+            Ok(ch) => ch,
+            Err(surrogate) => {
+                write!(w, "\\u{:04x}", surrogate)?;
+                continue;
+            }
+        };
+        match ch {
+            '\x08' => w.write_str(r#"\b"#)?,
+            '\x0c' => w.write_str(r#"\f"#)?,
+            '\n' => w.write_str(r#"\n"#)?,
+            '\r' => w.write_str(r#"\r"#)?,
+            '\t' => w.write_str(r#"\t"#)?,
+            '\"' => w.write_str(r#"\""#)?,
+            '\\' => w.write_str(r#"\\"#)?,
+            '\0'..='\x1f' => write!(w, "\\u{:04x}", ch as u32)?,
+            _ => w.write_char(ch)?,
+        }
+    }
+    w.write_char('"')
+}
+
+/// Writes the value as compact JSON, identical to the output of `serialize`.
+impl fmt::Display for JsonValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonValue::Object(obj) => {
+                f.write_char('{')?;
+                for (i, (key, val)) in obj.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_string(f, key)?;
+                    f.write_char(':')?;
+                    fmt::Display::fmt(val, f)?;
+                }
+                f.write_char('}')
+            }
+            JsonValue::Array(arr) => {
+                f.write_char('[')?;
+                for (i, val) in arr.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    fmt::Display::fmt(val, f)?;
+                }
+                f.write_char(']')
+            }
+            JsonValue::String(str) => write_string(f, str),
+            JsonValue::Utf16String(str) => write_units(f, decode_utf16(str)),
+            JsonValue::Number(num) => fmt::Display::fmt(num, f),
+            JsonValue::BigNumber(num) => {
+                for c in num {
+                    f.write_char(*c as char)?;
+                }
+                Ok(())
+            }
+            JsonValue::NonFinite(n) => f.write_str(n.literal()),
+            JsonValue::Boolean(true) => f.write_str("true"),
+            JsonValue::Boolean(false) => f.write_str("false"),
+            JsonValue::Null => f.write_str("null"),
+        }
     }
 }
 
@@ -426,6 +1243,385 @@ mod tests {
         assert_eq!(null.clone().to_string(), None);
     }
 
+    #[cfg(feature = "scale-info")]
+    #[test]
+    fn json_value_has_type_info() {
+        use scale_info::{meta_type, Registry, TypeDef};
+
+        let mut registry = Registry::new();
+        registry.register_type(&meta_type::<JsonValue>());
+        let registry: scale_info::PortableRegistry = registry.into();
+        let value = registry
+            .types
+            .iter()
+            .find(|t| t.ty.path.ident().as_deref() == Some("JsonValue"))
+            .unwrap();
+        match &value.ty.type_def {
+            TypeDef::Variant(def) => assert_eq!(def.variants.len(), 9),
+            _ => panic!("JsonValue should be an enum"),
+        }
+        assert!(registry
+            .types
+            .iter()
+            .any(|t| t.ty.path.ident().as_deref() == Some("NumberValue")));
+    }
+
+    #[test]
+    fn json_value_can_be_hashed() {
+        use std::collections::HashSet;
+
+        let one = NumberValue {
+            integer: 1,
+            fraction: 0,
+            fraction_length: 0,
+            exponent: 0,
+            negative: false,
+        };
+        let mut set = HashSet::new();
+        assert!(set.insert(JsonValue::Number(one)));
+        assert!(!set.insert(JsonValue::Number(one)));
+        assert!(set.insert(JsonValue::Number(NumberValue { exponent: 1, ..one })));
+        assert!(set.insert(JsonValue::Array(vec![JsonValue::Null])));
+        assert!(!set.insert(JsonValue::Array(vec![JsonValue::Null])));
+        assert!(set.insert(JsonValue::Object(vec![(vec!['a'], JsonValue::Null)])));
+        assert_eq!(set.len(), 4);
+    }
+
+    #[test]
+    fn number_value_ordering() {
+        let num = |integer, fraction, fraction_length, exponent, negative| NumberValue {
+            integer,
+            fraction,
+            fraction_length,
+            exponent,
+            negative,
+        };
+
+        assert!(num(1, 0, 0, 0, false) < num(2, 0, 0, 0, false));
+        assert!(num(2, 0, 0, 0, true) < num(1, 0, 0, 0, true));
+        assert!(num(1, 0, 0, 0, true) < num(0, 0, 0, 0, false));
+        assert!(num(1, 5, 1, 0, false) < num(1, 51, 2, 0, false));
+        assert!(num(1, 5, 2, 0, false) < num(1, 5, 1, 0, false));
+        assert!(num(9, 0, 0, 0, false) < num(1, 0, 0, 1, false));
+        assert!(num(1, 0, 0, -3, false) < num(0, 2, 3, 0, false));
+        assert_eq!(
+            num(1, 0, 0, 3, false).partial_cmp(&num(1000, 0, 0, 0, false)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            num(0, 0, 0, 0, true).cmp(&num(0, 0, 0, 0, false)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            num(12, 5, 1, 0, false).cmp(&num(12, 5, 1, 0, false)),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn json_value_map_conversion() {
+        let obj = JsonValue::Object(vec![
+            (vec!['b'], JsonValue::Null),
+            (vec!['a'], JsonValue::Boolean(false)),
+            (vec!['b'], JsonValue::Boolean(true)),
+        ]);
+        let map = obj.into_map().unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["a"], JsonValue::Boolean(false));
+        assert_eq!(map["b"], JsonValue::Boolean(true));
+
+        assert_eq!(
+            JsonValue::from(map),
+            JsonValue::Object(vec![
+                (vec!['a'], JsonValue::Boolean(false)),
+                (vec!['b'], JsonValue::Boolean(true)),
+            ])
+        );
+        assert_eq!(JsonValue::Null.into_map(), None);
+    }
+
+    #[test]
+    fn json_value_eq_unordered() {
+        let num = |integer, exponent| {
+            JsonValue::Number(NumberValue {
+                integer,
+                fraction: 0,
+                fraction_length: 0,
+                exponent,
+                negative: false,
+            })
+        };
+        let a = JsonValue::Object(vec![
+            (vec!['a'], num(1, 3)),
+            (vec!['b'], JsonValue::Array(vec![num(1, 0), num(2, 0)])),
+        ]);
+        let b = JsonValue::Object(vec![
+            (vec!['b'], JsonValue::Array(vec![num(1, 0), num(2, 0)])),
+            (vec!['a'], num(1000, 0)),
+        ]);
+        assert_ne!(a, b);
+        assert!(a.eq_unordered(&b));
+        assert!(b.eq_unordered(&a));
+
+        let c = JsonValue::Object(vec![
+            (vec!['a'], num(1000, 0)),
+            (vec!['b'], JsonValue::Array(vec![num(2, 0), num(1, 0)])),
+        ]);
+        assert!(!a.eq_unordered(&c));
+
+        let d = JsonValue::Object(vec![(vec!['a'], num(1000, 0))]);
+        assert!(!a.eq_unordered(&d));
+        assert!(!d.eq_unordered(&a));
+        assert!(!num(1, 0).eq_unordered(&JsonValue::Null));
+    }
+
+    #[test]
+    fn json_value_pluck() {
+        let value = JsonValue::Array(vec![
+            JsonValue::Object(vec![(vec!['i', 'd'], JsonValue::Boolean(true))]),
+            JsonValue::Null,
+            JsonValue::Object(vec![(vec!['x'], JsonValue::Null)]),
+            JsonValue::Object(vec![
+                (vec!['x'], JsonValue::Null),
+                (vec!['i', 'd'], JsonValue::Boolean(false)),
+            ]),
+        ]);
+        assert_eq!(
+            value.pluck("id"),
+            vec![&JsonValue::Boolean(true), &JsonValue::Boolean(false)]
+        );
+        assert!(value.pluck("missing").is_empty());
+        assert!(JsonValue::Null.pluck("id").is_empty());
+    }
+
+    #[test]
+    fn json_value_dedup_array() {
+        let num = |integer, exponent| {
+            JsonValue::Number(NumberValue {
+                integer,
+                fraction: 0,
+                fraction_length: 0,
+                exponent,
+                negative: false,
+            })
+        };
+        let obj =
+            |a: JsonValue, b: JsonValue| JsonValue::Object(vec![(vec!['a'], a), (vec!['b'], b)]);
+        let mut value = JsonValue::Array(vec![
+            num(1, 1),
+            obj(JsonValue::Null, JsonValue::Boolean(true)),
+            num(10, 0),
+            JsonValue::Object(vec![
+                (vec!['b'], JsonValue::Boolean(true)),
+                (vec!['a'], JsonValue::Null),
+            ]),
+            obj(JsonValue::Null, JsonValue::Boolean(false)),
+            num(1, 1),
+        ]);
+        value.dedup_array();
+        assert_eq!(
+            value,
+            JsonValue::Array(vec![
+                num(1, 1),
+                obj(JsonValue::Null, JsonValue::Boolean(true)),
+                obj(JsonValue::Null, JsonValue::Boolean(false)),
+            ])
+        );
+
+        let mut value = JsonValue::Null;
+        value.dedup_array();
+        assert_eq!(value, JsonValue::Null);
+    }
+
+    #[test]
+    fn json_value_sort_keys() {
+        let mut value = JsonValue::Object(vec![
+            (
+                vec!['b'],
+                JsonValue::Array(vec![JsonValue::Object(vec![
+                    (vec!['y'], JsonValue::Null),
+                    (vec!['x'], JsonValue::Null),
+                ])]),
+            ),
+            (vec!['a', 'b'], JsonValue::Boolean(true)),
+            (vec!['a'], JsonValue::Boolean(false)),
+            (vec!['a', 'b'], JsonValue::Null),
+        ]);
+        value.sort_keys();
+        assert_eq!(
+            value,
+            JsonValue::Object(vec![
+                (vec!['a'], JsonValue::Boolean(false)),
+                (vec!['a', 'b'], JsonValue::Boolean(true)),
+                (vec!['a', 'b'], JsonValue::Null),
+                (
+                    vec!['b'],
+                    JsonValue::Array(vec![JsonValue::Object(vec![
+                        (vec!['x'], JsonValue::Null),
+                        (vec!['y'], JsonValue::Null),
+                    ])]),
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn number_value_value_eq() {
+        let num = |integer, fraction, fraction_length, exponent, negative| NumberValue {
+            integer,
+            fraction,
+            fraction_length,
+            exponent,
+            negative,
+        };
+
+        assert!(num(1, 0, 0, 3, false).value_eq(&num(1000, 0, 0, 0, false)));
+        assert!(num(1000, 0, 3, 0, false).value_eq(&num(1, 0, 0, 3, false)));
+        assert!(num(12, 5, 1, -1, false).value_eq(&num(1, 25, 2, 0, false)));
+        assert!(num(0, 5, 2, 2, true).value_eq(&num(5, 0, 0, 0, true)));
+        assert!(num(0, 0, 0, 0, true).value_eq(&num(0, 0, 1, 5, false)));
+        assert!(!num(1, 0, 0, 0, true).value_eq(&num(1, 0, 0, 0, false)));
+        assert!(!num(1, 5, 1, 0, false).value_eq(&num(1, 5, 2, 0, false)));
+        assert_ne!(num(1, 0, 0, 3, false), num(1000, 0, 0, 0, false));
+
+        assert_eq!(
+            num(1, 0, 0, 3, false).value_cmp(&num(1000, 0, 0, 0, false)),
+            Ordering::Equal
+        );
+        assert_eq!(
+            num(1, 0, 0, 3, false).value_cmp(&num(999, 0, 0, 0, false)),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn number_value_normalize() {
+        let num = |integer, fraction, fraction_length, exponent, negative| NumberValue {
+            integer,
+            fraction,
+            fraction_length,
+            exponent,
+            negative,
+        };
+
+        assert_eq!(
+            num(1, 0, 0, 3, false).normalize(),
+            num(1000, 0, 0, 0, false)
+        );
+        assert_eq!(num(1, 500, 3, 0, true).normalize(), num(1, 5, 1, 0, true));
+        assert_eq!(num(1, 5, 2, 0, false).normalize(), num(1, 5, 2, 0, false));
+        assert_eq!(
+            num(1234, 0, 0, -2, false).normalize(),
+            num(12, 34, 2, 0, false)
+        );
+        assert_eq!(num(5, 0, 0, -3, false).normalize(), num(0, 5, 3, 0, false));
+        assert_eq!(num(0, 5, 1, 2, false).normalize(), num(50, 0, 0, 0, false));
+        assert_eq!(num(0, 0, 2, 7, true).normalize(), num(0, 0, 0, 0, false));
+        assert_eq!(num(1, 0, 0, 40, false).normalize(), num(1, 0, 0, 40, false));
+        assert_eq!(num(25, 0, 0, 39, true).normalize(), num(25, 0, 0, 39, true));
+        assert_eq!(
+            num(u128::MAX, 1, 39, 0, false).normalize(),
+            num(u128::MAX, 1, 39, 0, false)
+        );
+        assert_eq!(
+            num(u128::MAX, 1, 39, 50, true).normalize(),
+            num(u128::MAX, 1, 39, 50, true)
+        );
+
+        let a = num(12, 5, 1, 1, false);
+        let b = num(1250, 0, 0, -1, false);
+        assert!(a.value_eq(&b));
+        assert_eq!(a.normalize(), b.normalize());
+    }
+
+    #[test]
+    fn big_number_compares_by_value() {
+        let big = JsonValue::BigNumber(b"-1.5e400".to_vec());
+        let bigger = JsonValue::BigNumber(b"1000000000000000000000000000000000000000000".to_vec());
+        let small = JsonValue::Number(NumberValue {
+            integer: 1,
+            fraction: 0,
+            fraction_length: 0,
+            exponent: 42,
+            negative: false,
+        });
+
+        assert!(big < small);
+        assert!(small < bigger);
+        assert!(bigger.eq_unordered(&small));
+        assert!(small.eq_unordered(&bigger));
+        assert!(!big.eq_unordered(&bigger));
+        assert!(JsonValue::BigNumber(b"1E+3".to_vec())
+            .eq_unordered(&JsonValue::BigNumber(b"1000.0".to_vec())));
+        assert_eq!(format!("{}", big), "-1.5e400");
+        assert_eq!(big.serialize(), b"-1.5e400");
+        assert_eq!(big.as_big_number(), Some(&b"-1.5e400"[..]));
+        assert_eq!(small.as_big_number(), None);
+    }
+
+    #[test]
+    fn json_value_ordering() {
+        let number = JsonValue::Number(NumberValue {
+            integer: 1,
+            fraction: 0,
+            fraction_length: 0,
+            exponent: 0,
+            negative: false,
+        });
+        let mut values = vec![
+            JsonValue::Object(vec![]),
+            JsonValue::Array(vec![JsonValue::Null]),
+            JsonValue::String(vec!['b']),
+            JsonValue::Array(vec![]),
+            JsonValue::String(vec!['a']),
+            number.clone(),
+            JsonValue::Boolean(true),
+            JsonValue::Boolean(false),
+            JsonValue::Null,
+        ];
+        values.sort();
+        assert_eq!(
+            values,
+            vec![
+                JsonValue::Null,
+                JsonValue::Boolean(false),
+                JsonValue::Boolean(true),
+                number,
+                JsonValue::String(vec!['a']),
+                JsonValue::String(vec!['b']),
+                JsonValue::Array(vec![]),
+                JsonValue::Array(vec![JsonValue::Null]),
+                JsonValue::Object(vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn number_value_to_integer() {
+        let num = |integer, fraction, fraction_length, exponent, negative| NumberValue {
+            integer,
+            fraction,
+            fraction_length,
+            exponent,
+            negative,
+        };
+
+        assert_eq!(num(42, 0, 0, 0, false).to_u64(), Some(42));
+        assert_eq!(num(42, 0, 0, 0, true).to_u64(), None);
+        assert_eq!(num(42, 0, 0, 0, true).to_i64(), Some(-42));
+        assert_eq!(num(1, 5, 1, 1, false).to_u64(), Some(15));
+        assert_eq!(num(1, 5, 1, 0, false).to_u64(), None);
+        assert_eq!(num(1500, 0, 0, -2, false).to_u64(), Some(15));
+        assert_eq!(num(2, 0, 0, 30, false).to_u64(), None);
+        assert_eq!(
+            num(u64::MAX as u128, 0, 0, 0, false).to_u64(),
+            Some(u64::MAX)
+        );
+        assert_eq!(num(u64::MAX as u128, 0, 0, 0, false).to_i64(), None);
+        assert_eq!(num(1 << 63, 0, 0, 0, true).to_i64(), Some(i64::MIN));
+        assert_eq!(num(0, 0, 0, 0, true).to_u64(), Some(0));
+    }
+
     #[test]
     fn serialize_number_value() {
         let val = NumberValue {
@@ -501,12 +1697,25 @@ mod tests {
         assert_eq!(val.serialize(), b"-1234.05e5");
     }
 
+    #[test]
+    fn control_characters_round_trip() {
+        use crate::json_parser::parse_json;
+
+        let chars: Vec<char> = ('\0'..='\x1f').chain("\"\\a\u{7f}".chars()).collect();
+        let value = JsonValue::Array(vec![JsonValue::String(chars)]);
+        let text = value.serialize();
+        assert!(text.iter().all(|b| *b >= 0x20));
+        assert!(text.starts_with(br#"["\u0000\u0001"#));
+        assert_eq!(
+            parse_json(std::str::from_utf8(&text).unwrap()),
+            Ok(value.clone())
+        );
+        assert_eq!(format!("{}", value).into_bytes(), text);
+    }
+
     #[test]
     fn serialize_works() {
-        let obj = JsonValue::Object(vec![(
-            "test\"123".chars().into_iter().collect(),
-            JsonValue::Null,
-        )]);
+        let obj = JsonValue::Object(vec![("test\"123".chars().collect(), JsonValue::Null)]);
         assert_eq!(
             std::str::from_utf8(&obj.format(4)[..]).unwrap(),
             r#"{
@@ -575,6 +1784,101 @@ mod tests {
         );
     }
 
+    #[test]
+    fn display_works() {
+        let obj = JsonValue::Object(vec![
+            (
+                vec!['a'],
+                JsonValue::Array(vec![
+                    JsonValue::Number(NumberValue {
+                        integer: 2,
+                        fraction: 41,
+                        fraction_length: 3,
+                        exponent: -2,
+                        negative: true,
+                    }),
+                    JsonValue::Boolean(true),
+                    JsonValue::Null,
+                    JsonValue::String(vec!['\"', '\n', 'é']),
+                ]),
+            ),
+            (vec!['b'], JsonValue::Object(vec![])),
+        ]);
+        let displayed = format!("{}", obj);
+        assert_eq!(displayed, r#"{"a":[-2.041e-2,true,null,"\"\né"],"b":{}}"#);
+        assert_eq!(displayed.as_bytes(), &obj.serialize()[..]);
+    }
+
+    #[test]
+    fn non_finite_serialization() {
+        let value = JsonValue::Array(vec![
+            JsonValue::NonFinite(NonFinite::NaN),
+            JsonValue::NonFinite(NonFinite::NegativeInfinity),
+            JsonValue::Null,
+        ]);
+        assert_eq!(value.serialize(), b"[NaN,-Infinity,null]");
+        assert_eq!(format!("{}", value), "[NaN,-Infinity,null]");
+        assert_eq!(
+            value.format_with(0, NonFinitePolicy::Literal),
+            Ok(value.serialize())
+        );
+        assert_eq!(
+            value.format_with(2, NonFinitePolicy::Null),
+            Ok(b"[\n  null,\n  null,\n  null\n]".to_vec())
+        );
+        assert_eq!(
+            value.format_with(0, NonFinitePolicy::Error),
+            Err(NonFiniteError)
+        );
+        assert_eq!(
+            JsonValue::Null.format_with(0, NonFinitePolicy::Error),
+            Ok(b"null".to_vec())
+        );
+        assert_eq!(value.serialize_canonical(), None);
+
+        let one = JsonValue::Number(NumberValue {
+            integer: 1,
+            fraction: 0,
+            fraction_length: 0,
+            exponent: 0,
+            negative: false,
+        });
+        let mut values = vec![
+            JsonValue::NonFinite(NonFinite::NaN),
+            JsonValue::String(vec![]),
+            JsonValue::NonFinite(NonFinite::Infinity),
+            one.clone(),
+            JsonValue::NonFinite(NonFinite::NegativeInfinity),
+            JsonValue::Null,
+        ];
+        values.sort();
+        assert_eq!(
+            values,
+            vec![
+                JsonValue::Null,
+                JsonValue::NonFinite(NonFinite::NegativeInfinity),
+                one,
+                JsonValue::NonFinite(NonFinite::Infinity),
+                JsonValue::NonFinite(NonFinite::NaN),
+                JsonValue::String(vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn number_value_display() {
+        let val = NumberValue {
+            integer: 1234,
+            fraction: 56,
+            fraction_length: 4,
+            exponent: -5,
+            negative: true,
+        };
+        assert_eq!(format!("{}", val), "-1234.0056e-5");
+        assert_eq!(format!("{}", val).as_bytes(), &val.serialize()[..]);
+        assert_eq!(format!("{}", NumberValue::default()), "0");
+    }
+
     #[test]
     fn to_f64_works() {
         use assert_float_eq::*;
@@ -639,4 +1943,102 @@ mod tests {
             -0.1015
         );
     }
+
+    #[test]
+    fn to_f64_breaks_ties_with_distant_fractions() {
+        let num = |integer, fraction, fraction_length| NumberValue {
+            integer,
+            fraction,
+            fraction_length,
+            exponent: 0,
+            negative: false,
+        };
+        // 2^53 + 1 lies halfway between two floats and rounds to the even one.
+        assert_eq!(num(9007199254740993, 0, 0).to_f64(), 9007199254740992.0);
+        assert_eq!(
+            num(9007199254740993, 1, u32::MAX).to_f64(),
+            9007199254740994.0
+        );
+        assert_eq!(num(0, 1, u32::MAX).to_f64(), 0.0);
+        assert_eq!(num(u128::MAX, u128::MAX, 64).to_f64(), u128::MAX as f64);
+
+        // The widest fraction that is written out in full: 64 zeros before 39 digits.
+        let widest = NumberValue {
+            negative: true,
+            exponent: i32::MIN,
+            ..num(u128::MAX, u128::MAX, 103)
+        };
+        assert_eq!(widest.to_f64(), -0.0);
+        let widest = NumberValue {
+            exponent: 0,
+            ..widest
+        };
+        assert_eq!(widest.to_f64(), -(u128::MAX as f64));
+        assert_eq!(widest.to_f32(), f32::NEG_INFINITY);
+        let text = "340282366920938463463374607431768211455.\
+            0000000000000000000000000000000000000000000000000000000000000\
+            340282366920938463463374607431768211455";
+        let parsed = crate::json_parser::parse_json(text).unwrap();
+        let parsed = parsed.as_number().unwrap();
+        assert_eq!(parsed.fraction_length, 100);
+        assert_eq!(parsed.to_f64(), u128::MAX as f64);
+    }
+
+    #[test]
+    fn negative_zero_works() {
+        use crate::json_parser::parse_json;
+
+        let value = parse_json("[-0,-0.0,-0e400,0]").unwrap();
+        let zeros = value.as_array().unwrap();
+        for zero in &zeros[..3] {
+            let num = zero.as_number().unwrap();
+            assert!(num.negative);
+            assert!(num.to_f64().is_sign_negative());
+            assert_eq!(num.to_f64(), 0.0);
+            assert_eq!(num.to_u64(), Some(0));
+            assert!(num.value_eq(zeros[3].as_number().unwrap()));
+            assert_eq!(num.normalize(), NumberValue::default());
+            assert_eq!(zero.stable_hash(), zeros[3].stable_hash());
+        }
+        assert_eq!(value.serialize(), b"[-0,-0,-0e400,0]".to_vec());
+    }
+
+    #[test]
+    fn to_f32_works() {
+        use assert_float_eq::*;
+
+        assert_f32_near!(
+            NumberValue {
+                integer: 1,
+                fraction: 5,
+                fraction_length: 1,
+                exponent: 0,
+                negative: true,
+            }
+            .to_f32(),
+            -1.5
+        );
+
+        assert_f32_near!(
+            NumberValue {
+                integer: 1,
+                fraction: 15,
+                fraction_length: 3,
+                exponent: -1,
+                negative: false,
+            }
+            .to_f32(),
+            0.1015
+        );
+
+        let val: f32 = NumberValue {
+            integer: 3,
+            fraction: 0,
+            fraction_length: 0,
+            exponent: 38,
+            negative: false,
+        }
+        .into();
+        assert_f32_near!(val, 3e38);
+    }
 }
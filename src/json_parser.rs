@@ -2,21 +2,28 @@
 extern crate alloc;
 
 #[cfg(not(feature = "std"))]
-use alloc::vec::Vec;
+use alloc::{string, vec, vec::Vec};
+#[cfg(feature = "std")]
+use std::string;
 
-use crate::json::{JsonObject, JsonValue, NumberValue};
+use crate::json::{utf16_lossy, JsonObject, JsonValue, NonFinite, NumberValue};
+use crate::parse_error::{ParseError, ParseErrorKind};
 use lite_parser::{
-    impls::SimpleError,
+    impls::SimplePosition,
     literals,
     parser::{
-        Concat, Concat3, Either, OneOf, OneOrMore, Parser, ParserContext, ParserOptions,
+        Concat, Concat3, Concat4, Either, OneOf, OneOrMore, Parser, ParserContext, ParserState,
         ZeroOrMore, ZeroOrOne,
     },
     parsers,
     traits::{Error, Input, ResultOf},
 };
 
+pub use lite_parser::parser::{LoneSurrogates, NumberOverflow, ParserOptions};
+
 use core::convert::TryInto;
+use core::mem::size_of;
+use core::str::FromStr;
 
 literals! {
     pub WhitespaceChar => '\u{0020}' | '\u{000D}' | '\u{000A}' | '\u{0009}';
@@ -35,77 +42,204 @@ literals! {
     pub CloseSquareBracketChar => ']';
 }
 
-pub type Whitespace = ZeroOrMore<WhitespaceChar>;
+/// Whether `ParserOptions::json5` is set and `ParserOptions::strict` is not. Always false
+/// without the `json5` feature.
+fn json5(options: &ParserOptions) -> bool {
+    #[cfg(feature = "json5")]
+    let enabled = options.json5 && !options.strict;
+    #[cfg(not(feature = "json5"))]
+    let enabled = {
+        let _ = options;
+        false
+    };
+    enabled
+}
 
-pub type Sign = ZeroOrOne<SignChar>;
+/// Whether strings may be in single quotes.
+fn single_quotes(options: &ParserOptions) -> bool {
+    !options.strict && options.allow_single_quotes || json5(options)
+}
 
-pub type Digits = OneOrMore<DigitChar>;
+/// Whether object keys may be identifiers.
+fn unquoted_keys(options: &ParserOptions) -> bool {
+    !options.strict && options.allow_unquoted_keys || json5(options)
+}
 
-parsers! {
-    pub PositiveInteger = OneOf<Concat<OneToNineChar, Digits>, DigitChar>, u128, (output) => {
-        match output {
-            Either::A((c, cs)) => {
-                let mut val = c.to_digit(10).unwrap() as u128;
-                for c in cs {
-                    val *= 10;
-                    val += c.to_digit(10).unwrap() as u128;
+/// Whether `NaN` and infinities are accepted.
+fn non_finite(options: &ParserOptions) -> bool {
+    !options.strict && options.allow_non_finite || json5(options)
+}
+
+/// Whether hexadecimal integers are accepted.
+fn hex_numbers(options: &ParserOptions) -> bool {
+    !options.strict && options.allow_hex_numbers || json5(options)
+}
+
+/// Whether numbers may be written as `+1`, `.5` or `5.`.
+fn loose_numbers(options: &ParserOptions) -> bool {
+    !options.strict && options.allow_loose_numbers || json5(options)
+}
+
+/// Whether strings may contain `\xNN` escapes.
+fn hex_escapes(options: &ParserOptions) -> bool {
+    !options.strict && options.allow_hex_escapes || json5(options)
+}
+
+/// Whether the unescaped control character `c` may appear in a string. JSON5 allows all but line
+/// breaks.
+fn control_character_allowed(c: char, options: &ParserOptions) -> bool {
+    !options.strict && options.allow_control_characters || json5(options) && c != '\n' && c != '\r'
+}
+
+/// Whether a backslash before a line break continues a string on the next line. Raw line breaks
+/// are only allowed by `ParserOptions::allow_multiline_strings`, as JSON5 does not have them.
+fn line_continuations(options: &ParserOptions) -> bool {
+    raw_line_breaks(options) || json5(options)
+}
+
+/// Whether strings may contain raw line breaks.
+fn raw_line_breaks(options: &ParserOptions) -> bool {
+    !options.strict && options.allow_multiline_strings
+}
+
+/// Whether `//` and `/* */` comments are skipped as whitespace.
+fn comments(options: &ParserOptions) -> bool {
+    !options.strict && options.allow_comments || json5(options)
+}
+
+/// Skips the sign of a number: `-`, or `+` if loose numbers are allowed. Returns whether the
+/// number is negative.
+fn sign<I: Input>(input: &I, current: I::Position, options: &ParserOptions) -> (bool, I::Position) {
+    match input.next(current) {
+        Ok(('-', next)) => (true, next),
+        Ok(('+', next)) if loose_numbers(options) => (false, next),
+        _ => (false, current),
+    }
+}
+
+/// Skips a line terminator, treating `\r\n` as one. Returns None if there is none.
+fn line_terminator<I: Input>(input: &I, current: I::Position) -> Option<I::Position> {
+    match input.next(current) {
+        Ok(('\r', after)) => match input.next(after) {
+            Ok(('\n', end)) => Some(end),
+            _ => Some(after),
+        },
+        Ok(('\n' | '\u{2028}' | '\u{2029}', after)) => Some(after),
+        _ => None,
+    }
+}
+
+/// Skips whitespace, and comments if `ParserOptions::allow_comments` is set. Under JSON5 any
+/// Unicode space separator or byte order mark is whitespace too.
+pub struct Whitespace;
+
+impl<I: Input> Parser<I> for Whitespace {
+    type Output = ();
+    fn parse(
+        input: &I,
+        current: I::Position,
+        context: &ParserContext,
+    ) -> ResultOf<I, Self::Output> {
+        let json5 = json5(context.options());
+        let mut next = current;
+        loop {
+            if let Ok((_, after)) = <WhitespaceChar as Parser<I>>::parse(input, next, context) {
+                next = after;
+            } else if comments(context.options()) && matches!(input.next(next), Ok(('/', _))) {
+                let (_, after) = <Comment as Parser<I>>::parse(input, next, context)?;
+                next = after;
+            } else {
+                match input.next(next) {
+                    Ok((c, after)) if json5 && (c.is_whitespace() || c == '\u{FEFF}') => {
+                        next = after;
+                    }
+                    _ => return Ok(((), next)),
+                }
+            }
+        }
+    }
+}
+
+/// A `// line` or `/* block */` comment, whose text is returned without the delimiters.
+pub struct Comment;
+
+impl<I: Input> Parser<I> for Comment {
+    type Output = Vec<char>;
+    fn parse(
+        input: &I,
+        current: I::Position,
+        _context: &ParserContext,
+    ) -> ResultOf<I, Self::Output> {
+        let mut text = Vec::new();
+        let (_, next) = input.next(current)?;
+        match input.next(next) {
+            Ok(('/', mut next)) => {
+                while let Ok((c, after)) = input.next(next) {
+                    if c == '\n' {
+                        break;
+                    }
+                    text.push(c);
+                    next = after;
                 }
-                val
+                if text.last() == Some(&'\r') {
+                    text.pop();
+                }
+                Ok((text, next))
+            }
+            Ok(('*', mut next)) => loop {
+                let (c, after) = input
+                    .next(next)
+                    .map_err(|e| e.add_reason(current, "Comment"))?;
+                if c == '*' {
+                    if let Ok(('/', end)) = input.next(after) {
+                        return Ok((text, end));
+                    }
+                }
+                text.push(c);
+                next = after;
             },
-            Either::B(c) => c.to_digit(10).unwrap() as u128,
+            _ => Err(input.error_at(current, "Comment")),
         }
-    };
+    }
+}
 
-    pub NegativeInteger = Concat<NegativeSignChar, PositiveInteger>, u128, (output) => {
-        let (_, output) = output;
-        output
-    };
+pub type Sign = ZeroOrOne<SignChar>;
 
-    pub Integer = OneOf<PositiveInteger, NegativeInteger>, (bool, u128), (output) => {
+pub type Digits = OneOrMore<DigitChar>;
+
+parsers! {
+    pub IntegerDigits = OneOf<Concat<OneToNineChar, Digits>, DigitChar>, Vec<char>, (output) => {
         match output {
-            Either::A(a) => (false, a),
-            Either::B(b) => (true, b),
+            Either::A((c, mut cs)) => {
+                cs.insert(0, c);
+                cs
+            },
+            Either::B(c) => vec![c],
         }
     };
 
-    pub Fraction = ZeroOrOne<Concat<DotChar, Digits>>, (u128, u32), (output) => {
+    pub FractionDigits = ZeroOrOne<Concat<DotChar, Digits>>, Vec<char>, (output) => {
         match output {
-            Either::A((_, cs)) => {
-                let mut val = 0u128;
-                let len = cs.len();
-                for c in cs {
-                    val *= 10u128;
-                    val += c.to_digit(10).unwrap() as u128;
-                }
-                (val, len as u32)
-            },
-            Either::B(_) => (0u128, 0u32),
+            Either::A((_, cs)) => cs,
+            Either::B(_) => Vec::new(),
         }
     };
 
-    pub Exponent = ZeroOrOne<Concat3<EChar, Sign, Digits>>, i32, (output) => {
+    pub ExponentDigits = ZeroOrOne<Concat3<EChar, Sign, Digits>>, (bool, Vec<char>), (output) => {
         match output {
-            Either::A((_, (s, cs))) => {
-                let mul = if let Either::A('-') = s { -1 } else { 1 };
-                let mut val = 0i32;
-                for c in cs {
-                    val *= 10;
-                    val += c.to_digit(10).unwrap() as i32;
-                }
-                val * mul
-            },
-            Either::B(_) => 0,
+            Either::A((_, (s, cs))) => (matches!(s, Either::A('-')), cs),
+            Either::B(_) => (false, Vec::new()),
         }
     };
 
-    pub Number = Concat3<Integer, Fraction, Exponent>, NumberValue, (output) => {
-        let ((s, n), (f, e)) = output;
-        NumberValue {
-            integer: n,
-            fraction: f.0,
-            fraction_length: f.1,
-            exponent: e,
-            negative: s,
+    pub NumberLiteral = Concat4<ZeroOrOne<NegativeSignChar>, IntegerDigits, FractionDigits, ExponentDigits>, NumberParts, (output) => {
+        let (sign, (integer, (fraction, (exponent_negative, exponent)))) = output;
+        NumberParts {
+            negative: matches!(sign, Either::A(_)),
+            integer,
+            fraction,
+            exponent_negative,
+            exponent,
         }
     };
 
@@ -113,276 +247,1128 @@ parsers! {
         output.to_digit(16).unwrap() as u8
     };
 
-    pub String = Concat3<DoubleQuoteChar, Characters, DoubleQuoteChar>, Vec<char>, (output) => {
-        match (output.1).0 {
-            Either::A(bytes) => bytes,
-            Either::B(_) => Vec::new(),
+}
+
+fn digits_value(digits: &[char]) -> Option<u128> {
+    digits.iter().try_fold(0u128, |acc, c| {
+        acc.checked_mul(10)?
+            .checked_add(c.to_digit(10).unwrap() as u128)
+    })
+}
+
+/// Returns the longest prefix of `digits` whose value fits in `u128`.
+fn fitting_prefix(digits: &[char]) -> &[char] {
+    let mut len = digits.len().min(39);
+    while digits_value(&digits[..len]).is_none() {
+        len -= 1;
+    }
+    &digits[..len]
+}
+
+/// The digits of a number as written, before they are converted to a `NumberValue`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberParts {
+    pub negative: bool,
+    pub integer: Vec<char>,
+    pub fraction: Vec<char>,
+    pub exponent_negative: bool,
+    pub exponent: Vec<char>,
+}
+
+impl NumberParts {
+    /// Converts the digits to a `NumberValue`, applying the overflow policies of `options`.
+    ///
+    /// Also returns whether digits were dropped or the exponent was clamped. Fails with the
+    /// policy that refused to convert the number, either `Error` or `Raw`.
+    pub fn to_number(
+        &self,
+        options: &ParserOptions,
+    ) -> Result<(NumberValue, bool), NumberOverflow> {
+        let policy = options.number_overflow;
+        let fraction_policy = options.fraction_overflow.unwrap_or(policy);
+        let refuse = |policy| match policy {
+            NumberOverflow::Error | NumberOverflow::Raw => Err(policy),
+            NumberOverflow::Saturate | NumberOverflow::Lossy => Ok(()),
+        };
+        let mut lost = false;
+
+        let sign = if self.exponent_negative { -1 } else { 1 };
+        let exponent = self.exponent.iter().try_fold(0i32, |acc, c| {
+            acc.checked_mul(10)?
+                .checked_add(sign * c.to_digit(10).unwrap() as i32)
+        });
+        let exponent = match exponent {
+            Some(exponent) => exponent,
+            None => {
+                refuse(policy)?;
+                lost = true;
+                if self.exponent_negative {
+                    i32::MIN
+                } else {
+                    i32::MAX
+                }
+            }
+        };
+
+        let (integer, fraction_digits, exponent) = match digits_value(&self.integer) {
+            Some(integer) => (integer, &self.fraction[..], exponent),
+            None => {
+                refuse(policy)?;
+                lost = true;
+                if policy == NumberOverflow::Saturate {
+                    (u128::MAX, &[][..], exponent)
+                } else {
+                    let kept = fitting_prefix(&self.integer);
+                    let dropped = (self.integer.len() - kept.len())
+                        .try_into()
+                        .unwrap_or(i32::MAX);
+                    let integer = digits_value(kept).ok_or(NumberOverflow::Error)?;
+                    (integer, &[][..], exponent.saturating_add(dropped))
+                }
+            }
+        };
+        let fraction_digits = match digits_value(fraction_digits) {
+            Some(_) => fraction_digits,
+            None => {
+                refuse(fraction_policy)?;
+                lost = true;
+                fitting_prefix(fraction_digits)
+            }
+        };
+        let num = NumberValue {
+            integer,
+            fraction: digits_value(fraction_digits).ok_or(NumberOverflow::Error)?,
+            fraction_length: fraction_digits
+                .len()
+                .try_into()
+                .map_err(|_| NumberOverflow::Error)?,
+            exponent,
+            negative: self.negative,
+        };
+        Ok((num, lost))
+    }
+
+    /// Returns the number as JSON text, as stored by `JsonValue::BigNumber`.
+    pub fn to_text(&self) -> Vec<u8> {
+        let mut text = Vec::with_capacity(self.integer.len() + self.fraction.len() + 4);
+        if self.negative {
+            text.push(b'-');
         }
-    };
+        text.extend(self.integer.iter().map(|c| *c as u8));
+        if !self.fraction.is_empty() {
+            text.push(b'.');
+            text.extend(self.fraction.iter().map(|c| *c as u8));
+        }
+        if !self.exponent.is_empty() {
+            text.push(b'e');
+            if self.exponent_negative {
+                text.push(b'-');
+            }
+            text.extend(self.exponent.iter().map(|c| *c as u8));
+        }
+        text
+    }
 }
 
-pub struct Escape;
+pub struct Number;
 
-impl<I: Input> Parser<I> for Escape {
-    type Output = char;
+impl<I: Input> Parser<I> for Number {
+    type Output = NumberValue;
     fn parse(
         input: &I,
         current: I::Position,
         context: &ParserContext,
     ) -> ResultOf<I, Self::Output> {
-        let (c, next) = input
-            .next(current)
-            .map_err(|e| e.add_reason(current, "Escape"))?;
-        match c {
-            '"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't' => Ok((c, next)),
-            'u' => {
-                let (b1, next) = <Hex as Parser<I>>::parse(input, next, context)?;
-                let (b2, next) = <Hex as Parser<I>>::parse(input, next, context)?;
-                let (b3, next) = <Hex as Parser<I>>::parse(input, next, context)?;
-                let (b4, next) = <Hex as Parser<I>>::parse(input, next, context)?;
-                let byte = (b1 as u32) << 24 | (b2 as u32) << 16 | (b3 as u32) << 8 | (b4 as u32);
-                let c = byte
-                    .try_into()
-                    .map_err(|_| input.error_at(current, "Escape"))?;
-                Ok((c, next))
-            }
-            _ => Err(input.error_at(current, "Escape")),
+        let (parts, next) = <NumberLiteral as Parser<I>>::parse(input, current, context)?;
+        let (num, lost) = parts
+            .to_number(context.options())
+            .map_err(|_| input.error_with_kind(current, ParseErrorKind::NumberOutOfRange))?;
+        if lost {
+            context.mark_lost_precision();
         }
+        Ok((num, next))
     }
 }
 
-pub struct Character;
+/// A number like `NumberLiteral`, which may also start with `+` or a decimal point, or end
+/// with a decimal point. A missing integer part is read as `0`.
+pub struct LooseNumberLiteral;
 
-impl<I: Input> Parser<I> for Character {
-    type Output = char;
+impl<I: Input> Parser<I> for LooseNumberLiteral {
+    type Output = NumberParts;
     fn parse(
         input: &I,
         current: I::Position,
         context: &ParserContext,
     ) -> ResultOf<I, Self::Output> {
-        let (c, next) = input
-            .next(current)
-            .map_err(|e| e.add_reason(current, "Character"))?;
-        match c {
-            '\\' => <Escape as Parser<I>>::parse(input, next, context),
-            '"' => Err(input.error_at(current, "Character")),
-            _ => Ok((c, next)),
+        let (negative, next) = sign(input, current, context.options());
+        let (integer, next) = <IntegerDigits as Parser<I>>::parse(input, next, context)
+            .unwrap_or_else(|_| (Vec::new(), next));
+        let (fraction, next) = match input.next(next) {
+            Ok(('.', next)) => <Digits as Parser<I>>::parse(input, next, context)
+                .unwrap_or_else(|_| (Vec::new(), next)),
+            _ if integer.is_empty() => {
+                return Err(input.error_with_kind(current, ParseErrorKind::InvalidNumber))
+            }
+            _ => (Vec::new(), next),
+        };
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(input.error_with_kind(current, ParseErrorKind::InvalidNumber));
         }
+        let ((exponent_negative, exponent), next) =
+            <ExponentDigits as Parser<I>>::parse(input, next, context)?;
+        let parts = NumberParts {
+            negative,
+            integer: if integer.is_empty() {
+                vec!['0']
+            } else {
+                integer
+            },
+            fraction,
+            exponent_negative,
+            exponent,
+        };
+        Ok((parts, next))
     }
 }
 
-pub type Characters = ZeroOrMore<Character>;
-
-pub struct Member;
+/// A hexadecimal integer such as `0x1F` or `-0xff`, or `+0x1F` with loose numbers, which fails
+/// with `NumberOutOfRange` if it does not fit `u128`.
+pub struct HexNumber;
 
-impl<I: Input> Parser<I> for Member {
-    type Output = (Vec<char>, JsonValue);
+impl<I: Input> Parser<I> for HexNumber {
+    type Output = NumberValue;
     fn parse(
         input: &I,
         current: I::Position,
         context: &ParserContext,
     ) -> ResultOf<I, Self::Output> {
-        let (_, next) = <Whitespace as Parser<I>>::parse(input, current, context)?;
-        let (key, next) = <String as Parser<I>>::parse(input, next, context)?;
-        let (_, next) = <Whitespace as Parser<I>>::parse(input, next, context)?;
-        let next = input
-            .next(next)
-            .and_then(|(c, next)| {
-                if c == ':' {
-                    Ok(next)
-                } else {
-                    Err(input.error_at(next, "Character"))
-                }
-            })
-            .map_err(|e| e.add_reason(current, "Member"))?;
-        let (value, next) = <Element as Parser<I>>::parse(input, next, context)?;
-        Ok(((key, value), next))
+        let (negative, next) = sign(input, current, context.options());
+        let next = match input.next(next) {
+            Ok(('0', next)) => next,
+            _ => return Err(input.error_with_kind(current, ParseErrorKind::InvalidNumber)),
+        };
+        let next = match input.next(next) {
+            Ok(('x' | 'X', next)) => next,
+            _ => return Err(input.error_with_kind(current, ParseErrorKind::InvalidNumber)),
+        };
+        let (digits, next) = <OneOrMore<Hex> as Parser<I>>::parse(input, next, context)
+            .map_err(|_| input.error_with_kind(current, ParseErrorKind::InvalidNumber))?;
+        let integer = digits
+            .iter()
+            .try_fold(0u128, |acc, d| acc.checked_mul(16)?.checked_add(*d as u128))
+            .ok_or_else(|| input.error_with_kind(current, ParseErrorKind::NumberOutOfRange))?;
+        let num = NumberValue {
+            integer,
+            fraction: 0,
+            fraction_length: 0,
+            exponent: 0,
+            negative,
+        };
+        Ok((num, next))
     }
 }
 
-pub struct Element;
+/// Whether a hexadecimal number, optionally signed, starts at `current`.
+fn hex_prefix<I: Input>(input: &I, current: I::Position, options: &ParserOptions) -> bool {
+    let (_, next) = sign(input, current, options);
+    match input.next(next) {
+        Ok(('0', next)) => matches!(input.next(next), Ok(('x' | 'X', _))),
+        _ => false,
+    }
+}
 
-impl<I: Input> Parser<I> for Element {
-    type Output = JsonValue;
+/// `NaN`, `Infinity` or `-Infinity`. Under JSON5 `-NaN` is accepted as `NaN` too, and with
+/// loose numbers a `+` sign is allowed.
+pub struct NonFiniteNumber;
+
+impl<I: Input> Parser<I> for NonFiniteNumber {
+    type Output = NonFinite;
     fn parse(
         input: &I,
         current: I::Position,
         context: &ParserContext,
     ) -> ResultOf<I, Self::Output> {
-        let (_, next) = <Whitespace as Parser<I>>::parse(input, current, context)?;
-        let (output, next) = <Value as Parser<I>>::parse(input, next, context)?;
-        let (_, next) = <Whitespace as Parser<I>>::parse(input, next, context)?;
-        Ok((output, next))
+        let (negative, next) = sign(input, current, context.options());
+        let (text, value) = match input.next(next) {
+            Ok(('I', _)) if negative => ("Infinity", NonFinite::NegativeInfinity),
+            Ok(('I', _)) => ("Infinity", NonFinite::Infinity),
+            Ok(('N', _)) if !negative || json5(context.options()) => ("NaN", NonFinite::NaN),
+            _ => return Err(input.error_at(current, "Value")),
+        };
+        let (_, next) = keyword(input, next, text).map_err(|e| match e.reasons().first() {
+            Some((pos, _)) if input.is_end(*pos) => e,
+            _ => input.error_at(current, "Value"),
+        })?;
+        Ok((value, next))
     }
 }
 
-pub struct Value;
+/// Whether `NaN` or an infinity, optionally signed, starts at `current`.
+fn non_finite_prefix<I: Input>(input: &I, current: I::Position, options: &ParserOptions) -> bool {
+    let (_, next) = sign(input, current, options);
+    matches!(input.next(next), Ok(('I' | 'N', _)))
+}
 
-impl<I: Input> Parser<I> for Value
-where
-    I::Position: Copy,
-{
-    type Output = JsonValue;
+/// Matches the word `text` at `current`.
+fn keyword<I: Input>(input: &I, current: I::Position, text: &str) -> ResultOf<I, ()> {
+    let mut next = current;
+    for expected in text.chars() {
+        let (c, after) = input.next(next)?;
+        if c != expected {
+            return Err(input.error_at(current, "Value"));
+        }
+        next = after;
+    }
+    Ok(((), next))
+}
+
+/// Reads the four hexadecimal digits of a `\u` escape.
+fn code_unit<I: Input>(
+    input: &I,
+    current: I::Position,
+    context: &ParserContext,
+) -> ResultOf<I, u32> {
+    let (b1, next) = <Hex as Parser<I>>::parse(input, current, context)?;
+    let (b2, next) = <Hex as Parser<I>>::parse(input, next, context)?;
+    let (b3, next) = <Hex as Parser<I>>::parse(input, next, context)?;
+    let (b4, next) = <Hex as Parser<I>>::parse(input, next, context)?;
+    Ok((
+        (b1 as u32) << 12 | (b2 as u32) << 8 | (b3 as u32) << 4 | (b4 as u32),
+        next,
+    ))
+}
+
+/// A character of a string, or an unpaired surrogate kept by `LoneSurrogates::Preserve`.
+enum StringUnit {
+    Char(char),
+    LoneSurrogate(u16),
+}
+
+impl StringUnit {
+    /// Returns the character, with U+FFFD for an unpaired surrogate.
+    fn to_char(&self) -> char {
+        match self {
+            StringUnit::Char(c) => *c,
+            StringUnit::LoneSurrogate(_) => char::REPLACEMENT_CHARACTER,
+        }
+    }
+}
+
+/// Reads the unpaired surrogate `unit` of the `\u` escape at `current` as
+/// `ParserOptions::lone_surrogates` says.
+fn lone_surrogate<I: Input>(
+    input: &I,
+    current: I::Position,
+    unit: u32,
+    context: &ParserContext,
+) -> Result<StringUnit, I::Error> {
+    let options = context.options();
+    match options.lone_surrogates {
+        LoneSurrogates::Replace if !options.strict => {
+            Ok(StringUnit::Char(char::REPLACEMENT_CHARACTER))
+        }
+        LoneSurrogates::Preserve if !options.strict => Ok(StringUnit::LoneSurrogate(unit as u16)),
+        _ => Err(input.error_with_kind(current, ParseErrorKind::InvalidEscape)),
+    }
+}
+
+/// The character after a backslash in a string, where a `\u` escape of a high surrogate is
+/// combined with the escaped low surrogate after it. `\'` is an escape too if single quotes are
+/// allowed, and `\xNN` if hex escapes are. Under JSON5, so are `\v` and `\0`, and any other
+/// character except a digit escapes to itself. An unpaired surrogate kept by
+/// `LoneSurrogates::Preserve` is read as U+FFFD, as only string values can hold it.
+pub struct Escape;
+
+impl<I: Input> Parser<I> for Escape {
+    type Output = char;
     fn parse(
         input: &I,
         current: I::Position,
         context: &ParserContext,
     ) -> ResultOf<I, Self::Output> {
-        if let Ok((output, next)) = <Object as Parser<I>>::parse(input, current, context) {
-            return Ok((JsonValue::Object(output), next));
-        }
-        if let Ok((output, next)) = <Array as Parser<I>>::parse(input, current, context) {
-            return Ok((JsonValue::Array(output), next));
-        }
-        if let Ok((output, next)) = <String as Parser<I>>::parse(input, current, context) {
-            return Ok((JsonValue::String(output), next));
-        }
-        if let Ok((output, next)) = <Number as Parser<I>>::parse(input, current, context) {
-            return Ok((JsonValue::Number(output), next));
+        let (unit, next) = escape(input, current, context)?;
+        Ok((unit.to_char(), next))
+    }
+}
+
+/// Parses an escape like `Escape`, keeping an unpaired surrogate if `LoneSurrogates::Preserve`
+/// is set.
+fn escape<I: Input>(
+    input: &I,
+    current: I::Position,
+    context: &ParserContext,
+) -> ResultOf<I, StringUnit> {
+    let (c, next) = input
+        .next(current)
+        .map_err(|e| e.add_reason(current, "Escape"))?;
+    let json5 = json5(context.options());
+    let c = match c {
+        '"' | '\\' | '/' => c,
+        'b' => '\u{8}',
+        'f' => '\u{C}',
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        'u' => {
+            let (unit, next) = code_unit(input, next, context)?;
+            let (code, next) = match unit {
+                // A high surrogate must be followed by an escaped low surrogate.
+                0xD800..=0xDBFF => {
+                    let low = match input.next(next) {
+                        Ok(('\\', after)) => match input.next(after) {
+                            Ok(('u', after)) => Some(code_unit(input, after, context)?),
+                            _ => None,
+                        },
+                        _ => None,
+                    };
+                    match low {
+                        Some((low @ 0xDC00..=0xDFFF, end)) => {
+                            (0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00), end)
+                        }
+                        _ => return Ok((lone_surrogate(input, current, unit, context)?, next)),
+                    }
+                }
+                0xDC00..=0xDFFF => {
+                    return Ok((lone_surrogate(input, current, unit, context)?, next))
+                }
+                _ => (unit, next),
+            };
+            let c = code
+                .try_into()
+                .map_err(|_| input.error_with_kind(current, ParseErrorKind::InvalidEscape))?;
+            return Ok((StringUnit::Char(c), next));
         }
-        let (value, next) = input.next_range(current, 4)?;
-        if value == "null" {
-            return Ok((JsonValue::Null, next));
+        '\'' if single_quotes(context.options()) => c,
+        'x' if hex_escapes(context.options()) => {
+            let (b1, next) = <Hex as Parser<I>>::parse(input, next, context)?;
+            let (b2, next) = <Hex as Parser<I>>::parse(input, next, context)?;
+            return Ok((StringUnit::Char(char::from(b1 << 4 | b2)), next));
         }
-        if value == "true" {
-            return Ok((JsonValue::Boolean(true), next));
+        'v' if json5 => '\u{B}',
+        '0' if json5 && !matches!(input.next(next), Ok(('0'..='9', _))) => '\0',
+        '0'..='9' | 'x' => {
+            return Err(input.error_with_kind(current, ParseErrorKind::InvalidEscape))
         }
-        let (value, next) = input.next_range(current, 5)?;
-        if value == "false" {
-            return Ok((JsonValue::Boolean(false), next));
+        _ if json5 && line_terminator(input, current).is_none() => c,
+        _ => return Err(input.error_with_kind(current, ParseErrorKind::InvalidEscape)),
+    };
+    Ok((StringUnit::Char(c), next))
+}
+
+/// A character in a string: an escape, or anything but `"` and control characters unless
+/// `ParserOptions::allow_control_characters` is set.
+pub struct Character;
+
+impl<I: Input> Parser<I> for Character {
+    type Output = char;
+    fn parse(
+        input: &I,
+        current: I::Position,
+        context: &ParserContext,
+    ) -> ResultOf<I, Self::Output> {
+        let (unit, next) = character(input, current, context)?;
+        Ok((unit.to_char(), next))
+    }
+}
+
+/// Parses a character like `Character`, keeping an unpaired surrogate if
+/// `LoneSurrogates::Preserve` is set.
+fn character<I: Input>(
+    input: &I,
+    current: I::Position,
+    context: &ParserContext,
+) -> ResultOf<I, StringUnit> {
+    let (c, next) = input
+        .next(current)
+        .map_err(|e| e.add_reason(current, "Character"))?;
+    match c {
+        '\\' => escape(input, next, context).map_err(|e| {
+            // Point at the backslash, unless the input ended inside the escape.
+            match e.reasons().first() {
+                Some((pos, _)) if input.is_end(*pos) => e,
+                _ => input.error_with_kind(current, ParseErrorKind::InvalidEscape),
+            }
+        }),
+        '"' => Err(input.error_at(current, "Character")),
+        '\u{0}'..='\u{1F}' if !control_character_allowed(c, context.options()) => {
+            Err(input.error_at(current, "Character"))
         }
-        Err(input.error_at(current, "Value"))
+        _ => Ok((StringUnit::Char(c), next)),
     }
 }
 
-pub struct Object;
+pub type Characters = ZeroOrMore<Character>;
 
-impl<I: Input> Parser<I> for Object {
-    type Output = JsonObject;
+/// A string in double quotes, or in single quotes if `ParserOptions::allow_single_quotes` is
+/// set. With multi-line strings or JSON5, a backslash before a line break continues the string
+/// on the next line. An unpaired surrogate kept by `LoneSurrogates::Preserve` is read as
+/// U+FFFD, as only string values can hold it.
+pub struct String;
+
+impl<I: Input> Parser<I> for String {
+    type Output = Vec<char>;
     fn parse(
         input: &I,
         current: I::Position,
         context: &ParserContext,
     ) -> ResultOf<I, Self::Output> {
-        let context = &context.nest(input, current)?;
-        let (_, next) = <OpenCurlyBracketChar as Parser<I>>::parse(input, current, context)?;
-        let (output, next) =
-            <OneOf<Members, Whitespace> as Parser<I>>::parse(input, next, context)?;
-        let (_, next) = <CloseCurlyBracketChar as Parser<I>>::parse(input, next, context)?;
-        let output = match output {
-            Either::A(a) => a,
-            Either::B(_) => Vec::new(),
+        let (value, next) = string_value(input, current, context)?;
+        let chars = match value {
+            JsonValue::Utf16String(units) => utf16_lossy(&units),
+            JsonValue::String(chars) => chars,
+            _ => unreachable!("strings are parsed as strings"),
         };
-        Ok((output, next))
+        Ok((chars, next))
+    }
+}
+
+/// Parses a string like `String` as a `JsonValue::String`, or as a `JsonValue::Utf16String`
+/// if it has an unpaired surrogate kept by `LoneSurrogates::Preserve`.
+fn string_value<I: Input>(
+    input: &I,
+    current: I::Position,
+    context: &ParserContext,
+) -> ResultOf<I, JsonValue> {
+    let (quote, mut next) = match input.next(current) {
+        Ok(('\'', next)) if single_quotes(context.options()) => ('\'', next),
+        _ => <DoubleQuoteChar as Parser<I>>::parse(input, current, context)?,
+    };
+    let mut chars = Vec::new();
+    // The code units of the string from its first unpaired surrogate on.
+    let mut units: Option<Vec<u16>> = None;
+    let mut len = 0;
+    loop {
+        match input.next(next) {
+            Ok((c, end)) if c == quote => {
+                let value = match units {
+                    Some(units) => JsonValue::Utf16String(units),
+                    None => JsonValue::String(chars),
+                };
+                return Ok((value, end));
+            }
+            Ok(('\\', after)) if line_continuations(context.options()) => {
+                if let Some(after) = line_terminator(input, after) {
+                    next = after;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        if Some(len) == context.options().max_string_length {
+            return Err(input.error_with_kind(current, ParseErrorKind::StringTooLong));
+        }
+        context.allocate(input, current, size_of::<char>())?;
+        let (unit, after) = match input.next(next) {
+            // Only reached inside a single-quoted string.
+            Ok(('"', after)) => (StringUnit::Char('"'), after),
+            Ok((c @ ('\n' | '\r'), after)) if raw_line_breaks(context.options()) => {
+                (StringUnit::Char(c), after)
+            }
+            _ => character(input, next, context).map_err(|e| e.add_reason(current, "String"))?,
+        };
+        match (unit, &mut units) {
+            (StringUnit::Char(c), None) => chars.push(c),
+            (StringUnit::Char(c), Some(units)) => {
+                units.extend_from_slice(c.encode_utf16(&mut [0; 2]));
+            }
+            (StringUnit::LoneSurrogate(surrogate), units) => {
+                let units = units.get_or_insert_with(|| {
+                    chars
+                        .iter()
+                        .collect::<string::String>()
+                        .encode_utf16()
+                        .collect()
+                });
+                units.push(surrogate);
+            }
+        }
+        len += 1;
+        next = after;
     }
 }
 
-pub struct Members;
+/// An unquoted object key such as `$id` or `_tag2`, as described by
+/// `ParserOptions::allow_unquoted_keys`. Like an ECMAScript identifier it may contain `\uXXXX`
+/// escapes, which must also stand for allowed characters.
+pub struct Identifier;
 
-impl<I: Input> Parser<I> for Members {
-    type Output = Vec<(Vec<char>, JsonValue)>;
+impl<I: Input> Parser<I> for Identifier {
+    type Output = Vec<char>;
     fn parse(
         input: &I,
         current: I::Position,
         context: &ParserContext,
     ) -> ResultOf<I, Self::Output> {
-        let (output, next) = <Member as Parser<I>>::parse(input, current, context)?;
-        let (rest, next) =
-            <ZeroOrMore<Concat<CommaChar, Member>> as Parser<I>>::parse(input, next, context)?;
-        let mut result = Vec::new();
-        result.push(output);
-        if let Either::A(rest) = rest {
-            result.extend(rest.into_iter().map(|(_, m)| m))
+        let mut output = Vec::new();
+        let mut next = current;
+        loop {
+            let (c, after, escaped) = match input.next(next) {
+                Ok(('\\', after)) => match input.next(after) {
+                    Ok(('u', _)) => {
+                        let (c, after) = <Escape as Parser<I>>::parse(input, after, context)?;
+                        (c, after, true)
+                    }
+                    _ => return Err(input.error_with_kind(next, ParseErrorKind::InvalidEscape)),
+                },
+                Ok((c, after)) => (c, after, false),
+                Err(_) => break,
+            };
+            let valid = c == '$'
+                || c == '_'
+                || c.is_alphabetic()
+                || (!output.is_empty()
+                    && (c.is_alphanumeric() || c == '\u{200C}' || c == '\u{200D}'));
+            if !valid {
+                if escaped {
+                    return Err(input.error_with_kind(next, ParseErrorKind::InvalidEscape));
+                }
+                break;
+            }
+            if Some(output.len()) == context.options().max_string_length {
+                return Err(input.error_with_kind(current, ParseErrorKind::StringTooLong));
+            }
+            context.allocate(input, current, size_of::<char>())?;
+            output.push(c);
+            next = after;
         }
-        Ok((result, next))
+        if output.is_empty() {
+            return Err(input.error_at(current, "Identifier"));
+        }
+        Ok((output, next))
     }
 }
 
-pub struct Elements;
+/// Parses the start of a member, up to and including its colon, and returns its key.
+fn member_key<I: Input>(
+    input: &I,
+    current: I::Position,
+    context: &ParserContext,
+) -> ResultOf<I, Vec<char>> {
+    let (_, next) = <Whitespace as Parser<I>>::parse(input, current, context)?;
+    context.allocate(input, next, size_of::<Vec<char>>())?;
+    let (key, next) = match input.next(next) {
+        Ok((c, _)) if unquoted_keys(context.options()) && c != '"' && c != '\'' => {
+            <Identifier as Parser<I>>::parse(input, next, context)?
+        }
+        _ => <String as Parser<I>>::parse(input, next, context)?,
+    };
+    let (_, next) = <Whitespace as Parser<I>>::parse(input, next, context)?;
+    let next = input
+        .next(next)
+        .and_then(|(c, after)| {
+            if c == ':' {
+                Ok(after)
+            } else {
+                Err(input.error_at(next, "Character"))
+            }
+        })
+        .map_err(|e| e.add_reason(current, "Member"))?;
+    Ok((key, next))
+}
+
+pub struct Member;
 
-impl<I: Input> Parser<I> for Elements {
-    type Output = Vec<JsonValue>;
+impl<I: Input> Parser<I> for Member {
+    type Output = (Vec<char>, JsonValue);
     fn parse(
         input: &I,
         current: I::Position,
         context: &ParserContext,
     ) -> ResultOf<I, Self::Output> {
-        let (output, next) = <Element as Parser<I>>::parse(input, current, context)?;
-        let (rest, next) =
-            <ZeroOrMore<Concat<CommaChar, Element>> as Parser<I>>::parse(input, next, context)?;
-        let mut result = Vec::new();
-        result.push(output);
-        if let Either::A(rest) = rest {
-            result.extend(rest.into_iter().map(|(_, m)| m))
-        }
-        Ok((result, next))
+        let (key, next) = member_key(input, current, context)?;
+        let (value, next) = <Element as Parser<I>>::parse(input, next, context)?;
+        Ok(((key, value), next))
     }
 }
 
-pub struct Array;
+pub struct Element;
 
-impl<I: Input> Parser<I> for Array {
-    type Output = Vec<JsonValue>;
+impl<I: Input> Parser<I> for Element {
+    type Output = JsonValue;
     fn parse(
         input: &I,
         current: I::Position,
         context: &ParserContext,
     ) -> ResultOf<I, Self::Output> {
-        let context = &context.nest(input, current)?;
-        let (_, next) = <OpenSquareBracketChar as Parser<I>>::parse(input, current, context)?;
-        let (output, next) =
-            <OneOf<Elements, Whitespace> as Parser<I>>::parse(input, next, context)?;
-        let (_, next) = <CloseSquareBracketChar as Parser<I>>::parse(input, next, context)?;
-        let output = match output {
-            Either::A(a) => a,
-            Either::B(_) => Vec::new(),
-        };
+        let (_, next) = <Whitespace as Parser<I>>::parse(input, current, context)?;
+        let (output, next) = <Value as Parser<I>>::parse(input, next, context)?;
+        let (_, next) = <Whitespace as Parser<I>>::parse(input, next, context)?;
         Ok((output, next))
     }
 }
 
-pub struct Json;
+pub struct Value;
 
-impl<I: Input> Parser<I> for Json {
-    type Output = <Element as Parser<I>>::Output;
+impl<I: Input> Parser<I> for Value
+where
+    I::Position: Copy,
+{
+    type Output = JsonValue;
     fn parse(
         input: &I,
         current: I::Position,
         context: &ParserContext,
     ) -> ResultOf<I, Self::Output> {
-        let (_, next) = <Whitespace as Parser<I>>::parse(input, current, context)?;
-        let (res, next) = <Element as Parser<I>>::parse(input, next, context)?;
-        let (_, next) = <Whitespace as Parser<I>>::parse(input, next, context)?;
-        if input.is_end(next) {
-            Ok((res, next))
-        } else {
-            Err(input.error_at(next, "Expect end of input"))
+        context.step(input, current, 1)?;
+        context.allocate(input, current, size_of::<JsonValue>())?;
+        // The first character decides the kind of value, so errors inside it are reported
+        // where they happen rather than at the start of the value.
+        let (c, _) = input
+            .next(current)
+            .map_err(|e| e.add_reason(current, "Value"))?;
+        match c {
+            '{' | '[' => nested(input, current, context),
+            _ => scalar(input, current, c, context),
         }
     }
 }
 
-pub fn parse_json(input: &str) -> Result<JsonValue, SimpleError> {
-    parse_json_with_options(input, Default::default())
-}
+/// Parses a value other than an array or object, which starts with `c`.
+fn scalar<I: Input>(
+    input: &I,
+    current: I::Position,
+    c: char,
+    context: &ParserContext,
+) -> ResultOf<I, JsonValue> {
+    match c {
+        '"' | '\'' => string_value(input, current, context),
+        '-' | '+' | 'I' | 'N'
+            if non_finite(context.options())
+                && non_finite_prefix(input, current, context.options()) =>
+        {
+            let (n, next) = <NonFiniteNumber as Parser<I>>::parse(input, current, context)?;
+            Ok((JsonValue::NonFinite(n), next))
+        }
+        '-' | '+' | '0'
+            if hex_numbers(context.options()) && hex_prefix(input, current, context.options()) =>
+        {
+            let (num, next) = <HexNumber as Parser<I>>::parse(input, current, context)?;
+            Ok((JsonValue::Number(num), next))
+        }
+        '+' | '.' if !loose_numbers(context.options()) => Err(input.error_at(current, "Value")),
+        '-' | '+' | '.' | '0'..='9' => {
+            let parts = if loose_numbers(context.options()) {
+                <LooseNumberLiteral as Parser<I>>::parse(input, current, context)
+            } else {
+                <NumberLiteral as Parser<I>>::parse(input, current, context)
+            };
+            let (parts, next) =
+                parts.map_err(|_| input.error_with_kind(current, ParseErrorKind::InvalidNumber))?;
+            // A number cannot be followed by more number characters, as in `01` or `1.`.
+            if let Ok(('0'..='9' | '.' | 'e' | 'E' | '+' | '-', _)) = input.next(next) {
+                return Err(input.error_with_kind(current, ParseErrorKind::InvalidNumber));
+            }
+            match parts.to_number(context.options()) {
+                Ok((num, lost)) => {
+                    if lost {
+                        context.mark_lost_precision();
+                    }
+                    Ok((JsonValue::Number(num), next))
+                }
+                Err(NumberOverflow::Raw) => {
+                    let text = parts.to_text();
+                    context.allocate(input, current, text.len())?;
+                    Ok((JsonValue::BigNumber(text), next))
+                }
+                Err(_) => Err(input.error_with_kind(current, ParseErrorKind::NumberOutOfRange)),
+            }
+        }
+        _ => {
+            let (text, value) = match c {
+                'n' => ("null", JsonValue::Null),
+                't' => ("true", JsonValue::Boolean(true)),
+                'f' => ("false", JsonValue::Boolean(false)),
+                _ => return Err(input.error_at(current, "Value")),
+            };
+            let (_, next) = keyword(input, current, text)?;
+            Ok((value, next))
+        }
+    }
+}
+
+/// An array or object being parsed by `nested`.
+struct Open<'a> {
+    object: bool,
+    items: Vec<JsonValue>,
+    members: JsonObject,
+    /// The key of the member whose value is being parsed.
+    key: Vec<char>,
+    /// The context for the contents.
+    context: ParserContext<'a>,
+}
+
+impl Open<'_> {
+    fn close(&self) -> char {
+        if self.object {
+            '}'
+        } else {
+            ']'
+        }
+    }
+
+    fn push(&mut self, value: JsonValue) {
+        if self.object {
+            let key = core::mem::take(&mut self.key);
+            self.members.push((key, value));
+        } else {
+            self.items.push(value);
+        }
+    }
+
+    fn into_value(self) -> JsonValue {
+        if self.object {
+            JsonValue::Object(self.members)
+        } else {
+            JsonValue::Array(self.items)
+        }
+    }
+}
+
+/// What `nested` does next.
+enum Step<P> {
+    /// Parse an item or member starting here.
+    Entry(P),
+    /// Close the innermost array or object, whose bracket ends here.
+    Close(P),
+}
+
+/// Parses what follows an item or member, a comma or the closing bracket.
+fn separator<I: Input>(
+    input: &I,
+    current: I::Position,
+    open: &Open,
+) -> Result<Step<I::Position>, I::Error> {
+    match input.next(current) {
+        Ok((',', next)) => {
+            if json5(open.context.options()) {
+                let (_, start) = <Whitespace as Parser<I>>::parse(input, next, &open.context)?;
+                if let Ok((c, end)) = input.next(start) {
+                    if c == open.close() {
+                        return Ok(Step::Close(end));
+                    }
+                }
+            }
+            Ok(Step::Entry(next))
+        }
+        Ok((c, end)) if c == open.close() => Ok(Step::Close(end)),
+        _ => Err(input.error_at(current, if open.object { "Object" } else { "Array" })),
+    }
+}
+
+/// Parses the array or object at `current`, like `Array` and `Object`. The arrays and objects
+/// being parsed are kept on the heap rather than the call stack, so deeply nested input cannot
+/// overflow it, even without a `max_nest_level`.
+fn nested<I: Input>(
+    input: &I,
+    current: I::Position,
+    context: &ParserContext,
+) -> ResultOf<I, JsonValue> {
+    let mut stack: Vec<Open> = Vec::new();
+    let mut start = current;
+    loop {
+        // Open the array or object at `start`, whose step and allocation are counted.
+        let parent = stack.last().map_or(context, |open| &open.context);
+        let context = parent.nest(input, start)?;
+        let (bracket, next) = input.next(start)?;
+        let (_, next) = <Whitespace as Parser<I>>::parse(input, next, &context)?;
+        let open = Open {
+            object: bracket == '{',
+            items: Vec::new(),
+            members: Vec::new(),
+            key: Vec::new(),
+            context,
+        };
+        let mut step = match input.next(next) {
+            Ok((c, end)) if c == open.close() => Step::Close(end),
+            _ => Step::Entry(next),
+        };
+        stack.push(open);
+
+        // Parse entries and close containers until another array or object starts.
+        start = loop {
+            let open = stack.last_mut().expect("inside an array or object");
+            step = match step {
+                Step::Entry(next) => {
+                    let context = &open.context;
+                    let options = context.options();
+                    let (count, limit, kind) = if open.object {
+                        (
+                            open.members.len(),
+                            options.max_object_members,
+                            ParseErrorKind::TooManyObjectMembers,
+                        )
+                    } else {
+                        (
+                            open.items.len(),
+                            options.max_array_length,
+                            ParseErrorKind::TooManyArrayItems,
+                        )
+                    };
+                    if Some(count) == limit {
+                        let (_, start) = <Whitespace as Parser<I>>::parse(input, next, context)?;
+                        return Err(input.error_with_kind(start, kind));
+                    }
+                    let next = if open.object {
+                        let (key, next) = member_key(input, next, context)?;
+                        open.key = key;
+                        next
+                    } else {
+                        next
+                    };
+                    // The rest is `Element` and `Value`, without descending.
+                    let (_, next) = <Whitespace as Parser<I>>::parse(input, next, context)?;
+                    context.step(input, next, 1)?;
+                    context.allocate(input, next, size_of::<JsonValue>())?;
+                    let (c, _) = input.next(next).map_err(|e| e.add_reason(next, "Value"))?;
+                    if c == '{' || c == '[' {
+                        break next;
+                    }
+                    let (value, end) = scalar(input, next, c, context)?;
+                    let (_, after) = <Whitespace as Parser<I>>::parse(input, end, context)?;
+                    open.push(value);
+                    separator(input, after, open)?
+                }
+                Step::Close(end) => {
+                    let value = stack.pop().expect("inside an array or object").into_value();
+                    let parent = match stack.last_mut() {
+                        Some(parent) => parent,
+                        None => return Ok((value, end)),
+                    };
+                    let (_, after) = <Whitespace as Parser<I>>::parse(input, end, &parent.context)?;
+                    parent.push(value);
+                    separator(input, after, parent)?
+                }
+            };
+        };
+    }
+}
+
+pub struct Object;
+
+impl<I: Input> Parser<I> for Object {
+    type Output = JsonObject;
+    fn parse(
+        input: &I,
+        current: I::Position,
+        context: &ParserContext,
+    ) -> ResultOf<I, Self::Output> {
+        <OpenCurlyBracketChar as Parser<I>>::parse(input, current, context)?;
+        match nested(input, current, context)? {
+            (JsonValue::Object(output), next) => Ok((output, next)),
+            _ => unreachable!("the input starts with an opening brace"),
+        }
+    }
+}
+
+pub struct Array;
+
+impl<I: Input> Parser<I> for Array {
+    type Output = Vec<JsonValue>;
+    fn parse(
+        input: &I,
+        current: I::Position,
+        context: &ParserContext,
+    ) -> ResultOf<I, Self::Output> {
+        <OpenSquareBracketChar as Parser<I>>::parse(input, current, context)?;
+        match nested(input, current, context)? {
+            (JsonValue::Array(output), next) => Ok((output, next)),
+            _ => unreachable!("the input starts with an opening bracket"),
+        }
+    }
+}
+
+pub struct Json;
+
+impl<I: Input> Parser<I> for Json {
+    type Output = <Element as Parser<I>>::Output;
+    fn parse(
+        input: &I,
+        current: I::Position,
+        context: &ParserContext,
+    ) -> ResultOf<I, Self::Output> {
+        let (_, next) = <Whitespace as Parser<I>>::parse(input, current, context)?;
+        let (res, next) = <Element as Parser<I>>::parse(input, next, context)?;
+        let (_, next) = <Whitespace as Parser<I>>::parse(input, next, context)?;
+        if input.is_end(next) {
+            Ok((res, next))
+        } else {
+            Err(input.error_with_kind(next, ParseErrorKind::TrailingCharacters))
+        }
+    }
+}
+
+pub fn parse_json(input: &str) -> Result<JsonValue, ParseError> {
+    parse_json_with_options(input, Default::default())
+}
 
 pub fn parse_json_with_options(
     input: &str,
     options: ParserOptions,
-) -> Result<JsonValue, SimpleError> {
-    Json::parse(&input, Default::default(), &ParserContext::new(options)).map(|(ret, _)| ret)
+) -> Result<JsonValue, ParseError> {
+    parse_with(input, &options)
+}
+
+/// Parses JSON with a shared configuration, such as one of the `ParserOptions::strict` and
+/// `ParserOptions::relaxed` presets, or a `ParserOptions` kept for all the documents of a
+/// service. The options are borrowed, not copied.
+pub fn parse_with(input: &str, config: &ParserOptions) -> Result<JsonValue, ParseError> {
+    parse_json_with_context(input, &ParserContext::new(config, &ParserState::default()))
+}
+
+/// Parses JSON with a caller-provided context, whose `ParserState` can be inspected afterwards,
+/// for example with `ParserState::lost_precision` or `ParserState::steps`.
+///
+/// Every byte of input is counted as a step before parsing starts, so input that cannot fit the
+/// step budget is rejected without being read, as is input of 4 GiB or more. A leading UTF-8
+/// byte order mark is skipped, unless `ParserOptions::strict` is set.
+pub fn parse_json_with_context(
+    input: &str,
+    context: &ParserContext,
+) -> Result<JsonValue, ParseError> {
+    let position = SimplePosition::default();
+    // Positions hold `u32` byte offsets, so longer input is rejected whatever the options say.
+    let max = context
+        .options()
+        .max_input_length
+        .map_or(u32::MAX as usize, |max| max.min(u32::MAX as usize));
+    if input.len() > max {
+        return Err(ParseError::new(
+            input,
+            position,
+            ParseErrorKind::InputTooLong,
+        ));
+    }
+    context
+        .step(&input, position, input.len() as u64)
+        .map_err(|e| ParseError::from_simple(input, e))?;
+    let start = if context.options().strict {
+        position
+    } else {
+        skip_bom(input)
+    };
+    Json::parse(&input, start, context)
+        .map(|(ret, _)| ret)
+        .map_err(|e| ParseError::from_simple(input, e))
+}
+
+/// Returns where parsing starts: after the UTF-8 byte order mark, if any.
+pub(crate) fn skip_bom(input: &str) -> SimplePosition {
+    SimplePosition {
+        index: if input.starts_with('\u{FEFF}') { 3 } else { 0 },
+        line: 0,
+        column: 0,
+    }
+}
+
+/// Fails if `input` starts with a UTF-16 byte order mark, in either byte order.
+pub(crate) fn reject_utf16(input: &[u8]) -> Result<(), ParseError> {
+    if input.starts_with(&[0xFF, 0xFE]) || input.starts_with(&[0xFE, 0xFF]) {
+        return Err(ParseError::new(
+            "",
+            SimplePosition::default(),
+            ParseErrorKind::UnsupportedEncoding,
+        ));
+    }
+    Ok(())
+}
+
+/// Parses one value from the start of `input`, after optional whitespace, and returns it with
+/// the unconsumed remainder instead of rejecting trailing data.
+pub fn parse_partial(input: &str) -> Result<(JsonValue, &str), ParseError> {
+    let options = ParserOptions::default();
+    let state = ParserState::default();
+    let context = ParserContext::new(&options, &state);
+    let (value, next) = <Whitespace as Parser<&str>>::parse(&input, skip_bom(input), &context)
+        .and_then(|(_, next)| <Value as Parser<&str>>::parse(&input, next, &context))
+        .map_err(|e| ParseError::from_simple(input, e))?;
+    Ok((value, &input[next.index as usize..]))
+}
+
+/// Parses bytes that may not be valid UTF-8, replacing each invalid sequence with U+FFFD as
+/// `String::from_utf8_lossy` does, so a damaged character does not lose the whole document.
+/// Error offsets refer to the text after replacement. A UTF-16 byte order mark is an
+/// `UnsupportedEncoding` error.
+pub fn parse_lossy(input: &[u8]) -> Result<JsonValue, ParseError> {
+    reject_utf16(input)?;
+    parse_json(&string::String::from_utf8_lossy(input))
+}
+
+/// Parses JSON straight from bytes, such as an HTTP body, checking that they are UTF-8 without
+/// copying them. Invalid UTF-8 is an `InvalidUtf8` error spanning the offending bytes, and a
+/// UTF-16 byte order mark is an `UnsupportedEncoding` error.
+pub fn parse_slice(input: &[u8]) -> Result<JsonValue, ParseError> {
+    reject_utf16(input)?;
+    match core::str::from_utf8(input) {
+        Ok(text) => parse_json(text),
+        Err(e) => {
+            let valid = core::str::from_utf8(&input[..e.valid_up_to()]).unwrap_or_default();
+            let position = valid
+                .chars()
+                .fold(SimplePosition::default(), |position, c| position.next(c));
+            let mut error = ParseError::new(valid, position, ParseErrorKind::InvalidUtf8);
+            error.span.end = e.error_len().map_or(input.len(), |len| error.offset + len);
+            Err(error)
+        }
+    }
+}
+
+/// Parses JSON5 text, as used by configuration files, with the default limits.
+#[cfg(feature = "json5")]
+pub fn parse_json5(input: &str) -> Result<JsonValue, ParseError> {
+    parse_json_with_options(input, ParserOptions::default().json5(true))
+}
+
+impl FromStr for JsonValue {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_json(s)
+    }
+}
+
+/// Parses a single JSON number such as `-1.5e3`, without surrounding whitespace.
+impl FromStr for NumberValue {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let options = ParserOptions::default();
+        let state = ParserState::default();
+        let context = ParserContext::new(&options, &state);
+        let (num, next) = Number::parse(&s, Default::default(), &context)
+            .map_err(|e| ParseError::from_simple(s, e))?;
+        if s.is_end(next) {
+            Ok(num)
+        } else {
+            Err(ParseError::new(s, next, ParseErrorKind::TrailingCharacters))
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::NumberValue;
-    use lite_parser::impls::SimplePosition;
+    use crate::{NumberValue, Serialize};
+
+    #[test]
+    fn parse_partial_returns_remainder() {
+        assert_eq!(
+            parse_partial(r#" {"a":[1]} tail"#),
+            Ok((parse_json(r#"{"a":[1]}"#).unwrap(), " tail"))
+        );
+        assert_eq!(
+            parse_partial("12,13"),
+            Ok((parse_json("12").unwrap(), ",13"))
+        );
+        assert_eq!(parse_partial("null"), Ok((JsonValue::Null, "")));
+        assert!(parse_partial("  ").is_err());
+    }
 
     #[test]
     fn it_works() {
         assert_eq!(
             parse_json(
-                &r#"{ "test": 1, "test2": [1e-4, 2.041e2, true, false, null, "\"1\n\""], "test3": [], "test4": {} }"#
+                r#"{ "test": 1, "test2": [1e-4, 2.041e2, true, false, null, "\"1\n\""], "test3": [], "test4": {} }"#
             ),
             Ok(JsonValue::Object(vec![
                 (
@@ -415,7 +1401,7 @@ mod tests {
                         JsonValue::Boolean(true),
                         JsonValue::Boolean(false),
                         JsonValue::Null,
-                        JsonValue::String(vec!['\"', '1', 'n', '\"'])
+                        JsonValue::String(vec!['\"', '1', '\n', '\"'])
                     ])
                 ),
                 (vec!['t', 'e', 's', 't', '3'], JsonValue::Array(vec![])),
@@ -427,28 +1413,223 @@ mod tests {
     #[test]
     fn it_should_consume_all() {
         assert_eq!(
-            parse_json(&r#""1"a"#),
-            Err(SimpleError {
-                reasons: vec![(
-                    SimplePosition {
-                        index: 3,
-                        line: 0,
-                        column: 3
-                    },
-                    "Expect end of input"
-                )]
+            parse_json(r#""1"a"#),
+            Err(ParseError {
+                kind: ParseErrorKind::TrailingCharacters,
+                line: 1,
+                column: 4,
+                offset: 3,
+                span: 3..4,
+                snippet: r#""1"a"#.into(),
             })
         )
     }
 
+    #[test]
+    fn it_parses_from_str() {
+        assert_eq!(
+            r#"{"a":[true,null]}"#.parse::<JsonValue>(),
+            Ok(JsonValue::Object(vec![(
+                vec!['a'],
+                JsonValue::Array(vec![JsonValue::Boolean(true), JsonValue::Null])
+            )]))
+        );
+        assert_eq!("[1,".parse::<JsonValue>(), parse_json("[1,"));
+    }
+
+    #[test]
+    fn it_parses_number_from_str() {
+        assert_eq!(
+            "-12.05e3".parse::<NumberValue>(),
+            Ok(NumberValue {
+                integer: 12,
+                fraction: 5,
+                fraction_length: 2,
+                exponent: 3,
+                negative: true,
+            })
+        );
+        let num: NumberValue = "0.001".parse().unwrap();
+        assert_eq!(num.to_string(), "0.001");
+        assert!("1.5 ".parse::<NumberValue>().is_err());
+        assert!("abc".parse::<NumberValue>().is_err());
+        assert!("".parse::<NumberValue>().is_err());
+    }
+
+    #[test]
+    fn it_handles_number_overflow() {
+        let big = "1234567890123456789012345678901234567890123";
+        let with_overflow =
+            |number_overflow| ParserOptions::default().number_overflow(number_overflow);
+
+        assert!(parse_json(big).is_err());
+        assert_eq!(
+            big.parse::<NumberValue>(),
+            Err(ParseError {
+                kind: ParseErrorKind::NumberOutOfRange,
+                line: 1,
+                column: 1,
+                offset: 0,
+                span: 0..big.len(),
+                snippet: big[..20].into(),
+            })
+        );
+        assert_eq!(
+            parse_json_with_options(
+                &format!("-{}.5", big),
+                with_overflow(NumberOverflow::Saturate)
+            ),
+            Ok(JsonValue::Number(NumberValue {
+                integer: u128::MAX,
+                fraction: 0,
+                fraction_length: 0,
+                exponent: 0,
+                negative: true,
+            }))
+        );
+        assert_eq!(
+            parse_json_with_options(
+                &format!("{}.5e-2", big),
+                with_overflow(NumberOverflow::Lossy)
+            ),
+            Ok(JsonValue::Number(NumberValue {
+                integer: 123456789012345678901234567890123456789,
+                fraction: 0,
+                fraction_length: 0,
+                exponent: 2,
+                negative: false,
+            }))
+        );
+
+        let long_fraction = format!("0.{}", big);
+        assert!(parse_json(&long_fraction).is_err());
+        let truncate_fraction =
+            ParserOptions::default().fraction_overflow(Some(NumberOverflow::Saturate));
+        let state = ParserState::default();
+        let context = ParserContext::new(&truncate_fraction, &state);
+        assert_eq!(
+            parse_json_with_context(&format!("[1.5,{}]", long_fraction), &context)
+                .unwrap()
+                .as_array()
+                .unwrap()[1]
+                .as_number()
+                .unwrap()
+                .fraction_length,
+            39
+        );
+        assert!(state.lost_precision());
+        let state = ParserState::default();
+        let context = ParserContext::new(&truncate_fraction, &state);
+        assert!(parse_json_with_context("[1.5]", &context).is_ok());
+        assert!(!state.lost_precision());
+        assert!(parse_json_with_options(big, truncate_fraction).is_err());
+        let raw_fraction = ParserOptions::default().fraction_overflow(Some(NumberOverflow::Raw));
+        assert_eq!(
+            parse_json_with_options(&long_fraction, raw_fraction),
+            Ok(JsonValue::BigNumber(long_fraction.clone().into_bytes()))
+        );
+        assert_eq!(
+            parse_json_with_options(&long_fraction, with_overflow(NumberOverflow::Lossy)),
+            Ok(JsonValue::Number(NumberValue {
+                integer: 0,
+                fraction: 123456789012345678901234567890123456789,
+                fraction_length: 39,
+                exponent: 0,
+                negative: false,
+            }))
+        );
+
+        let huge_exponent = "-2.5e99999999999";
+        assert!(parse_json(huge_exponent).is_err());
+        assert_eq!(
+            huge_exponent.parse::<NumberValue>(),
+            Err(ParseError {
+                kind: ParseErrorKind::NumberOutOfRange,
+                line: 1,
+                column: 1,
+                offset: 0,
+                span: 0..huge_exponent.len(),
+                snippet: huge_exponent.into(),
+            })
+        );
+        let clamped =
+            parse_json_with_options(huge_exponent, with_overflow(NumberOverflow::Saturate))
+                .unwrap()
+                .to_number()
+                .unwrap();
+        assert_eq!(clamped.exponent, i32::MAX);
+        assert_eq!(clamped.to_f64(), f64::NEG_INFINITY);
+        let clamped =
+            parse_json_with_options("1e-99999999999", with_overflow(NumberOverflow::Lossy))
+                .unwrap()
+                .to_number()
+                .unwrap();
+        assert_eq!(clamped.exponent, i32::MIN);
+        assert_eq!(clamped.to_f64(), 0.0);
+        assert_eq!(
+            "2147483647e-2147483648"
+                .parse::<NumberValue>()
+                .map(|n| n.exponent),
+            Ok(i32::MIN)
+        );
+
+        assert_eq!(
+            parse_json("340282366920938463463374607431768211455"),
+            Ok(JsonValue::Number(NumberValue {
+                integer: u128::MAX,
+                fraction: 0,
+                fraction_length: 0,
+                exponent: 0,
+                negative: false,
+            }))
+        );
+    }
+
+    #[test]
+    fn it_keeps_big_numbers_as_text() {
+        let options = ParserOptions::default().number_overflow(NumberOverflow::Raw);
+        let balance =
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        let input = format!(
+            r#"{{"balance":{},"small":-1.5E+2,"tiny":1e-99999999999}}"#,
+            balance
+        );
+
+        let value = parse_json_with_options(&input, options.clone()).unwrap();
+        let obj = value.as_object().unwrap();
+        assert_eq!(obj[0].1, JsonValue::BigNumber(balance.as_bytes().to_vec()));
+        assert_eq!(
+            obj[1].1,
+            JsonValue::Number(NumberValue {
+                integer: 1,
+                fraction: 5,
+                fraction_length: 1,
+                exponent: 2,
+                negative: true,
+            })
+        );
+        assert_eq!(obj[2].1, JsonValue::BigNumber(b"1e-99999999999".to_vec()));
+        assert_eq!(
+            value.serialize(),
+            format!(
+                r#"{{"balance":{},"small":-1.5e2,"tiny":1e-99999999999}}"#,
+                balance
+            )
+            .as_bytes()
+        );
+
+        assert!(parse_json(balance).is_err());
+        let state = ParserState::default();
+        let context = ParserContext::new(&options, &state);
+        assert!(Number::parse(&balance, Default::default(), &context).is_err());
+    }
+
     #[test]
     fn it_accepts_nest_level() {
         assert_eq!(
             parse_json_with_options(
-                &r#"{ "test": 1 }"#,
-                ParserOptions {
-                    max_nest_level: Some(1)
-                }
+                r#"{ "test": 1 }"#,
+                ParserOptions::default().max_nest_level(Some(1))
             ),
             Ok(JsonValue::Object(vec![(
                 vec!['t', 'e', 's', 't'],
@@ -467,10 +1648,8 @@ mod tests {
     fn it_accepts_more_nest_level() {
         assert_eq!(
             parse_json_with_options(
-                &r#"{ "test": { "a": [ {} ] } }"#,
-                ParserOptions {
-                    max_nest_level: Some(5)
-                }
+                r#"{ "test": { "a": [ {} ] } }"#,
+                ParserOptions::default().max_nest_level(Some(5))
             ),
             Ok(JsonValue::Object(vec![(
                 vec!['t', 'e', 's', 't'],
@@ -482,24 +1661,694 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_parses_deep_nesting_without_recursion() {
+        let depth = 100_000;
+        let input = format!("{}{}", r#"{"a": ["#.repeat(depth), "]}".repeat(depth));
+        // Recursing once per level would overflow this stack.
+        let parse = std::thread::Builder::new()
+            .stack_size(256 * 1024)
+            .spawn(move || {
+                let options = ParserOptions::default().max_nest_level(None);
+                let error = parse_json_with_options(&input[..input.len() / 2], options.clone())
+                    .unwrap_err();
+                assert_eq!(error.kind, ParseErrorKind::UnexpectedEof);
+
+                // Take the value apart one level at a time, as dropping it would recurse.
+                let mut value = parse_json_with_options(&input, options).unwrap();
+                let mut levels = 1;
+                loop {
+                    value = match value {
+                        JsonValue::Object(mut obj) => obj.pop().unwrap().1,
+                        JsonValue::Array(mut arr) => match arr.pop() {
+                            Some(item) => item,
+                            None => return levels,
+                        },
+                        _ => unreachable!(),
+                    };
+                    levels += 1;
+                }
+            })
+            .unwrap();
+        assert_eq!(parse.join().unwrap(), 2 * depth);
+    }
+
+    #[test]
+    fn errors_locate_the_problem() {
+        let input = "{\n  \"name\": \"Zoë\",\n  \"tags\": [1, 2,, 3]\n}";
+        assert_eq!(
+            parse_json(input),
+            Err(ParseError {
+                kind: ParseErrorKind::UnexpectedToken,
+                line: 3,
+                column: 17,
+                offset: 36,
+                span: 36..37,
+                snippet: r#"  "tags": [1, 2,, 3]"#.into(),
+            })
+        );
+
+        let long = format!(r#"{{"items": [{}{{"id" 1}}]}}"#, "0, ".repeat(1000));
+        let err = parse_json(&long).unwrap_err();
+        assert_eq!((err.line, err.column, err.offset), (1, 3018, 3017));
+        assert_eq!(err.snippet, r#", 0, 0, 0, 0, {"id" 1}]}"#);
+
+        assert_eq!(
+            parse_json(r#""Zoë""#),
+            Ok(JsonValue::String(vec!['Z', 'o', 'ë']))
+        );
+    }
+
+    #[test]
+    fn errors_have_kinds() {
+        let kind = |s: &str| parse_json(s).unwrap_err().kind;
+        for (input, expected) in [
+            ("", ParseErrorKind::UnexpectedEof),
+            ("[1, 2", ParseErrorKind::UnexpectedEof),
+            (r#"{"a": "b"#, ParseErrorKind::UnexpectedEof),
+            ("nul", ParseErrorKind::UnexpectedEof),
+            (r#"{"a" 1}"#, ParseErrorKind::UnexpectedToken),
+            ("[1 2]", ParseErrorKind::UnexpectedToken),
+            ("nil", ParseErrorKind::UnexpectedToken),
+            (r#""\q""#, ParseErrorKind::InvalidEscape),
+            (r#""\u12x4""#, ParseErrorKind::InvalidEscape),
+            ("01", ParseErrorKind::InvalidNumber),
+            ("[1.]", ParseErrorKind::InvalidNumber),
+            ("-", ParseErrorKind::InvalidNumber),
+            ("1e400000000000", ParseErrorKind::NumberOutOfRange),
+            ("{} {}", ParseErrorKind::TrailingCharacters),
+        ] {
+            assert_eq!(kind(input), expected, "{}", input);
+        }
+        assert_eq!(
+            parse_json_with_options("[[[]]]", ParserOptions::default().max_nest_level(Some(2)))
+                .unwrap_err()
+                .kind,
+            ParseErrorKind::DepthLimitExceeded
+        );
+    }
+
+    #[test]
+    fn errors_have_spans() {
+        let span = |s: &'static str| {
+            let span = parse_json(s).unwrap_err().span;
+            &s[span]
+        };
+        assert_eq!(span(r#"{"a": [1, 02, 3]}"#), "02");
+        assert_eq!(span(r#"{"a": nil}"#), "nil");
+        assert_eq!(span(r#"{"a" "b"}"#), r#""b""#);
+        assert_eq!(span(r#"["a\qb"]"#), r#"\q"#);
+        assert_eq!(span(r#"["\u12x4"]"#), r#"\u12x4"#);
+        assert_eq!(span(r#"[1] true"#), "true");
+        assert_eq!(span(r#"[1, 2"#), "");
+        assert_eq!(parse_json("[1, 2").unwrap_err().span, 5..5);
+    }
+
+    #[test]
+    fn it_enforces_size_limits() {
+        let limits = ParserOptions::default()
+            .max_input_length(Some(32))
+            .max_string_length(Some(3))
+            .max_array_length(Some(3))
+            .max_object_members(Some(2));
+        let error = |s: &'static str| {
+            let err = parse_json_with_options(s, limits.clone()).unwrap_err();
+            (err.kind, &s[err.span])
+        };
+        assert!(
+            parse_json_with_options(r#"{"abc": [1, 2, 3], "d": "efg"}"#, limits.clone()).is_ok()
+        );
+        assert_eq!(
+            error(r#"{"abc": [1, 2, 3], "d": "efg"}        "#),
+            (
+                ParseErrorKind::InputTooLong,
+                r#"{"abc": [1, 2, 3], "d": "efg"}        "#
+            )
+        );
+        assert_eq!(
+            error(r#"["abcd"]"#),
+            (ParseErrorKind::StringTooLong, r#""abcd""#)
+        );
+        assert_eq!(
+            error(r#"{"abcd": 1}"#),
+            (ParseErrorKind::StringTooLong, r#""abcd""#)
+        );
+        assert_eq!(
+            error("[1, 2, 3, 4]"),
+            (ParseErrorKind::TooManyArrayItems, "4")
+        );
+        assert_eq!(
+            error(r#"{"a": 1, "b": 2, "c": 3}"#),
+            (ParseErrorKind::TooManyObjectMembers, r#""c""#)
+        );
+    }
+
+    #[test]
+    fn it_enforces_memory_budget() {
+        let input = r#"{"key": ["abcdefgh", 1, null]}"#;
+        let options = ParserOptions::default();
+        let state = ParserState::default();
+        let context = ParserContext::new(&options, &state);
+        let value = parse_json_with_context(input, &context).unwrap();
+        let used = state.allocated();
+        assert_eq!(
+            used,
+            5 * size_of::<JsonValue>() + size_of::<Vec<char>>() + 11 * size_of::<char>()
+        );
+
+        let with_budget =
+            |memory_budget| ParserOptions::default().memory_budget(Some(memory_budget));
+        assert_eq!(parse_json_with_options(input, with_budget(used)), Ok(value));
+        let err = parse_json_with_options(input, with_budget(used - 1)).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::BudgetExceeded);
+
+        let huge = format!("[{}]", "1,".repeat(100_000) + "1");
+        let err = parse_json_with_options(&huge, with_budget(1024)).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::BudgetExceeded);
+        assert!(err.offset < 100);
+    }
+
+    #[test]
+    fn it_meters_steps() {
+        let input = r#"{"a": [1, 2], "b": null}"#;
+        let options = ParserOptions::default();
+        let state = ParserState::default();
+        let context = ParserContext::new(&options, &state);
+        parse_json_with_context(input, &context).unwrap();
+        let steps = input.len() as u64 + 5;
+        assert_eq!(state.steps(), steps);
+
+        let with_budget = |step_budget| ParserOptions::default().step_budget(Some(step_budget));
+        assert!(parse_json_with_options(input, with_budget(steps)).is_ok());
+        let err = parse_json_with_options(input, with_budget(steps - 1)).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::StepBudgetExceeded);
+        assert_eq!(err.offset, 19);
+
+        let err = parse_json_with_options(input, with_budget(10)).unwrap_err();
+        assert_eq!(
+            (err.kind, err.offset),
+            (ParseErrorKind::StepBudgetExceeded, 0)
+        );
+    }
+
+    #[test]
+    fn it_accepts_comments_when_allowed() {
+        let jsonc = ParserOptions::default().allow_comments(true);
+        let input = "// settings\r\n{\n  /* the port */ \"port\": 80, // http\n  \"tls\"/**/: false\n}\n// end";
+        assert_eq!(
+            parse_json_with_options(input, jsonc.clone()),
+            parse_json(r#"{"port": 80, "tls": false}"#)
+        );
+        assert_eq!(
+            parse_json(input).unwrap_err().kind,
+            ParseErrorKind::UnexpectedToken
+        );
+
+        let kind = |s| parse_json_with_options(s, jsonc.clone()).unwrap_err().kind;
+        assert_eq!(kind("[1 /* open"), ParseErrorKind::UnexpectedEof);
+        assert_eq!(kind("[1 / 2]"), ParseErrorKind::UnexpectedToken);
+        assert_eq!(
+            kind(r#"["// not a comment" // but this is"#),
+            ParseErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn it_accepts_single_quotes_when_allowed() {
+        let options = ParserOptions::default().allow_single_quotes(true);
+        let input = r#"{'name': 'it\'s "quoted"', "mixed": ["a\'b", '']}"#;
+        assert_eq!(
+            parse_json_with_options(input, options.clone()),
+            parse_json(r#"{"name": "it's \"quoted\"", "mixed": ["a'b", ""]}"#)
+        );
+        assert_eq!(
+            parse_json(input).unwrap_err().kind,
+            ParseErrorKind::UnexpectedToken
+        );
+
+        let kind = |s| {
+            parse_json_with_options(s, options.clone())
+                .unwrap_err()
+                .kind
+        };
+        assert_eq!(kind("'open"), ParseErrorKind::UnexpectedEof);
+        assert_eq!(kind("{a: 1}"), ParseErrorKind::UnexpectedToken);
+        assert_eq!(kind(r#"'\v'"#), ParseErrorKind::InvalidEscape);
+    }
+
+    #[test]
+    fn it_accepts_unquoted_keys_when_allowed() {
+        let options = ParserOptions::default().allow_unquoted_keys(true);
+        let input = r#"{id: 1, $ref: "a", _x2: [], "quoted": null, café: true, a1: 0}"#;
+        assert_eq!(
+            parse_json_with_options(input, options.clone()).map(|v| v.as_object().unwrap().len()),
+            Ok(6)
+        );
+        assert_eq!(
+            parse_json(input).unwrap_err().kind,
+            ParseErrorKind::UnexpectedToken
+        );
+
+        let error = |s| parse_json_with_options(s, options.clone()).unwrap_err();
+        for bad in ["{2x: 1}", "{foo-bar: 1}", "{a b: 1}", "{: 1}", "{'a': 1}"] {
+            assert_eq!(error(bad).kind, ParseErrorKind::UnexpectedToken, "{}", bad);
+        }
+        assert_eq!(error("{foo-bar: 1}").offset, 4);
+        assert_eq!(error(r#"{a\n: 1}"#).kind, ParseErrorKind::InvalidEscape);
+
+        let value = parse_json_with_options(r#"{\u0041b: 1, a\uD840\uDC0B: 2}"#, options.clone());
+        assert_eq!(value, parse_json(r#"{"Ab": 1, "a𠀋": 2}"#));
+        assert_eq!(error(r#"{\u0031: 1}"#).kind, ParseErrorKind::InvalidEscape);
+        assert_eq!(error(r#"{a\uD83D: 1}"#).kind, ParseErrorKind::InvalidEscape);
+    }
+
+    #[test]
+    fn it_accepts_hex_numbers_when_allowed() {
+        let options = ParserOptions::default().allow_hex_numbers(true);
+        let value = parse_json_with_options("[0x1A2B, -0Xff, 0x0, 10]", options.clone()).unwrap();
+        assert_eq!(value, parse_json("[6699, -255, 0, 10]").unwrap());
+        assert_eq!(value.serialize(), b"[6699,-255,0,10]");
+        assert_eq!(
+            parse_json("0x10").unwrap_err().kind,
+            ParseErrorKind::TrailingCharacters
+        );
+
+        let kind = |s| {
+            parse_json_with_options(s, options.clone())
+                .unwrap_err()
+                .kind
+        };
+        assert_eq!(kind("0x"), ParseErrorKind::InvalidNumber);
+        assert_eq!(kind("[0xg]"), ParseErrorKind::InvalidNumber);
+        assert_eq!(kind("[0x1g]"), ParseErrorKind::UnexpectedToken);
+        assert_eq!(kind("0x1.5"), ParseErrorKind::TrailingCharacters);
+        assert_eq!(
+            kind(&format!("0x1{}", "0".repeat(32))),
+            ParseErrorKind::NumberOutOfRange
+        );
+    }
+
+    #[test]
+    fn it_accepts_loose_numbers_when_allowed() {
+        let options = ParserOptions::default()
+            .allow_loose_numbers(true)
+            .allow_hex_numbers(true);
+        let value =
+            parse_json_with_options("[+1.5, .5, 5., -.5e1, +0x10, 0.]", options.clone()).unwrap();
+        assert_eq!(value, parse_json("[1.5, 0.5, 5, -0.5e1, 16, 0]").unwrap());
+        assert_eq!(value.serialize(), b"[1.5,0.5,5,-0.5e1,16,0]");
+        for s in ["+1", ".5", "5."] {
+            assert!(parse_json(s).is_err(), "{}", s);
+        }
+
+        let kind = |s| {
+            parse_json_with_options(s, options.clone())
+                .unwrap_err()
+                .kind
+        };
+        for bad in ["+", ".", "-.", "+-1", "5..", "01", ".e1", "1.5."] {
+            assert_eq!(kind(bad), ParseErrorKind::InvalidNumber, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn it_accepts_multiline_strings_when_allowed() {
+        let options = ParserOptions::default().allow_multiline_strings(true);
+        let input = "[\"-----BEGIN-----\\\nAAAA\\\r\nBBBB\\\r-----END-----\", \"a\nb\r\nc\"]";
+        assert_eq!(
+            parse_json_with_options(input, options.clone()),
+            Ok(JsonValue::Array(vec![
+                JsonValue::String("-----BEGIN-----AAAABBBB-----END-----".chars().collect()),
+                JsonValue::String("a\nb\r\nc".chars().collect()),
+            ]))
+        );
+        assert_eq!(
+            parse_json(input).unwrap_err().kind,
+            ParseErrorKind::InvalidEscape
+        );
+        assert_eq!(
+            parse_json_with_options("\"a\\\n", options)
+                .unwrap_err()
+                .kind,
+            ParseErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn it_decodes_surrogate_pairs() {
+        let string = |s: &str| {
+            parse_json(s).map(|v| {
+                v.as_string()
+                    .unwrap()
+                    .iter()
+                    .collect::<std::string::String>()
+            })
+        };
+        assert_eq!(string(r#""\uD83D\uDE00""#), Ok("😀".into()));
+        assert_eq!(
+            string(r#""a\ud83d\ude00b\uD83C\uDDEF\uD83C\uDDF5""#),
+            Ok("a😀b🇯🇵".into())
+        );
+        assert_eq!(string(r#""\u4E2D\uD840\uDC0B""#), Ok("中𠀋".into()));
+        assert_eq!(string(r#""\uDBFF\uDFFF""#), Ok("\u{10FFFF}".into()));
+        assert_eq!(
+            parse_json(r#"{"\uD83D\uDE00": 1}"#).unwrap(),
+            parse_json(r#"{"😀": 1}"#).unwrap()
+        );
+
+        let error = |s| {
+            let err = parse_json(s).unwrap_err();
+            (err.kind, err.offset, &s[err.span])
+        };
+        assert_eq!(
+            error(r#"["ok", "\uD83D"]"#),
+            (ParseErrorKind::InvalidEscape, 8, r#"\uD83D"#)
+        );
+        assert_eq!(
+            error(r#""ab\uDE00\uD83D""#),
+            (ParseErrorKind::InvalidEscape, 3, r#"\uDE00"#)
+        );
+        assert_eq!(
+            error(r#""\uD83D\u0041""#),
+            (ParseErrorKind::InvalidEscape, 1, r#"\uD83D"#)
+        );
+        assert_eq!(
+            error(r#""\uD83Dx""#),
+            (ParseErrorKind::InvalidEscape, 1, r#"\uD83D"#)
+        );
+        assert_eq!(error(r#""\uD83D\uDE0""#).0, ParseErrorKind::InvalidEscape);
+    }
+
+    #[test]
+    fn it_applies_the_lone_surrogate_policy() {
+        let input = r#"["\uD800", "a\uDFFFb", "\uD83D\u0041", "\uD83D\uDE00"]"#;
+        let parse = |lone_surrogates| {
+            parse_json_with_options(
+                input,
+                ParserOptions::default().lone_surrogates(lone_surrogates),
+            )
+        };
+        assert_eq!(parse(LoneSurrogates::Error).unwrap_err().offset, 2);
+        assert_eq!(
+            parse(LoneSurrogates::Replace),
+            parse_json(r#"["\uFFFD", "a\uFFFDb", "\uFFFDA", "😀"]"#)
+        );
+
+        let value = parse(LoneSurrogates::Preserve).unwrap();
+        let strings = value.as_array().unwrap();
+        assert_eq!(strings[0], JsonValue::Utf16String(vec![0xD800]));
+        assert_eq!(strings[1], JsonValue::Utf16String(vec![0x61, 0xDFFF, 0x62]));
+        assert_eq!(strings[2], JsonValue::Utf16String(vec![0xD83D, 0x41]));
+        assert_eq!(strings[3].as_string(), Some(&['😀'][..]));
+        assert_eq!(
+            value.serialize(),
+            r#"["\ud800","a\udfffb","\ud83dA","😀"]"#.as_bytes()
+        );
+
+        // A real private-use character stays apart from the surrogate next to it, and both
+        // survive writing and reading back.
+        let preserve = ParserOptions::default().lone_surrogates(LoneSurrogates::Preserve);
+        let value =
+            parse_json_with_options("{\"\\uD800\": \"\u{10F800}\\uD800\"}", preserve.clone())
+                .unwrap();
+        let (key, string) = &value.as_object().unwrap()[0];
+        assert_eq!(key, &['\u{FFFD}']);
+        assert_eq!(
+            string,
+            &JsonValue::Utf16String(vec![0xDBFE, 0xDC00, 0xD800])
+        );
+        let text = value.serialize();
+        assert_eq!(text, "{\"\u{FFFD}\":\"\u{10F800}\\ud800\"}".as_bytes());
+        assert_eq!(format!("{}", value).as_bytes(), &text[..]);
+        let text = core::str::from_utf8(&text).unwrap();
+        assert_eq!(parse_json_with_options(text, preserve), Ok(value));
+
+        let strict = ParserOptions::strict().lone_surrogates(LoneSurrogates::Replace);
+        assert_eq!(
+            parse_json_with_options(r#""\uDC00""#, strict)
+                .unwrap_err()
+                .kind,
+            ParseErrorKind::InvalidEscape
+        );
+    }
+
+    #[test]
+    fn it_accepts_hex_escapes_when_allowed() {
+        let options = ParserOptions::default().allow_hex_escapes(true);
+        assert_eq!(
+            parse_json_with_options(r#""\x41\x7a\xE9\x00""#, options.clone()),
+            Ok(JsonValue::String(vec!['A', 'z', 'é', '\0']))
+        );
+        assert_eq!(
+            parse_json(r#""\x41""#).unwrap_err().kind,
+            ParseErrorKind::InvalidEscape
+        );
+
+        let error = |s| parse_json_with_options(s, options.clone()).unwrap_err();
+        let err = error(r#"["ok", "\x4g"]"#);
+        assert_eq!(
+            (err.kind, err.offset, err.span),
+            (ParseErrorKind::InvalidEscape, 8, 8..12)
+        );
+        assert_eq!(error(r#""\x4"#).kind, ParseErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn it_rejects_control_characters_by_default() {
+        let input = "{\"log\": \"a\tb\nc\u{0}\"}";
+        let err = parse_json(input).unwrap_err();
+        assert_eq!(
+            (err.kind, err.offset),
+            (ParseErrorKind::UnexpectedToken, 10)
+        );
+        assert_eq!(
+            parse_json_with_options(
+                input,
+                ParserOptions::default().allow_control_characters(true)
+            ),
+            Ok(JsonValue::Object(vec![(
+                "log".chars().collect(),
+                JsonValue::String("a\tb\nc\u{0}".chars().collect())
+            )]))
+        );
+        assert!(parse_json("\"\u{7F}\u{80}\u{2028}\"").is_ok());
+        assert!(parse_json("\t[\"\\t\"]\n").is_ok());
+    }
+
+    #[test]
+    fn it_parses_slices() {
+        let body = "{\"name\": \"café\", \"tags\": [1, 2]}";
+        assert_eq!(parse_slice(body.as_bytes()), parse_json(body));
+        assert_eq!(
+            parse_slice(b"[1,]").unwrap_err(),
+            parse_json("[1,]").unwrap_err()
+        );
+
+        let err = parse_slice(b"[\"ok\",\n \"a\xff\xfeb\"]").unwrap_err();
+        assert_eq!(
+            (err.kind, err.line, err.column, err.span),
+            (ParseErrorKind::InvalidUtf8, 2, 4, 10..11)
+        );
+        assert_eq!(parse_slice(b"\"\xe2\x82").unwrap_err().span, 1..3);
+        assert_eq!(
+            parse_slice(b"\xff\xfe[\x00]\x00").unwrap_err().kind,
+            ParseErrorKind::UnsupportedEncoding
+        );
+    }
+
+    #[test]
+    fn it_parses_with_presets() {
+        let input = "{\n  // ports\n  ports: [0x50, +443],\n  name: 'web'\n}";
+        let config = ParserOptions::relaxed();
+        assert_eq!(
+            parse_with(input, &config),
+            parse_json(r#"{"ports": [80, 443], "name": "web"}"#)
+        );
+        assert_eq!(
+            parse_with(r#""\uD800""#, &config),
+            parse_json(r#""\uFFFD""#)
+        );
+
+        let strict = ParserOptions::strict().allow_comments(true);
+        assert_eq!(parse_with("[1]", &strict), parse_json("[1]"));
+        assert!(parse_with("[1] // one", &strict).is_err());
+        assert!(parse_with("\u{FEFF}[1]", &strict).is_err());
+    }
+
+    #[test]
+    fn it_replaces_invalid_utf8_when_lossy() {
+        assert_eq!(
+            parse_lossy(b"{\"a\": \"x\xff\xc3y\", \"b\xe2\x82\": [1]}"),
+            parse_json("{\"a\": \"x\u{FFFD}\u{FFFD}y\", \"b\u{FFFD}\": [1]}")
+        );
+        assert_eq!(parse_lossy("[\"€\"]".as_bytes()), parse_json("[\"€\"]"));
+        assert_eq!(
+            parse_lossy(b"[1, \xff]").unwrap_err().kind,
+            ParseErrorKind::UnexpectedToken
+        );
+        assert_eq!(
+            parse_lossy(b"\xff\xfe[\x001\x00]\x00").unwrap_err().kind,
+            ParseErrorKind::UnsupportedEncoding
+        );
+    }
+
+    #[test]
+    fn it_handles_byte_order_marks() {
+        let input = "\u{FEFF}{\"a\": [1,]}";
+        let err = parse_json(input).unwrap_err();
+        assert_eq!((err.offset, err.line, err.column), (12, 1, 10));
+        assert_eq!(parse_json("\u{FEFF}[1]"), parse_json("[1]"),);
+        assert_eq!(
+            parse_partial("\u{FEFF}1 2"),
+            Ok((parse_json("1").unwrap(), " 2"))
+        );
+        assert!(parse_json("[\u{FEFF}1]").is_err());
+        assert!(parse_json("\u{FEFF}\u{FEFF}1").is_err());
+
+        let err = parse_slice(b"\xFE\xFF\x00[\x001\x00]").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnsupportedEncoding);
+        assert_eq!(
+            err.to_string(),
+            "UTF-16 input is not supported at line 1 column 1"
+        );
+        assert_eq!(
+            parse_json("[\"\u{FFFD}\u{FFFD}\"]"),
+            parse_json(r#"["\uFFFD\uFFFD"]"#)
+        );
+        assert_eq!(
+            parse_json("\u{FFFD}\u{FFFD}").unwrap_err().kind,
+            ParseErrorKind::UnexpectedToken
+        );
+    }
+
+    #[test]
+    fn it_accepts_non_finite_when_allowed() {
+        let options = ParserOptions::default().allow_non_finite(true);
+        assert_eq!(
+            parse_json_with_options("[NaN, Infinity, -Infinity, -1]", options.clone()),
+            Ok(JsonValue::Array(vec![
+                JsonValue::NonFinite(NonFinite::NaN),
+                JsonValue::NonFinite(NonFinite::Infinity),
+                JsonValue::NonFinite(NonFinite::NegativeInfinity),
+                parse_json("-1").unwrap(),
+            ]))
+        );
+        assert_eq!(
+            parse_json("NaN").unwrap_err().kind,
+            ParseErrorKind::UnexpectedToken
+        );
+
+        let kind = |s| {
+            parse_json_with_options(s, options.clone())
+                .unwrap_err()
+                .kind
+        };
+        assert_eq!(kind("Infinit"), ParseErrorKind::UnexpectedEof);
+        assert_eq!(kind("[Infinite]"), ParseErrorKind::UnexpectedToken);
+        assert_eq!(kind("-NaN"), ParseErrorKind::UnexpectedToken);
+        assert_eq!(kind("nan"), ParseErrorKind::UnexpectedToken);
+    }
+
+    #[cfg(feature = "json5")]
+    #[test]
+    fn it_parses_json5() {
+        let input = r#"// JSON5
+{
+  unquoted: 'and you can quote me on that',
+  singleQuotes: 'I can use "double quotes" here',
+  lineBreaks: "Look, Mom! \
+No \\n's!",
+  hexadecimal: 0xdecaf,
+  negativeHex: -0XFF,
+  $_ident: [1, 2,],
+  escapes: '\'\v\0\a',
+  "backwardsCompatible": "with JSON",
+}
+"#;
+        let expected = JsonValue::Object(vec![
+            (
+                "unquoted".chars().collect(),
+                JsonValue::String("and you can quote me on that".chars().collect()),
+            ),
+            (
+                "singleQuotes".chars().collect(),
+                JsonValue::String("I can use \"double quotes\" here".chars().collect()),
+            ),
+            (
+                "lineBreaks".chars().collect(),
+                JsonValue::String("Look, Mom! No \\n's!".chars().collect()),
+            ),
+            (
+                "hexadecimal".chars().collect(),
+                parse_json("912559").unwrap(),
+            ),
+            ("negativeHex".chars().collect(), parse_json("-255").unwrap()),
+            ("$_ident".chars().collect(), parse_json("[1, 2]").unwrap()),
+            (
+                "escapes".chars().collect(),
+                JsonValue::String("'\u{B}\0a".chars().collect()),
+            ),
+            (
+                "backwardsCompatible".chars().collect(),
+                JsonValue::String("with JSON".chars().collect()),
+            ),
+        ]);
+        assert_eq!(parse_json5(input), Ok(expected));
+        assert_eq!(
+            parse_json5("[-NaN, -Infinity, +Infinity, .5]"),
+            Ok(JsonValue::Array(vec![
+                JsonValue::NonFinite(NonFinite::NaN),
+                JsonValue::NonFinite(NonFinite::NegativeInfinity),
+                JsonValue::NonFinite(NonFinite::Infinity),
+                parse_json("0.5").unwrap(),
+            ]))
+        );
+        assert_eq!(
+            parse_json(input).unwrap_err().kind,
+            ParseErrorKind::UnexpectedToken
+        );
+
+        assert!(parse_json5("'a\tb'").is_ok());
+
+        let kind = |s| parse_json5(s).unwrap_err().kind;
+        assert_eq!(kind("'a\nb'"), ParseErrorKind::UnexpectedToken);
+        assert_eq!(kind("[1,,]"), ParseErrorKind::UnexpectedToken);
+        assert_eq!(kind("{1a: 1}"), ParseErrorKind::UnexpectedToken);
+        assert_eq!(kind("0x"), ParseErrorKind::InvalidNumber);
+        assert_eq!(kind(r#""\1""#), ParseErrorKind::InvalidEscape);
+        assert_eq!(kind(r#"{a\x41: 1}"#), ParseErrorKind::InvalidEscape);
+        assert_eq!(
+            kind("0x100000000000000000000000000000000"),
+            ParseErrorKind::NumberOutOfRange
+        );
+    }
+
+    #[test]
+    fn it_rejects_adversarial_nesting() {
+        for (open, close) in [("[", "]"), (r#"{"a":"#, "}")] {
+            let input = format!("{}{}", open.repeat(10_000), close.repeat(10_000));
+            let err = parse_json(&input).unwrap_err();
+            assert_eq!(err.kind, ParseErrorKind::DepthLimitExceeded);
+            assert_eq!(err.offset, 100 * open.len());
+        }
+        let shallow = format!("{}{}", "[".repeat(100), "]".repeat(100));
+        assert!(parse_json(&shallow).is_ok());
+    }
+
     #[test]
     fn it_error_on_too_deep_nest() {
         assert_eq!(
             parse_json_with_options(
-                &r#"{ "test": { "a": [ {} ] } }"#,
-                ParserOptions {
-                    max_nest_level: Some(3)
-                }
+                r#"{ "test": { "a": [ {} ] } }"#,
+                ParserOptions::default().max_nest_level(Some(3))
             ),
-            Err(SimpleError {
-                reasons: vec![(
-                    SimplePosition {
-                        index: 0,
-                        line: 0,
-                        column: 0
-                    },
-                    "Value"
-                )]
+            Err(ParseError {
+                kind: ParseErrorKind::DepthLimitExceeded,
+                line: 1,
+                column: 20,
+                offset: 19,
+                span: 19..20,
+                snippet: r#"{ "test": { "a": [ {} ] } }"#.into(),
             })
         );
     }
@@ -507,7 +2356,7 @@ mod tests {
     #[test]
     fn handles_decimal_number() {
         assert_eq!(
-            parse_json(&r#"-1.5"#,),
+            parse_json(r#"-1.5"#,),
             Ok(JsonValue::Number(NumberValue {
                 integer: 1,
                 fraction: 5,
@@ -518,7 +2367,7 @@ mod tests {
         );
 
         assert_eq!(
-            parse_json(&r#"-0.5"#,),
+            parse_json(r#"-0.5"#,),
             Ok(JsonValue::Number(NumberValue {
                 integer: 0,
                 fraction: 5,
@@ -529,7 +2378,7 @@ mod tests {
         );
 
         assert_eq!(
-            parse_json(&r#"0.5"#,),
+            parse_json(r#"0.5"#,),
             Ok(JsonValue::Number(NumberValue {
                 integer: 0,
                 fraction: 5,
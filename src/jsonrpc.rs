@@ -0,0 +1,517 @@
+//! JSON-RPC 2.0 requests, responses and errors.
+//!
+//! The types convert to and from `JsonValue` with `ToJson` and `FromJson`, and `parse`
+//! reports malformed messages as the matching JSON-RPC error, ready to send back. A batch of
+//! requests is sent as a slice with `ToJson`; `Incoming`, `batch_response`, `parse_batch` and
+//! `correlate` handle the rest of a batch exchange.
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec, vec::Vec};
+
+use crate::convert::{
+    __private::{field, key, object},
+    FromJson, FromJsonError, FromJsonErrorKind, ToJson,
+};
+use crate::json::{JsonObject, JsonValue};
+use crate::json_parser::parse_json;
+use core::fmt;
+
+/// Invalid JSON was received.
+pub const PARSE_ERROR: i64 = -32700;
+/// The JSON sent is not a valid request object.
+pub const INVALID_REQUEST: i64 = -32600;
+/// The method does not exist or is not available.
+pub const METHOD_NOT_FOUND: i64 = -32601;
+/// Invalid method parameters.
+pub const INVALID_PARAMS: i64 = -32602;
+/// Internal JSON-RPC error.
+pub const INTERNAL_ERROR: i64 = -32603;
+
+/// A request id, which the response echoes back.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Id {
+    Number(i64),
+    String(String),
+    /// Allowed by the specification, but discouraged for requests.
+    Null,
+}
+
+impl From<i64> for Id {
+    fn from(id: i64) -> Self {
+        Id::Number(id)
+    }
+}
+
+impl From<&str> for Id {
+    fn from(id: &str) -> Self {
+        Id::String(id.into())
+    }
+}
+
+impl ToJson for Id {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            Id::Number(id) => id.to_json(),
+            Id::String(id) => id.to_json(),
+            Id::Null => JsonValue::Null,
+        }
+    }
+}
+
+impl FromJson for Id {
+    fn from_json(value: JsonValue) -> Result<Self, FromJsonError> {
+        match value {
+            JsonValue::Null => Ok(Id::Null),
+            JsonValue::String(id) => Ok(Id::String(id.into_iter().collect())),
+            JsonValue::Number(_) => i64::from_json(value).map(Id::Number),
+            _ => Err(FromJsonError::new(FromJsonErrorKind::WrongType(
+                "string, integer or null",
+            ))),
+        }
+    }
+}
+
+/// The error object of a failed call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub code: i64,
+    pub message: String,
+    pub data: Option<JsonValue>,
+}
+
+impl Error {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Error {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    pub fn with_data(self, data: JsonValue) -> Self {
+        Error {
+            data: Some(data),
+            ..self
+        }
+    }
+
+    pub fn parse_error() -> Self {
+        Self::new(PARSE_ERROR, "Parse error")
+    }
+
+    pub fn invalid_request() -> Self {
+        Self::new(INVALID_REQUEST, "Invalid Request")
+    }
+
+    pub fn method_not_found() -> Self {
+        Self::new(METHOD_NOT_FOUND, "Method not found")
+    }
+
+    pub fn invalid_params() -> Self {
+        Self::new(INVALID_PARAMS, "Invalid params")
+    }
+
+    pub fn internal_error() -> Self {
+        Self::new(INTERNAL_ERROR, "Internal error")
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.message, self.code)
+    }
+}
+
+impl ToJson for Error {
+    fn to_json(&self) -> JsonValue {
+        let mut obj = vec![
+            (key("code"), self.code.to_json()),
+            (key("message"), self.message.to_json()),
+        ];
+        if let Some(data) = &self.data {
+            obj.push((key("data"), data.clone()));
+        }
+        JsonValue::Object(obj)
+    }
+}
+
+impl FromJson for Error {
+    fn from_json(value: JsonValue) -> Result<Self, FromJsonError> {
+        let mut obj = object(value)?;
+        Ok(Error {
+            code: field(&mut obj, "code")?,
+            message: field(&mut obj, "message")?,
+            data: field(&mut obj, "data")?,
+        })
+    }
+}
+
+/// Checks the `"jsonrpc": "2.0"` member.
+fn version(obj: &mut JsonObject) -> Result<(), FromJsonError> {
+    let version: String = field(obj, "jsonrpc")?;
+    if version == "2.0" {
+        Ok(())
+    } else {
+        Err(FromJsonError::new(FromJsonErrorKind::UnknownVariant).at_key("jsonrpc"))
+    }
+}
+
+/// Takes the member named `name` out of `obj`, telling a `null` member apart from a missing one.
+fn take(obj: &mut JsonObject, name: &str) -> Option<JsonValue> {
+    let i = obj
+        .iter()
+        .position(|(k, _)| k.iter().copied().eq(name.chars()))?;
+    Some(obj.swap_remove(i).1)
+}
+
+/// Converts a parsed message, reporting invalid JSON and invalid messages as JSON-RPC errors.
+fn parse<T: FromJson>(text: &str) -> Result<T, Error> {
+    let value = parse_json(text).map_err(|_| Error::parse_error())?;
+    T::from_json(value).map_err(|_| Error::invalid_request())
+}
+
+/// A call, or a notification when it has no id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    /// An array of positional or an object of named parameters.
+    pub params: Option<JsonValue>,
+    /// The id, or None for a notification, which gets no response.
+    pub id: Option<Id>,
+}
+
+impl Request {
+    pub fn new(method: impl Into<String>, params: Option<JsonValue>, id: impl Into<Id>) -> Self {
+        Request {
+            method: method.into(),
+            params,
+            id: Some(id.into()),
+        }
+    }
+
+    pub fn notification(method: impl Into<String>, params: Option<JsonValue>) -> Self {
+        Request {
+            method: method.into(),
+            params,
+            id: None,
+        }
+    }
+
+    pub fn is_notification(&self) -> bool {
+        self.id.is_none()
+    }
+
+    /// Parses a request, failing with `PARSE_ERROR` for invalid JSON and `INVALID_REQUEST`
+    /// for anything that is not a request object.
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        parse(text)
+    }
+}
+
+impl ToJson for Request {
+    fn to_json(&self) -> JsonValue {
+        let mut obj = vec![
+            (key("jsonrpc"), "2.0".to_json()),
+            (key("method"), self.method.to_json()),
+        ];
+        if let Some(params) = &self.params {
+            obj.push((key("params"), params.clone()));
+        }
+        if let Some(id) = &self.id {
+            obj.push((key("id"), id.to_json()));
+        }
+        JsonValue::Object(obj)
+    }
+}
+
+impl FromJson for Request {
+    fn from_json(value: JsonValue) -> Result<Self, FromJsonError> {
+        let mut obj = object(value)?;
+        version(&mut obj)?;
+        let params = match field(&mut obj, "params")? {
+            params @ Some(JsonValue::Array(_)) | params @ Some(JsonValue::Object(_)) => params,
+            None => None,
+            Some(_) => {
+                return Err(
+                    FromJsonError::new(FromJsonErrorKind::WrongType("array or object"))
+                        .at_key("params"),
+                )
+            }
+        };
+        // A null id is still an id, while a missing one makes a notification.
+        let id = take(&mut obj, "id")
+            .map(|id| Id::from_json(id).map_err(|e| e.at_key("id")))
+            .transpose()?;
+        Ok(Request {
+            method: field(&mut obj, "method")?,
+            params,
+            id,
+        })
+    }
+}
+
+/// The reply to a call, carrying either its result or an error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    /// The id of the request, or `Id::Null` if it could not be determined.
+    pub id: Id,
+    pub result: Result<JsonValue, Error>,
+}
+
+impl Response {
+    pub fn success(id: impl Into<Id>, result: JsonValue) -> Self {
+        Response {
+            id: id.into(),
+            result: Ok(result),
+        }
+    }
+
+    pub fn error(id: impl Into<Id>, error: Error) -> Self {
+        Response {
+            id: id.into(),
+            result: Err(error),
+        }
+    }
+
+    /// Parses a response, failing with `PARSE_ERROR` for invalid JSON and `INVALID_REQUEST`
+    /// for anything that is not a response object.
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        parse(text)
+    }
+}
+
+impl ToJson for Response {
+    fn to_json(&self) -> JsonValue {
+        let outcome = match &self.result {
+            Ok(result) => (key("result"), result.clone()),
+            Err(error) => (key("error"), error.to_json()),
+        };
+        JsonValue::Object(vec![
+            (key("jsonrpc"), "2.0".to_json()),
+            outcome,
+            (key("id"), self.id.to_json()),
+        ])
+    }
+}
+
+impl FromJson for Response {
+    fn from_json(value: JsonValue) -> Result<Self, FromJsonError> {
+        let mut obj = object(value)?;
+        version(&mut obj)?;
+        let result = match (take(&mut obj, "result"), take(&mut obj, "error")) {
+            (Some(result), None) => Ok(result),
+            (None, Some(error)) => Err(Error::from_json(error).map_err(|e| e.at_key("error"))?),
+            (Some(_), Some(_)) => {
+                return Err(FromJsonError::new(FromJsonErrorKind::WrongType(
+                    "either result or error",
+                ))
+                .at_key("error"))
+            }
+            (None, None) => {
+                return Err(FromJsonError::new(FromJsonErrorKind::MissingField).at_key("result"))
+            }
+        };
+        Ok(Response {
+            id: field(&mut obj, "id")?,
+            result,
+        })
+    }
+}
+
+/// A message received by a server: a single request or a batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Incoming {
+    Single(Request),
+    /// The entries of a batch, each either a request or the error to answer it with.
+    Batch(Vec<Result<Request, Error>>),
+}
+
+impl Incoming {
+    /// Parses a request or batch, failing with `PARSE_ERROR` for invalid JSON and
+    /// `INVALID_REQUEST` for an empty batch or a single invalid request.
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        match parse_json(text).map_err(|_| Error::parse_error())? {
+            JsonValue::Array(entries) if entries.is_empty() => Err(Error::invalid_request()),
+            JsonValue::Array(entries) => Ok(Incoming::Batch(
+                entries
+                    .into_iter()
+                    .map(|entry| Request::from_json(entry).map_err(|_| Error::invalid_request()))
+                    .collect(),
+            )),
+            value => Request::from_json(value)
+                .map(Incoming::Single)
+                .map_err(|_| Error::invalid_request()),
+        }
+    }
+}
+
+/// Builds the reply to a batch, or None when there is nothing to send because every entry
+/// was a notification.
+pub fn batch_response(responses: Vec<Response>) -> Option<JsonValue> {
+    if responses.is_empty() {
+        None
+    } else {
+        Some(responses.into_json())
+    }
+}
+
+/// Parses the reply to a batch. A single response is accepted too, since servers answer a
+/// batch they cannot read with one error.
+pub fn parse_batch(text: &str) -> Result<Vec<Response>, Error> {
+    match parse_json(text).map_err(|_| Error::parse_error())? {
+        value @ JsonValue::Array(_) => Vec::from_json(value),
+        value => Response::from_json(value).map(|response| vec![response]),
+    }
+    .map_err(|_| Error::invalid_request())
+}
+
+/// Responses matched to the requests of a batch by `correlate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Correlation {
+    /// The response to each request, in request order. Notifications and requests that got
+    /// no reply have None.
+    pub responses: Vec<Option<Response>>,
+    /// Responses whose id matches no outstanding request, such as errors with a null id.
+    pub unmatched: Vec<Response>,
+}
+
+/// Matches the responses of a batch to its requests by id, since servers may reply in any
+/// order. A repeated id is matched to the requests using it in order.
+pub fn correlate(requests: &[Request], responses: Vec<Response>) -> Correlation {
+    let mut correlation = Correlation {
+        responses: vec![None; requests.len()],
+        unmatched: Vec::new(),
+    };
+    for response in responses {
+        let slot = requests.iter().enumerate().position(|(i, request)| {
+            request.id.as_ref() == Some(&response.id) && correlation.responses[i].is_none()
+        });
+        match slot {
+            Some(i) => correlation.responses[i] = Some(response),
+            None => correlation.unmatched.push(response),
+        }
+    }
+    correlation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Serialize;
+
+    fn text(value: &impl ToJson) -> String {
+        String::from_utf8(value.to_json().serialize()).unwrap()
+    }
+
+    #[test]
+    fn requests_round_trip() {
+        let request = Request::new("add", Some(parse_json("[1,2]").unwrap()), 7);
+        assert_eq!(
+            text(&request),
+            r#"{"jsonrpc":"2.0","method":"add","params":[1,2],"id":7}"#
+        );
+        assert_eq!(Request::parse(&text(&request)), Ok(request));
+
+        let notification = Request::notification("ping", None);
+        assert_eq!(text(&notification), r#"{"jsonrpc":"2.0","method":"ping"}"#);
+        assert!(Request::parse(&text(&notification))
+            .unwrap()
+            .is_notification());
+        assert_eq!(
+            Request::parse(r#"{"jsonrpc":"2.0","method":"x","id":null}"#)
+                .unwrap()
+                .id,
+            Some(Id::Null)
+        );
+
+        assert_eq!(Request::parse("{"), Err(Error::parse_error()));
+        for bad in [
+            r#"{"jsonrpc":"1.0","method":"x"}"#,
+            r#"{"jsonrpc":"2.0","method":1}"#,
+            r#"{"jsonrpc":"2.0","method":"x","params":3}"#,
+            r#"{"jsonrpc":"2.0","method":"x","id":1.5}"#,
+            "[]",
+        ] {
+            assert_eq!(
+                Request::parse(bad),
+                Err(Error::invalid_request()),
+                "{}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn responses_round_trip() {
+        let response = Response::success("a", JsonValue::Null);
+        assert_eq!(
+            text(&response),
+            r#"{"jsonrpc":"2.0","result":null,"id":"a"}"#
+        );
+        assert_eq!(Response::parse(&text(&response)), Ok(response));
+
+        let error = Error::method_not_found().with_data(parse_json(r#""nope""#).unwrap());
+        let response = Response::error(Id::Null, error);
+        assert_eq!(
+            text(&response),
+            r#"{"jsonrpc":"2.0","error":{"code":-32601,"message":"Method not found","data":"nope"},"id":null}"#
+        );
+        assert_eq!(Response::parse(&text(&response)), Ok(response));
+        assert_eq!(
+            Response::parse(r#"{"jsonrpc":"2.0","id":1}"#),
+            Err(Error::invalid_request())
+        );
+    }
+
+    #[test]
+    fn batches_work() {
+        let requests = [
+            Request::new("a", None, 1),
+            Request::notification("log", None),
+            Request::new("b", None, "x"),
+        ];
+        let text = String::from_utf8(requests.to_json().serialize()).unwrap();
+        match Incoming::parse(&text) {
+            Ok(Incoming::Batch(entries)) => {
+                let parsed: Vec<_> = entries.into_iter().map(Result::unwrap).collect();
+                assert_eq!(parsed, requests);
+            }
+            other => panic!("{:?}", other),
+        }
+        assert_eq!(
+            Incoming::parse(r#"[1, {"jsonrpc":"2.0","method":"a"}]"#),
+            Ok(Incoming::Batch(vec![
+                Err(Error::invalid_request()),
+                Ok(Request::notification("a", None))
+            ]))
+        );
+        assert_eq!(Incoming::parse("[]"), Err(Error::invalid_request()));
+        assert_eq!(batch_response(Vec::new()), None);
+
+        let responses = vec![
+            Response::success("x", JsonValue::Boolean(true)),
+            Response::error(Id::Null, Error::internal_error()),
+            Response::success(1, JsonValue::Null),
+        ];
+        let text =
+            String::from_utf8(batch_response(responses.clone()).unwrap().serialize()).unwrap();
+        let parsed = parse_batch(&text).unwrap();
+        assert_eq!(parsed, responses);
+        let correlation = correlate(&requests, parsed);
+        assert_eq!(
+            correlation.responses,
+            [Some(responses[2].clone()), None, Some(responses[0].clone())]
+        );
+        assert_eq!(correlation.unmatched, [responses[1].clone()]);
+
+        assert_eq!(
+            parse_batch(
+                r#"{"jsonrpc":"2.0","error":{"code":-32700,"message":"Parse error"},"id":null}"#
+            ),
+            Ok(vec![Response::error(Id::Null, Error::parse_error())])
+        );
+    }
+}
@@ -0,0 +1,390 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::json::{JsonObject, JsonValue};
+use crate::json_parser::{skip_bom, String, Value, Whitespace};
+use crate::parse_error::{token_end, ParseError, ParseErrorKind};
+use lite_parser::{
+    impls::SimplePosition,
+    parser::{Parser, ParserContext, ParserOptions, ParserState},
+    traits::Input,
+};
+
+struct Lenient<'a> {
+    input: &'a str,
+    errors: Vec<ParseError>,
+}
+
+impl<'a> Lenient<'a> {
+    fn peek(&self, pos: SimplePosition) -> Option<char> {
+        self.input.next(pos).ok().map(|(c, _)| c)
+    }
+
+    fn advance(&self, pos: SimplePosition) -> SimplePosition {
+        self.input.next(pos).map_or(pos, |(_, next)| next)
+    }
+
+    fn skip_whitespace(&self, pos: SimplePosition, context: &ParserContext) -> SimplePosition {
+        <Whitespace as Parser<&str>>::parse(&self.input, pos, context).map_or(pos, |(_, next)| next)
+    }
+
+    /// Moves to the byte offset `end`, keeping the line and column up to date.
+    fn skip_to(&self, mut pos: SimplePosition, end: usize) -> SimplePosition {
+        while (pos.index as usize) < end {
+            pos = self.advance(pos);
+        }
+        pos
+    }
+
+    /// Skips the token at `pos`, always moving forward unless the input has ended.
+    fn skip_token(&self, pos: SimplePosition) -> SimplePosition {
+        let end = token_end(
+            self.input,
+            pos.index as usize,
+            ParseErrorKind::UnexpectedToken,
+        );
+        self.skip_to(self.advance(pos), end)
+    }
+
+    /// Skips an array or object up to its matching closing bracket.
+    fn skip_nested(&self, mut pos: SimplePosition) -> SimplePosition {
+        let mut depth = 0;
+        while let Some(c) = self.peek(pos) {
+            match c {
+                '[' | '{' => depth += 1,
+                ']' | '}' => depth -= 1,
+                '"' => {
+                    pos = self.skip_token(pos);
+                    continue;
+                }
+                _ => {}
+            }
+            pos = self.advance(pos);
+            if depth == 0 {
+                break;
+            }
+        }
+        pos
+    }
+
+    /// Records an error, unless one was already reported at the same place.
+    fn error(&mut self, pos: SimplePosition, kind: ParseErrorKind) {
+        if self.errors.last().map(|e| e.offset) == Some(pos.index as usize) {
+            return;
+        }
+        let kind = if self.peek(pos).is_none() {
+            ParseErrorKind::UnexpectedEof
+        } else {
+            kind
+        };
+        self.errors.push(ParseError::new(self.input, pos, kind));
+    }
+
+    fn value(
+        &mut self,
+        pos: SimplePosition,
+        context: &ParserContext,
+    ) -> (JsonValue, SimplePosition) {
+        match self.peek(pos) {
+            Some(c @ ('{' | '[')) => match context.nest(&self.input, pos) {
+                Ok(context) => {
+                    if c == '{' {
+                        let (object, next) = self.object(self.advance(pos), &context);
+                        (JsonValue::Object(object), next)
+                    } else {
+                        let (array, next) = self.array(self.advance(pos), &context);
+                        (JsonValue::Array(array), next)
+                    }
+                }
+                Err(_) => {
+                    self.error(pos, ParseErrorKind::DepthLimitExceeded);
+                    (JsonValue::Null, self.skip_nested(pos))
+                }
+            },
+            None | Some(',' | ']' | '}') => {
+                self.error(pos, ParseErrorKind::UnexpectedToken);
+                (JsonValue::Null, pos)
+            }
+            Some(_) => match <Value as Parser<&str>>::parse(&self.input, pos, context) {
+                Ok(result) => result,
+                Err(e) => {
+                    self.errors.push(ParseError::from_simple(self.input, e));
+                    (JsonValue::Null, self.skip_token(pos))
+                }
+            },
+        }
+    }
+
+    /// Reads the items of an array after its `[`.
+    fn array(
+        &mut self,
+        mut pos: SimplePosition,
+        context: &ParserContext,
+    ) -> (Vec<JsonValue>, SimplePosition) {
+        let mut output = Vec::new();
+        pos = self.skip_whitespace(pos, context);
+        if self.peek(pos) == Some(']') {
+            return (output, self.advance(pos));
+        }
+        loop {
+            pos = self.skip_whitespace(pos, context);
+            if self.peek(pos) == Some(',') {
+                // An empty item is dropped.
+                self.error(pos, ParseErrorKind::UnexpectedToken);
+                pos = self.advance(pos);
+                continue;
+            }
+            let (value, next) = self.value(pos, context);
+            output.push(value);
+            pos = self.skip_whitespace(next, context);
+            match self.peek(pos) {
+                Some(',') => {
+                    pos = self.skip_whitespace(self.advance(pos), context);
+                    if self.peek(pos) == Some(']') {
+                        self.error(pos, ParseErrorKind::UnexpectedToken);
+                        return (output, self.advance(pos));
+                    }
+                }
+                Some(']') => return (output, self.advance(pos)),
+                // A mismatched bracket or the end of input closes the array.
+                None | Some('}') => {
+                    self.error(pos, ParseErrorKind::UnexpectedToken);
+                    return (output, pos);
+                }
+                // A missing comma.
+                Some(_) => self.error(pos, ParseErrorKind::UnexpectedToken),
+            }
+        }
+    }
+
+    /// Reads the members of an object after its `{`. Members with a malformed key are dropped.
+    fn object(
+        &mut self,
+        mut pos: SimplePosition,
+        context: &ParserContext,
+    ) -> (JsonObject, SimplePosition) {
+        let mut output = Vec::new();
+        loop {
+            pos = self.skip_whitespace(pos, context);
+            let key = match self.peek(pos) {
+                Some('}') => return (output, self.advance(pos)),
+                None | Some(']') => {
+                    self.error(pos, ParseErrorKind::UnexpectedToken);
+                    return (output, pos);
+                }
+                Some(',') => {
+                    self.error(pos, ParseErrorKind::UnexpectedToken);
+                    pos = self.advance(pos);
+                    continue;
+                }
+                Some('"') => match <String as Parser<&str>>::parse(&self.input, pos, context) {
+                    Ok((key, next)) => {
+                        pos = next;
+                        Some(key)
+                    }
+                    Err(e) => {
+                        self.errors.push(ParseError::from_simple(self.input, e));
+                        pos = self.skip_token(pos);
+                        None
+                    }
+                },
+                Some(_) => {
+                    self.error(pos, ParseErrorKind::UnexpectedToken);
+                    pos = self.skip_token(pos);
+                    None
+                }
+            };
+
+            pos = self.skip_whitespace(pos, context);
+            let value = match self.peek(pos) {
+                Some(':') => {
+                    let next = self.skip_whitespace(self.advance(pos), context);
+                    let (value, next) = self.value(next, context);
+                    pos = next;
+                    value
+                }
+                // A missing colon and value.
+                None | Some(',' | '}') => {
+                    self.error(pos, ParseErrorKind::UnexpectedToken);
+                    JsonValue::Null
+                }
+                // A missing colon.
+                Some(_) => {
+                    self.error(pos, ParseErrorKind::UnexpectedToken);
+                    let (value, next) = self.value(pos, context);
+                    pos = next;
+                    value
+                }
+            };
+            if let Some(key) = key {
+                output.push((key, value));
+            }
+
+            pos = self.skip_whitespace(pos, context);
+            match self.peek(pos) {
+                Some(',') => {
+                    pos = self.skip_whitespace(self.advance(pos), context);
+                    if self.peek(pos) == Some('}') {
+                        self.error(pos, ParseErrorKind::UnexpectedToken);
+                        return (output, self.advance(pos));
+                    }
+                }
+                Some('}') => return (output, self.advance(pos)),
+                None | Some(']') => {
+                    self.error(pos, ParseErrorKind::UnexpectedToken);
+                    return (output, pos);
+                }
+                // A missing comma.
+                Some(_) => self.error(pos, ParseErrorKind::UnexpectedToken),
+            }
+        }
+    }
+}
+
+/// Parses as much of a malformed document as possible, for tools such as linters and viewers.
+///
+/// Every problem is recorded and parsing continues: unparseable values become `Null`, empty
+/// array items and members with a malformed key are dropped, and missing commas, colons and
+/// closing brackets are assumed. For valid input the result matches `parse_json` and no errors
+/// are returned.
+pub fn parse_lenient(input: &str) -> (JsonValue, Vec<ParseError>) {
+    let options = ParserOptions::default();
+    let state = ParserState::default();
+    let context = ParserContext::new(&options, &state);
+    let mut parser = Lenient {
+        input,
+        errors: Vec::new(),
+    };
+    let pos = parser.skip_whitespace(skip_bom(input), &context);
+    let (value, pos) = parser.value(pos, &context);
+    let pos = parser.skip_whitespace(pos, &context);
+    if parser.peek(pos).is_some() {
+        parser.error(pos, ParseErrorKind::TrailingCharacters);
+    }
+    (value, parser.errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_parser::parse_json;
+
+    #[test]
+    fn lenient_parsing_recovers() {
+        let input = r#"{"a": [1, 2,, 3,], "b" 4, "c": tru, bad: 5, "d": 6 "e": {"f": }, "g": [1"#;
+        let (value, errors) = parse_lenient(input);
+        assert_eq!(
+            value,
+            parse_json(
+                r#"{"a": [1, 2, 3], "b": 4, "c": null, "d": 6, "e": {"f": null}, "g": [1]}"#
+            )
+            .unwrap()
+        );
+        let kinds: Vec<_> = errors
+            .iter()
+            .map(|e| (e.kind, &input[e.span.clone()]))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                (ParseErrorKind::UnexpectedToken, ","),
+                (ParseErrorKind::UnexpectedToken, "]"),
+                (ParseErrorKind::UnexpectedToken, "4"),
+                (ParseErrorKind::UnexpectedToken, "tru"),
+                (ParseErrorKind::UnexpectedToken, "bad"),
+                (ParseErrorKind::UnexpectedToken, "\"e\""),
+                (ParseErrorKind::UnexpectedToken, "}"),
+                (ParseErrorKind::UnexpectedEof, ""),
+            ]
+        );
+
+        let (value, errors) = parse_lenient(r#"{"a": 1,}"#);
+        assert_eq!(value, parse_json(r#"{"a": 1}"#).unwrap());
+        assert_eq!(errors[0].offset, 8);
+
+        let valid = r#"{"a": [1, {"b": null}], "c": "d"}"#;
+        assert_eq!(parse_lenient(valid), (parse_json(valid).unwrap(), vec![]));
+
+        let (value, errors) = parse_lenient("[1, [[[[2]]]], 3] x");
+        assert_eq!(value, parse_json("[1, [[[[2]]]], 3]").unwrap());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ParseErrorKind::TrailingCharacters);
+
+        let (value, errors) = parse_lenient("");
+        assert_eq!(value, JsonValue::Null);
+        assert_eq!(errors[0].kind, ParseErrorKind::UnexpectedEof);
+
+        let deep = format!("[1, {}2{}, 3]", "[".repeat(200), "]".repeat(200));
+        let (value, errors) = parse_lenient(&deep);
+        let kept = format!("[1, {}null{}, 3]", "[".repeat(99), "]".repeat(99));
+        assert_eq!(value, parse_json(&kept).unwrap());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ParseErrorKind::DepthLimitExceeded);
+    }
+
+    #[test]
+    fn lenient_parsing_reports_each_problem() {
+        let cases = [
+            ("[1}", "[1]", (ParseErrorKind::UnexpectedToken, 2, "}")),
+            (
+                r#"{"a": 1]"#,
+                r#"{"a": 1}"#,
+                (ParseErrorKind::UnexpectedToken, 7, "]"),
+            ),
+            (
+                r#"{"a\q": 1, "b": 2}"#,
+                r#"{"b": 2}"#,
+                (ParseErrorKind::InvalidEscape, 3, "\\q"),
+            ),
+            (
+                r#"["a\q", 2]"#,
+                "[null, 2]",
+                (ParseErrorKind::InvalidEscape, 3, "\\q"),
+            ),
+            (r#"["abc"#, "[null]", (ParseErrorKind::UnexpectedEof, 5, "")),
+            (
+                r#"{"a""#,
+                r#"{"a": null}"#,
+                (ParseErrorKind::UnexpectedEof, 4, ""),
+            ),
+            (
+                r#"{"a":"#,
+                r#"{"a": null}"#,
+                (ParseErrorKind::UnexpectedEof, 5, ""),
+            ),
+            (
+                "[1, 01, 2]",
+                "[1, null, 2]",
+                (ParseErrorKind::InvalidNumber, 4, "01"),
+            ),
+            (
+                "[1] [2]",
+                "[1]",
+                (ParseErrorKind::TrailingCharacters, 4, "["),
+            ),
+            (
+                "[é, 1]",
+                "[null, 1]",
+                (ParseErrorKind::UnexpectedToken, 1, "é"),
+            ),
+        ];
+        for (input, expected, error) in cases {
+            let (value, errors) = parse_lenient(input);
+            assert_eq!(value, parse_json(expected).unwrap(), "{}", input);
+            let errors: Vec<_> = errors
+                .iter()
+                .map(|e| (e.kind, e.offset, &input[e.span.clone()]))
+                .collect();
+            assert_eq!(errors, [error], "{}", input);
+        }
+
+        // A byte order mark is skipped like `parse_json` does.
+        assert_eq!(
+            parse_lenient("\u{FEFF}[1]"),
+            (parse_json("[1]").unwrap(), vec![])
+        );
+    }
+}
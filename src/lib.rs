@@ -1,9 +1,91 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+// Lets the derive macros refer to `::lite_json` from within this crate.
+extern crate self as lite_json;
+
+mod base64;
+#[cfg(feature = "bignum")]
+mod bignum;
+#[cfg(feature = "bson")]
+pub mod bson;
+#[cfg(any(feature = "std", feature = "float"))]
+mod canonical;
+pub mod case;
+pub mod codegen;
+mod commented;
+#[cfg(test)]
+mod conformance;
+pub mod convert;
+#[cfg(feature = "serde")]
+pub mod de;
+pub mod diff;
+mod feed;
+mod hash;
+mod hex;
 pub mod json;
 pub mod json_parser;
+pub mod jsonrpc;
+pub mod jwt;
+mod lenient;
+#[cfg(feature = "msgpack")]
+pub mod msgpack;
+mod number;
+mod parse_error;
+mod pointer;
+mod pull;
+mod raw;
+mod reader;
+pub mod schema;
+#[cfg(feature = "serde")]
+pub mod ser;
+#[cfg(feature = "serde_json")]
+mod serde_json_value;
+#[cfg(any(feature = "std", feature = "float"))]
+pub mod simple;
+mod spanned;
+pub mod stream;
+mod time;
 pub mod traits;
+#[cfg(feature = "ubjson")]
+pub mod ubjson;
+mod visit;
 
+pub use crate::base64::Base64Alphabet;
+#[cfg(feature = "bson")]
+pub use crate::bson::{from_bson, to_bson};
+pub use crate::case::*;
+pub use crate::commented::{parse_json_with_comments, CommentedJson, JsonComment};
+pub use crate::convert::*;
+#[cfg(feature = "serde")]
+pub use crate::de::{from_str, from_value};
+pub use crate::diff::*;
+pub use crate::feed::{FeedParser, FeedStatus};
 pub use crate::json::*;
 pub use crate::json_parser::*;
+pub use crate::jwt::{decode_jwt, Jwt};
+pub use crate::lenient::parse_lenient;
+#[cfg(feature = "msgpack")]
+pub use crate::msgpack::{from_msgpack, to_msgpack};
+pub use crate::parse_error::{ParseError, ParseErrorKind};
+pub use crate::pull::{extract, parse_with_handler, Handler, JsonEvent, PullParser};
+pub use crate::raw::{parse_json_with_raw, RawJsonValue, RawValue};
+#[cfg(feature = "mmap")]
+pub use crate::reader::parse_file;
+pub use crate::reader::{parse_bytes, parse_chars};
+#[cfg(feature = "std")]
+pub use crate::reader::{parse_reader, ReadError};
+#[cfg(feature = "serde")]
+pub use crate::ser::{to_string, to_value, to_vec};
+#[cfg(any(feature = "std", feature = "float"))]
+pub use crate::simple::*;
+pub use crate::spanned::{parse_json_spanned, SpannedJsonValue, SpannedValue};
+pub use crate::stream::*;
 pub use crate::traits::*;
+#[cfg(feature = "ubjson")]
+pub use crate::ubjson::{from_ubjson, to_ubjson};
+pub use crate::visit::{visit_json, VisitError, Visitor};
+
+#[cfg(feature = "bignum")]
+pub use crate::bignum::MAX_BIG_INTEGER_DIGITS;
+#[cfg(feature = "bignum")]
+pub use num_bigint;
@@ -0,0 +1,344 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::json::{utf16_lossy, JsonValue, NumberValue};
+use core::convert::TryInto;
+
+/// Nesting depth at which `from_msgpack` gives up, matching the parser's default limit.
+const MAX_DEPTH: usize = 100;
+
+/// The error returned by `from_msgpack`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MsgpackError {
+    /// The input ends in the middle of a value.
+    UnexpectedEnd,
+    /// The input continues after the first value.
+    TrailingBytes,
+    /// A string is not valid UTF-8.
+    InvalidUtf8,
+    /// A map key is not a string.
+    InvalidKey,
+    /// The value uses a type with no JSON equivalent, such as an extension type. The
+    /// offending marker byte is included.
+    Unsupported(u8),
+    /// Arrays and maps are nested too deeply.
+    TooDeep,
+}
+
+fn write_len(buffer: &mut Vec<u8>, len: usize, fix: u8, fix_max: usize, markers: [u8; 3]) {
+    if len <= fix_max {
+        buffer.push(fix | len as u8);
+    } else if len <= u8::MAX as usize && markers[0] != 0 {
+        buffer.push(markers[0]);
+        buffer.push(len as u8);
+    } else if len <= u16::MAX as usize {
+        buffer.push(markers[1]);
+        buffer.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        buffer.push(markers[2]);
+        buffer.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+}
+
+fn write_str(buffer: &mut Vec<u8>, s: &[char]) {
+    let len = s.iter().map(|c| c.len_utf8()).sum();
+    write_len(buffer, len, 0xa0, 31, [0xd9, 0xda, 0xdb]);
+    let mut utf8 = [0; 4];
+    for c in s {
+        buffer.extend_from_slice(c.encode_utf8(&mut utf8).as_bytes());
+    }
+}
+
+fn write_integer(buffer: &mut Vec<u8>, negative: bool, magnitude: u64) {
+    if !negative {
+        match magnitude {
+            0..=0x7f => buffer.push(magnitude as u8),
+            0x80..=0xff => buffer.extend_from_slice(&[0xcc, magnitude as u8]),
+            0x100..=0xffff => {
+                buffer.push(0xcd);
+                buffer.extend_from_slice(&(magnitude as u16).to_be_bytes());
+            }
+            0x1_0000..=0xffff_ffff => {
+                buffer.push(0xce);
+                buffer.extend_from_slice(&(magnitude as u32).to_be_bytes());
+            }
+            _ => {
+                buffer.push(0xcf);
+                buffer.extend_from_slice(&magnitude.to_be_bytes());
+            }
+        }
+    } else {
+        let val = (magnitude as i128).wrapping_neg() as i64;
+        if val >= -32 {
+            buffer.push(val as u8);
+        } else if val >= i8::MIN as i64 {
+            buffer.extend_from_slice(&[0xd0, val as u8]);
+        } else if val >= i16::MIN as i64 {
+            buffer.push(0xd1);
+            buffer.extend_from_slice(&(val as i16).to_be_bytes());
+        } else if val >= i32::MIN as i64 {
+            buffer.push(0xd2);
+            buffer.extend_from_slice(&(val as i32).to_be_bytes());
+        } else {
+            buffer.push(0xd3);
+            buffer.extend_from_slice(&val.to_be_bytes());
+        }
+    }
+}
+
+fn write_value(buffer: &mut Vec<u8>, value: &JsonValue) {
+    match value {
+        JsonValue::Null => buffer.push(0xc0),
+        JsonValue::Boolean(b) => buffer.push(if *b { 0xc3 } else { 0xc2 }),
+        JsonValue::Number(n) => {
+            let integer = n.to_integer().filter(|(negative, magnitude)| {
+                if *negative {
+                    *magnitude <= 1 << 63
+                } else {
+                    *magnitude <= u64::MAX as u128
+                }
+            });
+            match integer {
+                // -0 is kept as a float so its sign survives.
+                Some((negative, magnitude)) if !(n.negative && magnitude == 0) => {
+                    write_integer(buffer, negative, magnitude as u64)
+                }
+                _ => {
+                    buffer.push(0xcb);
+                    buffer.extend_from_slice(&n.to_f64().to_be_bytes());
+                }
+            }
+        }
+        JsonValue::BigNumber(text) => {
+            let val: f64 = core::str::from_utf8(text)
+                .ok()
+                .and_then(|t| t.parse().ok())
+                .unwrap_or(f64::NAN);
+            buffer.push(0xcb);
+            buffer.extend_from_slice(&val.to_be_bytes());
+        }
+        JsonValue::NonFinite(n) => {
+            buffer.push(0xcb);
+            buffer.extend_from_slice(&n.to_f64().to_be_bytes());
+        }
+        JsonValue::String(s) => write_str(buffer, s),
+        JsonValue::Utf16String(s) => write_str(buffer, &utf16_lossy(s)),
+        JsonValue::Array(arr) => {
+            write_len(buffer, arr.len(), 0x90, 15, [0, 0xdc, 0xdd]);
+            for item in arr {
+                write_value(buffer, item);
+            }
+        }
+        JsonValue::Object(obj) => {
+            write_len(buffer, obj.len(), 0x80, 15, [0, 0xde, 0xdf]);
+            for (key, val) in obj {
+                write_str(buffer, key);
+                write_value(buffer, val);
+            }
+        }
+    }
+}
+
+/// Encodes a value as MessagePack.
+///
+/// Integers that fit `i64` or `u64` use the smallest integer encoding; all other numbers,
+/// including big numbers, become 64-bit floats and may lose precision.
+pub fn to_msgpack(value: &JsonValue) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    write_value(&mut buffer, value);
+    buffer
+}
+
+struct Reader<'a> {
+    input: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], MsgpackError> {
+        if self.input.len() < len {
+            return Err(MsgpackError::UnexpectedEnd);
+        }
+        let (head, rest) = self.input.split_at(len);
+        self.input = rest;
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], MsgpackError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn len(&mut self, size: usize) -> Result<usize, MsgpackError> {
+        Ok(match size {
+            1 => self.take_array::<1>()?[0] as usize,
+            2 => u16::from_be_bytes(self.take_array()?) as usize,
+            _ => u32::from_be_bytes(self.take_array()?) as usize,
+        })
+    }
+
+    fn string(&mut self, len: usize) -> Result<Vec<char>, MsgpackError> {
+        let bytes = self.take(len)?;
+        core::str::from_utf8(bytes)
+            .map(|s| s.chars().collect())
+            .map_err(|_| MsgpackError::InvalidUtf8)
+    }
+
+    fn array(&mut self, len: usize, depth: usize) -> Result<JsonValue, MsgpackError> {
+        // Every item takes at least one byte, which bounds the allocation.
+        let mut arr = Vec::with_capacity(len.min(self.input.len()));
+        for _ in 0..len {
+            arr.push(self.value(depth + 1)?);
+        }
+        Ok(JsonValue::Array(arr))
+    }
+
+    fn map(&mut self, len: usize, depth: usize) -> Result<JsonValue, MsgpackError> {
+        let mut obj = Vec::with_capacity(len.min(self.input.len() / 2));
+        for _ in 0..len {
+            let key = match self.value(depth + 1)? {
+                JsonValue::String(key) => key,
+                _ => return Err(MsgpackError::InvalidKey),
+            };
+            obj.push((key, self.value(depth + 1)?));
+        }
+        Ok(JsonValue::Object(obj))
+    }
+
+    fn value(&mut self, depth: usize) -> Result<JsonValue, MsgpackError> {
+        if depth > MAX_DEPTH {
+            return Err(MsgpackError::TooDeep);
+        }
+        let marker = self.take_array::<1>()?[0];
+        let unsigned = |val: u64| {
+            JsonValue::Number(NumberValue {
+                integer: val as u128,
+                ..Default::default()
+            })
+        };
+        let signed = |val: i64| {
+            JsonValue::Number(NumberValue {
+                integer: val.unsigned_abs() as u128,
+                negative: val < 0,
+                ..Default::default()
+            })
+        };
+        Ok(match marker {
+            0x00..=0x7f => unsigned(marker as u64),
+            0x80..=0x8f => self.map((marker & 0x0f) as usize, depth)?,
+            0x90..=0x9f => self.array((marker & 0x0f) as usize, depth)?,
+            0xa0..=0xbf => JsonValue::String(self.string((marker & 0x1f) as usize)?),
+            0xc0 => JsonValue::Null,
+            0xc2 => JsonValue::Boolean(false),
+            0xc3 => JsonValue::Boolean(true),
+            0xc4..=0xc6 => {
+                let len = self.len(1 << (marker - 0xc4))?;
+                let bytes = self.take(len)?;
+                JsonValue::Array(bytes.iter().map(|b| unsigned(*b as u64)).collect())
+            }
+            0xca => NumberValue::from_f32(f32::from_be_bytes(self.take_array()?))
+                .map_or(JsonValue::Null, JsonValue::Number),
+            0xcb => f64::from_be_bytes(self.take_array()?).into(),
+            0xcc => unsigned(self.take_array::<1>()?[0] as u64),
+            0xcd => unsigned(u16::from_be_bytes(self.take_array()?) as u64),
+            0xce => unsigned(u32::from_be_bytes(self.take_array()?) as u64),
+            0xcf => unsigned(u64::from_be_bytes(self.take_array()?)),
+            0xd0 => signed(i8::from_be_bytes(self.take_array()?) as i64),
+            0xd1 => signed(i16::from_be_bytes(self.take_array()?) as i64),
+            0xd2 => signed(i32::from_be_bytes(self.take_array()?) as i64),
+            0xd3 => signed(i64::from_be_bytes(self.take_array()?)),
+            0xd9..=0xdb => {
+                let len = self.len(1 << (marker - 0xd9))?;
+                JsonValue::String(self.string(len)?)
+            }
+            0xdc | 0xdd => {
+                let len = self.len(2 << (marker - 0xdc))?;
+                self.array(len, depth)?
+            }
+            0xde | 0xdf => {
+                let len = self.len(2 << (marker - 0xde))?;
+                self.map(len, depth)?
+            }
+            0xe0..=0xff => signed(marker as i8 as i64),
+            _ => return Err(MsgpackError::Unsupported(marker)),
+        })
+    }
+}
+
+/// Decodes a single MessagePack value.
+///
+/// Binary data becomes an array of byte values and non-finite floats become `NonFinite`.
+/// Extension types and maps with non-string keys are rejected.
+pub fn from_msgpack(input: &[u8]) -> Result<JsonValue, MsgpackError> {
+    let mut reader = Reader { input };
+    let value = reader.value(0)?;
+    if !reader.input.is_empty() {
+        return Err(MsgpackError::TrailingBytes);
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_parser::parse_json;
+
+    #[test]
+    fn msgpack_round_trips() {
+        let value = parse_json(
+            r#"{"a":[0,127,128,-32,-33,-129,65536,-2147483649,18446744073709551615],"b":1.5,"c":"x","d":[null,true,false,{}]}"#,
+        )
+        .unwrap();
+        let bytes = to_msgpack(&value);
+        assert_eq!(&bytes[..5], &[0x84, 0xa1, b'a', 0x99, 0x00]);
+        assert_eq!(from_msgpack(&bytes), Ok(value));
+
+        let unicode = JsonValue::String(vec!['é', '😀']);
+        assert_eq!(&to_msgpack(&unicode)[..1], &[0xa6]);
+        assert_eq!(from_msgpack(&to_msgpack(&unicode)), Ok(unicode));
+
+        let long = JsonValue::String(vec!['x'; 300]);
+        assert_eq!(&to_msgpack(&long)[..3], &[0xda, 0x01, 0x2c]);
+        assert_eq!(from_msgpack(&to_msgpack(&long)), Ok(long));
+    }
+
+    #[test]
+    fn msgpack_converts_floats_and_binary() {
+        let value = parse_json("[0.1, -0, 1e400]").unwrap();
+        assert_eq!(
+            format!("{}", from_msgpack(&to_msgpack(&value)).unwrap()),
+            "[0.1,-0,Infinity]"
+        );
+        assert_eq!(
+            format!("{}", from_msgpack(&[0xc4, 0x02, 0x01, 0xff]).unwrap()),
+            "[1,255]"
+        );
+    }
+
+    #[test]
+    fn msgpack_rejects_bad_input() {
+        assert_eq!(
+            from_msgpack(&[0x92, 0x01]),
+            Err(MsgpackError::UnexpectedEnd)
+        );
+        assert_eq!(
+            from_msgpack(&[0x01, 0x02]),
+            Err(MsgpackError::TrailingBytes)
+        );
+        assert_eq!(
+            from_msgpack(&[0x81, 0x01, 0x01]),
+            Err(MsgpackError::InvalidKey)
+        );
+        assert_eq!(from_msgpack(&[0xa1, 0xff]), Err(MsgpackError::InvalidUtf8));
+        assert_eq!(
+            from_msgpack(&[0xd4, 0x01, 0x00]),
+            Err(MsgpackError::Unsupported(0xd4))
+        );
+        assert_eq!(from_msgpack(&[0x91; 200]), Err(MsgpackError::TooDeep));
+        assert_eq!(
+            from_msgpack(&[0xdd, 0xff, 0xff, 0xff, 0xff]),
+            Err(MsgpackError::UnexpectedEnd)
+        );
+    }
+}
@@ -0,0 +1,324 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(all(not(feature = "std"), feature = "float"))]
+use alloc::string::String;
+
+use crate::json::{Decimal, NumberValue};
+#[cfg(any(feature = "std", feature = "float"))]
+use crate::json::{NonFinite, PrecisionLoss};
+use core::convert::TryInto;
+#[cfg(any(feature = "std", feature = "float"))]
+use core::fmt::Write as _;
+
+/// A number as `mantissa * 10^exponent`, the form arithmetic is done in.
+struct Scaled {
+    negative: bool,
+    mantissa: u128,
+    exponent: i64,
+}
+
+impl Scaled {
+    fn from_number(num: &NumberValue) -> Option<Self> {
+        let decimal = Decimal::from_number(num);
+        let mantissa = decimal.digits.iter().try_fold(0u128, |acc, d| {
+            acc.checked_mul(10)?.checked_add((d - b'0') as u128)
+        })?;
+        Some(Scaled {
+            negative: decimal.negative,
+            mantissa,
+            exponent: decimal.point - decimal.digits.len() as i64,
+        })
+    }
+
+    fn to_number(&self) -> Option<NumberValue> {
+        let exponent = if self.mantissa == 0 {
+            0
+        } else {
+            self.exponent.try_into().ok()?
+        };
+        Some(
+            NumberValue {
+                integer: self.mantissa,
+                fraction: 0,
+                fraction_length: 0,
+                exponent,
+                negative: self.negative,
+            }
+            .normalize(),
+        )
+    }
+
+    /// Returns the mantissa expressed with the smaller `exponent`.
+    fn rescale(&self, exponent: i64) -> Option<u128> {
+        let shift: u32 = (self.exponent - exponent).try_into().ok()?;
+        self.mantissa.checked_mul(10u128.checked_pow(shift)?)
+    }
+}
+
+/// Returns whether `val` is exactly the value of `num`.
+#[cfg(any(feature = "std", feature = "float"))]
+fn is_exact(num: &NumberValue, val: impl core::fmt::LowerExp) -> bool {
+    let mut text = String::new();
+    // Every finite `f64` is written exactly with 767 digits after the point.
+    write!(text, "{:.767e}", val).expect("writing to a string cannot fail");
+    Decimal::from_text(text.as_bytes()) == Decimal::from_number(num)
+}
+
+impl NumberValue {
+    /// Converts the value to `f64`, failing if the result is rounded, infinite or flushed to
+    /// zero.
+    #[cfg(any(feature = "std", feature = "float"))]
+    pub fn to_f64_exact(self) -> Result<f64, PrecisionLoss> {
+        let val = self.to_f64();
+        if val.is_finite() && is_exact(&self, val) {
+            Ok(val)
+        } else {
+            Err(PrecisionLoss)
+        }
+    }
+
+    /// Converts the value to `f32`, failing if the result is rounded, infinite or flushed to
+    /// zero.
+    #[cfg(any(feature = "std", feature = "float"))]
+    pub fn to_f32_exact(self) -> Result<f32, PrecisionLoss> {
+        let val = self.to_f32();
+        if val.is_finite() && is_exact(&self, val) {
+            Ok(val)
+        } else {
+            Err(PrecisionLoss)
+        }
+    }
+
+    /// Converts a float to the shortest number that reads back as the same `f64`.
+    ///
+    /// Negative zero becomes `-0`. Returns None for NaN and infinities, which JSON cannot
+    /// represent.
+    #[cfg(any(feature = "std", feature = "float"))]
+    pub fn from_f64(val: f64) -> Option<NumberValue> {
+        if !val.is_finite() {
+            return None;
+        }
+        let mut text = String::new();
+        write!(text, "{:e}", val).ok()?;
+        let num = text.parse::<NumberValue>().ok()?.normalize();
+        Some(NumberValue {
+            negative: val.is_sign_negative(),
+            ..num
+        })
+    }
+
+    /// Converts a float to the shortest number that reads back as the same `f32`.
+    ///
+    /// Returns None for NaN and infinities.
+    #[cfg(any(feature = "std", feature = "float"))]
+    pub fn from_f32(val: f32) -> Option<NumberValue> {
+        if !val.is_finite() {
+            return None;
+        }
+        let mut text = String::new();
+        write!(text, "{:e}", val).ok()?;
+        let num = text.parse::<NumberValue>().ok()?.normalize();
+        Some(NumberValue {
+            negative: val.is_sign_negative(),
+            ..num
+        })
+    }
+
+    /// Converts the value to a fixed-point integer with `decimals` decimal places.
+    ///
+    /// For example `123.456` with 6 decimals becomes `123_456_000`. Digits beyond `decimals` are
+    /// truncated. Returns None for negative numbers or if the result does not fit in `u128`.
+    pub fn to_fixed_point(self, decimals: u32) -> Option<u128> {
+        Decimal::from_number(&self).to_fixed_point(decimals)
+    }
+
+    /// Adds two numbers exactly, returning None if the result does not fit.
+    ///
+    /// The result is normalized. Arithmetic works on the decimal digits, so `0.1 + 0.2` is
+    /// exactly `0.3`; it fails when the combined significant digits exceed `u128`.
+    pub fn checked_add(&self, other: &NumberValue) -> Option<NumberValue> {
+        let a = Scaled::from_number(self)?;
+        let b = Scaled::from_number(other)?;
+        let exponent = a.exponent.min(b.exponent);
+        let (x, y) = (a.rescale(exponent)?, b.rescale(exponent)?);
+        let (negative, mantissa) = if a.negative == b.negative {
+            (a.negative, x.checked_add(y)?)
+        } else if x >= y {
+            (a.negative, x - y)
+        } else {
+            (b.negative, y - x)
+        };
+        Scaled {
+            negative,
+            mantissa,
+            exponent,
+        }
+        .to_number()
+    }
+
+    /// Subtracts `other` exactly, returning None if the result does not fit.
+    pub fn checked_sub(&self, other: &NumberValue) -> Option<NumberValue> {
+        self.checked_add(&NumberValue {
+            negative: !other.negative,
+            ..*other
+        })
+    }
+
+    /// Multiplies two numbers exactly, returning None if the result does not fit.
+    pub fn checked_mul(&self, other: &NumberValue) -> Option<NumberValue> {
+        let a = Scaled::from_number(self)?;
+        let b = Scaled::from_number(other)?;
+        Scaled {
+            negative: a.negative != b.negative,
+            mantissa: a.mantissa.checked_mul(b.mantissa)?,
+            exponent: a.exponent + b.exponent,
+        }
+        .to_number()
+    }
+}
+
+#[cfg(any(feature = "std", feature = "float"))]
+impl From<f64> for crate::json::JsonValue {
+    /// Builds a `Number`, or a `NonFinite` for NaN and infinities.
+    fn from(val: f64) -> Self {
+        match NumberValue::from_f64(val) {
+            Some(num) => Self::Number(num),
+            None if val.is_nan() => Self::NonFinite(NonFinite::NaN),
+            None if val > 0.0 => Self::NonFinite(NonFinite::Infinity),
+            None => Self::NonFinite(NonFinite::NegativeInfinity),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::JsonValue;
+
+    fn num(s: &str) -> NumberValue {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn checked_add_works() {
+        assert_eq!(num("0.1").checked_add(&num("0.2")), Some(num("0.3")));
+        assert_eq!(num("1e3").checked_add(&num("-0.5")), Some(num("999.5")));
+        assert_eq!(num("-2").checked_add(&num("1.5")), Some(num("-0.5")));
+        assert_eq!(num("-2").checked_add(&num("-1.25")), Some(num("-3.25")));
+        assert_eq!(num("2").checked_add(&num("-2")), Some(num("0")));
+        assert_eq!(
+            num("340282366920938463463374607431768211455").checked_add(&num("1")),
+            None
+        );
+        assert_eq!(num("1e30").checked_add(&num("1e-30")), None);
+    }
+
+    #[test]
+    fn checked_sub_works() {
+        assert_eq!(num("1").checked_sub(&num("0.75")), Some(num("0.25")));
+        assert_eq!(num("0.75").checked_sub(&num("1")), Some(num("-0.25")));
+        assert_eq!(num("-1").checked_sub(&num("-1")), Some(num("0")));
+    }
+
+    #[test]
+    fn checked_mul_works() {
+        assert_eq!(num("1.5").checked_mul(&num("-2")), Some(num("-3")));
+        assert_eq!(num("-0.1").checked_mul(&num("-0.1")), Some(num("0.01")));
+        assert_eq!(num("12e40").checked_mul(&num("1e-40")), Some(num("12")));
+        assert_eq!(num("0").checked_mul(&num("-5")), Some(num("0")));
+        assert_eq!(num("1e30").checked_mul(&num("1e-30")), Some(num("1")));
+        assert_eq!(
+            num("99999999999999999999").checked_mul(&num("99999999999999999999")),
+            None
+        );
+    }
+
+    #[test]
+    fn to_fixed_point_works() {
+        assert_eq!(num("123.456").to_fixed_point(6), Some(123_456_000));
+        assert_eq!(num("123.456").to_fixed_point(0), Some(123));
+        assert_eq!(num("123.456").to_fixed_point(2), Some(12_345));
+        assert_eq!(num("1.5e-3").to_fixed_point(4), Some(15));
+        assert_eq!(num("1.5e-3").to_fixed_point(2), Some(0));
+        assert_eq!(num("25e2").to_fixed_point(2), Some(250_000));
+        assert_eq!(num("0").to_fixed_point(18), Some(0));
+        assert_eq!(num("-0").to_fixed_point(18), Some(0));
+        assert_eq!(num("-1.5").to_fixed_point(2), None);
+        assert_eq!(num("1e30").to_fixed_point(18), None);
+        assert_eq!(num("1").to_fixed_point(38), Some(10u128.pow(38)));
+        assert_eq!(num("1").to_fixed_point(u32::MAX), None);
+        assert_eq!(num("0").to_fixed_point(u32::MAX), Some(0));
+        assert_eq!(num("1e-30").to_fixed_point(u32::MAX), None);
+        assert_eq!(num("1e2147483647").to_fixed_point(u32::MAX), None);
+    }
+
+    #[test]
+    fn from_f64_works() {
+        assert_eq!(NumberValue::from_f64(1.5), Some(num("1.5")));
+        assert_eq!(NumberValue::from_f64(-0.1), Some(num("-0.1")));
+        assert_eq!(NumberValue::from_f64(100.0), Some(num("100")));
+        assert_eq!(NumberValue::from_f64(1e300), Some(num("1e300")));
+        for val in [0.1 + 0.2, f64::MAX, f64::MIN_POSITIVE, 5e-324, -123.456] {
+            assert_eq!(NumberValue::from_f64(val).unwrap().to_f64(), val);
+        }
+        assert_eq!(NumberValue::from_f64(f64::NAN), None);
+        assert_eq!(NumberValue::from_f64(-0.0), Some(num("-0")));
+        assert_eq!(
+            NumberValue::from_f64(0.1 + 0.2),
+            Some(num("0.30000000000000004"))
+        );
+        assert_eq!(
+            NumberValue::from_f64(f64::MAX),
+            Some(num("17976931348623157e292"))
+        );
+        assert_eq!(format!("{}", JsonValue::from(2.5)), "2.5");
+        assert_eq!(
+            JsonValue::from(f64::INFINITY),
+            JsonValue::NonFinite(NonFinite::Infinity)
+        );
+        assert_eq!(
+            JsonValue::from(f64::NEG_INFINITY),
+            JsonValue::NonFinite(NonFinite::NegativeInfinity)
+        );
+        assert_eq!(
+            JsonValue::from(f64::NAN),
+            JsonValue::NonFinite(NonFinite::NaN)
+        );
+        assert_eq!(NumberValue::from_f32(0.1), Some(num("0.1")));
+        assert_eq!(NumberValue::from_f32(-0.0), Some(num("-0")));
+    }
+
+    #[test]
+    fn exact_float_conversion_works() {
+        assert_eq!(num("1.5").to_f64_exact(), Ok(1.5));
+        assert_eq!(num("-0").to_f64_exact(), Ok(-0.0));
+        assert_eq!(
+            num("9007199254740992").to_f64_exact(),
+            Ok(9007199254740992.0)
+        );
+        assert_eq!(num("9007199254740993").to_f64_exact(), Err(PrecisionLoss));
+        assert_eq!(num("0.1").to_f64_exact(), Err(PrecisionLoss));
+        assert_eq!(num("1e400").to_f64_exact(), Err(PrecisionLoss));
+        assert_eq!(num("1e-400").to_f64_exact(), Err(PrecisionLoss));
+        assert_eq!(
+            num("0.000030517578125").to_f64_exact(),
+            Ok(0.000030517578125)
+        );
+        assert_eq!(
+            num("4.9406564584124654e-324").to_f64_exact(),
+            Err(PrecisionLoss)
+        );
+
+        assert_eq!(num("16777216").to_f32_exact(), Ok(16777216.0));
+        assert_eq!(num("16777217").to_f32_exact(), Err(PrecisionLoss));
+        assert_eq!(num("0.75").to_f32_exact(), Ok(0.75));
+    }
+
+    #[test]
+    fn comparison_operators_use_value() {
+        assert!(num("1e3") > num("999.99"));
+        assert!(num("-1") < num("0.5"));
+        assert!(num("0.1").checked_add(&num("0.2")).unwrap() >= num("0.3"));
+    }
+}
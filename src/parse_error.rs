@@ -0,0 +1,151 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+
+use core::fmt;
+use core::ops::Range;
+pub use lite_parser::impls::ParseErrorKind;
+use lite_parser::impls::{SimpleError, SimplePosition};
+
+/// How many characters of context the snippet keeps on each side of the error.
+const SNIPPET_CONTEXT: usize = 20;
+
+/// The error returned when JSON text cannot be parsed, locating the offending input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// What went wrong.
+    pub kind: ParseErrorKind,
+    /// The 1-based line of the error.
+    pub line: usize,
+    /// The 1-based column of the error, counted in characters.
+    pub column: usize,
+    /// The byte offset of the error in the input.
+    pub offset: usize,
+    /// The byte range of the offending token, starting at `offset`. It is empty when the input
+    /// ended early.
+    pub span: Range<usize>,
+    /// Up to 20 characters on either side of the error, without crossing a line break.
+    pub snippet: String,
+}
+
+/// Finds where the token starting at `start` ends: a whole string or escape, a single bracket
+/// or separator, or otherwise a run of characters up to the next delimiter.
+pub(crate) fn token_end(input: &str, start: usize, kind: ParseErrorKind) -> usize {
+    let rest = &input[start..];
+    let mut chars = rest.char_indices();
+    let len = match (kind, chars.next()) {
+        (ParseErrorKind::UnexpectedEof, _) | (_, None) => 0,
+        (ParseErrorKind::InputTooLong, _) => rest.len(),
+        (ParseErrorKind::InvalidEscape, Some(_)) => {
+            let escape = match rest[1..].chars().next() {
+                Some('u') => 6,
+                Some('x') => 4,
+                _ => 2,
+            };
+            rest.char_indices()
+                .nth(escape)
+                .map_or(rest.len(), |(i, _)| i)
+        }
+        (_, Some((_, '{' | '}' | '[' | ']' | ',' | ':'))) => 1,
+        (_, Some((_, '"'))) => {
+            let mut escaped = false;
+            chars
+                .find(|(_, c)| {
+                    let end = !escaped && (*c == '"' || *c == '\n');
+                    escaped = !escaped && *c == '\\';
+                    end
+                })
+                .map_or(rest.len(), |(i, c)| if c == '"' { i + 1 } else { i })
+        }
+        (_, Some(_)) => rest
+            .find(|c: char| c.is_whitespace() || "{}[],:\"".contains(c))
+            .unwrap_or(rest.len()),
+    };
+    start + len
+}
+
+impl ParseError {
+    pub(crate) fn new(input: &str, position: SimplePosition, kind: ParseErrorKind) -> Self {
+        let offset = (position.index as usize).min(input.len());
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        let before = input[line_start..offset]
+            .char_indices()
+            .rev()
+            .nth(SNIPPET_CONTEXT - 1)
+            .map_or(line_start, |(i, _)| line_start + i);
+        let after = input[offset..line_end]
+            .char_indices()
+            .nth(SNIPPET_CONTEXT)
+            .map_or(line_end, |(i, _)| offset + i);
+        ParseError {
+            kind,
+            line: position.line as usize + 1,
+            column: position.column as usize + 1,
+            offset,
+            span: offset..token_end(input, offset, kind),
+            snippet: input[before..after].trim_end_matches('\r').into(),
+        }
+    }
+
+    /// Locates the innermost failure of a parser error within `input`.
+    pub(crate) fn from_simple(input: &str, error: SimpleError) -> Self {
+        let position = error
+            .reasons
+            .first()
+            .map_or(SimplePosition::default(), |(position, _)| *position);
+        let kind = match error.kind {
+            ParseErrorKind::UnexpectedToken if position.index as usize >= input.len() => {
+                ParseErrorKind::UnexpectedEof
+            }
+            kind => kind,
+        };
+        Self::new(input, position, kind)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {} column {}",
+            self.kind, self.line, self.column
+        )?;
+        if !self.snippet.is_empty() {
+            write!(f, " near `{}`", self.snippet)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::json_parser::parse_json;
+
+    #[test]
+    fn parse_errors_display() {
+        let err = parse_json("{\n  \"a\": tru\n}").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unexpected character at line 2 column 8 near `  \"a\": tru`"
+        );
+        assert_eq!(
+            parse_json("").unwrap_err().to_string(),
+            "unexpected end of input at line 1 column 1"
+        );
+
+        fn parse(text: &str) -> Result<(), Box<dyn std::error::Error>> {
+            parse_json(text)?;
+            Ok(())
+        }
+        assert!(parse("[1]").is_ok());
+        assert!(parse("[1,]").is_err());
+    }
+}
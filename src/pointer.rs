@@ -0,0 +1,344 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+use crate::json::JsonValue;
+use core::fmt::Write as _;
+
+/// Appends an object key to a JSON Pointer, escaping `~` and `/`.
+pub(crate) fn push_key(path: &mut String, key: &[char]) {
+    path.push('/');
+    for ch in key {
+        match ch {
+            '~' => path.push_str("~0"),
+            '/' => path.push_str("~1"),
+            _ => path.push(*ch),
+        }
+    }
+}
+
+/// Appends an array index to a JSON Pointer.
+pub(crate) fn push_index(path: &mut String, index: usize) {
+    let _ = write!(path, "/{}", index);
+}
+
+/// Splits a JSON Pointer into its unescaped reference tokens.
+///
+/// Returns None if the pointer is neither empty nor starts with `/`, or contains an invalid
+/// `~` escape.
+pub(crate) fn tokens<I: Iterator<Item = char>>(mut path: I) -> Option<Vec<Vec<char>>> {
+    let mut tokens = Vec::new();
+    match path.next() {
+        None => return Some(tokens),
+        Some('/') => tokens.push(Vec::new()),
+        Some(_) => return None,
+    }
+    while let Some(ch) = path.next() {
+        let token = tokens.last_mut()?;
+        match ch {
+            '/' => tokens.push(Vec::new()),
+            '~' => match path.next() {
+                Some('0') => token.push('~'),
+                Some('1') => token.push('/'),
+                _ => return None,
+            },
+            _ => token.push(ch),
+        }
+    }
+    Some(tokens)
+}
+
+/// Parses a reference token as an array index, rejecting leading zeros.
+pub(crate) fn parse_index(token: &[char]) -> Option<usize> {
+    if token.is_empty() || (token.len() > 1 && token[0] == '0') {
+        return None;
+    }
+    token.iter().try_fold(0usize, |acc, ch| {
+        acc.checked_mul(10)?.checked_add(ch.to_digit(10)? as usize)
+    })
+}
+
+impl JsonValue {
+    /// Flattens the document into a single-level object keyed by JSON Pointer paths.
+    ///
+    /// Scalars, empty arrays and empty objects become members, e.g. `{"a":[1]}` becomes
+    /// `{"/a/0":1}`. A scalar document is keyed by the empty path.
+    pub fn flatten(&self) -> JsonValue {
+        let mut members = Vec::new();
+        flatten_into(&mut String::new(), self, &mut members);
+        JsonValue::Object(members)
+    }
+
+    /// Returns the value at the JSON Pointer `path`, or None if the path is invalid or
+    /// nothing is there. Repeated object keys resolve to the last member.
+    pub fn pointer(&self, path: &str) -> Option<&JsonValue> {
+        tokens(path.chars())?
+            .iter()
+            .try_fold(self, |value, token| match value {
+                JsonValue::Object(obj) => {
+                    obj.iter().rev().find(|(k, _)| k == token).map(|(_, v)| v)
+                }
+                JsonValue::Array(arr) => arr.get(parse_index(token)?),
+                _ => None,
+            })
+    }
+
+    /// Rebuilds a document from an object produced by `flatten`.
+    ///
+    /// Tokens that are array indexes create arrays and all other tokens create objects, so an
+    /// object whose keys look like indexes comes back as an array. Returns None if this value is
+    /// not an object, a key is not a valid JSON Pointer, an array index skips ahead of the items
+    /// before it or two paths conflict.
+    pub fn unflatten(&self) -> Option<JsonValue> {
+        let mut root = None;
+        for (path, val) in self.as_object()? {
+            let tokens = tokens(path.iter().copied())?;
+            insert_at(&mut root, &tokens, val.clone())?;
+        }
+        Some(root.unwrap_or(JsonValue::Object(Vec::new())))
+    }
+
+    /// Replaces every value matching one of the JSON Pointer `paths` with `replacement`.
+    ///
+    /// A `*` token matches any key or index at its level, so `/*/password` redacts the
+    /// `password` member of every child. Every member with a matching key is replaced, including
+    /// repeated keys. Paths that are not valid JSON Pointers match nothing.
+    pub fn redact(&mut self, paths: &[&str], replacement: JsonValue) {
+        for path in paths {
+            if let Some(tokens) = tokens(path.chars()) {
+                redact_at(self, &tokens, &replacement);
+            }
+        }
+    }
+}
+
+fn redact_at(value: &mut JsonValue, tokens: &[Vec<char>], replacement: &JsonValue) {
+    let (token, rest) = match tokens.split_first() {
+        None => {
+            *value = replacement.clone();
+            return;
+        }
+        Some(split) => split,
+    };
+    let wildcard = token[..] == ['*'];
+    match value {
+        JsonValue::Object(obj) => {
+            for (key, val) in obj.iter_mut() {
+                if wildcard || key == token {
+                    redact_at(val, rest, replacement);
+                }
+            }
+        }
+        JsonValue::Array(arr) => {
+            if wildcard {
+                for val in arr.iter_mut() {
+                    redact_at(val, rest, replacement);
+                }
+            } else if let Some(val) = parse_index(token).and_then(|i| arr.get_mut(i)) {
+                redact_at(val, rest, replacement);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn flatten_into(path: &mut String, value: &JsonValue, members: &mut Vec<(Vec<char>, JsonValue)>) {
+    let len = path.len();
+    match value {
+        JsonValue::Object(obj) if !obj.is_empty() => {
+            for (key, val) in obj {
+                push_key(path, key);
+                flatten_into(path, val, members);
+                path.truncate(len);
+            }
+        }
+        JsonValue::Array(arr) if !arr.is_empty() => {
+            for (i, val) in arr.iter().enumerate() {
+                push_index(path, i);
+                flatten_into(path, val, members);
+                path.truncate(len);
+            }
+        }
+        _ => members.push((path.chars().collect(), value.clone())),
+    }
+}
+
+fn insert_at(slot: &mut Option<JsonValue>, tokens: &[Vec<char>], value: JsonValue) -> Option<()> {
+    let (token, rest) = match tokens.split_first() {
+        None => {
+            return match slot {
+                None => {
+                    *slot = Some(value);
+                    Some(())
+                }
+                Some(_) => None,
+            };
+        }
+        Some(split) => split,
+    };
+
+    let container = slot.get_or_insert_with(|| match parse_index(token) {
+        Some(_) => JsonValue::Array(Vec::new()),
+        None => JsonValue::Object(Vec::new()),
+    });
+    match container {
+        JsonValue::Array(arr) => {
+            // Flattened arrays are dense, so an index may only add the next item.
+            let index = parse_index(token).filter(|index| *index <= arr.len())?;
+            match arr.get_mut(index) {
+                Some(existing) => {
+                    let mut child = Some(core::mem::replace(existing, JsonValue::Null));
+                    let res = insert_at(&mut child, rest, value);
+                    *existing = child.unwrap_or(JsonValue::Null);
+                    res?;
+                }
+                None => {
+                    let mut child = None;
+                    insert_at(&mut child, rest, value)?;
+                    arr.push(child.unwrap_or(JsonValue::Null));
+                }
+            }
+        }
+        JsonValue::Object(obj) => match obj.iter_mut().find(|(k, _)| k == token) {
+            Some((_, existing)) => {
+                let mut child = Some(core::mem::replace(existing, JsonValue::Null));
+                let res = insert_at(&mut child, rest, value);
+                *existing = child.unwrap_or(JsonValue::Null);
+                res?;
+            }
+            None => {
+                let mut child = None;
+                insert_at(&mut child, rest, value)?;
+                obj.push((token.clone(), child.unwrap_or(JsonValue::Null)));
+            }
+        },
+        _ => return None,
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_parser::parse_json;
+
+    #[test]
+    fn tokens_works() {
+        assert_eq!(tokens("".chars()), Some(vec![]));
+        assert_eq!(tokens("/".chars()), Some(vec![vec![]]));
+        assert_eq!(
+            tokens("/a~1b/~0/0".chars()),
+            Some(vec![vec!['a', '/', 'b'], vec!['~'], vec!['0']])
+        );
+        assert_eq!(tokens("a".chars()), None);
+        assert_eq!(tokens("/~2".chars()), None);
+        assert_eq!(parse_index(&['1', '0']), Some(10));
+        assert_eq!(parse_index(&['0', '1']), None);
+        assert_eq!(parse_index(&['-']), None);
+    }
+
+    #[test]
+    fn pointer_works() {
+        let value = parse_json(r#"{"a":[{"b/c":1}],"":2}"#).unwrap();
+        assert_eq!(value.pointer(""), Some(&value));
+        assert_eq!(format!("{}", value.pointer("/a/0/b~1c").unwrap()), "1");
+        assert_eq!(format!("{}", value.pointer("/").unwrap()), "2");
+        assert_eq!(value.pointer("/a/01"), None);
+        assert_eq!(value.pointer("/a/0/b~1c/d"), None);
+        assert_eq!(value.pointer("a"), None);
+
+        let value = parse_json(r#"{"a":{"b":1},"a":{"b":2}}"#).unwrap();
+        assert_eq!(format!("{}", value.pointer("/a/b").unwrap()), "2");
+    }
+
+    #[test]
+    fn flatten_works() {
+        let value = parse_json(r#"{"a":{"b":[1,{"c":null}],"d":[]},"e/f":{},"g~":"x"}"#).unwrap();
+        let flat = value.flatten();
+        assert_eq!(
+            flat,
+            parse_json(r#"{"/a/b/0":1,"/a/b/1/c":null,"/a/d":[],"/e~1f":{},"/g~0":"x"}"#).unwrap()
+        );
+        assert_eq!(flat.unflatten(), Some(value));
+
+        assert_eq!(
+            JsonValue::Boolean(true).flatten(),
+            parse_json(r#"{"":true}"#).unwrap()
+        );
+        assert_eq!(
+            parse_json(r#"{"":true}"#).unwrap().unflatten(),
+            Some(JsonValue::Boolean(true))
+        );
+    }
+
+    #[test]
+    fn redact_works() {
+        let mut value = parse_json(
+            r#"{"users":[{"name":"a","password":"x"},{"name":"b"}],"token":"t","db":{"password":"y","password":"z"}}"#,
+        )
+        .unwrap();
+        value.redact(
+            &[
+                "/users/*/password",
+                "/token",
+                "/db/password",
+                "/missing/x",
+                "bad",
+            ],
+            JsonValue::String(vec!['*']),
+        );
+        assert_eq!(
+            value,
+            parse_json(
+                r#"{"users":[{"name":"a","password":"*"},{"name":"b"}],"token":"*","db":{"password":"*","password":"*"}}"#
+            )
+            .unwrap()
+        );
+
+        let mut value = parse_json(r#"[[1,2],[3]]"#).unwrap();
+        value.redact(&["/*/0"], JsonValue::Null);
+        assert_eq!(value, parse_json(r#"[[null,2],[null]]"#).unwrap());
+
+        value.redact(&[""], JsonValue::Null);
+        assert_eq!(value, JsonValue::Null);
+    }
+
+    #[test]
+    fn unflatten_works() {
+        assert_eq!(
+            parse_json(r#"{"/a/0":null,"/a/1/b":true}"#)
+                .unwrap()
+                .unflatten(),
+            Some(parse_json(r#"{"a":[null,{"b":true}]}"#).unwrap())
+        );
+        assert_eq!(parse_json(r#"{"/a/2":true}"#).unwrap().unflatten(), None);
+        assert_eq!(
+            parse_json(r#"{"/a/18446744073709551615":1}"#)
+                .unwrap()
+                .unflatten(),
+            None
+        );
+        assert_eq!(
+            parse_json(r#"{"/a/0":1,"/a/01":2}"#).unwrap().unflatten(),
+            None
+        );
+        assert_eq!(
+            parse_json(r#"{"/a":1,"/a/b":2}"#).unwrap().unflatten(),
+            None
+        );
+        // A null is a value like any other, not a free slot.
+        for flat in [
+            r#"{"/a":null,"/a/b":2}"#,
+            r#"{"/a/b":2,"/a":null}"#,
+            r#"{"/a/0":null,"/a/0/b":2}"#,
+            r#"{"/a/0":null,"/a/0":2}"#,
+            r#"{"/a/0/b":2,"/a/0":null}"#,
+        ] {
+            assert_eq!(parse_json(flat).unwrap().unflatten(), None, "{}", flat);
+        }
+        assert_eq!(parse_json(r#"{"a":1}"#).unwrap().unflatten(), None);
+        assert_eq!(JsonValue::Null.unflatten(), None);
+    }
+}
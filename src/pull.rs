@@ -0,0 +1,725 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::json::{JsonValue, NumberValue};
+use crate::json_parser::{String, Value};
+use crate::parse_error::{ParseError, ParseErrorKind};
+use crate::pointer::{parse_index, tokens};
+use crate::reader::{Reader, Utf8Chars};
+use core::ops::ControlFlow;
+use lite_parser::{
+    impls::SimplePosition,
+    parser::{Parser, ParserContext, ParserOptions, ParserState},
+};
+
+/// A piece of a JSON document, as yielded by `PullParser`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsonEvent {
+    StartObject,
+    EndObject,
+    StartArray,
+    EndArray,
+    /// The key of the object member whose value follows.
+    Key(Vec<char>),
+    String(Vec<char>),
+    Number(NumberValue),
+    Boolean(bool),
+    Null,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Container {
+    Array,
+    Object,
+}
+
+/// What the parser expects next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Start,
+    Value,
+    /// After `[`, where the array may end.
+    FirstItem,
+    /// After `{`, where the object may end.
+    FirstMember,
+    Member,
+    /// After a value, where a comma or the end of its container comes next.
+    AfterValue,
+    Done,
+    Failed,
+}
+
+/// Reads a JSON document as a series of `JsonEvent`s, on demand and without building values
+/// for arrays and objects, so huge documents can be processed in constant memory. Only the
+/// token being read and one entry per open array or object are kept.
+///
+/// Parsing stops after the first error. The default `ParserOptions` limits apply, so nesting
+/// deeper than 100 levels is rejected.
+pub struct PullParser<It> {
+    reader: Reader<Utf8Chars<It>>,
+    options: ParserOptions,
+    parser_state: ParserState,
+    /// The open arrays and objects, with how many items or members each has so far.
+    stack: Vec<(Container, usize)>,
+    state: State,
+}
+
+impl<It: Iterator<Item = u8>> PullParser<It> {
+    /// Reads UTF-8 bytes, such as `text.bytes()` or the bytes of a buffered reader.
+    pub fn new(bytes: impl IntoIterator<IntoIter = It>) -> Self {
+        PullParser {
+            reader: Reader::new(Utf8Chars::new(bytes.into_iter())),
+            options: ParserOptions::default(),
+            parser_state: ParserState::default(),
+            stack: Vec::new(),
+            state: State::Start,
+        }
+    }
+
+    /// How many arrays and objects the parser is inside of.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Skips the next value without reading its events, such as the value of the member whose
+    /// `Key` was just yielded or the next item of an array. Where an object key is expected, the
+    /// whole member is skipped, and at the end of an array or object nothing is.
+    ///
+    /// Brackets are balanced and strings are passed over without unescaping, but the skipped
+    /// text is not otherwise checked, which makes this much cheaper than reading its events.
+    pub fn skip_value(&mut self) -> Result<(), ParseError> {
+        let result = self.skip_next();
+        if result.is_err() {
+            self.state = State::Failed;
+        }
+        result.map(|_| ())
+    }
+
+    /// Skips like `skip_value`, returning false if there was nothing to skip.
+    fn skip_next(&mut self) -> Result<bool, ParseError> {
+        if !self.enter_next()? {
+            return Ok(false);
+        }
+        if self.state == State::Member {
+            if self.reader.peek()? != Some('"') {
+                return Err(self.reader.unexpected());
+            }
+            self.skip_raw()?;
+            self.reader.skip_whitespace()?;
+            if self.reader.peek()? != Some(':') {
+                return Err(self.reader.unexpected());
+            }
+            self.reader.bump();
+            self.reader.skip_whitespace()?;
+        }
+        self.skip_raw()?;
+        self.state = State::AfterValue;
+        Ok(true)
+    }
+
+    /// Moves to the start of the next value, or of the next member where an object key is
+    /// expected. Returns false if there is none, at the end of an array, object or document.
+    fn enter_next(&mut self) -> Result<bool, ParseError> {
+        if self.state == State::Start {
+            self.reader.skip_bom()?;
+            self.state = State::Value;
+        }
+        self.reader.skip_whitespace()?;
+        let close = self.stack.last().map(|(container, _)| match container {
+            Container::Array => ']',
+            Container::Object => '}',
+        });
+        match self.state {
+            State::Start | State::Done | State::Failed => Ok(false),
+            // The whole document has been read.
+            State::AfterValue if close.is_none() => Ok(false),
+            State::Value | State::Member => Ok(true),
+            State::FirstItem | State::FirstMember if self.reader.peek()? == close => Ok(false),
+            State::FirstItem | State::FirstMember => self.enter_entry().map(|_| true),
+            State::AfterValue => match self.reader.peek()? {
+                Some(',') => {
+                    self.reader.bump();
+                    self.reader.skip_whitespace()?;
+                    self.enter_entry().map(|_| true)
+                }
+                c if c == close => Ok(false),
+                _ => Err(self.reader.unexpected()),
+            },
+        }
+    }
+
+    /// Passes over the value at the current position, checking only its brackets and strings.
+    fn skip_raw(&mut self) -> Result<(), ParseError> {
+        let mut closes = Vec::new();
+        let mut empty = true;
+        loop {
+            let c = match self.reader.peek()? {
+                Some(c) => c,
+                None if closes.is_empty() && !empty => return Ok(()),
+                None => return Err(self.reader.error(ParseErrorKind::UnexpectedEof)),
+            };
+            // A number or literal ends at the next delimiter.
+            if closes.is_empty()
+                && !empty
+                && matches!(
+                    c,
+                    ' ' | '\t' | '\n' | '\r' | ',' | ':' | '[' | ']' | '{' | '}' | '"'
+                )
+            {
+                return Ok(());
+            }
+            match c {
+                '"' => self.skip_string()?,
+                '[' => closes.push(']'),
+                '{' => closes.push('}'),
+                ']' | '}' if closes.last() == Some(&c) => {
+                    closes.pop();
+                }
+                ']' | '}' => return Err(self.reader.unexpected()),
+                ',' | ':' if empty => return Err(self.reader.unexpected()),
+                _ => {}
+            }
+            if c != '"' {
+                self.reader.bump();
+            }
+            if closes.is_empty() && matches!(c, '"' | ']' | '}') {
+                return Ok(());
+            }
+            empty = false;
+        }
+    }
+
+    /// Passes over the string at the current position without unescaping it.
+    fn skip_string(&mut self) -> Result<(), ParseError> {
+        self.reader.bump();
+        loop {
+            match self.reader.peek()? {
+                Some('"') => {
+                    self.reader.bump();
+                    return Ok(());
+                }
+                Some('\\') => {
+                    self.reader.bump();
+                    if self.reader.peek()?.is_none() {
+                        return Err(self.reader.error(ParseErrorKind::UnexpectedEof));
+                    }
+                    self.reader.bump();
+                }
+                Some(_) => self.reader.bump(),
+                None => return Err(self.reader.error(ParseErrorKind::UnexpectedEof)),
+            }
+        }
+    }
+
+    /// Counts another item or member of the innermost container, which is then expected.
+    fn enter_entry(&mut self) -> Result<(), ParseError> {
+        let options = &self.options;
+        let (container, count) = self.stack.last_mut().expect("inside a container");
+        let (limit, kind, state) = match container {
+            Container::Array => (
+                options.max_array_length,
+                ParseErrorKind::TooManyArrayItems,
+                State::Value,
+            ),
+            Container::Object => (
+                options.max_object_members,
+                ParseErrorKind::TooManyObjectMembers,
+                State::Member,
+            ),
+        };
+        if Some(*count) == limit {
+            return Err(self.reader.error(kind));
+        }
+        *count += 1;
+        self.state = state;
+        Ok(())
+    }
+
+    /// Consumes the bracket at the current position, which closes the innermost container.
+    fn close(&mut self) -> JsonEvent {
+        self.reader.bump();
+        self.state = State::AfterValue;
+        match self.stack.pop() {
+            Some((Container::Object, _)) => JsonEvent::EndObject,
+            _ => JsonEvent::EndArray,
+        }
+    }
+
+    fn value(&mut self) -> Result<JsonEvent, ParseError> {
+        let (container, event) = match self.reader.peek()? {
+            Some('{') => (Container::Object, JsonEvent::StartObject),
+            Some('[') => (Container::Array, JsonEvent::StartArray),
+            _ => {
+                self.state = State::AfterValue;
+                return match self
+                    .reader
+                    .token::<Value, _>(&ParserContext::new(&self.options, &self.parser_state))?
+                {
+                    JsonValue::String(s) => Ok(JsonEvent::String(s)),
+                    JsonValue::Number(n) => Ok(JsonEvent::Number(n)),
+                    JsonValue::Boolean(b) => Ok(JsonEvent::Boolean(b)),
+                    JsonValue::Null => Ok(JsonEvent::Null),
+                    _ => unreachable!("only scalars are parsed with the default options"),
+                };
+            }
+        };
+        if Some(self.stack.len() as u32) == self.options.max_nest_level {
+            return Err(self.reader.error(ParseErrorKind::DepthLimitExceeded));
+        }
+        self.reader.bump();
+        self.stack.push((container, 0));
+        self.state = match container {
+            Container::Array => State::FirstItem,
+            Container::Object => State::FirstMember,
+        };
+        Ok(event)
+    }
+
+    fn event(&mut self) -> Result<Option<JsonEvent>, ParseError> {
+        match self.state {
+            State::Start => {
+                self.reader.skip_bom()?;
+                self.state = State::Value;
+            }
+            State::Failed => return Ok(None),
+            _ => {}
+        }
+        loop {
+            self.reader.skip_whitespace()?;
+            match self.state {
+                State::Start | State::Failed => unreachable!("handled above"),
+                State::Value => return self.value().map(Some),
+                State::FirstItem if self.reader.peek()? == Some(']') => {
+                    return Ok(Some(self.close()));
+                }
+                State::FirstMember if self.reader.peek()? == Some('}') => {
+                    return Ok(Some(self.close()));
+                }
+                State::FirstItem | State::FirstMember => self.enter_entry()?,
+                State::Member => {
+                    let key = self.reader.token::<String, _>(&ParserContext::new(
+                        &self.options,
+                        &self.parser_state,
+                    ))?;
+                    self.reader.skip_whitespace()?;
+                    if self.reader.peek()? != Some(':') {
+                        return Err(self.reader.unexpected());
+                    }
+                    self.reader.bump();
+                    self.state = State::Value;
+                    return Ok(Some(JsonEvent::Key(key)));
+                }
+                State::AfterValue => {
+                    let container = match self.stack.last() {
+                        Some((container, _)) => *container,
+                        None => {
+                            self.state = State::Done;
+                            continue;
+                        }
+                    };
+                    match (self.reader.peek()?, container) {
+                        (Some(','), _) => {
+                            self.reader.bump();
+                            self.enter_entry()?;
+                        }
+                        (Some(']'), Container::Array) | (Some('}'), Container::Object) => {
+                            return Ok(Some(self.close()));
+                        }
+                        _ => return Err(self.reader.unexpected()),
+                    }
+                }
+                State::Done => {
+                    return match self.reader.peek()? {
+                        Some(_) => Err(self.reader.error(ParseErrorKind::TrailingCharacters)),
+                        None => Ok(None),
+                    };
+                }
+            }
+        }
+    }
+}
+
+impl<It: Iterator<Item = u8>> Iterator for PullParser<It> {
+    type Item = Result<JsonEvent, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = self.event();
+        if event.is_err() {
+            self.state = State::Failed;
+        }
+        event.transpose()
+    }
+}
+
+/// Callbacks for the events of a JSON document, as called by `parse_with_handler`. Each one
+/// returns whether to go on, and does nothing by default.
+pub trait Handler {
+    fn start_object(&mut self) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    fn end_object(&mut self) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    fn start_array(&mut self) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    fn end_array(&mut self) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// The key of the object member whose value follows.
+    fn key(&mut self, _key: &[char]) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    fn string(&mut self, _value: &[char]) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    fn number(&mut self, _value: NumberValue) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    fn boolean(&mut self, _value: bool) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    fn null(&mut self) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+}
+
+/// Parses `input` in one pass, calling `handler` for each event instead of building values, so
+/// data can be aggregated or filtered as it is read. Returns `ControlFlow::Break` if the handler
+/// stopped early, in which case the rest of the input is not checked.
+pub fn parse_with_handler(
+    input: &str,
+    handler: &mut impl Handler,
+) -> Result<ControlFlow<()>, ParseError> {
+    for event in PullParser::new(input.bytes()) {
+        let event = event.map_err(|e| with_snippet(input, e))?;
+        let flow = match event {
+            JsonEvent::StartObject => handler.start_object(),
+            JsonEvent::EndObject => handler.end_object(),
+            JsonEvent::StartArray => handler.start_array(),
+            JsonEvent::EndArray => handler.end_array(),
+            JsonEvent::Key(key) => handler.key(&key),
+            JsonEvent::String(value) => handler.string(&value),
+            JsonEvent::Number(value) => handler.number(value),
+            JsonEvent::Boolean(value) => handler.boolean(value),
+            JsonEvent::Null => handler.null(),
+        };
+        if flow.is_break() {
+            return Ok(flow);
+        }
+    }
+    Ok(ControlFlow::Continue(()))
+}
+
+/// Rebuilds an error of a `PullParser` over `input`, which has no snippet.
+pub(crate) fn with_snippet(input: &str, error: ParseError) -> ParseError {
+    let position = SimplePosition {
+        index: error.offset as u32,
+        line: error.line as u32 - 1,
+        column: error.column as u32 - 1,
+    };
+    ParseError::new(input, position, error.kind)
+}
+
+/// Parses only the value at the JSON Pointer `path`, such as `/result/price`, passing over
+/// everything before it like `PullParser::skip_value` and stopping after it. A single field of a
+/// large response can be read this way without building the rest.
+///
+/// Returns None if the path is invalid or nothing is there. The input after the value is not
+/// read, so unlike `JsonValue::pointer` repeated object keys resolve to the first member.
+pub fn extract(input: &str, path: &str) -> Result<Option<JsonValue>, ParseError> {
+    let tokens = match tokens(path.chars()) {
+        Some(tokens) => tokens,
+        None => return Ok(None),
+    };
+    let mut parser = PullParser::new(input.bytes());
+    let error = |e| with_snippet(input, e);
+    for token in &tokens {
+        parser.enter_next().map_err(error)?;
+        match parser.next().transpose().map_err(error)? {
+            Some(JsonEvent::StartObject) => loop {
+                if !parser.enter_next().map_err(error)? {
+                    return Ok(None);
+                }
+                match parser.next().transpose().map_err(error)? {
+                    Some(JsonEvent::Key(key)) if key == *token => break,
+                    _ => parser.skip_value().map_err(error)?,
+                }
+            },
+            Some(JsonEvent::StartArray) => {
+                let index = match parse_index(token) {
+                    Some(index) => index,
+                    None => return Ok(None),
+                };
+                for _ in 0..index {
+                    if !parser.skip_next().map_err(error)? {
+                        return Ok(None);
+                    }
+                }
+                if !parser.enter_next().map_err(error)? {
+                    return Ok(None);
+                }
+            }
+            _ => return Ok(None),
+        }
+    }
+    parser.enter_next().map_err(error)?;
+    <Value as Parser<&str>>::parse(
+        &input,
+        parser.reader.position(),
+        &ParserContext::new(&parser.options, &parser.parser_state),
+    )
+    .map(|(value, _)| Some(value))
+    .map_err(|e| ParseError::from_simple(input, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_parser::parse_json;
+
+    fn events(input: &str) -> Vec<Result<JsonEvent, ParseErrorKind>> {
+        PullParser::new(input.bytes())
+            .map(|event| event.map_err(|e| e.kind))
+            .collect()
+    }
+
+    #[test]
+    fn pull_parser_yields_events() {
+        use JsonEvent::*;
+        let number = |n| match parse_json(n) {
+            Ok(JsonValue::Number(n)) => Number(n),
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            events(r#" {"a": [1, -2.5, {}], "b": {"c": null, "d": [true, false, "x"]}, "e": []} "#),
+            [
+                StartObject,
+                Key(vec!['a']),
+                StartArray,
+                number("1"),
+                number("-2.5"),
+                StartObject,
+                EndObject,
+                EndArray,
+                Key(vec!['b']),
+                StartObject,
+                Key(vec!['c']),
+                Null,
+                Key(vec!['d']),
+                StartArray,
+                Boolean(true),
+                Boolean(false),
+                String(vec!['x']),
+                EndArray,
+                EndObject,
+                Key(vec!['e']),
+                StartArray,
+                EndArray,
+                EndObject,
+            ]
+            .map(Ok)
+        );
+        assert_eq!(events("\u{FEFF}\"a\""), [Ok(String(vec!['a']))]);
+
+        assert_eq!(
+            events("[1, 2, x"),
+            [
+                Ok(StartArray),
+                Ok(number("1")),
+                Ok(number("2")),
+                Err(ParseErrorKind::UnexpectedToken)
+            ]
+        );
+        for input in [
+            "",
+            "[1,]",
+            "[1 2]",
+            "{\"a\" 1}",
+            "[1}",
+            "{1: 2}",
+            "[1] x",
+            "[[1]",
+        ] {
+            let expected = parse_json(input).unwrap_err();
+            let error = PullParser::new(input.bytes())
+                .find_map(Result::err)
+                .unwrap();
+            assert_eq!(
+                (error.kind, error.offset),
+                (expected.kind, expected.offset),
+                "{}",
+                input
+            );
+        }
+        assert_eq!(
+            events(&"[".repeat(200)).pop(),
+            Some(Err(ParseErrorKind::DepthLimitExceeded))
+        );
+
+        let mut parser = PullParser::new("[[{}]]".bytes());
+        let depths: Vec<_> = core::iter::from_fn(|| {
+            parser.next()?.ok()?;
+            Some(parser.depth())
+        })
+        .collect();
+        assert_eq!(depths, [1, 2, 3, 2, 1, 0]);
+
+        let mut parser = PullParser::new(b"[\xff, \xff]".iter().copied());
+        assert_eq!(parser.next(), Some(Ok(StartArray)));
+        assert_eq!(
+            parser.next().unwrap().unwrap_err().kind,
+            ParseErrorKind::InvalidUtf8
+        );
+        assert_eq!(parser.next(), None);
+    }
+
+    #[test]
+    fn values_are_skipped() {
+        use JsonEvent::*;
+        let input =
+            r#"{"skip": {"a": [1, "]}\"", {}], "b": null}, "keep": [true, [2, 3], 4], "x": 5}"#;
+        let mut parser = PullParser::new(input.bytes());
+        assert_eq!(parser.next(), Some(Ok(StartObject)));
+        assert_eq!(parser.next(), Some(Ok(Key(vec!['s', 'k', 'i', 'p']))));
+        parser.skip_value().unwrap();
+        assert_eq!(parser.next(), Some(Ok(Key(vec!['k', 'e', 'e', 'p']))));
+        assert_eq!(parser.next(), Some(Ok(StartArray)));
+        parser.skip_value().unwrap();
+        parser.skip_value().unwrap();
+        assert_eq!(parser.next().unwrap().map(|_| ()), Ok(()));
+        parser.skip_value().unwrap();
+        assert_eq!(parser.next(), Some(Ok(EndArray)));
+        parser.skip_value().unwrap();
+        assert_eq!(parser.next(), Some(Ok(EndObject)));
+        assert_eq!(parser.next(), None);
+
+        let mut parser = PullParser::new(" [1, 2] ".bytes());
+        parser.skip_value().unwrap();
+        parser.skip_value().unwrap();
+        assert_eq!(parser.next(), None);
+
+        for input in ["[1, ", "[1, }", "[\"a", "{\"a\": [}", "[1, ]", "{1: 2}"] {
+            let mut parser = PullParser::new(input.bytes());
+            assert!(matches!(parser.next(), Some(Ok(StartArray | StartObject))));
+            let result = parser.skip_value().and_then(|_| parser.skip_value());
+            assert!(result.is_err(), "{}", input);
+            assert_eq!(parser.next(), None);
+        }
+    }
+
+    #[test]
+    fn paths_are_extracted() {
+        let input = r#" {"id": 7, "result": {"items": [{"p": 1}, {"p": [2, "x"]}], "price": 12.5, "a/b": "}"}} "#;
+        let document = parse_json(input).unwrap();
+        for path in [
+            "",
+            "/id",
+            "/result",
+            "/result/price",
+            "/result/items/1/p/1",
+            "/result/a~1b",
+            "/result/items/2",
+            "/result/missing",
+            "/id/x",
+            "/result/items/x",
+            "/result/items/01",
+            "/result/items/18446744073709551615",
+            "result",
+        ] {
+            assert_eq!(
+                extract(input, path),
+                Ok(document.pointer(path).cloned()),
+                "{}",
+                path
+            );
+        }
+
+        // Only what comes before the value is read, so the first of repeated keys is found.
+        assert_eq!(
+            extract(r#"{"a": 1, "a": 2}"#, "/a"),
+            Ok(Some(parse_json("1").unwrap()))
+        );
+        assert_eq!(
+            extract(r#"{"a": [1, 2], "b": true, oops"#, "/b"),
+            Ok(Some(JsonValue::Boolean(true)))
+        );
+        let error = extract("{\n\"a\": [1}, \"b\": 1}", "/b").unwrap_err();
+        assert_eq!(
+            (error.kind, error.line, error.column),
+            (ParseErrorKind::UnexpectedToken, 2, 8)
+        );
+        assert_eq!(error.snippet, "\"a\": [1}, \"b\": 1}");
+        assert_eq!(
+            extract(r#"{"a": {"b": tru}}"#, "/a/b").unwrap_err().kind,
+            ParseErrorKind::UnexpectedToken
+        );
+    }
+
+    #[test]
+    fn handlers_receive_events() {
+        /// Sums the numbers under "price" keys and stops at the first "stop" string.
+        #[derive(Default)]
+        struct Prices {
+            in_price: bool,
+            total: u128,
+            keys: usize,
+        }
+
+        impl Handler for Prices {
+            fn key(&mut self, key: &[char]) -> ControlFlow<()> {
+                self.keys += 1;
+                self.in_price = key == ['p', 'r', 'i', 'c', 'e'];
+                ControlFlow::Continue(())
+            }
+
+            fn number(&mut self, value: NumberValue) -> ControlFlow<()> {
+                if self.in_price {
+                    self.total += value.integer;
+                }
+                ControlFlow::Continue(())
+            }
+
+            fn string(&mut self, value: &[char]) -> ControlFlow<()> {
+                if value == ['s', 't', 'o', 'p'] {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            }
+        }
+
+        let mut prices = Prices::default();
+        let input = r#"[{"price": 3, "qty": 7}, {"price": 4, "tags": ["a"]}]"#;
+        assert_eq!(
+            parse_with_handler(input, &mut prices),
+            Ok(ControlFlow::Continue(()))
+        );
+        assert_eq!((prices.total, prices.keys), (7, 4));
+
+        let mut prices = Prices::default();
+        let input = r#"[{"price": 3}, "stop", {"price": 4}, oops"#;
+        assert_eq!(
+            parse_with_handler(input, &mut prices),
+            Ok(ControlFlow::Break(()))
+        );
+        assert_eq!(prices.total, 3);
+
+        let input = "[1,\n  {\"price\": tru}]";
+        assert_eq!(
+            parse_with_handler(input, &mut Prices::default()),
+            Err(parse_json(input).unwrap_err())
+        );
+    }
+}
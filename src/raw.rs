@@ -0,0 +1,424 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{borrow::Cow, string, vec::Vec};
+#[cfg(feature = "std")]
+use std::{borrow::Cow, string};
+
+use crate::json::{push_new_line_indent, push_string, write_string, JsonValue};
+use crate::json_parser::{parse_json, skip_bom, String, Value, Whitespace};
+use crate::parse_error::{ParseError, ParseErrorKind};
+use crate::pointer::{parse_index, push_index, push_key, tokens};
+use crate::traits::Serialize;
+use core::fmt::{self, Write as _};
+use lite_parser::{
+    impls::SimplePosition,
+    parser::{Parser, ParserContext, ParserOptions, ParserState},
+    traits::{Input, ResultOf},
+};
+
+/// A single JSON value kept as its text instead of being parsed, so an opaque payload can be
+/// passed through untouched. A `RawJsonValue` serializes it verbatim.
+///
+/// The text is always valid JSON without surrounding whitespace. It is borrowed where possible,
+/// and `into_owned` detaches it from the input.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RawValue<'a> {
+    text: Cow<'a, str>,
+}
+
+impl<'a> RawValue<'a> {
+    /// Checks that `text` is a single JSON value, dropping whitespace around it.
+    pub fn new(text: &'a str) -> Result<Self, ParseError> {
+        parse_json(text)?;
+        Ok(RawValue {
+            text: Cow::Borrowed(text.trim_matches(&[' ', '\t', '\n', '\r', '\u{FEFF}'][..])),
+        })
+    }
+
+    /// The JSON text.
+    pub fn get(&self) -> &str {
+        &self.text
+    }
+
+    /// Parses the text.
+    pub fn parse(&self) -> Result<JsonValue, ParseError> {
+        parse_json(&self.text)
+    }
+
+    pub fn into_owned(self) -> RawValue<'static> {
+        RawValue {
+            text: Cow::Owned(self.text.into_owned()),
+        }
+    }
+}
+
+impl fmt::Display for RawValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// A value parsed by `parse_json_with_raw`, mirroring `JsonValue`, in which the values at the
+/// chosen paths are kept as `RawValue` text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RawJsonValue<'a> {
+    Object(Vec<(Vec<char>, RawJsonValue<'a>)>),
+    Array(Vec<RawJsonValue<'a>>),
+    /// A value kept verbatim.
+    Raw(RawValue<'a>),
+    /// Any other value, which is never an array or object.
+    Value(JsonValue),
+}
+
+impl<'a> RawJsonValue<'a> {
+    /// Returns the value at the JSON Pointer `path`, like `JsonValue::pointer`.
+    pub fn pointer(&self, path: &str) -> Option<&RawJsonValue<'a>> {
+        tokens(path.chars())?
+            .iter()
+            .try_fold(self, |node, token| match node {
+                RawJsonValue::Object(obj) => {
+                    obj.iter().rev().find(|(k, _)| k == token).map(|(_, v)| v)
+                }
+                RawJsonValue::Array(arr) => arr.get(parse_index(token)?),
+                _ => None,
+            })
+    }
+
+    /// Parses the raw values, giving the `JsonValue` that `parse_json` would have returned.
+    pub fn into_value(self) -> Result<JsonValue, ParseError> {
+        Ok(match self {
+            RawJsonValue::Object(obj) => JsonValue::Object(
+                obj.into_iter()
+                    .map(|(k, v)| Ok((k, v.into_value()?)))
+                    .collect::<Result<_, ParseError>>()?,
+            ),
+            RawJsonValue::Array(arr) => JsonValue::Array(
+                arr.into_iter()
+                    .map(Self::into_value)
+                    .collect::<Result<_, ParseError>>()?,
+            ),
+            RawJsonValue::Raw(raw) => raw.parse()?,
+            RawJsonValue::Value(value) => value,
+        })
+    }
+
+    /// Detaches the raw values from the input.
+    pub fn into_owned(self) -> RawJsonValue<'static> {
+        match self {
+            RawJsonValue::Object(obj) => {
+                RawJsonValue::Object(obj.into_iter().map(|(k, v)| (k, v.into_owned())).collect())
+            }
+            RawJsonValue::Array(arr) => {
+                RawJsonValue::Array(arr.into_iter().map(Self::into_owned).collect())
+            }
+            RawJsonValue::Raw(raw) => RawJsonValue::Raw(raw.into_owned()),
+            RawJsonValue::Value(value) => RawJsonValue::Value(value),
+        }
+    }
+}
+
+/// Splices the raw values back verbatim, so they are not reformatted by `format`.
+impl Serialize for RawJsonValue<'_> {
+    fn serialize_to(&self, buffer: &mut Vec<u8>, indent: u32, level: u32) {
+        match self {
+            RawJsonValue::Object(obj) => {
+                buffer.push(b'{');
+                for (i, (key, val)) in obj.iter().enumerate() {
+                    if i > 0 {
+                        buffer.push(b',');
+                    }
+                    push_new_line_indent(buffer, indent, level + 1);
+                    push_string(buffer, key);
+                    buffer.push(b':');
+                    if indent > 0 {
+                        buffer.push(b' ');
+                    }
+                    val.serialize_to(buffer, indent, level + 1);
+                }
+                if !obj.is_empty() {
+                    push_new_line_indent(buffer, indent, level);
+                }
+                buffer.push(b'}');
+            }
+            RawJsonValue::Array(arr) => {
+                buffer.push(b'[');
+                for (i, val) in arr.iter().enumerate() {
+                    if i > 0 {
+                        buffer.push(b',');
+                    }
+                    push_new_line_indent(buffer, indent, level + 1);
+                    val.serialize_to(buffer, indent, level + 1);
+                }
+                if !arr.is_empty() {
+                    push_new_line_indent(buffer, indent, level);
+                }
+                buffer.push(b']');
+            }
+            RawJsonValue::Raw(raw) => buffer.extend_from_slice(raw.get().as_bytes()),
+            RawJsonValue::Value(value) => value.serialize_to(buffer, indent, level),
+        }
+    }
+}
+
+/// Writes the value as compact JSON, identical to the output of `serialize`.
+impl fmt::Display for RawJsonValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RawJsonValue::Object(obj) => {
+                f.write_char('{')?;
+                for (i, (key, val)) in obj.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_string(f, key)?;
+                    f.write_char(':')?;
+                    fmt::Display::fmt(val, f)?;
+                }
+                f.write_char('}')
+            }
+            RawJsonValue::Array(arr) => {
+                f.write_char('[')?;
+                for (i, val) in arr.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    fmt::Display::fmt(val, f)?;
+                }
+                f.write_char(']')
+            }
+            RawJsonValue::Raw(raw) => fmt::Display::fmt(raw, f),
+            RawJsonValue::Value(value) => fmt::Display::fmt(value, f),
+        }
+    }
+}
+
+/// Parses JSON like `parse_json`, but keeps the values at the JSON Pointer `paths` as
+/// `RawValue` text borrowed from `input`. They are still checked, but not built, so they come
+/// back exactly as they were written.
+pub fn parse_json_with_raw<'a>(
+    input: &'a str,
+    paths: &[&str],
+) -> Result<RawJsonValue<'a>, ParseError> {
+    let options = ParserOptions::default();
+    let state = ParserState::default();
+    let context = ParserContext::new(&options, &state);
+    let mut raw = RawPaths {
+        input,
+        paths,
+        path: string::String::new(),
+    };
+    let (_, next) = <Whitespace as Parser<&str>>::parse(&input, skip_bom(input), &context)
+        .map_err(|e| ParseError::from_simple(input, e))?;
+    let (value, next) = raw
+        .value(next, &context)
+        .and_then(|(value, next)| {
+            <Whitespace as Parser<&str>>::parse(&input, next, &context)
+                .map(|(_, next)| (value, next))
+        })
+        .map_err(|e| ParseError::from_simple(input, e))?;
+    if input.is_end(next) {
+        Ok(value)
+    } else {
+        Err(ParseError::new(
+            input,
+            next,
+            ParseErrorKind::TrailingCharacters,
+        ))
+    }
+}
+
+/// Parses a value like `Value`, tracking the JSON Pointer of each node to find the raw ones.
+struct RawPaths<'a, 'p> {
+    input: &'a str,
+    paths: &'p [&'p str],
+    path: string::String,
+}
+
+impl<'a> RawPaths<'a, '_> {
+    fn value(
+        &mut self,
+        current: SimplePosition,
+        context: &ParserContext,
+    ) -> ResultOf<&str, RawJsonValue<'a>> {
+        let input = self.input;
+        if self.paths.contains(&self.path.as_str()) {
+            let (_, next) = <Value as Parser<&str>>::parse(&input, current, context)?;
+            let text = &input[current.index as usize..next.index as usize];
+            return Ok((
+                RawJsonValue::Raw(RawValue {
+                    text: Cow::Borrowed(text),
+                }),
+                next,
+            ));
+        }
+        match input.next(current) {
+            Ok(('{', next)) => {
+                let context = &context.nest(&input, current)?;
+                self.members(next, context)
+            }
+            Ok(('[', next)) => {
+                let context = &context.nest(&input, current)?;
+                self.items(next, context)
+            }
+            _ => <Value as Parser<&str>>::parse(&input, current, context)
+                .map(|(value, next)| (RawJsonValue::Value(value), next)),
+        }
+    }
+
+    /// Parses the items of an array after its `[`.
+    fn items(
+        &mut self,
+        current: SimplePosition,
+        context: &ParserContext,
+    ) -> ResultOf<&str, RawJsonValue<'a>> {
+        let input = self.input;
+        let (_, mut next) = <Whitespace as Parser<&str>>::parse(&input, current, context)?;
+        let mut output = Vec::new();
+        if let Ok((']', end)) = input.next(next) {
+            return Ok((RawJsonValue::Array(output), end));
+        }
+        loop {
+            let (_, start) = <Whitespace as Parser<&str>>::parse(&input, next, context)?;
+            let len = self.path.len();
+            push_index(&mut self.path, output.len());
+            let item = self.value(start, context);
+            self.path.truncate(len);
+            let (item, after) = item?;
+            let (_, after) = <Whitespace as Parser<&str>>::parse(&input, after, context)?;
+            output.push(item);
+            match input.next(after) {
+                Ok((',', after)) => next = after,
+                Ok((']', end)) => return Ok((RawJsonValue::Array(output), end)),
+                _ => return Err(input.error_at(after, "Array")),
+            }
+        }
+    }
+
+    /// Parses the members of an object after its `{`.
+    fn members(
+        &mut self,
+        current: SimplePosition,
+        context: &ParserContext,
+    ) -> ResultOf<&str, RawJsonValue<'a>> {
+        let input = self.input;
+        let (_, mut next) = <Whitespace as Parser<&str>>::parse(&input, current, context)?;
+        let mut output = Vec::new();
+        if let Ok(('}', end)) = input.next(next) {
+            return Ok((RawJsonValue::Object(output), end));
+        }
+        loop {
+            let (_, start) = <Whitespace as Parser<&str>>::parse(&input, next, context)?;
+            let (key, after) = <String as Parser<&str>>::parse(&input, start, context)?;
+            let (_, after) = <Whitespace as Parser<&str>>::parse(&input, after, context)?;
+            let after = match input.next(after) {
+                Ok((':', after)) => after,
+                _ => return Err(input.error_at(after, "Character")),
+            };
+            let (_, after) = <Whitespace as Parser<&str>>::parse(&input, after, context)?;
+            let len = self.path.len();
+            push_key(&mut self.path, &key);
+            let value = self.value(after, context);
+            self.path.truncate(len);
+            let (value, after) = value?;
+            let (_, after) = <Whitespace as Parser<&str>>::parse(&input, after, context)?;
+            output.push((key, value));
+            match input.next(after) {
+                Ok((',', after)) => next = after,
+                Ok(('}', end)) => return Ok((RawJsonValue::Object(output), end)),
+                _ => return Err(input.error_at(after, "Object")),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_values_pass_through() {
+        let input = r#" {"id": 1, "payload": {"b" : [1.50, "A"]}, "list": [true, 1e2]} "#;
+        let value = parse_json_with_raw(input, &["/payload", "/list/1", "/missing"]).unwrap();
+        let raw = |text| RawJsonValue::Raw(RawValue::new(text).unwrap());
+        assert_eq!(
+            value.pointer("/payload").unwrap().clone(),
+            raw(r#"{"b" : [1.50, "A"]}"#)
+        );
+        assert_eq!(value.pointer("/list/1").unwrap().clone(), raw("1e2"));
+        assert_eq!(
+            value.pointer("/list/0").unwrap().clone(),
+            RawJsonValue::Value(JsonValue::Boolean(true))
+        );
+        assert!(matches!(
+            value.pointer("/payload"),
+            Some(RawJsonValue::Raw(RawValue {
+                text: Cow::Borrowed(_)
+            }))
+        ));
+        assert_eq!(
+            string::String::from_utf8(value.serialize()).unwrap(),
+            r#"{"id":1,"payload":{"b" : [1.50, "A"]},"list":[true,1e2]}"#
+        );
+        assert_eq!(
+            string::String::from_utf8(value.format(2)).unwrap(),
+            "{\n  \"id\": 1,\n  \"payload\": {\"b\" : [1.50, \"A\"]},\n  \"list\": [\n    true,\n    1e2\n  ]\n}"
+        );
+        assert_eq!(
+            format!("{}", value),
+            string::String::from_utf8(value.serialize()).unwrap()
+        );
+        assert_eq!(value.clone().into_owned(), value);
+        assert_eq!(value.into_value(), parse_json(input));
+        assert_eq!(
+            parse_json_with_raw(input, &[""]).unwrap(),
+            raw(input.trim())
+        );
+        assert_eq!(
+            parse_json_with_raw(input, &[]).unwrap().into_value(),
+            parse_json(input)
+        );
+
+        let error = parse_json_with_raw(r#"{"a": [1, }"#, &["/a"]).unwrap_err();
+        assert_eq!(error, parse_json(r#"{"a": [1, }"#).unwrap_err());
+
+        let text = " [1, 2]\n";
+        let borrowed = RawValue::new(text).unwrap();
+        assert!(matches!(borrowed.text, Cow::Borrowed("[1, 2]")));
+        assert_eq!(borrowed.parse(), parse_json(text));
+        assert_eq!(format!("{}", borrowed), "[1, 2]");
+        assert!(RawValue::new("[1,").is_err());
+    }
+    #[test]
+    fn errors_match_parse_json() {
+        let deep = "[".repeat(101) + &"]".repeat(101);
+        for input in [
+            "",
+            " ",
+            "{\"a\" 1}",
+            "{\"a\": 1,}",
+            "{\"a\": [1 2]}",
+            "{\"a\": [\"é\\x\"]}",
+            "{\"a\": {\"b\": tru}}",
+            "{1: 2}",
+            "[\"abc",
+            "\u{FEFF}{\"a\": 1} x",
+            &deep,
+        ] {
+            let expected = parse_json(input).unwrap_err();
+            for paths in [&[][..], &[""], &["/a"], &["/a/0", "/a/b"]] {
+                assert_eq!(
+                    parse_json_with_raw(input, paths).unwrap_err(),
+                    expected,
+                    "{} {:?}",
+                    input,
+                    paths
+                );
+            }
+            assert_eq!(RawValue::new(input).unwrap_err(), expected, "{}", input);
+        }
+        assert!(RawValue::new("1 2").is_err());
+        assert!(RawValue::new("\"a\" \"b\"").is_err());
+    }
+}
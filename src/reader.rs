@@ -0,0 +1,514 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string, vec::Vec};
+#[cfg(feature = "std")]
+use std::string;
+
+use crate::json::{JsonObject, JsonValue};
+#[cfg(feature = "mmap")]
+use crate::json_parser::parse_slice;
+use crate::json_parser::{reject_utf16, String, Value};
+use crate::parse_error::{ParseError, ParseErrorKind};
+#[cfg(feature = "std")]
+use core::fmt;
+use core::mem::size_of;
+use lite_parser::{
+    impls::SimplePosition,
+    parser::{Parser, ParserContext, ParserOptions, ParserState},
+};
+
+/// Decodes UTF-8 bytes, yielding the length of each invalid sequence as an error.
+pub(crate) struct Utf8Chars<It> {
+    bytes: It,
+}
+
+impl<It> Utf8Chars<It> {
+    pub(crate) fn new(bytes: It) -> Self {
+        Utf8Chars { bytes }
+    }
+}
+
+impl<It: Iterator<Item = u8>> Iterator for Utf8Chars<It> {
+    type Item = Result<char, usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.bytes.next()?;
+        let len = match first {
+            0x00..=0x7F => return Some(Ok(first as char)),
+            0xC2..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF4 => 4,
+            _ => return Some(Err(1)),
+        };
+        let mut buffer = [first, 0, 0, 0];
+        for (i, byte) in buffer.iter_mut().enumerate().take(len).skip(1) {
+            match self.bytes.next() {
+                Some(b) if b & 0xC0 == 0x80 => *byte = b,
+                _ => return Some(Err(i)),
+            }
+        }
+        Some(
+            core::str::from_utf8(&buffer[..len])
+                .ok()
+                .and_then(|s| s.chars().next())
+                .ok_or(len),
+        )
+    }
+}
+
+/// Parses JSON one character at a time, keeping only the current string, number or literal in
+/// memory besides the values read so far. Each token is handed to the same parsers as
+/// `parse_json`, so the result and errors match it.
+pub(crate) struct Reader<It> {
+    chars: It,
+    peeked: Option<Option<Result<char, usize>>>,
+    position: SimplePosition,
+}
+
+impl<It: Iterator<Item = Result<char, usize>>> Reader<It> {
+    pub(crate) fn new(chars: It) -> Self {
+        Reader {
+            chars,
+            peeked: None,
+            position: SimplePosition::default(),
+        }
+    }
+
+    pub(crate) fn peek(&mut self) -> Result<Option<char>, ParseError> {
+        let chars = &mut self.chars;
+        match *self.peeked.get_or_insert_with(|| chars.next()) {
+            // Positions hold `u32` byte offsets, so reading stops before they would overflow.
+            Some(Ok(c))
+                if self
+                    .position
+                    .index
+                    .checked_add(c.len_utf8() as u32)
+                    .is_none() =>
+            {
+                Err(self.error(ParseErrorKind::InputTooLong))
+            }
+            Some(Ok(c)) => Ok(Some(c)),
+            Some(Err(len)) => {
+                let mut error = self.error(ParseErrorKind::InvalidUtf8);
+                error.span.end += len;
+                Err(error)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// The position of the next character.
+    pub(crate) fn position(&self) -> SimplePosition {
+        self.position
+    }
+
+    pub(crate) fn bump(&mut self) {
+        if let Some(Some(Ok(c))) = self.peeked.take() {
+            self.position = self.position.next(c);
+        }
+    }
+
+    pub(crate) fn skip_whitespace(&mut self) -> Result<(), ParseError> {
+        while let Some(' ' | '\t' | '\n' | '\r') = self.peek()? {
+            self.bump();
+        }
+        Ok(())
+    }
+
+    /// An error at the current position. There is no snippet, as the input before it is gone.
+    pub(crate) fn error(&self, kind: ParseErrorKind) -> ParseError {
+        let offset = self.position.index as usize;
+        let len = match self.peeked {
+            Some(Some(Ok(c))) if kind != ParseErrorKind::UnexpectedEof => c.len_utf8(),
+            _ => 0,
+        };
+        ParseError {
+            kind,
+            line: self.position.line as usize + 1,
+            column: self.position.column as usize + 1,
+            offset,
+            span: offset..offset + len,
+            snippet: string::String::new(),
+        }
+    }
+
+    pub(crate) fn unexpected(&mut self) -> ParseError {
+        match self.peek() {
+            Ok(Some(_)) => self.error(ParseErrorKind::UnexpectedToken),
+            Ok(None) => self.error(ParseErrorKind::UnexpectedEof),
+            Err(error) => error,
+        }
+    }
+
+    /// Reads the string, number or literal at the current position and parses it with `P`.
+    pub(crate) fn token<P, T>(&mut self, context: &ParserContext) -> Result<T, ParseError>
+    where
+        P: for<'a> Parser<&'a str, Output = T>,
+    {
+        let start = self.position;
+        let mut text = string::String::new();
+        if self.peek()? == Some('"') {
+            let mut escaped = false;
+            while let Some(c) = self.peek()? {
+                text.push(c);
+                self.bump();
+                if c == '"' && !escaped && text.len() > 1 {
+                    break;
+                }
+                escaped = !escaped && c == '\\';
+            }
+        } else {
+            while let Some(c) = self.peek()? {
+                if matches!(
+                    c,
+                    ' ' | '\t' | '\n' | '\r' | ',' | ':' | '[' | ']' | '{' | '}' | '"'
+                ) {
+                    break;
+                }
+                text.push(c);
+                self.bump();
+            }
+        }
+
+        // The parsers look at the character after the token, so it is included but not consumed.
+        let end = text.len();
+        if let Some(c) = self.peek()? {
+            text.push(c);
+        }
+        let error = match P::parse(&text.as_str(), SimplePosition::default(), context) {
+            Ok((value, next)) if next.index as usize == end => return Ok(value),
+            Ok((_, next)) => ParseError::new(&text, next, ParseErrorKind::UnexpectedToken),
+            Err(e) => ParseError::from_simple(&text, e),
+        };
+        let shift = start.index as usize;
+        Err(ParseError {
+            kind: error.kind,
+            column: if error.line == 1 {
+                error.column + start.column as usize
+            } else {
+                error.column
+            },
+            line: error.line + start.line as usize,
+            offset: error.offset + shift,
+            span: error.span.start + shift..error.span.end + shift,
+            snippet: error.snippet,
+        })
+    }
+
+    fn value(&mut self, context: &ParserContext) -> Result<JsonValue, ParseError> {
+        let open = match self.peek()? {
+            Some(c @ ('{' | '[')) => c,
+            _ => return self.token::<Value, _>(context),
+        };
+        let here = self.position;
+        context
+            .step(&"", here, 1)
+            .map_err(|_| self.error(ParseErrorKind::StepBudgetExceeded))?;
+        context
+            .allocate(&"", here, size_of::<JsonValue>())
+            .map_err(|_| self.error(ParseErrorKind::BudgetExceeded))?;
+        let context = &context
+            .nest(&"", here)
+            .map_err(|_| self.error(ParseErrorKind::DepthLimitExceeded))?;
+        self.bump();
+        if open == '{' {
+            self.object(context).map(JsonValue::Object)
+        } else {
+            self.array(context).map(JsonValue::Array)
+        }
+    }
+
+    fn array(&mut self, context: &ParserContext) -> Result<Vec<JsonValue>, ParseError> {
+        let mut output = Vec::new();
+        self.skip_whitespace()?;
+        if self.peek()? == Some(']') {
+            self.bump();
+            return Ok(output);
+        }
+        loop {
+            self.skip_whitespace()?;
+            if Some(output.len()) == context.options().max_array_length {
+                return Err(self.error(ParseErrorKind::TooManyArrayItems));
+            }
+            output.push(self.value(context)?);
+            self.skip_whitespace()?;
+            match self.peek()? {
+                Some(',') => self.bump(),
+                Some(']') => {
+                    self.bump();
+                    return Ok(output);
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn object(&mut self, context: &ParserContext) -> Result<JsonObject, ParseError> {
+        let mut output = Vec::new();
+        self.skip_whitespace()?;
+        if self.peek()? == Some('}') {
+            self.bump();
+            return Ok(output);
+        }
+        loop {
+            self.skip_whitespace()?;
+            if Some(output.len()) == context.options().max_object_members {
+                return Err(self.error(ParseErrorKind::TooManyObjectMembers));
+            }
+            context
+                .allocate(&"", self.position, size_of::<Vec<char>>())
+                .map_err(|_| self.error(ParseErrorKind::BudgetExceeded))?;
+            let key = self.token::<String, _>(context)?;
+            self.skip_whitespace()?;
+            if self.peek()? != Some(':') {
+                return Err(self.unexpected());
+            }
+            self.bump();
+            self.skip_whitespace()?;
+            let value = self.value(context)?;
+            output.push((key, value));
+            self.skip_whitespace()?;
+            match self.peek()? {
+                Some(',') => self.bump(),
+                Some('}') => {
+                    self.bump();
+                    return Ok(output);
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    /// Skips a leading byte order mark, like `skip_bom`.
+    pub(crate) fn skip_bom(&mut self) -> Result<(), ParseError> {
+        if self.peek()? == Some('\u{FEFF}') {
+            self.bump();
+            self.position.column = 0;
+        }
+        Ok(())
+    }
+
+    fn parse(mut self) -> Result<JsonValue, ParseError> {
+        let options = ParserOptions::default();
+        let state = ParserState::default();
+        let context = ParserContext::new(&options, &state);
+        self.skip_bom()?;
+        self.skip_whitespace()?;
+        let value = self.value(&context)?;
+        self.skip_whitespace()?;
+        match self.peek()? {
+            None => Ok(value),
+            Some(_) => Err(self.error(ParseErrorKind::TrailingCharacters)),
+        }
+    }
+}
+
+/// Parses JSON from characters as they arrive, for example off a UART or socket buffer,
+/// without collecting the document first. Only the token being read is buffered.
+///
+/// The default `ParserOptions` are used, so only RFC 8259 JSON is accepted. Errors have no
+/// snippet, as the input before them is gone.
+pub fn parse_chars(chars: impl IntoIterator<Item = char>) -> Result<JsonValue, ParseError> {
+    Reader::new(chars.into_iter().map(Ok)).parse()
+}
+
+/// Like `parse_chars`, but decodes UTF-8 bytes. Invalid UTF-8 is an `InvalidUtf8` error, and
+/// a UTF-16 byte order mark is an `UnsupportedEncoding` error.
+pub fn parse_bytes(bytes: impl IntoIterator<Item = u8>) -> Result<JsonValue, ParseError> {
+    let mut bytes = bytes.into_iter();
+    let head = [bytes.next(), bytes.next()];
+    if let [Some(first), Some(second)] = head {
+        reject_utf16(&[first, second])?;
+    }
+    Reader::new(Utf8Chars::new(head.iter().flatten().copied().chain(bytes))).parse()
+}
+
+/// The error returned by `parse_reader`.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub enum ReadError {
+    /// Reading failed.
+    Io(std::io::Error),
+    /// What was read is not valid JSON.
+    Parse(ParseError),
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(e) => write!(f, "failed to read JSON: {}", e),
+            ReadError::Parse(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadError::Io(e) => Some(e),
+            ReadError::Parse(e) => Some(e),
+        }
+    }
+}
+
+/// Parses JSON from a file, socket or other reader through an internal buffer, like
+/// `parse_bytes`, so the document is never held in memory as text.
+#[cfg(feature = "std")]
+pub fn parse_reader(reader: impl std::io::Read) -> Result<JsonValue, ReadError> {
+    use std::io::Read;
+    let mut failure = None;
+    let bytes = std::io::BufReader::new(reader)
+        .bytes()
+        .map_while(|byte| byte.map_err(|e| failure = Some(e)).ok());
+    let result = parse_bytes(bytes);
+    match failure {
+        Some(e) => Err(ReadError::Io(e)),
+        None => result.map_err(ReadError::Parse),
+    }
+}
+
+/// Memory-maps the file at `path` and parses it like `parse_slice`, so a large export is paged
+/// in by the operating system rather than read into a buffer first. The file must not be
+/// changed while it is being parsed. Files of 4 GiB or more are an `InputTooLong` error.
+#[cfg(feature = "mmap")]
+pub fn parse_file(path: impl AsRef<std::path::Path>) -> Result<JsonValue, ReadError> {
+    let file = std::fs::File::open(path).map_err(ReadError::Io)?;
+    // Safety: the map is dropped before returning, and the caller keeps the file unchanged
+    // until then.
+    let map = unsafe { memmap2::Mmap::map(&file) }.map_err(ReadError::Io)?;
+    parse_slice(&map).map_err(ReadError::Parse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_parser::parse_json;
+
+    #[test]
+    fn iterators_parse_like_strings() {
+        let inputs = [
+            r#"{"a": [1, -2.5e3, true, false, null], "b": {"c": "d\né\"", "": []}}"#,
+            " \n[ \"x\" , {} ]\r\n",
+            "\u{FEFF}\"\u{1F600}\"",
+            "0",
+        ];
+        for input in inputs {
+            assert_eq!(parse_chars(input.chars()), parse_json(input), "{}", input);
+            assert_eq!(parse_bytes(input.bytes()), parse_json(input), "{}", input);
+        }
+
+        let errors = [
+            "",
+            "[1,]",
+            "[1 2]",
+            "{\"a\" 1}",
+            "{\"a\": 1,}",
+            "{1: 2}",
+            "[tru]",
+            "[true",
+            "[\"a\\q\"]",
+            "[\"a\nb\"]",
+            "[\n  01]",
+            "[1] x",
+            "[\"abc",
+            "{\"a\":",
+            "[-]",
+        ];
+        for input in errors {
+            let expected = parse_json(input).unwrap_err();
+            let error = parse_bytes(input.bytes()).unwrap_err();
+            assert_eq!(
+                (error.kind, error.line, error.column, error.offset),
+                (
+                    expected.kind,
+                    expected.line,
+                    expected.column,
+                    expected.offset
+                ),
+                "{}",
+                input
+            );
+        }
+
+        let deep = "[".repeat(200);
+        assert_eq!(
+            parse_chars(deep.chars()).unwrap_err().kind,
+            ParseErrorKind::DepthLimitExceeded
+        );
+
+        let error = parse_bytes(b"[\"ok\", \"a\xe2\x82\"]".iter().copied()).unwrap_err();
+        assert_eq!(
+            (error.kind, error.offset, error.span),
+            (ParseErrorKind::InvalidUtf8, 9, 9..11)
+        );
+        assert_eq!(
+            parse_bytes(b"[\xff]".iter().copied()).unwrap_err().kind,
+            ParseErrorKind::InvalidUtf8
+        );
+        assert_eq!(
+            parse_bytes(b"\xff\xfe[\x00]\x00".iter().copied())
+                .unwrap_err()
+                .kind,
+            ParseErrorKind::UnsupportedEncoding
+        );
+        assert_eq!(parse_bytes(b"1".iter().copied()), parse_json("1"));
+    }
+
+    #[test]
+    fn readers_stop_before_positions_overflow() {
+        let mut reader = Reader::new("[1]".chars().map(Ok));
+        reader.position.index = u32::MAX;
+        assert_eq!(
+            reader.peek().unwrap_err().kind,
+            ParseErrorKind::InputTooLong
+        );
+    }
+
+    #[test]
+    fn readers_parse() {
+        use std::io::{Cursor, Error, ErrorKind, Read};
+
+        let input = r#"{"items": [1, 2, {"name": "x"}]}"#;
+        assert_eq!(
+            parse_reader(Cursor::new(input)).unwrap(),
+            parse_json(input).unwrap()
+        );
+        match parse_reader(Cursor::new("[1, 2")) {
+            Err(ReadError::Parse(e)) => assert_eq!(e.kind, ParseErrorKind::UnexpectedEof),
+            other => panic!("{:?}", other),
+        }
+
+        struct Failing;
+        impl Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(Error::new(ErrorKind::ConnectionReset, "reset"))
+            }
+        }
+        match parse_reader(Cursor::new("[1, ").chain(Failing)) {
+            Err(ReadError::Io(e)) => assert_eq!(e.kind(), ErrorKind::ConnectionReset),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn files_parse() {
+        let path = std::env::temp_dir().join(format!("lite-json-{}.json", std::process::id()));
+        std::fs::write(&path, "\u{FEFF}[1, {\"a\": \"b\"}]\n").unwrap();
+        let value = parse_file(&path);
+        std::fs::write(&path, "[1, ").unwrap();
+        let error = parse_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(value.unwrap(), parse_json(r#"[1, {"a": "b"}]"#).unwrap());
+        match error {
+            Err(ReadError::Parse(e)) => assert_eq!(e.kind, ParseErrorKind::UnexpectedEof),
+            other => panic!("{:?}", other),
+        }
+        assert!(matches!(parse_file(&path), Err(ReadError::Io(_))));
+    }
+}
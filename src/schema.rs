@@ -0,0 +1,930 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+
+use crate::json::{Decimal, JsonValue};
+use crate::pointer::{push_index, push_key};
+use core::cmp::Ordering;
+use core::fmt;
+
+/// Why a schema could not be compiled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaErrorKind {
+    /// The keyword's value has the wrong type or is out of range.
+    InvalidKeyword,
+    /// The keyword is valid JSON Schema but not supported, such as `pattern`, which would
+    /// need a regex engine.
+    Unsupported,
+    /// A `$ref` is not a local JSON Pointer into the schema, points at nothing, or only leads
+    /// back to itself through other `$ref`s.
+    UnresolvedRef,
+}
+
+/// The error returned by `Validator::compile`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError {
+    /// JSON Pointer to the offending keyword in the schema.
+    pub path: String,
+    pub kind: SchemaErrorKind,
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self.kind {
+            SchemaErrorKind::InvalidKeyword => "invalid keyword",
+            SchemaErrorKind::Unsupported => "unsupported keyword",
+            SchemaErrorKind::UnresolvedRef => "unresolved $ref",
+        };
+        write!(f, "{} at {:?}", reason, self.path)
+    }
+}
+
+/// A keyword a document failed, located by a JSON Pointer into the document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub path: String,
+    pub keyword: &'static str,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} fails {}", self.path, self.keyword)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Type {
+    Null,
+    Boolean,
+    Object,
+    Array,
+    Number,
+    String,
+    Integer,
+}
+
+/// A single compiled keyword. `usize` fields are indexes of sub-schemas in `Validator::nodes`.
+enum Check {
+    Type(Vec<Type>),
+    Enum(Vec<JsonValue>),
+    Const(JsonValue),
+    Minimum(JsonValue),
+    Maximum(JsonValue),
+    ExclusiveMinimum(JsonValue),
+    ExclusiveMaximum(JsonValue),
+    MultipleOf(u64, i64),
+    MinLength(usize),
+    MaxLength(usize),
+    Items(usize),
+    TupleItems(Vec<usize>, Option<usize>),
+    Contains(usize),
+    MinItems(usize),
+    MaxItems(usize),
+    UniqueItems,
+    Properties(Vec<(Vec<char>, usize)>),
+    AdditionalProperties(Vec<Vec<char>>, usize),
+    Required(Vec<Vec<char>>),
+    PropertyNames(usize),
+    MinProperties(usize),
+    MaxProperties(usize),
+    AllOf(Vec<usize>),
+    AnyOf(Vec<usize>),
+    OneOf(Vec<usize>),
+    Not(usize),
+    If(usize, Option<usize>, Option<usize>),
+    Ref(usize),
+}
+
+enum Node {
+    Bool(bool),
+    Checks(Vec<Check>),
+}
+
+/// A JSON Schema compiled for validation.
+///
+/// Supports the draft-07 validation keywords except `pattern`, `patternProperties`,
+/// `dependencies` and remote `$ref`s, which are rejected when compiling so that documents are
+/// never accepted by a check that was silently skipped. `format` is treated as an annotation
+/// and not checked. Numbers are compared by their decimal value, without going through floats.
+pub struct Validator {
+    nodes: Vec<Node>,
+}
+
+struct Compiler<'a> {
+    root: &'a JsonValue,
+    nodes: Vec<Node>,
+    compiled: Vec<(String, usize)>,
+}
+
+/// Appends a schema keyword to a JSON Pointer.
+fn child(path: &str, keyword: &str) -> String {
+    let mut path = String::from(path);
+    push_key(&mut path, &keyword.chars().collect::<Vec<_>>());
+    path
+}
+
+fn error<T>(path: &str, keyword: &str, kind: SchemaErrorKind) -> Result<T, SchemaError> {
+    Err(SchemaError {
+        path: child(path, keyword),
+        kind,
+    })
+}
+
+fn type_of(name: &[char]) -> Option<Type> {
+    let name: String = name.iter().collect();
+    Some(match name.as_str() {
+        "null" => Type::Null,
+        "boolean" => Type::Boolean,
+        "object" => Type::Object,
+        "array" => Type::Array,
+        "number" => Type::Number,
+        "string" => Type::String,
+        "integer" => Type::Integer,
+        _ => return None,
+    })
+}
+
+fn is_integer(value: &JsonValue) -> bool {
+    value
+        .decimal()
+        .is_some_and(|d| d.point >= d.digits.len() as i64)
+}
+
+impl<'a> Compiler<'a> {
+    /// Follows the `$ref` of a schema, and of every schema it reaches that is only a `$ref`
+    /// too, to the first one with other keywords. Returns `None` if a reference is not a local
+    /// JSON Pointer, points at nothing, or loops back into the chain, which would never reach
+    /// a keyword to check.
+    fn resolve<'s>(
+        &self,
+        mut obj: &'s [(Vec<char>, JsonValue)],
+        path: &str,
+    ) -> Option<(&'a JsonValue, String)>
+    where
+        'a: 's,
+    {
+        let mut chain = vec![String::from(path)];
+        loop {
+            let pointer = schema_member(obj, "$ref")?
+                .as_string()?
+                .split_first()
+                .filter(|(first, _)| **first == '#')
+                .map(|(_, pointer)| pointer.iter().collect::<String>())?;
+            if chain.contains(&pointer) {
+                return None;
+            }
+            match self.root.pointer(&pointer)? {
+                JsonValue::Object(next) if schema_member(next, "$ref").is_some() => {
+                    obj = next;
+                    chain.push(pointer);
+                }
+                target => return Some((target, pointer)),
+            }
+        }
+    }
+
+    /// Compiles the schema at `path` once, returning its node index.
+    fn compile(&mut self, schema: &JsonValue, path: &str) -> Result<usize, SchemaError> {
+        if let Some((_, index)) = self.compiled.iter().find(|(p, _)| p == path) {
+            return Ok(*index);
+        }
+        let index = self.nodes.len();
+        self.nodes.push(Node::Bool(true));
+        self.compiled.push((path.into(), index));
+        self.nodes[index] = self.compile_node(schema, path)?;
+        Ok(index)
+    }
+
+    fn compile_node(&mut self, schema: &JsonValue, path: &str) -> Result<Node, SchemaError> {
+        let obj = match schema {
+            JsonValue::Boolean(b) => return Ok(Node::Bool(*b)),
+            JsonValue::Object(obj) => obj,
+            _ => {
+                return Err(SchemaError {
+                    path: path.into(),
+                    kind: SchemaErrorKind::InvalidKeyword,
+                })
+            }
+        };
+
+        let mut checks = Vec::new();
+        // Sibling keywords of `$ref` are ignored in draft-07.
+        if schema_member(obj, "$ref").is_some() {
+            return match self.resolve(obj, path) {
+                Some((target, pointer)) => {
+                    let index = self.compile(target, &pointer)?;
+                    Ok(Node::Checks(vec![Check::Ref(index)]))
+                }
+                None => error(path, "$ref", SchemaErrorKind::UnresolvedRef),
+            };
+        }
+
+        for (key, value) in obj {
+            let keyword: String = key.iter().collect();
+            let keyword = keyword.as_str();
+            let invalid = || SchemaError {
+                path: child(path, keyword),
+                kind: SchemaErrorKind::InvalidKeyword,
+            };
+            let mut sub_path = String::from(path);
+            push_key(&mut sub_path, key);
+            let count = || {
+                let count = value.as_number().and_then(|n| n.to_u64());
+                count.map(|n| n as usize).ok_or_else(invalid)
+            };
+            let number = || match value {
+                JsonValue::Number(_) | JsonValue::BigNumber(_) => Ok(value.clone()),
+                _ => Err(invalid()),
+            };
+
+            let check = match keyword {
+                "type" => {
+                    let names = match value {
+                        JsonValue::String(name) => vec![name.as_slice()],
+                        JsonValue::Array(names) => names
+                            .iter()
+                            .map(JsonValue::as_string)
+                            .collect::<Option<_>>()
+                            .ok_or_else(invalid)?,
+                        _ => return Err(invalid()),
+                    };
+                    let types = names.into_iter().map(type_of).collect::<Option<_>>();
+                    Check::Type(types.ok_or_else(invalid)?)
+                }
+                "enum" => Check::Enum(value.as_array().ok_or_else(invalid)?.to_vec()),
+                "const" => Check::Const(value.clone()),
+                "minimum" => Check::Minimum(number()?),
+                "maximum" => Check::Maximum(number()?),
+                "exclusiveMinimum" => Check::ExclusiveMinimum(number()?),
+                "exclusiveMaximum" => Check::ExclusiveMaximum(number()?),
+                "multipleOf" => {
+                    let divisor = value
+                        .decimal()
+                        .filter(|d| !d.negative && !d.digits.is_empty());
+                    let divisor = divisor.ok_or_else(invalid)?;
+                    if divisor.digits.len() > 18 {
+                        return error(path, keyword, SchemaErrorKind::Unsupported);
+                    }
+                    let mantissa = core::str::from_utf8(&divisor.digits)
+                        .ok()
+                        .and_then(|d| d.parse().ok())
+                        .unwrap_or(1);
+                    Check::MultipleOf(mantissa, divisor.point - divisor.digits.len() as i64)
+                }
+                "minLength" => Check::MinLength(count()?),
+                "maxLength" => Check::MaxLength(count()?),
+                "items" => match value {
+                    JsonValue::Array(items) => {
+                        let mut indexes = Vec::new();
+                        for (i, item) in items.iter().enumerate() {
+                            let mut item_path = sub_path.clone();
+                            push_index(&mut item_path, i);
+                            indexes.push(self.compile(item, &item_path)?);
+                        }
+                        let additional = match schema_member(obj, "additionalItems") {
+                            Some(additional) => {
+                                Some(self.compile(additional, &child(path, "additionalItems"))?)
+                            }
+                            None => None,
+                        };
+                        Check::TupleItems(indexes, additional)
+                    }
+                    _ => Check::Items(self.compile(value, &sub_path)?),
+                },
+                "contains" => Check::Contains(self.compile(value, &sub_path)?),
+                "minItems" => Check::MinItems(count()?),
+                "maxItems" => Check::MaxItems(count()?),
+                "uniqueItems" => match value {
+                    JsonValue::Boolean(true) => Check::UniqueItems,
+                    JsonValue::Boolean(false) => continue,
+                    _ => return Err(invalid()),
+                },
+                "properties" => {
+                    let properties = value.as_object().ok_or_else(invalid)?;
+                    let mut indexes = Vec::new();
+                    for (name, sub) in properties {
+                        let mut property_path = sub_path.clone();
+                        push_key(&mut property_path, name);
+                        indexes.push((name.clone(), self.compile(sub, &property_path)?));
+                    }
+                    Check::Properties(indexes)
+                }
+                "additionalProperties" => {
+                    let known =
+                        match schema_member(obj, "properties").and_then(JsonValue::as_object) {
+                            Some(properties) => properties.iter().map(|(k, _)| k.clone()).collect(),
+                            None => Vec::new(),
+                        };
+                    Check::AdditionalProperties(known, self.compile(value, &sub_path)?)
+                }
+                "required" => {
+                    let names = value.as_array().and_then(|names| {
+                        names
+                            .iter()
+                            .map(|n| n.as_string().map(<[char]>::to_vec))
+                            .collect()
+                    });
+                    Check::Required(names.ok_or_else(invalid)?)
+                }
+                "propertyNames" => Check::PropertyNames(self.compile(value, &sub_path)?),
+                "minProperties" => Check::MinProperties(count()?),
+                "maxProperties" => Check::MaxProperties(count()?),
+                "allOf" | "anyOf" | "oneOf" => {
+                    let schemas = value.as_array().filter(|s| !s.is_empty());
+                    let schemas = schemas.ok_or_else(invalid)?;
+                    let mut indexes = Vec::new();
+                    for (i, sub) in schemas.iter().enumerate() {
+                        let mut item_path = sub_path.clone();
+                        push_index(&mut item_path, i);
+                        indexes.push(self.compile(sub, &item_path)?);
+                    }
+                    match keyword {
+                        "allOf" => Check::AllOf(indexes),
+                        "anyOf" => Check::AnyOf(indexes),
+                        _ => Check::OneOf(indexes),
+                    }
+                }
+                "not" => Check::Not(self.compile(value, &sub_path)?),
+                "if" => {
+                    let mut branch = |name: &str| match schema_member(obj, name) {
+                        Some(sub) => self.compile(sub, &child(path, name)).map(Some),
+                        None => Ok(None),
+                    };
+                    let (then, otherwise) = (branch("then")?, branch("else")?);
+                    Check::If(self.compile(value, &sub_path)?, then, otherwise)
+                }
+                "pattern" | "patternProperties" | "dependencies" => {
+                    return error(path, keyword, SchemaErrorKind::Unsupported)
+                }
+                "definitions" => {
+                    // Compiled on demand by `$ref`, but checked here so errors are not hidden.
+                    let definitions = value.as_object().ok_or_else(invalid)?;
+                    for (name, sub) in definitions {
+                        let mut definition_path = sub_path.clone();
+                        push_key(&mut definition_path, name);
+                        self.compile(sub, &definition_path)?;
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+            checks.push(check);
+        }
+        Ok(Node::Checks(checks))
+    }
+}
+
+fn schema_member<'a>(obj: &'a [(Vec<char>, JsonValue)], name: &str) -> Option<&'a JsonValue> {
+    obj.iter()
+        .find(|(k, _)| k.iter().copied().eq(name.chars()))
+        .map(|(_, v)| v)
+}
+
+/// Compares two numbers by value.
+fn cmp_number(value: &JsonValue, limit: &JsonValue) -> Option<Ordering> {
+    Some(value.decimal()?.cmp(&limit.decimal()?))
+}
+
+/// Returns whether the number is `mantissa * 10^exponent` times an integer.
+fn is_multiple_of(value: &Decimal, mantissa: u64, exponent: i64) -> bool {
+    if value.digits.is_empty() {
+        return true;
+    }
+    let shift = (value.point - value.digits.len() as i64).saturating_sub(exponent);
+    if shift < 0 {
+        // The digits have no trailing zeros, so they cannot absorb the divisor's scale.
+        return false;
+    }
+    let divisor = mantissa as u128;
+    let mut remainder = value
+        .digits
+        .iter()
+        .fold(0u128, |r, d| (r * 10 + (d - b'0') as u128) % divisor);
+    let (mut base, mut shift) = (10 % divisor, shift as u64);
+    while shift > 0 && remainder != 0 {
+        if shift & 1 == 1 {
+            remainder = remainder * base % divisor;
+        }
+        base = base * base % divisor;
+        shift >>= 1;
+    }
+    remainder == 0
+}
+
+fn fail(out: &mut Vec<Violation>, path: &str, keyword: &'static str) {
+    out.push(Violation {
+        path: path.into(),
+        keyword,
+    });
+}
+
+impl Validator {
+    /// Compiles a draft-07 JSON Schema.
+    pub fn compile(schema: &JsonValue) -> Result<Self, SchemaError> {
+        let mut compiler = Compiler {
+            root: schema,
+            nodes: Vec::new(),
+            compiled: Vec::new(),
+        };
+        compiler.compile(schema, "")?;
+        Ok(Validator {
+            nodes: compiler.nodes,
+        })
+    }
+
+    /// Checks `value` against the schema, returning every violation found.
+    pub fn validate(&self, value: &JsonValue) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+        self.check(0, value, &mut String::new(), &mut violations);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Returns whether `value` satisfies the schema.
+    pub fn is_valid(&self, value: &JsonValue) -> bool {
+        self.validate(value).is_ok()
+    }
+
+    fn passes(&self, node: usize, value: &JsonValue, path: &mut String) -> bool {
+        let mut violations = Vec::new();
+        self.check(node, value, path, &mut violations);
+        violations.is_empty()
+    }
+
+    fn check(&self, node: usize, value: &JsonValue, path: &mut String, out: &mut Vec<Violation>) {
+        let checks = match &self.nodes[node] {
+            Node::Bool(true) => return,
+            Node::Bool(false) => {
+                fail(out, path, "false");
+                return;
+            }
+            Node::Checks(checks) => checks,
+        };
+        let len = path.len();
+
+        for check in checks {
+            match (check, value) {
+                (Check::Type(types), _) => {
+                    let matches = types.iter().any(|ty| match ty {
+                        Type::Null => value.is_null(),
+                        Type::Boolean => value.is_bool(),
+                        Type::Object => value.is_object(),
+                        Type::Array => value.is_array(),
+                        Type::Number => value.decimal().is_some(),
+                        Type::String => value.is_string(),
+                        Type::Integer => is_integer(value),
+                    });
+                    if !matches {
+                        fail(out, path, "type");
+                    }
+                }
+                (Check::Enum(values), _) if !values.iter().any(|v| v.eq_unordered(value)) => {
+                    fail(out, path, "enum")
+                }
+                (Check::Const(expected), _) if !expected.eq_unordered(value) => {
+                    fail(out, path, "const")
+                }
+                (Check::Minimum(limit), _) if cmp_number(value, limit) == Some(Ordering::Less) => {
+                    fail(out, path, "minimum")
+                }
+                (Check::Maximum(limit), _)
+                    if cmp_number(value, limit) == Some(Ordering::Greater) =>
+                {
+                    fail(out, path, "maximum")
+                }
+                (Check::ExclusiveMinimum(limit), _) => {
+                    if matches!(
+                        cmp_number(value, limit),
+                        Some(Ordering::Less | Ordering::Equal)
+                    ) {
+                        fail(out, path, "exclusiveMinimum");
+                    }
+                }
+                (Check::ExclusiveMaximum(limit), _) => {
+                    if matches!(
+                        cmp_number(value, limit),
+                        Some(Ordering::Greater | Ordering::Equal)
+                    ) {
+                        fail(out, path, "exclusiveMaximum");
+                    }
+                }
+                (Check::MultipleOf(mantissa, exponent), _) => {
+                    if let Some(decimal) = value.decimal() {
+                        if !is_multiple_of(&decimal, *mantissa, *exponent) {
+                            fail(out, path, "multipleOf");
+                        }
+                    }
+                }
+                (Check::MinLength(min), JsonValue::String(s)) if s.len() < *min => {
+                    fail(out, path, "minLength")
+                }
+                (Check::MaxLength(max), JsonValue::String(s)) if s.len() > *max => {
+                    fail(out, path, "maxLength")
+                }
+                (Check::Items(items), JsonValue::Array(arr)) => {
+                    for (i, item) in arr.iter().enumerate() {
+                        push_index(path, i);
+                        self.check(*items, item, path, out);
+                        path.truncate(len);
+                    }
+                }
+                (Check::TupleItems(items, additional), JsonValue::Array(arr)) => {
+                    for (i, item) in arr.iter().enumerate() {
+                        let schema = items.get(i).copied().or(*additional);
+                        if let Some(schema) = schema {
+                            push_index(path, i);
+                            self.check(schema, item, path, out);
+                            path.truncate(len);
+                        }
+                    }
+                }
+                (Check::Contains(schema), JsonValue::Array(arr)) => {
+                    let found = arr.iter().enumerate().any(|(i, item)| {
+                        push_index(path, i);
+                        let passes = self.passes(*schema, item, path);
+                        path.truncate(len);
+                        passes
+                    });
+                    if !found {
+                        fail(out, path, "contains");
+                    }
+                }
+                (Check::MinItems(min), JsonValue::Array(arr)) if arr.len() < *min => {
+                    fail(out, path, "minItems")
+                }
+                (Check::MaxItems(max), JsonValue::Array(arr)) if arr.len() > *max => {
+                    fail(out, path, "maxItems")
+                }
+                (Check::UniqueItems, JsonValue::Array(arr)) => {
+                    let duplicate = arr
+                        .iter()
+                        .enumerate()
+                        .any(|(i, a)| arr[i + 1..].iter().any(|b| a.eq_unordered(b)));
+                    if duplicate {
+                        fail(out, path, "uniqueItems");
+                    }
+                }
+                (Check::Properties(properties), JsonValue::Object(obj)) => {
+                    for (key, member) in obj {
+                        if let Some((_, schema)) = properties.iter().find(|(k, _)| k == key) {
+                            push_key(path, key);
+                            self.check(*schema, member, path, out);
+                            path.truncate(len);
+                        }
+                    }
+                }
+                (Check::AdditionalProperties(known, schema), JsonValue::Object(obj)) => {
+                    for (key, member) in obj.iter().filter(|(k, _)| !known.contains(k)) {
+                        push_key(path, key);
+                        if !self.passes(*schema, member, path) {
+                            fail(out, path, "additionalProperties");
+                        }
+                        path.truncate(len);
+                    }
+                }
+                (Check::Required(names), JsonValue::Object(obj)) => {
+                    for name in names {
+                        if !obj.iter().any(|(k, _)| k == name) {
+                            push_key(path, name);
+                            fail(out, path, "required");
+                            path.truncate(len);
+                        }
+                    }
+                }
+                (Check::PropertyNames(schema), JsonValue::Object(obj)) => {
+                    for (key, _) in obj {
+                        push_key(path, key);
+                        if !self.passes(*schema, &JsonValue::String(key.clone()), path) {
+                            fail(out, path, "propertyNames");
+                        }
+                        path.truncate(len);
+                    }
+                }
+                (Check::MinProperties(min), JsonValue::Object(obj)) if obj.len() < *min => {
+                    fail(out, path, "minProperties")
+                }
+                (Check::MaxProperties(max), JsonValue::Object(obj)) if obj.len() > *max => {
+                    fail(out, path, "maxProperties")
+                }
+                (Check::AllOf(schemas), _) => {
+                    for schema in schemas {
+                        self.check(*schema, value, path, out);
+                    }
+                }
+                (Check::AnyOf(schemas), _)
+                    if !schemas.iter().any(|s| self.passes(*s, value, path)) =>
+                {
+                    fail(out, path, "anyOf")
+                }
+                (Check::OneOf(schemas), _) => {
+                    let passing = schemas.iter().filter(|s| self.passes(**s, value, path));
+                    if passing.count() != 1 {
+                        fail(out, path, "oneOf");
+                    }
+                }
+                (Check::Not(schema), _) if self.passes(*schema, value, path) => {
+                    fail(out, path, "not")
+                }
+                (Check::If(condition, then, otherwise), _) => {
+                    let branch = if self.passes(*condition, value, path) {
+                        then
+                    } else {
+                        otherwise
+                    };
+                    if let Some(branch) = branch {
+                        self.check(*branch, value, path, out);
+                    }
+                }
+                (Check::Ref(schema), _) => self.check(*schema, value, path, out),
+                // Keywords for other types do not apply.
+                _ => {}
+            }
+        }
+    }
+}
+
+/// The types seen at one location across all sample values.
+#[derive(Default)]
+struct Shape {
+    null: bool,
+    boolean: bool,
+    integer: bool,
+    number: bool,
+    string: bool,
+    /// The shape of all items, with `None` items when only empty arrays were seen.
+    array: Option<Option<Box<Shape>>>,
+    object: Option<ObjectShape>,
+}
+
+/// Every member seen with the number of objects containing it, and the number of objects.
+#[derive(Default)]
+struct ObjectShape {
+    members: Vec<(Vec<char>, Shape, usize)>,
+    count: usize,
+}
+
+impl Shape {
+    fn add(&mut self, value: &JsonValue) {
+        match value {
+            JsonValue::Null => self.null = true,
+            JsonValue::Boolean(_) => self.boolean = true,
+            JsonValue::Number(_) | JsonValue::BigNumber(_) if is_integer(value) => {
+                self.integer = true
+            }
+            JsonValue::Number(_) | JsonValue::BigNumber(_) | JsonValue::NonFinite(_) => {
+                self.number = true
+            }
+            JsonValue::String(_) | JsonValue::Utf16String(_) => self.string = true,
+            JsonValue::Array(arr) => {
+                let items = self.array.get_or_insert(None);
+                for item in arr {
+                    items.get_or_insert_with(Default::default).add(item);
+                }
+            }
+            JsonValue::Object(obj) => {
+                let ObjectShape { members, count } =
+                    self.object.get_or_insert_with(Default::default);
+                *count += 1;
+                for (i, (key, member)) in obj.iter().enumerate() {
+                    // Repeated keys count once per object.
+                    let repeated = obj[..i].iter().any(|(k, _)| k == key);
+                    match members.iter_mut().find(|(k, _, _)| k == key) {
+                        Some((_, shape, seen)) => {
+                            shape.add(member);
+                            if !repeated {
+                                *seen += 1;
+                            }
+                        }
+                        None => {
+                            let mut shape = Shape::default();
+                            shape.add(member);
+                            members.push((key.clone(), shape, 1));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn to_schema(&self) -> JsonValue {
+        let string = |s: &str| JsonValue::String(s.chars().collect());
+        let mut types = Vec::new();
+        let mut schema = Vec::new();
+        if let Some(ObjectShape { members, count }) = &self.object {
+            types.push(string("object"));
+            let properties = members
+                .iter()
+                .map(|(key, shape, _)| (key.clone(), shape.to_schema()))
+                .collect();
+            let required = members
+                .iter()
+                .filter(|(_, _, seen)| seen == count)
+                .map(|(key, _, _)| JsonValue::String(key.clone()))
+                .collect();
+            schema.push((
+                "properties".chars().collect(),
+                JsonValue::Object(properties),
+            ));
+            schema.push(("required".chars().collect(), JsonValue::Array(required)));
+        }
+        if let Some(items) = &self.array {
+            types.push(string("array"));
+            if let Some(items) = items {
+                schema.push(("items".chars().collect(), items.to_schema()));
+            }
+        }
+        if self.string {
+            types.push(string("string"));
+        }
+        if self.number {
+            types.push(string("number"));
+        } else if self.integer {
+            types.push(string("integer"));
+        }
+        if self.boolean {
+            types.push(string("boolean"));
+        }
+        if self.null {
+            types.push(string("null"));
+        }
+        let ty = match types.len() {
+            1 => types.pop().unwrap(),
+            _ => JsonValue::Array(types),
+        };
+        schema.insert(0, ("type".chars().collect(), ty));
+        JsonValue::Object(schema)
+    }
+}
+
+/// Describes the shape of a sample document as a JSON Schema.
+///
+/// The schema gives the `type` of every value, the `properties` of objects with the members
+/// present in every occurrence listed as `required`, and the `items` of arrays. All items of
+/// an array, and all occurrences of a member across the objects of an array, are merged: a
+/// location that held several types gets a list of types, and integers merged with other
+/// numbers become `number`.
+pub fn infer_schema(value: &JsonValue) -> JsonValue {
+    let mut shape = Shape::default();
+    shape.add(value);
+    shape.to_schema()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_parser::parse_json;
+
+    fn violations(schema: &str, value: &str) -> Vec<(String, &'static str)> {
+        let validator = Validator::compile(&parse_json(schema).unwrap()).unwrap();
+        match validator.validate(&parse_json(value).unwrap()) {
+            Ok(()) => Vec::new(),
+            Err(violations) => violations
+                .into_iter()
+                .map(|v| (v.path, v.keyword))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn validation_reports_all_violations() {
+        let schema = r##"{
+            "type": "object",
+            "required": ["price", "source"],
+            "properties": {
+                "price": {"type": "integer", "minimum": 0, "multipleOf": 0.5},
+                "source": {"type": "string"},
+                "tags": {"type": "array", "items": {"enum": ["a", "b"]}, "uniqueItems": true},
+                "nested": {"$ref": "#/definitions/node"}
+            },
+            "additionalProperties": false,
+            "definitions": {
+                "node": {"type": "object", "properties": {"next": {"$ref": "#/definitions/node"}}, "maxProperties": 1}
+            }
+        }"##;
+        assert_eq!(violations(schema, r#"{"price":3,"source":"x"}"#), vec![]);
+        assert_eq!(
+            violations(
+                schema,
+                r#"{"price":-1.5,"tags":["a","c","a"],"nested":{"next":{"next":{},"x":1}},"extra":1}"#
+            ),
+            vec![
+                ("/source".to_string(), "required"),
+                ("/price".to_string(), "type"),
+                ("/price".to_string(), "minimum"),
+                ("/tags/1".to_string(), "enum"),
+                ("/tags".to_string(), "uniqueItems"),
+                ("/nested/next".to_string(), "maxProperties"),
+                ("/extra".to_string(), "additionalProperties"),
+            ]
+        );
+    }
+
+    #[test]
+    fn numbers_compare_exactly() {
+        let schema = r#"{"exclusiveMaximum": 0.3, "multipleOf": 0.1}"#;
+        assert_eq!(violations(schema, "0.2"), vec![]);
+        assert_eq!(
+            violations(schema, "0.30"),
+            vec![(String::new(), "exclusiveMaximum")]
+        );
+        assert_eq!(
+            violations(schema, "0.25"),
+            vec![(String::new(), "multipleOf")]
+        );
+        assert_eq!(violations(schema, "-1e10"), vec![]);
+        assert_eq!(violations(r#"{"type":"integer"}"#, "1.0e2"), vec![]);
+        assert_eq!(
+            violations(r#"{"maximum": 1e400}"#, "1e401"),
+            vec![(String::new(), "maximum")]
+        );
+    }
+
+    #[test]
+    fn combinators_work() {
+        let schema = r#"{
+            "oneOf": [{"type": "string"}, {"type": "integer"}, {"type": "number", "minimum": 10}],
+            "not": {"const": "x"},
+            "if": {"type": "string"}, "then": {"maxLength": 2}, "else": {"maximum": 100}
+        }"#;
+        assert_eq!(violations(schema, r#""ab""#), vec![]);
+        assert_eq!(violations(schema, r#""x""#), vec![(String::new(), "not")]);
+        assert_eq!(
+            violations(schema, r#""abc""#),
+            vec![(String::new(), "maxLength")]
+        );
+        assert_eq!(violations(schema, "11"), vec![(String::new(), "oneOf")]);
+        assert_eq!(
+            violations(schema, "200.5"),
+            vec![(String::new(), "maximum")]
+        );
+        assert_eq!(violations("false", "1"), vec![(String::new(), "false")]);
+    }
+
+    #[test]
+    fn compile_rejects_bad_schemas() {
+        let compile = |schema: &str| Validator::compile(&parse_json(schema).unwrap()).err();
+        assert_eq!(
+            compile(r#"{"properties": {"a": {"pattern": "^a"}}}"#),
+            Some(SchemaError {
+                path: "/properties/a/pattern".into(),
+                kind: SchemaErrorKind::Unsupported,
+            })
+        );
+        assert_eq!(
+            compile(r##"{"items": {"$ref": "#/definitions/missing"}}"##).map(|e| e.kind),
+            Some(SchemaErrorKind::UnresolvedRef)
+        );
+        assert_eq!(
+            compile(r##"{"$ref": "#"}"##),
+            Some(SchemaError {
+                path: "/$ref".into(),
+                kind: SchemaErrorKind::UnresolvedRef,
+            })
+        );
+        assert_eq!(
+            compile(
+                r##"{"items": {"$ref": "#/definitions/a"}, "definitions": {"a": {"$ref": "#/definitions/b"}, "b": {"$ref": "#/definitions/a"}}}"##
+            )
+            .map(|e| e.path),
+            Some("/items/$ref".into())
+        );
+        assert_eq!(
+            compile(r#"{"minItems": -1}"#).map(|e| e.path),
+            Some("/minItems".into())
+        );
+        assert_eq!(
+            compile(r#"{"type": "float"}"#).map(|e| e.kind),
+            Some(SchemaErrorKind::InvalidKeyword)
+        );
+    }
+
+    #[test]
+    fn infer_schema_merges_samples() {
+        let sample = parse_json(
+            r#"[{"id":1,"tags":[],"meta":null},{"id":2.5,"tags":["a"],"meta":{"x":true}}]"#,
+        )
+        .unwrap();
+        let schema = infer_schema(&sample);
+        assert_eq!(
+            format!("{}", schema),
+            concat!(
+                r#"{"type":"array","items":{"type":"object","properties":{"#,
+                r#""id":{"type":"number"},"tags":{"type":"array","items":{"type":"string"}},"#,
+                r#""meta":{"type":["object","null"],"properties":{"x":{"type":"boolean"}},"required":["x"]}},"#,
+                r#""required":["id","tags","meta"]}}"#
+            )
+        );
+        let validator = Validator::compile(&schema).unwrap();
+        assert!(validator.is_valid(&sample));
+        assert!(!validator.is_valid(&parse_json(r#"[{"id":1,"tags":[1],"meta":null}]"#).unwrap()));
+        assert_eq!(
+            format!("{}", infer_schema(&parse_json("[]").unwrap())),
+            r#"{"type":"array"}"#
+        );
+    }
+}
@@ -0,0 +1,679 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+pub use crate::de::Error;
+use crate::json::{utf16_lossy, JsonValue, NumberValue};
+use core::fmt::{self, Write as _};
+use serde::ser::{self, Impossible, Serialize};
+
+/// Converts any `T: Serialize` into a `JsonValue`.
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<JsonValue, Error> {
+    value.serialize(Serializer)
+}
+
+/// Serializes any `T: Serialize` as compact JSON bytes.
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Error> {
+    Ok(crate::traits::Serialize::serialize(&to_value(value)?))
+}
+
+/// Serializes any `T: Serialize` as a compact JSON string.
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, Error> {
+    let mut text = String::new();
+    write!(text, "{}", to_value(value)?).expect("writing to a string cannot fail");
+    Ok(text)
+}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+impl Serialize for JsonValue {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use ser::{SerializeMap, SerializeSeq};
+
+        match self {
+            JsonValue::Object(obj) => {
+                let mut map = serializer.serialize_map(Some(obj.len()))?;
+                for (key, value) in obj {
+                    map.serialize_entry(&key.iter().collect::<String>(), value)?;
+                }
+                map.end()
+            }
+            JsonValue::Array(arr) => {
+                let mut seq = serializer.serialize_seq(Some(arr.len()))?;
+                for value in arr {
+                    seq.serialize_element(value)?;
+                }
+                seq.end()
+            }
+            JsonValue::String(s) => serializer.serialize_str(&s.iter().collect::<String>()),
+            JsonValue::Utf16String(s) => {
+                serializer.serialize_str(&utf16_lossy(s).into_iter().collect::<String>())
+            }
+            JsonValue::Number(n) => {
+                if let Some(n) = n.to_u64() {
+                    serializer.serialize_u64(n)
+                } else if let Some(n) = n.to_i64() {
+                    serializer.serialize_i64(n)
+                } else {
+                    serializer.serialize_f64(n.to_f64())
+                }
+            }
+            JsonValue::BigNumber(n) => serializer.serialize_f64(
+                core::str::from_utf8(n)
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .ok_or_else(|| ser::Error::custom("invalid number"))?,
+            ),
+            JsonValue::NonFinite(n) => serializer.serialize_f64(n.to_f64()),
+            JsonValue::Boolean(b) => serializer.serialize_bool(*b),
+            JsonValue::Null => serializer.serialize_unit(),
+        }
+    }
+}
+
+fn integer(negative: bool, magnitude: u128) -> JsonValue {
+    JsonValue::Number(NumberValue {
+        integer: magnitude,
+        negative,
+        ..Default::default()
+    })
+}
+
+fn signed(val: i128) -> JsonValue {
+    integer(val < 0, val.unsigned_abs())
+}
+
+fn string(s: &str) -> JsonValue {
+    JsonValue::String(s.chars().collect())
+}
+
+fn variant(name: &str, value: JsonValue) -> JsonValue {
+    JsonValue::Object(vec![(name.chars().collect(), value)])
+}
+
+/// A serializer that builds a `JsonValue`.
+pub struct Serializer;
+
+impl ser::Serializer for Serializer {
+    type Ok = JsonValue;
+    type Error = Error;
+    type SerializeSeq = SerializeVec;
+    type SerializeTuple = SerializeVec;
+    type SerializeTupleStruct = SerializeVec;
+    type SerializeTupleVariant = SerializeVec;
+    type SerializeMap = SerializeObject;
+    type SerializeStruct = SerializeObject;
+    type SerializeStructVariant = SerializeObject;
+
+    fn serialize_bool(self, v: bool) -> Result<JsonValue, Error> {
+        Ok(JsonValue::Boolean(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<JsonValue, Error> {
+        Ok(signed(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<JsonValue, Error> {
+        Ok(signed(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<JsonValue, Error> {
+        Ok(signed(v.into()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<JsonValue, Error> {
+        Ok(signed(v.into()))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<JsonValue, Error> {
+        Ok(signed(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<JsonValue, Error> {
+        Ok(integer(false, v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<JsonValue, Error> {
+        Ok(integer(false, v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<JsonValue, Error> {
+        Ok(integer(false, v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<JsonValue, Error> {
+        Ok(integer(false, v.into()))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<JsonValue, Error> {
+        Ok(integer(false, v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<JsonValue, Error> {
+        Ok(NumberValue::from_f32(v).map_or(JsonValue::Null, JsonValue::Number))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<JsonValue, Error> {
+        Ok(NumberValue::from_f64(v).map_or(JsonValue::Null, JsonValue::Number))
+    }
+
+    fn serialize_char(self, v: char) -> Result<JsonValue, Error> {
+        Ok(JsonValue::String(vec![v]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<JsonValue, Error> {
+        Ok(string(v))
+    }
+
+    /// Writes bytes as an array of numbers.
+    fn serialize_bytes(self, v: &[u8]) -> Result<JsonValue, Error> {
+        Ok(JsonValue::Array(
+            v.iter().map(|b| integer(false, (*b).into())).collect(),
+        ))
+    }
+
+    fn serialize_none(self) -> Result<JsonValue, Error> {
+        Ok(JsonValue::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<JsonValue, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<JsonValue, Error> {
+        Ok(JsonValue::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<JsonValue, Error> {
+        Ok(JsonValue::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<JsonValue, Error> {
+        Ok(string(variant))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<JsonValue, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<JsonValue, Error> {
+        Ok(self::variant(variant, to_value(value)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeVec, Error> {
+        Ok(SerializeVec {
+            variant: None,
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeVec, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeVec, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVec, Error> {
+        Ok(SerializeVec {
+            variant: Some(variant),
+            items: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeObject, Error> {
+        Ok(SerializeObject {
+            variant: None,
+            members: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeObject, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeObject, Error> {
+        Ok(SerializeObject {
+            variant: Some(variant),
+            members: Vec::with_capacity(len),
+            key: None,
+        })
+    }
+}
+
+/// Collects the items of a sequence, tuple or tuple variant.
+pub struct SerializeVec {
+    variant: Option<&'static str>,
+    items: Vec<JsonValue>,
+}
+
+impl SerializeVec {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.items.push(to_value(value)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<JsonValue, Error> {
+        let array = JsonValue::Array(self.items);
+        Ok(match self.variant {
+            Some(name) => variant(name, array),
+            None => array,
+        })
+    }
+}
+
+impl ser::SerializeSeq for SerializeVec {
+    type Ok = JsonValue;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<JsonValue, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SerializeVec {
+    type Ok = JsonValue;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<JsonValue, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeVec {
+    type Ok = JsonValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<JsonValue, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeVec {
+    type Ok = JsonValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<JsonValue, Error> {
+        self.finish()
+    }
+}
+
+/// Collects the members of a map, struct or struct variant.
+pub struct SerializeObject {
+    variant: Option<&'static str>,
+    members: Vec<(Vec<char>, JsonValue)>,
+    key: Option<Vec<char>>,
+}
+
+impl SerializeObject {
+    fn finish(self) -> Result<JsonValue, Error> {
+        let object = JsonValue::Object(self.members);
+        Ok(match self.variant {
+            Some(name) => variant(name, object),
+            None => object,
+        })
+    }
+}
+
+impl ser::SerializeMap for SerializeObject {
+    type Ok = JsonValue;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| <Error as ser::Error>::custom("value serialized before its key"))?;
+        self.members.push((key, to_value(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<JsonValue, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for SerializeObject {
+    type Ok = JsonValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.members.push((key.chars().collect(), to_value(value)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<JsonValue, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeObject {
+    type Ok = JsonValue;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        ser::SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn end(self) -> Result<JsonValue, Error> {
+        self.finish()
+    }
+}
+
+/// Serializes map keys, which must be strings, chars, integers or booleans.
+struct KeySerializer;
+
+impl KeySerializer {
+    fn display(v: impl fmt::Display) -> Result<Vec<char>, Error> {
+        Ok(v.to_string().chars().collect())
+    }
+}
+
+fn key_must_be_a_string() -> Error {
+    <Error as ser::Error>::custom("key must be a string")
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = Vec<char>;
+    type Error = Error;
+    type SerializeSeq = Impossible<Vec<char>, Error>;
+    type SerializeTuple = Impossible<Vec<char>, Error>;
+    type SerializeTupleStruct = Impossible<Vec<char>, Error>;
+    type SerializeTupleVariant = Impossible<Vec<char>, Error>;
+    type SerializeMap = Impossible<Vec<char>, Error>;
+    type SerializeStruct = Impossible<Vec<char>, Error>;
+    type SerializeStructVariant = Impossible<Vec<char>, Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<Vec<char>, Error> {
+        Self::display(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Vec<char>, Error> {
+        Self::display(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Vec<char>, Error> {
+        Self::display(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Vec<char>, Error> {
+        Self::display(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Vec<char>, Error> {
+        Self::display(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Vec<char>, Error> {
+        Self::display(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Vec<char>, Error> {
+        Self::display(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Vec<char>, Error> {
+        Self::display(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Vec<char>, Error> {
+        Self::display(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Vec<char>, Error> {
+        Self::display(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Vec<char>, Error> {
+        Self::display(v)
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<Vec<char>, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<Vec<char>, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<Vec<char>, Error> {
+        Ok(vec![v])
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Vec<char>, Error> {
+        Ok(v.chars().collect())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Vec<char>, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_none(self) -> Result<Vec<char>, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<Vec<char>, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit(self) -> Result<Vec<char>, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Vec<char>, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Vec<char>, Error> {
+        Ok(variant.chars().collect())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Vec<char>, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Vec<char>, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(key_must_be_a_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::de::from_str;
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Point,
+        Circle(f32),
+        Line(i32, i32),
+        Rect { w: u8, h: u8 },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Doc {
+        name: String,
+        id: u128,
+        delta: i64,
+        tags: Vec<char>,
+        parent: Option<Box<Doc>>,
+        shapes: Vec<Shape>,
+        extra: BTreeMap<u8, bool>,
+        unit: (),
+    }
+
+    #[test]
+    fn it_serializes_structs() {
+        let doc = Doc {
+            name: "root \"1\"".into(),
+            id: u64::MAX as u128 + 1,
+            delta: i64::MIN,
+            tags: vec!['a'],
+            parent: None,
+            shapes: vec![
+                Shape::Point,
+                Shape::Circle(0.1),
+                Shape::Line(1, -1),
+                Shape::Rect { w: 2, h: 3 },
+            ],
+            extra: vec![(7, true)].into_iter().collect(),
+            unit: (),
+        };
+
+        assert_eq!(
+            to_string(&doc).unwrap(),
+            r#"{"name":"root \"1\"","id":18446744073709551616,"delta":-9223372036854775808,"tags":["a"],"parent":null,"shapes":["Point",{"Circle":0.1},{"Line":[1,-1]},{"Rect":{"w":2,"h":3}}],"extra":{"7":true},"unit":null}"#
+        );
+        assert_eq!(to_vec(&doc).unwrap(), to_string(&doc).unwrap().into_bytes());
+        assert_eq!(to_value(&f64::NAN).unwrap(), JsonValue::Null);
+        assert_eq!(from_str::<Doc>(&to_string(&doc).unwrap()).unwrap(), doc);
+    }
+
+    #[test]
+    fn json_value_round_trips() {
+        let value = crate::json_parser::parse_json(r#"{"a":[1,-2,1.5,"x",null,true]}"#).unwrap();
+        assert_eq!(to_value(&value).unwrap(), value);
+        assert!(from_str::<Doc>(&to_string(&Shape::Point).unwrap()).is_err());
+
+        let mut map = BTreeMap::new();
+        map.insert(vec![1], 2);
+        assert!(matches!(to_value(&map), Err(Error::Message(_))));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::json::{utf16_lossy, JsonValue, NumberValue};
+use serde_json::{Map, Number, Value};
+
+impl From<Value> for JsonValue {
+    /// Converts numbers through their text, so they keep every digit `serde_json` stored.
+    /// Numbers too large for `NumberValue` become `BigNumber`.
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Object(obj) => JsonValue::Object(
+                obj.into_iter()
+                    .map(|(k, v)| (k.chars().collect(), v.into()))
+                    .collect(),
+            ),
+            Value::Array(arr) => JsonValue::Array(arr.into_iter().map(Into::into).collect()),
+            Value::String(s) => JsonValue::String(s.chars().collect()),
+            Value::Number(n) => {
+                let text = n.to_string();
+                match text.parse::<NumberValue>() {
+                    Ok(num) => JsonValue::Number(num),
+                    Err(_) => JsonValue::BigNumber(text.into_bytes()),
+                }
+            }
+            Value::Bool(b) => JsonValue::Boolean(b),
+            Value::Null => JsonValue::Null,
+        }
+    }
+}
+
+fn to_number(text: &str, approx: impl FnOnce() -> f64) -> Value {
+    text.parse::<Number>()
+        .ok()
+        .or_else(|| Number::from_f64(approx()))
+        .map_or(Value::Null, Value::Number)
+}
+
+impl From<JsonValue> for Value {
+    /// Converts numbers through their text, falling back to the nearest `f64` for numbers
+    /// `serde_json` rejects, and to `Null` for those beyond the range of `f64` and for `NaN`
+    /// and infinities. Duplicate object keys keep their last value.
+    fn from(value: JsonValue) -> Self {
+        match value {
+            JsonValue::Object(obj) => Value::Object(
+                obj.into_iter()
+                    .map(|(k, v)| (k.into_iter().collect(), v.into()))
+                    .collect::<Map<_, _>>(),
+            ),
+            JsonValue::Array(arr) => Value::Array(arr.into_iter().map(Into::into).collect()),
+            JsonValue::String(s) => Value::String(s.into_iter().collect()),
+            JsonValue::Utf16String(s) => Value::String(utf16_lossy(&s).into_iter().collect()),
+            JsonValue::Number(n) => to_number(&n.to_string(), || n.to_f64()),
+            JsonValue::BigNumber(n) => {
+                let text = String::from_utf8_lossy(&n);
+                to_number(&text, || text.parse().unwrap_or(f64::NAN))
+            }
+            JsonValue::NonFinite(_) => Value::Null,
+            JsonValue::Boolean(b) => Value::Bool(b),
+            JsonValue::Null => Value::Null,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_parser::parse_json;
+
+    #[test]
+    fn it_converts_both_ways() {
+        let text = r#"{"a":[1,-2,1.5,"x",null,true],"b":{"c":1e-7}}"#;
+        let ours = parse_json(text).unwrap();
+        let theirs: Value = serde_json::from_str(text).unwrap();
+
+        assert_eq!(Value::from(ours.clone()), theirs);
+        assert!(JsonValue::from(theirs).eq_unordered(&ours));
+    }
+
+    #[test]
+    fn it_converts_edge_numbers() {
+        assert_eq!(
+            Value::from(parse_json("18446744073709551615").unwrap()),
+            serde_json::json!(u64::MAX)
+        );
+        assert_eq!(
+            Value::from(parse_json("-0.25e2").unwrap()),
+            serde_json::json!(-25.0)
+        );
+        assert_eq!(Value::from(parse_json("1e400").unwrap()), Value::Null);
+        assert_eq!(
+            JsonValue::from(serde_json::json!(i64::MIN)),
+            parse_json("-9223372036854775808").unwrap()
+        );
+        assert_eq!(
+            Value::from(parse_json(r#"{"k":1,"k":2}"#).unwrap()),
+            serde_json::json!({"k": 2})
+        );
+    }
+}
@@ -0,0 +1,216 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+use crate::json::{push_new_line_indent, push_string, utf16_lossy, JsonValue, NumberValue};
+use crate::json_parser::parse_json;
+use crate::parse_error::ParseError;
+use crate::traits::Serialize;
+use core::convert::TryInto;
+use core::fmt::Write as _;
+
+/// A number stored as a native integer or float, like `serde_json` does.
+///
+/// Unlike `NumberValue` this does not keep the number's decimal digits, so it trades
+/// lossless round-tripping for direct numeric access.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimpleNumber {
+    /// A non-negative integer that fits `u64`.
+    PosInt(u64),
+    /// A negative integer that fits `i64`.
+    NegInt(i64),
+    /// Any other number.
+    Float(f64),
+}
+
+impl SimpleNumber {
+    /// Returns the number as `u64` if it is a non-negative integer.
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            SimpleNumber::PosInt(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// Returns the number as `i64` if it is an integer that fits.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            SimpleNumber::PosInt(n) => n.try_into().ok(),
+            SimpleNumber::NegInt(n) => Some(n),
+            SimpleNumber::Float(_) => None,
+        }
+    }
+
+    /// Returns the number as `f64`, rounding large integers.
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            SimpleNumber::PosInt(n) => n as f64,
+            SimpleNumber::NegInt(n) => n as f64,
+            SimpleNumber::Float(n) => n,
+        }
+    }
+}
+
+impl From<NumberValue> for SimpleNumber {
+    /// Negative zero becomes `Float(-0.0)`, so its sign is kept.
+    fn from(num: NumberValue) -> Self {
+        if num.negative && num.integer == 0 && num.fraction == 0 {
+            SimpleNumber::Float(-0.0)
+        } else if let Some(n) = num.to_u64() {
+            SimpleNumber::PosInt(n)
+        } else if let Some(n) = num.to_i64() {
+            SimpleNumber::NegInt(n)
+        } else {
+            SimpleNumber::Float(num.to_f64())
+        }
+    }
+}
+
+/// A JSON value whose numbers are `SimpleNumber`s.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimpleValue {
+    Object(Vec<(Vec<char>, SimpleValue)>),
+    Array(Vec<SimpleValue>),
+    String(Vec<char>),
+    Number(SimpleNumber),
+    Boolean(bool),
+    Null,
+}
+
+impl SimpleValue {
+    /// Returns the number if this value is one.
+    pub fn as_number(&self) -> Option<&SimpleNumber> {
+        match self {
+            SimpleValue::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+}
+
+impl From<JsonValue> for SimpleValue {
+    /// Converts every number, rounding those that are not `u64` or `i64` integers to `f64`.
+    fn from(value: JsonValue) -> Self {
+        match value {
+            JsonValue::Object(obj) => {
+                SimpleValue::Object(obj.into_iter().map(|(k, v)| (k, v.into())).collect())
+            }
+            JsonValue::Array(arr) => SimpleValue::Array(arr.into_iter().map(Into::into).collect()),
+            JsonValue::String(s) => SimpleValue::String(s),
+            JsonValue::Utf16String(s) => SimpleValue::String(utf16_lossy(&s)),
+            JsonValue::Number(n) => SimpleValue::Number(n.into()),
+            JsonValue::BigNumber(n) => SimpleValue::Number(SimpleNumber::Float(
+                core::str::from_utf8(&n)
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(f64::NAN),
+            )),
+            JsonValue::NonFinite(n) => SimpleValue::Number(SimpleNumber::Float(n.to_f64())),
+            JsonValue::Boolean(b) => SimpleValue::Boolean(b),
+            JsonValue::Null => SimpleValue::Null,
+        }
+    }
+}
+
+/// Parses JSON into a `SimpleValue`.
+pub fn parse_json_simple(input: &str) -> Result<SimpleValue, ParseError> {
+    parse_json(input).map(Into::into)
+}
+
+impl Serialize for SimpleNumber {
+    /// Writes non-finite floats as `null`, since JSON cannot represent them.
+    fn serialize_to(&self, buffer: &mut Vec<u8>, _indent: u32, _level: u32) {
+        let mut text = String::new();
+        match *self {
+            SimpleNumber::PosInt(n) => write!(text, "{}", n),
+            SimpleNumber::NegInt(n) => write!(text, "{}", n),
+            SimpleNumber::Float(n) if n.is_finite() => write!(text, "{:?}", n),
+            SimpleNumber::Float(_) => write!(text, "null"),
+        }
+        .expect("writing to a string cannot fail");
+        buffer.extend_from_slice(text.as_bytes());
+    }
+}
+
+impl Serialize for SimpleValue {
+    fn serialize_to(&self, buffer: &mut Vec<u8>, indent: u32, level: u32) {
+        match self {
+            SimpleValue::Object(obj) => {
+                buffer.push(b'{');
+                for (i, (key, val)) in obj.iter().enumerate() {
+                    if i > 0 {
+                        buffer.push(b',');
+                    }
+                    push_new_line_indent(buffer, indent, level + 1);
+                    push_string(buffer, key);
+                    buffer.push(b':');
+                    if indent > 0 {
+                        buffer.push(b' ');
+                    }
+                    val.serialize_to(buffer, indent, level + 1);
+                }
+                if !obj.is_empty() {
+                    push_new_line_indent(buffer, indent, level);
+                }
+                buffer.push(b'}');
+            }
+            SimpleValue::Array(arr) => {
+                buffer.push(b'[');
+                for (i, val) in arr.iter().enumerate() {
+                    if i > 0 {
+                        buffer.push(b',');
+                    }
+                    push_new_line_indent(buffer, indent, level + 1);
+                    val.serialize_to(buffer, indent, level + 1);
+                }
+                if !arr.is_empty() {
+                    push_new_line_indent(buffer, indent, level);
+                }
+                buffer.push(b']');
+            }
+            SimpleValue::String(str) => push_string(buffer, str),
+            SimpleValue::Number(num) => num.serialize_to(buffer, indent, level),
+            SimpleValue::Boolean(true) => buffer.extend_from_slice(b"true"),
+            SimpleValue::Boolean(false) => buffer.extend_from_slice(b"false"),
+            SimpleValue::Null => buffer.extend_from_slice(b"null"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_are_native() {
+        let value = parse_json_simple(r#"[0,18446744073709551615,-3,1.5,1e400,2e2,-0]"#).unwrap();
+        assert_eq!(
+            value,
+            SimpleValue::Array(vec![
+                SimpleValue::Number(SimpleNumber::PosInt(0)),
+                SimpleValue::Number(SimpleNumber::PosInt(u64::MAX)),
+                SimpleValue::Number(SimpleNumber::NegInt(-3)),
+                SimpleValue::Number(SimpleNumber::Float(1.5)),
+                SimpleValue::Number(SimpleNumber::Float(f64::INFINITY)),
+                SimpleValue::Number(SimpleNumber::PosInt(200)),
+                SimpleValue::Number(SimpleNumber::Float(-0.0)),
+            ])
+        );
+        assert_eq!(SimpleNumber::NegInt(-3).as_u64(), None);
+        assert_eq!(SimpleNumber::PosInt(3).as_i64(), Some(3));
+        assert_eq!(
+            value.serialize(),
+            b"[0,18446744073709551615,-3,1.5,null,200,-0.0]".to_vec()
+        );
+    }
+
+    #[test]
+    fn it_formats() {
+        let value = parse_json_simple(r#"{"a":[1,{"b":0.5}],"c":{}}"#).unwrap();
+        assert_eq!(
+            value.format(2),
+            b"{\n  \"a\": [\n    1,\n    {\n      \"b\": 0.5\n    }\n  ],\n  \"c\": {}\n}".to_vec()
+        );
+    }
+}
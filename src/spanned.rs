@@ -0,0 +1,306 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::json::{JsonValue, NonFinite, NumberValue};
+use crate::json_parser::{
+    skip_bom, CloseCurlyBracketChar, CloseSquareBracketChar, String, Value, Whitespace,
+};
+use crate::parse_error::{ParseError, ParseErrorKind};
+use crate::pointer::{parse_index, tokens};
+use core::ops::Range;
+use lite_parser::{
+    parser::{Parser, ParserContext, ParserOptions, ParserState},
+    traits::{Input, Position, ResultOf},
+};
+
+/// A parsed value that remembers where it came from, as returned by `parse_json_spanned`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpannedJsonValue {
+    /// The byte range of the value in the input, without surrounding whitespace.
+    pub span: Range<usize>,
+    pub value: SpannedValue,
+}
+
+/// The contents of a `SpannedJsonValue`, mirroring `JsonValue`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpannedValue {
+    Object(Vec<(Vec<char>, SpannedJsonValue)>),
+    Array(Vec<SpannedJsonValue>),
+    String(Vec<char>),
+    Utf16String(Vec<u16>),
+    Number(NumberValue),
+    BigNumber(Vec<u8>),
+    NonFinite(NonFinite),
+    Boolean(bool),
+    Null,
+}
+
+impl SpannedJsonValue {
+    /// Returns the value at the JSON Pointer `path`, like `JsonValue::pointer`.
+    pub fn pointer(&self, path: &str) -> Option<&SpannedJsonValue> {
+        tokens(path.chars())?
+            .iter()
+            .try_fold(self, |node, token| match &node.value {
+                SpannedValue::Object(obj) => {
+                    obj.iter().rev().find(|(k, _)| k == token).map(|(_, v)| v)
+                }
+                SpannedValue::Array(arr) => arr.get(parse_index(token)?),
+                _ => None,
+            })
+    }
+
+    /// Returns the 1-based line and column, counted in characters, where the value starts in
+    /// `input`, which must be the text it was parsed from.
+    pub fn line_column(&self, input: &str) -> (usize, usize) {
+        let before = &input[..self.span.start];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        (
+            before.matches('\n').count() + 1,
+            before[line_start..].chars().count() + 1,
+        )
+    }
+
+    /// Drops the spans.
+    pub fn into_value(self) -> JsonValue {
+        match self.value {
+            SpannedValue::Object(obj) => {
+                JsonValue::Object(obj.into_iter().map(|(k, v)| (k, v.into_value())).collect())
+            }
+            SpannedValue::Array(arr) => {
+                JsonValue::Array(arr.into_iter().map(Self::into_value).collect())
+            }
+            SpannedValue::String(s) => JsonValue::String(s),
+            SpannedValue::Utf16String(s) => JsonValue::Utf16String(s),
+            SpannedValue::Number(n) => JsonValue::Number(n),
+            SpannedValue::BigNumber(n) => JsonValue::BigNumber(n),
+            SpannedValue::NonFinite(n) => JsonValue::NonFinite(n),
+            SpannedValue::Boolean(b) => JsonValue::Boolean(b),
+            SpannedValue::Null => JsonValue::Null,
+        }
+    }
+}
+
+impl From<SpannedJsonValue> for JsonValue {
+    fn from(value: SpannedJsonValue) -> Self {
+        value.into_value()
+    }
+}
+
+/// Parses a value like `Value`, recording the span of every node.
+struct Spanned;
+
+impl<I: Input> Parser<I> for Spanned {
+    type Output = SpannedJsonValue;
+    fn parse(
+        input: &I,
+        current: I::Position,
+        context: &ParserContext,
+    ) -> ResultOf<I, Self::Output> {
+        let (value, next) = match input.next(current) {
+            Ok(('{', next)) => {
+                let context = &context.nest(input, current)?;
+                let (members, next) = <SpannedMembers as Parser<I>>::parse(input, next, context)?;
+                (SpannedValue::Object(members), next)
+            }
+            Ok(('[', next)) => {
+                let context = &context.nest(input, current)?;
+                let (items, next) = <SpannedItems as Parser<I>>::parse(input, next, context)?;
+                (SpannedValue::Array(items), next)
+            }
+            _ => {
+                let (value, next) = <Value as Parser<I>>::parse(input, current, context)?;
+                let value = match value {
+                    JsonValue::String(s) => SpannedValue::String(s),
+                    JsonValue::Utf16String(s) => SpannedValue::Utf16String(s),
+                    JsonValue::Number(n) => SpannedValue::Number(n),
+                    JsonValue::BigNumber(n) => SpannedValue::BigNumber(n),
+                    JsonValue::NonFinite(n) => SpannedValue::NonFinite(n),
+                    JsonValue::Boolean(b) => SpannedValue::Boolean(b),
+                    JsonValue::Null => SpannedValue::Null,
+                    JsonValue::Object(_) | JsonValue::Array(_) => {
+                        unreachable!("arrays and objects are parsed above")
+                    }
+                };
+                (value, next)
+            }
+        };
+        let span = current.index() as usize..next.index() as usize;
+        Ok((SpannedJsonValue { span, value }, next))
+    }
+}
+
+/// Parses the items of an array after its `[`.
+struct SpannedItems;
+
+impl<I: Input> Parser<I> for SpannedItems {
+    type Output = Vec<SpannedJsonValue>;
+    fn parse(
+        input: &I,
+        current: I::Position,
+        context: &ParserContext,
+    ) -> ResultOf<I, Self::Output> {
+        let (_, mut next) = <Whitespace as Parser<I>>::parse(input, current, context)?;
+        let mut output = Vec::new();
+        if let Ok((_, end)) = <CloseSquareBracketChar as Parser<I>>::parse(input, next, context) {
+            return Ok((output, end));
+        }
+        loop {
+            let (_, start) = <Whitespace as Parser<I>>::parse(input, next, context)?;
+            let (item, after) = <Spanned as Parser<I>>::parse(input, start, context)?;
+            let (_, after) = <Whitespace as Parser<I>>::parse(input, after, context)?;
+            output.push(item);
+            match input.next(after) {
+                Ok((',', after)) => next = after,
+                Ok((']', end)) => return Ok((output, end)),
+                _ => return Err(input.error_at(after, "Array")),
+            }
+        }
+    }
+}
+
+/// Parses the members of an object after its `{`.
+struct SpannedMembers;
+
+impl<I: Input> Parser<I> for SpannedMembers {
+    type Output = Vec<(Vec<char>, SpannedJsonValue)>;
+    fn parse(
+        input: &I,
+        current: I::Position,
+        context: &ParserContext,
+    ) -> ResultOf<I, Self::Output> {
+        let (_, mut next) = <Whitespace as Parser<I>>::parse(input, current, context)?;
+        let mut output = Vec::new();
+        if let Ok((_, end)) = <CloseCurlyBracketChar as Parser<I>>::parse(input, next, context) {
+            return Ok((output, end));
+        }
+        loop {
+            let (_, start) = <Whitespace as Parser<I>>::parse(input, next, context)?;
+            let (key, after) = <String as Parser<I>>::parse(input, start, context)?;
+            let (_, after) = <Whitespace as Parser<I>>::parse(input, after, context)?;
+            let after = match input.next(after) {
+                Ok((':', after)) => after,
+                _ => return Err(input.error_at(after, "Character")),
+            };
+            let (_, after) = <Whitespace as Parser<I>>::parse(input, after, context)?;
+            let (value, after) = <Spanned as Parser<I>>::parse(input, after, context)?;
+            let (_, after) = <Whitespace as Parser<I>>::parse(input, after, context)?;
+            output.push((key, value));
+            match input.next(after) {
+                Ok((',', after)) => next = after,
+                Ok(('}', end)) => return Ok((output, end)),
+                _ => return Err(input.error_at(after, "Object")),
+            }
+        }
+    }
+}
+
+/// Parses JSON like `parse_json`, but keeps the byte range of every value, so that later
+/// checks can point at the offending text, for example "/config/timeout at line 12 must be a
+/// number".
+pub fn parse_json_spanned(input: &str) -> Result<SpannedJsonValue, ParseError> {
+    let options = ParserOptions::default();
+    let state = ParserState::default();
+    let context = ParserContext::new(&options, &state);
+    let (_, next) = <Whitespace as Parser<&str>>::parse(&input, skip_bom(input), &context)
+        .map_err(|e| ParseError::from_simple(input, e))?;
+    let (value, next) = <Spanned as Parser<&str>>::parse(&input, next, &context)
+        .and_then(|(value, next)| {
+            <Whitespace as Parser<&str>>::parse(&input, next, &context)
+                .map(|(_, next)| (value, next))
+        })
+        .map_err(|e| ParseError::from_simple(input, e))?;
+    if input.is_end(next) {
+        Ok(value)
+    } else {
+        Err(ParseError::new(
+            input,
+            next,
+            ParseErrorKind::TrailingCharacters,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_parser::parse_json;
+
+    #[test]
+    fn spanned_values_locate_nodes() {
+        let input =
+            "{\n  \"config\": {\n    \"timeout\": \"30s\",\n    \"tags\": [1, true]\n  }\n}\n";
+        let value = parse_json_spanned(input).unwrap();
+        assert_eq!(value.span, 0..input.len() - 1);
+
+        let timeout = value.pointer("/config/timeout").unwrap();
+        assert_eq!(&input[timeout.span.clone()], "\"30s\"");
+        assert_eq!(timeout.line_column(input), (3, 16));
+        assert_eq!(timeout.value, SpannedValue::String(vec!['3', '0', 's']));
+
+        let tags = value.pointer("/config/tags").unwrap();
+        assert_eq!(&input[tags.span.clone()], "[1, true]");
+        let flag = value.pointer("/config/tags/1").unwrap();
+        assert_eq!(&input[flag.span.clone()], "true");
+        assert_eq!(value.pointer("/config/missing"), None);
+
+        assert_eq!(value.into_value(), parse_json(input).unwrap());
+
+        assert_eq!(
+            parse_json_spanned("[1, 2,]").unwrap_err().kind,
+            ParseErrorKind::UnexpectedToken
+        );
+        assert_eq!(
+            parse_json_spanned("[1] 2").unwrap_err().kind,
+            ParseErrorKind::TrailingCharacters
+        );
+        let deep = "[".repeat(200);
+        assert_eq!(
+            parse_json_spanned(&deep).unwrap_err().kind,
+            ParseErrorKind::DepthLimitExceeded
+        );
+    }
+    #[test]
+    fn errors_match_parse_json() {
+        for input in [
+            "",
+            "[1 2]",
+            "[1,]",
+            "{\"a\" 1}",
+            "{\"a\": 1,}",
+            "{1: 2}",
+            "[1}",
+            "[\"é\\x\"]",
+            "[01]",
+            "[\"abc",
+            "{\"a\": [1, {\"b\": tru}]}",
+            "\u{FEFF}[1] x",
+        ] {
+            assert_eq!(
+                parse_json_spanned(input).unwrap_err(),
+                parse_json(input).unwrap_err(),
+                "{}",
+                input
+            );
+        }
+        let depth = |n| "[".repeat(n) + &"]".repeat(n);
+        assert!(parse_json_spanned(&depth(100)).is_ok());
+        assert_eq!(parse_json_spanned(&depth(101)).unwrap_err().offset, 100);
+    }
+
+    #[test]
+    fn spans_and_lookups_handle_edge_cases() {
+        let input = "\u{FEFF}{\"é\": [\"ü\", 1], \"a\": 1, \"a\": 2}";
+        let value = parse_json_spanned(input).unwrap();
+        assert_eq!(value.span.start, 3);
+        let number = value.pointer("/é/1").unwrap();
+        assert_eq!(&input[number.span.clone()], "1");
+        assert_eq!(number.line_column(input), (1, 14));
+        assert_eq!(&input[value.pointer("/a").unwrap().span.clone()], "2");
+        for path in ["a", "/é/01", "/é/x", "/é/2", "/é/0/x", "/missing"] {
+            assert_eq!(value.pointer(path), None, "{}", path);
+        }
+    }
+}
@@ -0,0 +1,407 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::json::JsonValue;
+use crate::json_parser::{parse_json, skip_bom, Element, Member, Whitespace};
+use crate::parse_error::{ParseError, ParseErrorKind};
+use crate::traits::Serialize;
+use core::fmt;
+use core::marker::PhantomData;
+use lite_parser::{
+    impls::SimplePosition,
+    parser::{Parser, ParserContext, ParserOptions, ParserState},
+    traits::Input,
+};
+
+/// The record separator that starts each record of a JSON text sequence.
+const RS: char = '\x1e';
+
+/// The error for a record of a multi-record input, with the 1-based line it starts on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseErrorWithLine {
+    pub line: usize,
+    pub error: ParseError,
+}
+
+impl fmt::Display for ParseErrorWithLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid JSON on line {}: {}", self.line, self.error)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseErrorWithLine {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Parses newline-delimited JSON (NDJSON or JSON Lines), one record per line.
+///
+/// Records are parsed lazily as the iterator advances, so a large feed can be consumed without
+/// holding every value at once. Blank lines are skipped and a bad record does not stop the
+/// iteration, so callers can decide whether to skip it or bail out.
+pub fn parse_ndjson(
+    input: &str,
+) -> impl Iterator<Item = Result<JsonValue, ParseErrorWithLine>> + '_ {
+    input
+        .split('\n')
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            parse_json(line).map_err(|error| ParseErrorWithLine { line: i + 1, error })
+        })
+}
+
+/// Parses a JSON text sequence (RFC 7464, `application/json-seq`), where each record starts
+/// with an ASCII record separator.
+///
+/// A corrupt record is reported as an error and parsing resumes at the next separator. Empty
+/// records are skipped, and a top-level number, `true`, `false` or `null` not followed by
+/// whitespace is treated as truncated, as the RFC requires. Errors report the line the record
+/// starts on.
+pub fn parse_json_seq(
+    input: &str,
+) -> impl Iterator<Item = Result<JsonValue, ParseErrorWithLine>> + '_ {
+    let mut line = 1;
+    input.split(RS).enumerate().filter_map(move |(i, record)| {
+        let start = line;
+        line += record.matches('\n').count();
+        // Text before the first separator is not a record.
+        if i == 0 || record.trim().is_empty() {
+            return None;
+        }
+        let result = parse_json(record).and_then(|value| match value {
+            JsonValue::Number(_)
+            | JsonValue::BigNumber(_)
+            | JsonValue::Boolean(_)
+            | JsonValue::Null
+                if !record.ends_with(|c: char| c.is_ascii_whitespace()) =>
+            {
+                Err(ParseError::new(
+                    record,
+                    SimplePosition::default(),
+                    ParseErrorKind::UnexpectedEof,
+                ))
+            }
+            value => Ok(value),
+        });
+        Some(result.map_err(|error| ParseErrorWithLine { line: start, error }))
+    })
+}
+
+/// Writes values as a JSON text sequence, each record starting with a record separator and
+/// ending with a newline.
+pub fn to_json_seq<'a>(values: impl IntoIterator<Item = &'a JsonValue>) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    for value in values {
+        buffer.push(RS as u8);
+        value.serialize_to(&mut buffer, 0, 0);
+        buffer.push(b'\n');
+    }
+    buffer
+}
+
+/// Parses back-to-back JSON values, such as `{"a":1}{"b":2}` or `1 2`, as emitted by some
+/// streaming APIs.
+///
+/// Values may be separated by whitespace, which is required only between two numbers. The
+/// iteration ends after the first error, since the end of a malformed value cannot be found
+/// reliably. Error positions are relative to the whole input.
+pub fn parse_concatenated(input: &str) -> impl Iterator<Item = Result<JsonValue, ParseError>> + '_ {
+    let options = ParserOptions::default();
+    let state = ParserState::default();
+    let mut next = Some(skip_bom(input));
+    core::iter::from_fn(move || {
+        let context = ParserContext::new(&options, &state);
+        let current = match <Whitespace as Parser<&str>>::parse(&input, next.take()?, &context) {
+            Ok((_, current)) => current,
+            Err(err) => return Some(Err(ParseError::from_simple(input, err))),
+        };
+        if input.is_end(current) {
+            return None;
+        }
+        Some(
+            <Element as Parser<&str>>::parse(&input, current, &context)
+                .map(|(value, pos)| {
+                    next = Some(pos);
+                    value
+                })
+                .map_err(|err| ParseError::from_simple(input, err)),
+        )
+    })
+}
+
+/// How far `Entries` has got.
+enum State {
+    Start,
+    /// After an entry, where a comma or the closing bracket comes next.
+    Next(SimplePosition),
+    Done,
+}
+
+#[derive(Clone, Copy)]
+enum Container {
+    Array,
+    Object,
+}
+
+/// Parses the items of a top-level array, or the members of a top-level object, with `P` as
+/// the iterator advances.
+struct Entries<'a, P> {
+    input: &'a str,
+    options: ParserOptions,
+    parser_state: ParserState,
+    container: Container,
+    count: usize,
+    state: State,
+    entry: PhantomData<P>,
+}
+
+impl<'a, P: Parser<&'a str>> Entries<'a, P> {
+    fn new(input: &'a str, container: Container) -> Self {
+        Entries {
+            input,
+            options: ParserOptions::default(),
+            parser_state: ParserState::default(),
+            container,
+            count: 0,
+            state: State::Start,
+            entry: PhantomData,
+        }
+    }
+
+    fn advance(&mut self) -> Result<Option<P::Output>, ParseError> {
+        let input = self.input;
+        let error = |err| ParseError::from_simple(input, err);
+        let (open, close, reason, limit, limit_kind) = match self.container {
+            Container::Array => (
+                '[',
+                ']',
+                "Array",
+                self.options.max_array_length,
+                ParseErrorKind::TooManyArrayItems,
+            ),
+            Container::Object => (
+                '{',
+                '}',
+                "Object",
+                self.options.max_object_members,
+                ParseErrorKind::TooManyObjectMembers,
+            ),
+        };
+        let top = ParserContext::new(&self.options, &self.parser_state);
+        let next = match self.state {
+            State::Start => {
+                let start = skip_bom(input);
+                let (_, start) =
+                    <Whitespace as Parser<&str>>::parse(&input, start, &top).map_err(error)?;
+                top.nest(&input, start).map_err(error)?;
+                let next = match input.next(start) {
+                    Ok((c, next)) if c == open => next,
+                    _ => return Err(error(input.error_at(start, reason))),
+                };
+                let (_, next) =
+                    <Whitespace as Parser<&str>>::parse(&input, next, &top).map_err(error)?;
+                match input.next(next) {
+                    Ok((c, end)) if c == close => return self.end(end),
+                    _ => next,
+                }
+            }
+            State::Next(after) => match input.next(after) {
+                Ok((',', next)) => next,
+                Ok((c, end)) if c == close => return self.end(end),
+                _ => return Err(error(input.error_at(after, reason))),
+            },
+            State::Done => return Ok(None),
+        };
+        // The entries are inside the array or object, whose nesting was checked at the start.
+        let context = top.nest(&input, next).map_err(error)?;
+        if Some(self.count) == limit {
+            let (_, start) =
+                <Whitespace as Parser<&str>>::parse(&input, next, &context).map_err(error)?;
+            return Err(error(input.error_with_kind(start, limit_kind)));
+        }
+        let (entry, after) = P::parse(&input, next, &context).map_err(error)?;
+        self.count += 1;
+        self.state = State::Next(after);
+        Ok(Some(entry))
+    }
+
+    /// Checks that nothing but whitespace follows the closing bracket.
+    fn end(&mut self, end: SimplePosition) -> Result<Option<P::Output>, ParseError> {
+        let input = self.input;
+        self.state = State::Done;
+        let context = ParserContext::new(&self.options, &self.parser_state);
+        let end = <Whitespace as Parser<&str>>::parse(&input, end, &context)
+            .map_err(|err| ParseError::from_simple(input, err))?
+            .1;
+        if input.is_end(end) {
+            Ok(None)
+        } else {
+            Err(ParseError::new(
+                input,
+                end,
+                ParseErrorKind::TrailingCharacters,
+            ))
+        }
+    }
+}
+
+impl<'a, P: Parser<&'a str>> Iterator for Entries<'a, P> {
+    type Item = Result<P::Output, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.advance();
+        if result.is_err() {
+            self.state = State::Done;
+        }
+        result.transpose()
+    }
+}
+
+/// Parses the items of a top-level array one at a time as the iterator advances, so only one
+/// item is held in memory however long the array is.
+///
+/// The iteration ends after the first error, which may come after every item has been yielded,
+/// for example for trailing characters. Error positions are relative to the whole input.
+pub fn parse_array_iter(input: &str) -> impl Iterator<Item = Result<JsonValue, ParseError>> + '_ {
+    Entries::<Element>::new(input, Container::Array)
+}
+
+/// Like `parse_array_iter`, but for the members of a top-level object, yielded as key and value
+/// pairs. A caller looking for a few entries of a large object can stop once it has them.
+pub fn parse_object_iter(
+    input: &str,
+) -> impl Iterator<Item = Result<(Vec<char>, JsonValue), ParseError>> + '_ {
+    Entries::<Member>::new(input, Container::Object)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ndjson_parses_each_line() {
+        let input = "{\"a\":1}\r\n\n  [2, 3]\n{oops}\n\"x\"\n";
+        let records: Vec<_> = parse_ndjson(input).collect();
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0], Ok(parse_json(r#"{"a":1}"#).unwrap()));
+        assert_eq!(records[1], Ok(parse_json("[2,3]").unwrap()));
+        assert_eq!(records[2].as_ref().unwrap_err().line, 4);
+        assert_eq!(records[3], Ok(JsonValue::String(vec!['x'])));
+        assert_eq!(parse_ndjson("").count(), 0);
+    }
+
+    #[test]
+    fn json_seq_resynchronizes() {
+        let input = "ignored\x1e{\"a\":1}\n\x1e{\"b\":\n\x1e[true]\n\x1e\n\x1e12\x1e\"x\"\n";
+        let records: Vec<_> = parse_json_seq(input).collect();
+        assert_eq!(records.len(), 5);
+        assert_eq!(records[0], Ok(parse_json(r#"{"a":1}"#).unwrap()));
+        assert_eq!(records[1].as_ref().unwrap_err().line, 2);
+        assert_eq!(records[2], Ok(parse_json("[true]").unwrap()));
+        assert_eq!(
+            records[3].as_ref().unwrap_err().error.kind,
+            ParseErrorKind::UnexpectedEof
+        );
+        assert_eq!(records[4], Ok(JsonValue::String(vec!['x'])));
+
+        let values = [JsonValue::Null, parse_json("[1,2]").unwrap()];
+        let bytes = to_json_seq(&values);
+        assert_eq!(bytes, b"\x1enull\n\x1e[1,2]\n");
+        let text = String::from_utf8(bytes).unwrap();
+        let parsed: Vec<_> = parse_json_seq(&text).map(Result::unwrap).collect();
+        assert_eq!(parsed, values);
+    }
+
+    #[test]
+    fn concatenated_values_parse() {
+        let values: Vec<_> = parse_concatenated(r#" {"a":1}{"b":2}[3]"x"1 2 true"#)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(values.len(), 7);
+        assert_eq!(values[1], parse_json(r#"{"b":2}"#).unwrap());
+        assert_eq!(values[6], JsonValue::Boolean(true));
+        assert_eq!(parse_concatenated("  ").count(), 0);
+
+        let results: Vec<_> = parse_concatenated("[1]{oops}[2]").collect();
+        assert_eq!(results.len(), 2);
+        assert!(results[1].is_err());
+    }
+
+    #[test]
+    fn array_items_parse_lazily() {
+        let input = "\u{FEFF} [ {\"a\": [1]}, \"x\",\n 2.5, [] ] ";
+        let items: Vec<_> = parse_array_iter(input).map(Result::unwrap).collect();
+        match parse_json(input).unwrap() {
+            JsonValue::Array(expected) => assert_eq!(items, expected),
+            _ => unreachable!(),
+        }
+        assert_eq!(parse_array_iter(" [ ] ").count(), 0);
+
+        let one = parse_json("1").unwrap();
+        let kinds = |input| -> Vec<_> {
+            parse_array_iter(input)
+                .map(|item| item.map_err(|e| (e.kind, e.offset)))
+                .collect()
+        };
+        assert_eq!(
+            kinds("[1, oops, 2]"),
+            [Ok(one.clone()), Err((ParseErrorKind::UnexpectedToken, 4))]
+        );
+        assert_eq!(
+            kinds("[1] 2"),
+            [
+                Ok(one.clone()),
+                Err((ParseErrorKind::TrailingCharacters, 4))
+            ]
+        );
+        assert_eq!(
+            kinds("[1 2]"),
+            [Ok(one.clone()), Err((ParseErrorKind::UnexpectedToken, 3))]
+        );
+        assert_eq!(
+            kinds("[1,"),
+            [Ok(one.clone()), Err((ParseErrorKind::UnexpectedEof, 3))]
+        );
+        assert_eq!(kinds("{}"), [Err((ParseErrorKind::UnexpectedToken, 0))]);
+        assert_eq!(kinds(""), [Err((ParseErrorKind::UnexpectedEof, 0))]);
+    }
+
+    #[test]
+    fn object_members_parse_lazily() {
+        let input = r#" {"a": [1, {"b": null}], "": "x" , "a": true} "#;
+        let members: Vec<_> = parse_object_iter(input).map(Result::unwrap).collect();
+        match parse_json(input).unwrap() {
+            JsonValue::Object(expected) => assert_eq!(members, expected),
+            _ => unreachable!(),
+        }
+        assert_eq!(parse_object_iter("{}").count(), 0);
+
+        let found = parse_object_iter(r#"{"id1": 1, "id2": 2, "id3": oops}"#)
+            .map(Result::unwrap)
+            .find(|(key, _)| *key == ['i', 'd', '2']);
+        assert_eq!(found.map(|(_, value)| value), parse_json("2").ok());
+
+        let errors: Vec<_> = parse_object_iter(r#"{"a": 1, 2: 3}"#)
+            .filter_map(|member| member.err().map(|e| (e.kind, e.offset)))
+            .collect();
+        assert_eq!(errors, [(ParseErrorKind::UnexpectedToken, 9)]);
+        assert_eq!(
+            parse_object_iter("[]").next().unwrap().unwrap_err().kind,
+            ParseErrorKind::UnexpectedToken
+        );
+        assert_eq!(
+            parse_object_iter(r#"{"a": 1}}"#)
+                .last()
+                .unwrap()
+                .unwrap_err()
+                .kind,
+            ParseErrorKind::TrailingCharacters
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use crate::json::JsonValue;
+
+pub(crate) const MS_PER_DAY: i64 = 86_400_000;
+
+/// Converts a (year, month, day) date to days since 1970-01-01.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = year - (month <= 2) as i64;
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Reads a fixed number of ASCII digits.
+fn digits(text: &mut &[char], len: usize) -> Option<u32> {
+    if text.len() < len {
+        return None;
+    }
+    let (head, rest) = text.split_at(len);
+    *text = rest;
+    head.iter()
+        .try_fold(0, |acc, c| Some(acc * 10 + c.to_digit(10)?))
+}
+
+/// Consumes `expected` if it is the next character.
+fn expect(text: &mut &[char], expected: &[char]) -> Option<()> {
+    let (first, rest) = text.split_first()?;
+    if expected.contains(first) {
+        *text = rest;
+        Some(())
+    } else {
+        None
+    }
+}
+
+/// Parses an RFC 3339 date-time such as `2024-05-01T12:30:00.5+02:00` into milliseconds
+/// since the Unix epoch.
+///
+/// The separator may be `T`, `t` or a space, digits past milliseconds are truncated and a
+/// leap second counts as the first second of the next minute.
+pub(crate) fn parse_rfc3339(mut text: &[char]) -> Option<i64> {
+    let text = &mut text;
+    let year = digits(text, 4)? as i64;
+    expect(text, &['-'])?;
+    let month = digits(text, 2)?;
+    expect(text, &['-'])?;
+    let day = digits(text, 2)?;
+    expect(text, &['T', 't', ' '])?;
+    let hour = digits(text, 2)? as i64;
+    expect(text, &[':'])?;
+    let minute = digits(text, 2)? as i64;
+    expect(text, &[':'])?;
+    let second = digits(text, 2)? as i64;
+    if !(1..=12).contains(&month)
+        || !(1..=days_in_month(year, month)).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+
+    let mut millis = 0;
+    if expect(text, &['.']).is_some() {
+        let len = text.iter().take_while(|c| c.is_ascii_digit()).count();
+        if len == 0 {
+            return None;
+        }
+        let fraction = text[..len].iter().chain(core::iter::repeat(&'0')).take(3);
+        millis = fraction.fold(0, |acc, c| acc * 10 + c.to_digit(10).unwrap() as i64);
+        *text = &text[len..];
+    }
+
+    let offset = match text.split_first()? {
+        ('Z' | 'z', []) => 0,
+        (sign @ ('+' | '-'), mut rest) => {
+            let rest = &mut rest;
+            let hours = digits(rest, 2)? as i64;
+            expect(rest, &[':'])?;
+            let minutes = digits(rest, 2)? as i64;
+            if !rest.is_empty() || hours > 23 || minutes > 59 {
+                return None;
+            }
+            let offset = (hours * 60 + minutes) * 60_000;
+            if *sign == '+' {
+                offset
+            } else {
+                -offset
+            }
+        }
+        _ => return None,
+    };
+
+    let days = days_from_civil(year, month, day);
+    Some(days * MS_PER_DAY + ((hour * 60 + minute) * 60 + second) * 1000 + millis - offset)
+}
+
+impl JsonValue {
+    /// Parses an RFC 3339 timestamp string, such as `"2024-05-01T12:30:00Z"`, into seconds
+    /// since the Unix epoch. Fractional seconds are rounded down. Returns None for other
+    /// values or malformed timestamps.
+    pub fn as_timestamp(&self) -> Option<i64> {
+        self.as_timestamp_millis().map(|ms| ms.div_euclid(1000))
+    }
+
+    /// Like `as_timestamp`, but returns milliseconds since the Unix epoch.
+    pub fn as_timestamp_millis(&self) -> Option<i64> {
+        parse_rfc3339(self.as_string()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json_parser::parse_json;
+
+    #[test]
+    fn timestamps_parse() {
+        let millis = |s: &str| {
+            parse_json(&format!("\"{}\"", s))
+                .unwrap()
+                .as_timestamp_millis()
+        };
+        assert_eq!(millis("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(millis("2024-05-01T12:30:00Z"), Some(1_714_566_600_000));
+        assert_eq!(
+            millis("2024-05-01t14:30:00.1234+02:00"),
+            Some(1_714_566_600_123)
+        );
+        assert_eq!(
+            millis("2024-05-01 07:00:00.5-05:30"),
+            Some(1_714_566_600_500)
+        );
+        assert_eq!(millis("1969-12-31T23:59:59.999Z"), Some(-1));
+        assert_eq!(millis("2016-12-31T23:59:60Z"), Some(1_483_228_800_000));
+        assert_eq!(millis("2024-02-29T00:00:00Z"), Some(1_709_164_800_000));
+        for bad in [
+            "2023-02-29T00:00:00Z",
+            "2024-05-01T12:30:00",
+            "2024-05-01T24:00:00Z",
+            "2024-05-01T12:30:00.Z",
+            "2024-5-01T12:30:00Z",
+            "2024-05-01T12:30:00+0200",
+            "2024-05-01",
+        ] {
+            assert_eq!(millis(bad), None, "{}", bad);
+        }
+
+        let value = parse_json(r#""1969-12-31T23:59:59.5Z""#).unwrap();
+        assert_eq!(value.as_timestamp(), Some(-1));
+        assert_eq!(parse_json("0").unwrap().as_timestamp(), None);
+    }
+
+    #[test]
+    fn out_of_range_fields_are_rejected() {
+        let millis = |s: &str| {
+            parse_json(&format!("\"{}\"", s))
+                .unwrap()
+                .as_timestamp_millis()
+        };
+        // February 29 exists only in leap years, which skip centuries not divisible by 400.
+        assert_eq!(millis("2000-02-29T00:00:00Z"), Some(951_782_400_000));
+        for bad in [
+            "1900-02-29T00:00:00Z",
+            "2100-02-29T00:00:00Z",
+            "2024-02-30T00:00:00Z",
+        ] {
+            assert_eq!(millis(bad), None, "{}", bad);
+        }
+
+        // Only the second may go one past its usual range, for a leap second.
+        assert_eq!(millis("1970-01-01T00:00:60Z"), Some(60_000));
+        for bad in [
+            "2024-05-01T12:30:61Z",
+            "2024-05-01T12:60:00Z",
+            "2024-05-01T25:00:00Z",
+            "2024-00-01T00:00:00Z",
+            "2024-13-01T00:00:00Z",
+            "2024-05-00T00:00:00Z",
+            "2024-04-31T00:00:00Z",
+        ] {
+            assert_eq!(millis(bad), None, "{}", bad);
+        }
+
+        assert_eq!(millis("1970-01-01T23:59:00+23:59"), Some(0));
+        assert_eq!(millis("1970-01-01T00:00:00-00:00"), Some(0));
+        for bad in [
+            "2024-05-01T12:30:00+24:00",
+            "2024-05-01T12:30:00+02:60",
+            "2024-05-01T12:30:00+2:00",
+            "2024-05-01T12:30:00+02",
+            "2024-05-01T12:30:00+02:00Z",
+            "2024-05-01T12:30:00Z ",
+            "2024-05-01T12:30:00UTC",
+            "2024-05-01T12:30:00.5.5Z",
+        ] {
+            assert_eq!(millis(bad), None, "{}", bad);
+        }
+    }
+}
@@ -4,6 +4,8 @@ extern crate alloc;
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 
+use crate::json::{JsonObject, JsonValue};
+
 pub trait Serialize {
     fn serialize(&self) -> Vec<u8> {
         let mut res = Vec::new();
@@ -17,3 +19,166 @@ pub trait Serialize {
     }
     fn serialize_to(&self, buffer: &mut Vec<u8>, indent: u32, level: u32);
 }
+
+/// The step at which a typed lookup on an object failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GetError {
+    /// The value being looked up in is not an object.
+    NotAnObject,
+    /// The object has no member with the requested key.
+    MissingKey,
+    /// The member exists but holds a value of a different type.
+    WrongType,
+    /// The member is a number that is not an integer or does not fit the requested type.
+    OutOfRange,
+}
+
+/// Typed member lookups that combine finding a key and converting its value.
+pub trait JsonObjectExt {
+    /// Returns the value of the member named `key`. When a key appears more than once, the last
+    /// member wins.
+    fn member(&self, key: &str) -> Result<&JsonValue, GetError>;
+
+    /// Returns the member `key` as a char slice.
+    fn get_str(&self, key: &str) -> Result<&[char], GetError> {
+        self.member(key)?.as_string().ok_or(GetError::WrongType)
+    }
+
+    /// Returns the member `key` as an unsigned integer.
+    fn get_u64(&self, key: &str) -> Result<u64, GetError> {
+        match self.member(key)? {
+            JsonValue::Number(n) => n.to_u64().ok_or(GetError::OutOfRange),
+            JsonValue::BigNumber(_) => Err(GetError::OutOfRange),
+            _ => Err(GetError::WrongType),
+        }
+    }
+
+    /// Returns the member `key` as a signed integer.
+    fn get_i64(&self, key: &str) -> Result<i64, GetError> {
+        match self.member(key)? {
+            JsonValue::Number(n) => n.to_i64().ok_or(GetError::OutOfRange),
+            JsonValue::BigNumber(_) => Err(GetError::OutOfRange),
+            _ => Err(GetError::WrongType),
+        }
+    }
+
+    /// Returns the member `key` as a boolean.
+    fn get_bool(&self, key: &str) -> Result<bool, GetError> {
+        self.member(key)?
+            .as_bool()
+            .copied()
+            .ok_or(GetError::WrongType)
+    }
+
+    /// Returns the member `key` as an array slice.
+    fn get_array(&self, key: &str) -> Result<&[JsonValue], GetError> {
+        self.member(key)?.as_array().ok_or(GetError::WrongType)
+    }
+
+    /// Returns the member `key` as an object's key-value slice.
+    fn get_object(&self, key: &str) -> Result<&[(Vec<char>, JsonValue)], GetError> {
+        self.member(key)?.as_object().ok_or(GetError::WrongType)
+    }
+}
+
+impl JsonObjectExt for [(Vec<char>, JsonValue)] {
+    fn member(&self, key: &str) -> Result<&JsonValue, GetError> {
+        self.iter()
+            .rev()
+            .find(|(k, _)| k.iter().copied().eq(key.chars()))
+            .map(|(_, v)| v)
+            .ok_or(GetError::MissingKey)
+    }
+}
+
+impl JsonObjectExt for JsonObject {
+    fn member(&self, key: &str) -> Result<&JsonValue, GetError> {
+        self[..].member(key)
+    }
+}
+
+impl JsonObjectExt for JsonValue {
+    fn member(&self, key: &str) -> Result<&JsonValue, GetError> {
+        self.as_object().ok_or(GetError::NotAnObject)?.member(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_parser::{parse_json, parse_json_with_options, NumberOverflow, ParserOptions};
+
+    #[test]
+    fn typed_getters_work() {
+        let value =
+            parse_json(r#"{"s":"hi","u":7,"i":-7,"f":1.5,"b":true,"a":[1],"o":{"x":null}}"#)
+                .unwrap();
+
+        assert_eq!(value.get_str("s"), Ok(&['h', 'i'][..]));
+        assert_eq!(value.get_u64("u"), Ok(7));
+        assert_eq!(value.get_i64("i"), Ok(-7));
+        assert_eq!(value.get_bool("b"), Ok(true));
+        assert_eq!(value.get_array("a").map(|a| a.len()), Ok(1));
+        assert_eq!(
+            value.get_object("o").unwrap().get_bool("x"),
+            Err(GetError::WrongType)
+        );
+
+        assert_eq!(value.get_str("missing"), Err(GetError::MissingKey));
+        assert_eq!(value.get_u64("s"), Err(GetError::WrongType));
+        assert_eq!(value.get_u64("i"), Err(GetError::OutOfRange));
+        assert_eq!(value.get_i64("f"), Err(GetError::OutOfRange));
+        assert_eq!(JsonValue::Null.get_bool("b"), Err(GetError::NotAnObject));
+
+        let obj = value.to_object().unwrap();
+        assert_eq!(obj.get_u64("u"), Ok(7));
+    }
+
+    #[test]
+    fn last_duplicate_member_wins() {
+        let value = parse_json(r#"{"a":1,"b":"x","a":2}"#).unwrap();
+        assert_eq!(value.get_u64("a"), Ok(2));
+        assert_eq!(value.member("a"), Ok(&parse_json("2").unwrap()));
+        assert_eq!(value.get_str("b"), Ok(&['x'][..]));
+    }
+    #[test]
+    fn getters_report_each_failure() {
+        let value = parse_json(
+            r#"{"max":18446744073709551615,"over":18446744073709551616,"min":-9223372036854775808,"under":-9223372036854775809,"neg":-1,"half":0.5,"n":null,"a":[],"o":{},"":"empty"}"#,
+        )
+        .unwrap();
+        assert_eq!(value.get_u64("max"), Ok(u64::MAX));
+        assert_eq!(value.get_u64("over"), Err(GetError::OutOfRange));
+        assert_eq!(value.get_i64("min"), Ok(i64::MIN));
+        assert_eq!(value.get_i64("under"), Err(GetError::OutOfRange));
+        assert_eq!(value.get_u64("neg"), Err(GetError::OutOfRange));
+        assert_eq!(value.get_u64("half"), Err(GetError::OutOfRange));
+        assert_eq!(value.get_i64("n"), Err(GetError::WrongType));
+        assert_eq!(value.get_bool("n"), Err(GetError::WrongType));
+        assert_eq!(value.get_str("max"), Err(GetError::WrongType));
+        assert_eq!(value.get_array("o"), Err(GetError::WrongType));
+        assert_eq!(value.get_object("a"), Err(GetError::WrongType));
+        assert_eq!(value.get_str(""), Ok(&['e', 'm', 'p', 't', 'y'][..]));
+        assert_eq!(value.get_str("MAX"), Err(GetError::MissingKey));
+
+        let options = ParserOptions::default().number_overflow(NumberOverflow::Raw);
+        let big = parse_json_with_options(
+            r#"{"big":100000000000000000000000000000000000000000000000000}"#,
+            options,
+        )
+        .unwrap();
+        assert!(big.member("big").unwrap().as_big_number().is_some());
+        assert_eq!(big.get_u64("big"), Err(GetError::OutOfRange));
+        assert_eq!(big.get_i64("big"), Err(GetError::OutOfRange));
+
+        for not_object in ["[]", r#""a""#, "1", "null"] {
+            let value = parse_json(not_object).unwrap();
+            assert_eq!(
+                value.get_str("a"),
+                Err(GetError::NotAnObject),
+                "{}",
+                not_object
+            );
+        }
+    }
+}
@@ -0,0 +1,391 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, vec, vec::Vec};
+
+use crate::json::{utf16_lossy, JsonValue, NumberValue};
+use crate::json_parser::{parse_json_with_options, NumberOverflow, ParserOptions};
+use core::convert::{TryFrom, TryInto};
+
+/// Nesting depth at which `from_ubjson` gives up, matching the parser's default limit.
+const MAX_DEPTH: usize = 100;
+
+/// The error returned by `from_ubjson`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UbjsonError {
+    /// The input ends in the middle of a value.
+    UnexpectedEnd,
+    /// The input continues after the first value.
+    TrailingBytes,
+    /// A string or char is not valid UTF-8.
+    InvalidUtf8,
+    /// A length or count is negative.
+    InvalidLength,
+    /// A high-precision number is not a valid JSON number.
+    InvalidNumber,
+    /// The byte is not a valid type marker at this position. The marker is included.
+    InvalidMarker(u8),
+    /// Arrays and objects are nested too deeply.
+    TooDeep,
+}
+
+fn write_integer(buffer: &mut Vec<u8>, val: i64) {
+    if let Ok(val) = u8::try_from(val) {
+        buffer.extend_from_slice(&[b'U', val]);
+    } else if let Ok(val) = i8::try_from(val) {
+        buffer.extend_from_slice(&[b'i', val as u8]);
+    } else if let Ok(val) = i16::try_from(val) {
+        buffer.push(b'I');
+        buffer.extend_from_slice(&val.to_be_bytes());
+    } else if let Ok(val) = i32::try_from(val) {
+        buffer.push(b'l');
+        buffer.extend_from_slice(&val.to_be_bytes());
+    } else {
+        buffer.push(b'L');
+        buffer.extend_from_slice(&val.to_be_bytes());
+    }
+}
+
+/// Writes a string without its `S` marker, as used for object keys.
+fn write_str(buffer: &mut Vec<u8>, s: &[char]) {
+    let len = s.iter().map(|c| c.len_utf8()).sum::<usize>();
+    write_integer(buffer, len as i64);
+    let mut utf8 = [0; 4];
+    for c in s {
+        buffer.extend_from_slice(c.encode_utf8(&mut utf8).as_bytes());
+    }
+}
+
+fn write_value(buffer: &mut Vec<u8>, value: &JsonValue) {
+    match value {
+        JsonValue::Null => buffer.push(b'Z'),
+        JsonValue::Boolean(b) => buffer.push(if *b { b'T' } else { b'F' }),
+        JsonValue::Number(n) => match n.to_i64() {
+            // -0 is kept as a high-precision number so its sign survives.
+            Some(val) if !(n.negative && val == 0) => write_integer(buffer, val),
+            _ => {
+                buffer.push(b'H');
+                let text = format!("{}", n);
+                write_integer(buffer, text.len() as i64);
+                buffer.extend_from_slice(text.as_bytes());
+            }
+        },
+        JsonValue::BigNumber(text) => {
+            buffer.push(b'H');
+            write_integer(buffer, text.len() as i64);
+            buffer.extend_from_slice(text);
+        }
+        JsonValue::NonFinite(n) => {
+            buffer.push(b'D');
+            buffer.extend_from_slice(&n.to_f64().to_be_bytes());
+        }
+        JsonValue::String(s) => {
+            buffer.push(b'S');
+            write_str(buffer, s);
+        }
+        JsonValue::Utf16String(s) => {
+            buffer.push(b'S');
+            write_str(buffer, &utf16_lossy(s));
+        }
+        JsonValue::Array(arr) => {
+            buffer.push(b'[');
+            for item in arr {
+                write_value(buffer, item);
+            }
+            buffer.push(b']');
+        }
+        JsonValue::Object(obj) => {
+            buffer.push(b'{');
+            for (key, val) in obj {
+                write_str(buffer, key);
+                write_value(buffer, val);
+            }
+            buffer.push(b'}');
+        }
+    }
+}
+
+/// Encodes a value as UBJSON.
+///
+/// Containers are written with end markers rather than counts, so the output can be produced
+/// and consumed as a stream. Integers that fit `i64` use the smallest integer type; all other
+/// numbers are written as high-precision numbers and keep their exact value.
+pub fn to_ubjson(value: &JsonValue) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    write_value(&mut buffer, value);
+    buffer
+}
+
+struct Reader<'a> {
+    input: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], UbjsonError> {
+        if self.input.len() < len {
+            return Err(UbjsonError::UnexpectedEnd);
+        }
+        let (head, rest) = self.input.split_at(len);
+        self.input = rest;
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], UbjsonError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    /// Returns the next marker, skipping `N` no-op markers.
+    fn marker(&mut self) -> Result<u8, UbjsonError> {
+        loop {
+            match self.take_array::<1>()?[0] {
+                b'N' => continue,
+                marker => return Ok(marker),
+            }
+        }
+    }
+
+    fn peek(&mut self) -> Result<u8, UbjsonError> {
+        while self.input.first() == Some(&b'N') {
+            self.input = &self.input[1..];
+        }
+        self.input
+            .first()
+            .copied()
+            .ok_or(UbjsonError::UnexpectedEnd)
+    }
+
+    fn integer(&mut self, marker: u8) -> Result<Option<i64>, UbjsonError> {
+        Ok(Some(match marker {
+            b'i' => i8::from_be_bytes(self.take_array()?) as i64,
+            b'U' => self.take_array::<1>()?[0] as i64,
+            b'I' => i16::from_be_bytes(self.take_array()?) as i64,
+            b'l' => i32::from_be_bytes(self.take_array()?) as i64,
+            b'L' => i64::from_be_bytes(self.take_array()?),
+            _ => return Ok(None),
+        }))
+    }
+
+    fn len(&mut self) -> Result<usize, UbjsonError> {
+        let marker = self.marker()?;
+        match self.integer(marker)? {
+            Some(len) => len.try_into().map_err(|_| UbjsonError::InvalidLength),
+            None => Err(UbjsonError::InvalidMarker(marker)),
+        }
+    }
+
+    fn string(&mut self) -> Result<Vec<char>, UbjsonError> {
+        let len = self.len()?;
+        let bytes = self.take(len)?;
+        core::str::from_utf8(bytes)
+            .map(|s| s.chars().collect())
+            .map_err(|_| UbjsonError::InvalidUtf8)
+    }
+
+    /// Reads the optional `$` type and `#` count of a container.
+    ///
+    /// Types without a payload (`Z`, `T` and `F`) are rejected, since their count alone could
+    /// make a few bytes decode to any number of items.
+    fn header(&mut self) -> Result<(Option<u8>, Option<usize>), UbjsonError> {
+        let ty = match self.peek()? {
+            b'$' => {
+                self.input = &self.input[1..];
+                match self.take_array::<1>()?[0] {
+                    ty @ (b'Z' | b'T' | b'F') => return Err(UbjsonError::InvalidMarker(ty)),
+                    ty => Some(ty),
+                }
+            }
+            _ => None,
+        };
+        let count = match (self.peek()?, ty) {
+            (b'#', _) => {
+                self.input = &self.input[1..];
+                Some(self.len()?)
+            }
+            (_, Some(_)) => return Err(UbjsonError::InvalidMarker(b'$')),
+            _ => None,
+        };
+        Ok((ty, count))
+    }
+
+    /// Returns the marker of the next item, which is fixed in typed containers.
+    fn item_marker(&mut self, ty: Option<u8>) -> Result<u8, UbjsonError> {
+        match ty {
+            Some(ty) => Ok(ty),
+            None => self.marker(),
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> Result<JsonValue, UbjsonError> {
+        if depth > MAX_DEPTH {
+            return Err(UbjsonError::TooDeep);
+        }
+        let (ty, count) = self.header()?;
+        let mut arr = Vec::new();
+        match count {
+            Some(count) => {
+                // Capping the reservation at the remaining input guards against bogus counts.
+                arr.reserve(count.min(self.input.len()));
+                for _ in 0..count {
+                    let marker = self.item_marker(ty)?;
+                    arr.push(self.value(marker, depth)?);
+                }
+            }
+            None => loop {
+                match self.marker()? {
+                    b']' => break,
+                    marker => arr.push(self.value(marker, depth)?),
+                }
+            },
+        }
+        Ok(JsonValue::Array(arr))
+    }
+
+    fn object(&mut self, depth: usize) -> Result<JsonValue, UbjsonError> {
+        if depth > MAX_DEPTH {
+            return Err(UbjsonError::TooDeep);
+        }
+        let (ty, count) = self.header()?;
+        let mut obj = Vec::new();
+        match count {
+            Some(count) => {
+                obj.reserve(count.min(self.input.len()));
+                for _ in 0..count {
+                    let key = self.string()?;
+                    let marker = self.item_marker(ty)?;
+                    obj.push((key, self.value(marker, depth)?));
+                }
+            }
+            None => {
+                while self.peek()? != b'}' {
+                    let key = self.string()?;
+                    let marker = self.marker()?;
+                    obj.push((key, self.value(marker, depth)?));
+                }
+                self.input = &self.input[1..];
+            }
+        }
+        Ok(JsonValue::Object(obj))
+    }
+
+    fn value(&mut self, marker: u8, depth: usize) -> Result<JsonValue, UbjsonError> {
+        if let Some(val) = self.integer(marker)? {
+            return Ok(JsonValue::Number(NumberValue {
+                integer: val.unsigned_abs() as u128,
+                negative: val < 0,
+                ..Default::default()
+            }));
+        }
+        Ok(match marker {
+            b'Z' => JsonValue::Null,
+            b'T' => JsonValue::Boolean(true),
+            b'F' => JsonValue::Boolean(false),
+            b'd' => NumberValue::from_f32(f32::from_be_bytes(self.take_array()?))
+                .map_or(JsonValue::Null, JsonValue::Number),
+            b'D' => f64::from_be_bytes(self.take_array()?).into(),
+            b'H' => {
+                let len = self.len()?;
+                let text = core::str::from_utf8(self.take(len)?)
+                    .map_err(|_| UbjsonError::InvalidNumber)?;
+                let options = ParserOptions::default().number_overflow(NumberOverflow::Raw);
+                match parse_json_with_options(text, options) {
+                    Ok(number @ JsonValue::Number(_)) | Ok(number @ JsonValue::BigNumber(_)) => {
+                        number
+                    }
+                    _ => return Err(UbjsonError::InvalidNumber),
+                }
+            }
+            b'C' => {
+                let byte = self.take_array::<1>()?[0];
+                if !byte.is_ascii() {
+                    return Err(UbjsonError::InvalidUtf8);
+                }
+                JsonValue::String(vec![byte as char])
+            }
+            b'S' => JsonValue::String(self.string()?),
+            b'[' => self.array(depth + 1)?,
+            b'{' => self.object(depth + 1)?,
+            _ => return Err(UbjsonError::InvalidMarker(marker)),
+        })
+    }
+}
+
+/// Decodes a single UBJSON value.
+///
+/// Both plain containers and the optimized forms with a `$` type and `#` count are accepted,
+/// except a `$` type of `Z`, `T` or `F`, and `N` no-op markers are skipped. Non-finite floats
+/// become `NonFinite`.
+pub fn from_ubjson(input: &[u8]) -> Result<JsonValue, UbjsonError> {
+    let mut reader = Reader { input };
+    let marker = reader.marker()?;
+    let value = reader.value(marker, 0)?;
+    while reader.input.first() == Some(&b'N') {
+        reader.input = &reader.input[1..];
+    }
+    if !reader.input.is_empty() {
+        return Err(UbjsonError::TrailingBytes);
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_parser::parse_json;
+
+    #[test]
+    fn ubjson_round_trips() {
+        let value = parse_json(
+            r#"{"a":[0,255,-1,256,-129,40000,-2147483649],"b":1.5,"c":"x","d":[null,true,false,{}],"e":-0,"f":123456789012345678901234567890}"#,
+        )
+        .unwrap();
+        let bytes = to_ubjson(&value);
+        assert_eq!(&bytes[..8], b"{U\x01a[U\x00U");
+        assert_eq!(from_ubjson(&bytes), Ok(value));
+
+        let unicode = JsonValue::String(vec!['é', '😀']);
+        assert_eq!(to_ubjson(&unicode), "SU\x06é😀".as_bytes());
+        assert_eq!(from_ubjson(&to_ubjson(&unicode)), Ok(unicode));
+    }
+
+    #[test]
+    fn ubjson_reads_optimized_containers() {
+        let value = from_ubjson(b"[$U#U\x03\x01\x02\x03]").unwrap_err();
+        assert_eq!(value, UbjsonError::TrailingBytes);
+        assert_eq!(
+            format!("{}", from_ubjson(b"[$U#U\x03\x01\x02\x03").unwrap()),
+            "[1,2,3]"
+        );
+        assert_eq!(
+            format!("{}", from_ubjson(b"{#U\x02U\x01aTNU\x01bCz").unwrap()),
+            r#"{"a":true,"b":"z"}"#
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                from_ubjson(b"[D\x7f\xf0\0\0\0\0\0\0d\x3f\xc0\0\0]").unwrap()
+            ),
+            "[Infinity,1.5]"
+        );
+    }
+
+    #[test]
+    fn ubjson_rejects_bad_input() {
+        assert_eq!(from_ubjson(b"[U\x01"), Err(UbjsonError::UnexpectedEnd));
+        assert_eq!(from_ubjson(b"ZZ"), Err(UbjsonError::TrailingBytes));
+        assert_eq!(from_ubjson(b"SU\x01\xff"), Err(UbjsonError::InvalidUtf8));
+        assert_eq!(from_ubjson(b"Si\xff"), Err(UbjsonError::InvalidLength));
+        assert_eq!(from_ubjson(b"HU\x01x"), Err(UbjsonError::InvalidNumber));
+        assert_eq!(from_ubjson(b"[$U]"), Err(UbjsonError::InvalidMarker(b'$')));
+        assert_eq!(from_ubjson(b"x"), Err(UbjsonError::InvalidMarker(b'x')));
+        assert_eq!(from_ubjson(&[b'['; 200]), Err(UbjsonError::TooDeep));
+        assert_eq!(
+            from_ubjson(b"[$Z#l\x00\x98\x96\x80"),
+            Err(UbjsonError::InvalidMarker(b'Z'))
+        );
+        assert_eq!(
+            from_ubjson(b"{$T#L\x7f\xff\xff\xff\xff\xff\xff\xff"),
+            Err(UbjsonError::InvalidMarker(b'T'))
+        );
+    }
+}
@@ -0,0 +1,380 @@
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::json::{JsonValue, NumberValue};
+use crate::json_parser::{skip_bom, String, Value, Whitespace};
+use crate::parse_error::{ParseError, ParseErrorKind};
+use core::fmt;
+use lite_parser::{
+    impls::{SimpleError, SimplePosition},
+    parser::{Parser, ParserContext, ParserOptions, ParserState},
+    traits::Input,
+};
+
+/// Receives the pieces of a JSON document from `visit_json` as they are parsed, so typed
+/// structures can be built without any `JsonValue` in between. Returning an error stops
+/// parsing. Each method does nothing by default.
+pub trait Visitor {
+    type Error;
+
+    fn visit_object_start(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn visit_object_end(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn visit_array_start(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn visit_array_end(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// The key of the object member whose value is visited next.
+    fn visit_key(&mut self, _key: &[char]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn visit_string(&mut self, _value: &[char]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn visit_number(&mut self, _value: NumberValue) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn visit_bool(&mut self, _value: bool) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn visit_null(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// The error returned by `visit_json`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VisitError<E> {
+    /// The input is not valid JSON.
+    Parse(ParseError),
+    /// The visitor stopped parsing.
+    Visitor(E),
+}
+
+impl<E: fmt::Display> fmt::Display for VisitError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VisitError::Parse(e) => e.fmt(f),
+            VisitError::Visitor(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: std::error::Error + 'static> std::error::Error for VisitError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            VisitError::Parse(e) => Some(e),
+            VisitError::Visitor(e) => Some(e),
+        }
+    }
+}
+
+/// Parses `input` like `parse_json`, calling `visitor` for each piece instead of building
+/// values. Unlike `parse_with_handler`, this works on the text directly rather than through a
+/// `PullParser`, and the visitor can fail with its own error.
+///
+/// Nesting is tracked on the heap, but the default limit of 100 levels still applies.
+pub fn visit_json<V: Visitor>(input: &str, visitor: &mut V) -> Result<(), VisitError<V::Error>> {
+    let options = ParserOptions::default();
+    let state = ParserState::default();
+    let mut driver = Driver {
+        input,
+        context: ParserContext::new(&options, &state),
+        visitor,
+        stack: Vec::new(),
+    };
+    driver.run(skip_bom(input))
+}
+
+/// Calls a `Visitor` while parsing, without recursion.
+struct Driver<'a, V> {
+    input: &'a str,
+    context: ParserContext<'a>,
+    visitor: &'a mut V,
+    /// The closing bracket of each open array or object, innermost last.
+    stack: Vec<char>,
+}
+
+impl<V: Visitor> Driver<'_, V> {
+    fn parse_error(&self, error: SimpleError) -> VisitError<V::Error> {
+        VisitError::Parse(ParseError::from_simple(self.input, error))
+    }
+
+    fn whitespace(&self, current: SimplePosition) -> Result<SimplePosition, VisitError<V::Error>> {
+        <Whitespace as Parser<&str>>::parse(&self.input, current, &self.context)
+            .map(|(_, next)| next)
+            .map_err(|e| self.parse_error(e))
+    }
+
+    /// Reads a key and its colon, returning the position of the value.
+    fn key(&mut self, current: SimplePosition) -> Result<SimplePosition, VisitError<V::Error>> {
+        let input = self.input;
+        let (key, next) = <String as Parser<&str>>::parse(&input, current, &self.context)
+            .map_err(|e| self.parse_error(e))?;
+        self.visitor.visit_key(&key).map_err(VisitError::Visitor)?;
+        let next = self.whitespace(next)?;
+        match input.next(next) {
+            Ok((':', next)) => self.whitespace(next),
+            _ => Err(self.parse_error(input.error_at(next, "Character"))),
+        }
+    }
+
+    fn run(&mut self, start: SimplePosition) -> Result<(), VisitError<V::Error>> {
+        let input = self.input;
+        let mut next = self.whitespace(start)?;
+        loop {
+            // At a value.
+            match input.next(next) {
+                Ok((open @ ('{' | '['), after)) => {
+                    if Some(self.stack.len() as u32) == self.context.options().max_nest_level {
+                        return Err(self.parse_error(
+                            input.error_with_kind(next, ParseErrorKind::DepthLimitExceeded),
+                        ));
+                    }
+                    let close = if open == '{' { '}' } else { ']' };
+                    if open == '{' {
+                        self.visitor.visit_object_start()
+                    } else {
+                        self.visitor.visit_array_start()
+                    }
+                    .map_err(VisitError::Visitor)?;
+                    self.stack.push(close);
+                    next = self.whitespace(after)?;
+                    match input.next(next) {
+                        Ok((c, _)) if c == close => {}
+                        _ if open == '{' => {
+                            next = self.key(next)?;
+                            continue;
+                        }
+                        _ => continue,
+                    }
+                }
+                Ok(('"', _)) => {
+                    let (value, after) =
+                        <String as Parser<&str>>::parse(&input, next, &self.context)
+                            .map_err(|e| self.parse_error(e))?;
+                    self.visitor
+                        .visit_string(&value)
+                        .map_err(VisitError::Visitor)?;
+                    next = self.whitespace(after)?;
+                }
+                _ => {
+                    let (value, after) =
+                        <Value as Parser<&str>>::parse(&input, next, &self.context)
+                            .map_err(|e| self.parse_error(e))?;
+                    match value {
+                        JsonValue::Number(n) => self.visitor.visit_number(n),
+                        JsonValue::Boolean(b) => self.visitor.visit_bool(b),
+                        JsonValue::Null => self.visitor.visit_null(),
+                        _ => unreachable!("only scalars are parsed with the default options"),
+                    }
+                    .map_err(VisitError::Visitor)?;
+                    next = self.whitespace(after)?;
+                }
+            }
+
+            // After a value, close containers until another value is expected.
+            loop {
+                let close = match self.stack.last() {
+                    Some(close) => *close,
+                    None if input.is_end(next) => return Ok(()),
+                    None => {
+                        return Err(VisitError::Parse(ParseError::new(
+                            input,
+                            next,
+                            ParseErrorKind::TrailingCharacters,
+                        )))
+                    }
+                };
+                match input.next(next) {
+                    Ok((',', after)) => {
+                        next = self.whitespace(after)?;
+                        if close == '}' {
+                            next = self.key(next)?;
+                        }
+                        break;
+                    }
+                    Ok((c, after)) if c == close => {
+                        self.stack.pop();
+                        if close == '}' {
+                            self.visitor.visit_object_end()
+                        } else {
+                            self.visitor.visit_array_end()
+                        }
+                        .map_err(VisitError::Visitor)?;
+                        next = self.whitespace(after)?;
+                    }
+                    _ => {
+                        let reason = if close == '}' { "Object" } else { "Array" };
+                        return Err(self.parse_error(input.error_at(next, reason)));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_parser::parse_json;
+
+    /// Records the visits as text, failing on the string "fail".
+    #[derive(Default)]
+    struct Trace(Vec<&'static str>);
+
+    impl Visitor for Trace {
+        type Error = &'static str;
+
+        fn visit_object_start(&mut self) -> Result<(), Self::Error> {
+            self.0.push("{");
+            Ok(())
+        }
+
+        fn visit_object_end(&mut self) -> Result<(), Self::Error> {
+            self.0.push("}");
+            Ok(())
+        }
+
+        fn visit_array_start(&mut self) -> Result<(), Self::Error> {
+            self.0.push("[");
+            Ok(())
+        }
+
+        fn visit_array_end(&mut self) -> Result<(), Self::Error> {
+            self.0.push("]");
+            Ok(())
+        }
+
+        fn visit_key(&mut self, _key: &[char]) -> Result<(), Self::Error> {
+            self.0.push("key");
+            Ok(())
+        }
+
+        fn visit_string(&mut self, value: &[char]) -> Result<(), Self::Error> {
+            if value == ['f', 'a', 'i', 'l'] {
+                return Err("failed");
+            }
+            self.0.push("string");
+            Ok(())
+        }
+
+        fn visit_number(&mut self, _value: NumberValue) -> Result<(), Self::Error> {
+            self.0.push("number");
+            Ok(())
+        }
+
+        fn visit_bool(&mut self, _value: bool) -> Result<(), Self::Error> {
+            self.0.push("bool");
+            Ok(())
+        }
+
+        fn visit_null(&mut self) -> Result<(), Self::Error> {
+            self.0.push("null");
+            Ok(())
+        }
+    }
+
+    fn trace(input: &str) -> Result<Vec<&'static str>, VisitError<&'static str>> {
+        let mut trace = Trace::default();
+        visit_json(input, &mut trace).map(|_| trace.0)
+    }
+
+    #[test]
+    fn visitors_are_called() {
+        assert_eq!(
+            trace(r#" {"a": [1, "x", {}], "b": {"c": null, "d": [true, []]}, "e": -2.5} "#),
+            Ok(vec![
+                "{", "key", "[", "number", "string", "{", "}", "]", "key", "{", "key", "null",
+                "key", "[", "bool", "[", "]", "]", "}", "key", "number", "}",
+            ])
+        );
+        assert_eq!(trace("\u{FEFF}\"a\""), Ok(vec!["string"]));
+        assert_eq!(
+            trace(r#"["ok", "fail", 1]"#),
+            Err(VisitError::Visitor("failed"))
+        );
+
+        for input in [
+            "",
+            "[1,]",
+            "[1 2]",
+            "{\"a\" 1}",
+            "{\"a\": 1,}",
+            "[1}",
+            "{1: 2}",
+            "[1] x",
+            "[[1]",
+            "[tru]",
+        ] {
+            assert_eq!(
+                trace(input),
+                Err(VisitError::Parse(parse_json(input).unwrap_err())),
+                "{}",
+                input
+            );
+        }
+        match trace(&"[".repeat(200)) {
+            Err(VisitError::Parse(e)) => assert_eq!(e.kind, ParseErrorKind::DepthLimitExceeded),
+            other => panic!("{:?}", other),
+        }
+    }
+    #[test]
+    fn errors_stop_visiting() {
+        // Whichever failure comes first in the input is reported, and nothing after it is visited.
+        let mut visited = Trace::default();
+        assert_eq!(
+            visit_json(r#"[1, "fail", }"#, &mut visited),
+            Err(VisitError::Visitor("failed"))
+        );
+        assert_eq!(visited.0, ["[", "number"]);
+        let mut visited = Trace::default();
+        let error = visit_json(r#"[1, }, "fail"]"#, &mut visited).unwrap_err();
+        assert_eq!(visited.0, ["[", "number"]);
+        match error {
+            VisitError::Parse(e) => {
+                assert_eq!((e.kind, e.offset), (ParseErrorKind::UnexpectedToken, 4))
+            }
+            other => panic!("{:?}", other),
+        }
+
+        let kind = |input: &str| match trace(input) {
+            Err(VisitError::Parse(e)) => (e.kind, e.offset),
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(kind(r#"[1, "\x"]"#), (ParseErrorKind::InvalidEscape, 5));
+        assert_eq!(kind(r#"{"a": "é"#), (ParseErrorKind::UnexpectedEof, 9));
+        assert_eq!(kind("[01]"), (ParseErrorKind::InvalidNumber, 1));
+        assert_eq!(kind("[1] x"), (ParseErrorKind::TrailingCharacters, 4));
+        assert_eq!(
+            kind("{\"a\": 1 \"b\": 2}"),
+            (ParseErrorKind::UnexpectedToken, 8)
+        );
+
+        // The default nesting limit allows exactly 100 levels.
+        let nested = |depth| "[".repeat(depth) + &"]".repeat(depth);
+        assert!(trace(&nested(100)).is_ok());
+        assert_eq!(
+            kind(&nested(101)),
+            (ParseErrorKind::DepthLimitExceeded, 100)
+        );
+        assert_eq!(VisitError::<&str>::Visitor("failed").to_string(), "failed");
+    }
+}